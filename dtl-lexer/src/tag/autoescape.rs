@@ -5,6 +5,7 @@ use crate::tag::TagParts;
 use crate::types::{At, TemplateString};
 
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AutoescapeEnabled {
     On,
     Off,
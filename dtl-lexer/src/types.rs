@@ -67,7 +67,10 @@ impl<'t> Variable {
         Self { at }
     }
 
-    pub fn parts(&self, template: TemplateString<'t>) -> impl Iterator<Item = (&'t str, At)> {
+    pub fn parts(
+        &self,
+        template: TemplateString<'t>,
+    ) -> impl Iterator<Item = (&'t str, At)> + use<'t> {
         let start = self.at.0;
         let variable = template.content(self.at);
         PartsIterator { variable, start }
@@ -4,7 +4,7 @@ from django.template.backends.django import get_installed_libraries
 
 from .django_rusty_templates import Engine, Template
 
-__all__ = ["RustyTemplates", "Template"]
+__all__ = ["RustyTemplates", "Template", "precompile_templates"]
 
 
 class RustyTemplates(BaseEngine):
@@ -27,6 +27,20 @@ class RustyTemplates(BaseEngine):
     def get_template(self, template_name):
         return self.engine.get_template(template_name)
 
+    def precompile(self):
+        """
+        Compile every template the loaders can find into the
+        "template_cache_dir" option, returning the errors raised by any
+        templates which could not be compiled.
+        """
+        errors = {}
+        for template_name in self.engine.template_names():
+            try:
+                self.engine.precompile(template_name)
+            except Exception as e:
+                errors[template_name] = e
+        return errors
+
     def get_templatetag_libraries(self, custom_libraries):
         """
         Return a collation of template tag libraries from installed
@@ -35,3 +49,18 @@ class RustyTemplates(BaseEngine):
         libraries = get_installed_libraries()
         libraries.update(custom_libraries)
         return libraries
+
+
+def precompile_templates():
+    """
+    Precompile the templates of every RustyTemplates backend, for use in a
+    build step or management command. Return the errors raised by any
+    templates which could not be compiled, keyed by backend alias.
+    """
+    from django.template import engines
+
+    return {
+        engine.name: engine.precompile()
+        for engine in engines.all()
+        if isinstance(engine, RustyTemplates)
+    }
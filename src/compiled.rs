@@ -0,0 +1,624 @@
+//! Compiled templates, written to a cache directory so other processes can
+//! load them instead of parsing the template source again.
+//!
+//! The parsed nodes hold Python objects, such as the functions of custom
+//! filters and tags, which can't be written to disk. Those are stored by the
+//! library and name they were loaded with instead, and looked up again in
+//! the engine's libraries when the template is loaded.
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::intern;
+use pyo3::prelude::*;
+use pyo3::types::PyString;
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+
+use dtl_lexer::types::{At, TemplateString};
+
+use crate::filters::ExternalFilter;
+use crate::parse::{
+    Include, IncludeTemplateName, SimpleBlockTag, SimpleTag, TagElement, TokenTree,
+};
+use crate::template::django_rusty_templates::{Engine, Template, TemplateData};
+use crate::types::{Argument, Variable};
+
+thread_local! {
+    /// The template being loaded, used to relink its nodes.
+    static LINKER: RefCell<Option<Linker>> = const { RefCell::new(None) };
+}
+
+struct Linker {
+    template: String,
+    engine: Arc<Engine>,
+}
+
+fn with_linker<T>(f: impl FnOnce(Python<'_>, &Linker) -> Result<T, String>) -> Result<T, String> {
+    LINKER.with_borrow(|linker| {
+        let linker = linker
+            .as_ref()
+            .ok_or("Compiled templates can only be loaded by `compiled::load`")?;
+        Python::attach(|py| f(py, linker))
+    })
+}
+
+/// Look up a filter or tag function registered in one of the engine's libraries.
+fn library_item<'py>(
+    py: Python<'py>,
+    engine: &Engine,
+    library: &str,
+    kind: &Bound<'py, PyString>,
+    name: &str,
+) -> Result<Bound<'py, PyAny>, String> {
+    let Some(module) = engine.libraries.get(library) else {
+        return Err(format!("Template library '{library}' is not installed"));
+    };
+    module
+        .bind(py)
+        .getattr(kind)
+        .and_then(|items| items.get_item(name))
+        .map_err(|err| format!("Could not find '{name}' in '{library}': {err}"))
+}
+
+/// The function wrapped by a `simple_tag` or `simple_block_tag`, which
+/// Django keeps in the closure of the tag's compile function.
+fn tag_function<'py>(tag: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let py = tag.py();
+    let names: Vec<String> = tag
+        .getattr(intern!(py, "__code__"))?
+        .getattr(intern!(py, "co_freevars"))?
+        .extract()?;
+    let index = names
+        .iter()
+        .position(|name| name == "func")
+        .ok_or_else(|| PyValueError::new_err("Only simple tags can be relinked"))?;
+    tag.getattr(intern!(py, "__closure__"))?
+        .get_item(index)?
+        .getattr(intern!(py, "cell_contents"))
+}
+
+fn link_tag(library: &str, name: &str) -> Result<Arc<Py<PyAny>>, String> {
+    with_linker(|py, linker| {
+        let tag = library_item(py, &linker.engine, library, intern!(py, "tags"), name)?;
+        let func = tag_function(&tag).map_err(|err| err.to_string())?;
+        Ok(Arc::new(func.unbind()))
+    })
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct VariableRef {
+    at: At,
+}
+
+impl From<Variable> for VariableRef {
+    fn from(variable: Variable) -> Self {
+        Self { at: variable.at }
+    }
+}
+
+impl TryFrom<VariableRef> for Variable {
+    type Error = String;
+
+    fn try_from(variable: VariableRef) -> Result<Self, String> {
+        with_linker(|py, linker| Ok(Self::new(py, TemplateString(&linker.template), variable.at)))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ExternalFilterRef {
+    library: String,
+    name: String,
+    argument: Option<Argument>,
+}
+
+impl From<ExternalFilter> for ExternalFilterRef {
+    fn from(filter: ExternalFilter) -> Self {
+        Self {
+            library: filter.library,
+            name: filter.name,
+            argument: filter.argument,
+        }
+    }
+}
+
+impl TryFrom<ExternalFilterRef> for ExternalFilter {
+    type Error = String;
+
+    fn try_from(filter: ExternalFilterRef) -> Result<Self, String> {
+        let func = with_linker(|py, linker| {
+            let filters = intern!(py, "filters");
+            library_item(py, &linker.engine, &filter.library, filters, &filter.name)
+                .map(Bound::unbind)
+        })?;
+        Ok(Self::new(
+            func,
+            filter.library,
+            filter.name,
+            filter.argument,
+        ))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SimpleTagRef {
+    library: String,
+    name: String,
+    at: At,
+    takes_context: bool,
+    args: Vec<TagElement>,
+    kwargs: Vec<(String, TagElement)>,
+    target_var: Option<String>,
+}
+
+impl From<SimpleTag> for SimpleTagRef {
+    fn from(tag: SimpleTag) -> Self {
+        Self {
+            library: tag.library,
+            name: tag.name,
+            at: tag.at,
+            takes_context: tag.takes_context,
+            args: tag.args,
+            kwargs: tag.kwargs,
+            target_var: tag.target_var,
+        }
+    }
+}
+
+impl TryFrom<SimpleTagRef> for SimpleTag {
+    type Error = String;
+
+    fn try_from(tag: SimpleTagRef) -> Result<Self, String> {
+        Ok(Self {
+            func: link_tag(&tag.library, &tag.name)?,
+            library: tag.library,
+            name: tag.name,
+            at: tag.at,
+            takes_context: tag.takes_context,
+            args: tag.args,
+            kwargs: tag.kwargs,
+            target_var: tag.target_var,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SimpleBlockTagRef {
+    library: String,
+    name: String,
+    nodes: Vec<TokenTree>,
+    at: At,
+    takes_context: bool,
+    args: Vec<TagElement>,
+    kwargs: Vec<(String, TagElement)>,
+    target_var: Option<String>,
+}
+
+impl From<SimpleBlockTag> for SimpleBlockTagRef {
+    fn from(tag: SimpleBlockTag) -> Self {
+        Self {
+            library: tag.library,
+            name: tag.name,
+            nodes: tag.nodes,
+            at: tag.at,
+            takes_context: tag.takes_context,
+            args: tag.args,
+            kwargs: tag.kwargs,
+            target_var: tag.target_var,
+        }
+    }
+}
+
+impl TryFrom<SimpleBlockTagRef> for SimpleBlockTag {
+    type Error = String;
+
+    fn try_from(tag: SimpleBlockTagRef) -> Result<Self, String> {
+        Ok(Self {
+            func: link_tag(&tag.library, &tag.name)?,
+            library: tag.library,
+            name: tag.name,
+            nodes: tag.nodes,
+            at: tag.at,
+            takes_context: tag.takes_context,
+            args: tag.args,
+            kwargs: tag.kwargs,
+            target_var: tag.target_var,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct IncludeRef {
+    template_name: IncludeTemplateName,
+    origin: Option<String>,
+    only: bool,
+    kwargs: Vec<(At, TagElement)>,
+}
+
+impl From<Include> for IncludeRef {
+    fn from(include: Include) -> Self {
+        Self {
+            template_name: include.template_name,
+            origin: include.origin,
+            only: include.only,
+            kwargs: include.kwargs,
+        }
+    }
+}
+
+impl TryFrom<IncludeRef> for Include {
+    type Error = String;
+
+    fn try_from(include: IncludeRef) -> Result<Self, String> {
+        let engine = with_linker(|_py, linker| Ok(linker.engine.clone()))?;
+        Ok(Self {
+            template_name: include.template_name,
+            origin: include.origin,
+            engine,
+            only: include.only,
+            kwargs: include.kwargs,
+        })
+    }
+}
+
+/// The form of a template written to the cache directory.
+#[derive(Serialize, Deserialize)]
+struct CompiledTemplate<'a> {
+    /// The version of Django Rusty Templates which compiled the template.
+    #[serde(borrow)]
+    version: Cow<'a, str>,
+    #[serde(borrow)]
+    origin: Cow<'a, str>,
+    #[serde(borrow)]
+    source: Cow<'a, str>,
+    /// The libraries implemented natively, which change how their filters
+    /// are parsed.
+    #[serde(borrow)]
+    humanize_libraries: Vec<Cow<'a, str>>,
+    #[serde(borrow)]
+    nodes: &'a RawValue,
+}
+
+fn humanize_libraries(engine: &Engine) -> Vec<Cow<'_, str>> {
+    let mut libraries: Vec<_> = engine
+        .humanize_libraries
+        .iter()
+        .map(|library| Cow::Borrowed(library.as_str()))
+        .collect();
+    libraries.sort_unstable();
+    libraries
+}
+
+/// The file the template named `origin` at `filename` is compiled to.
+///
+/// The name is a 64-bit FNV-1a hash, which unlike `DefaultHasher` stays the
+/// same across Rust releases, so other builds can find the file.
+fn compiled_path(dir: &Path, filename: &Path, origin: &str) -> PathBuf {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    let bytes = filename
+        .as_os_str()
+        .as_encoded_bytes()
+        .iter()
+        // Neither part can contain a NUL byte, so it separates them.
+        .chain(&[0])
+        .chain(origin.as_bytes());
+    let hash = bytes.fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    });
+    dir.join(format!("{hash:016x}.json"))
+}
+
+/// Load the compiled form of a template, if one was written for the same
+/// source and engine configuration.
+pub fn load(
+    dir: &Path,
+    source: &str,
+    filename: &Path,
+    origin: &str,
+    engine: &Arc<Engine>,
+) -> Option<Template> {
+    let contents = std::fs::read_to_string(compiled_path(dir, filename, origin)).ok()?;
+    let compiled: CompiledTemplate = serde_json::from_str(&contents).ok()?;
+    if compiled.version != env!("CARGO_PKG_VERSION")
+        || compiled.origin != origin
+        || compiled.source != source
+        || compiled.humanize_libraries != humanize_libraries(engine)
+    {
+        return None;
+    }
+    LINKER.set(Some(Linker {
+        template: source.to_string(),
+        engine: engine.clone(),
+    }));
+    let nodes = serde_json::from_str::<Vec<TokenTree>>(compiled.nodes.get());
+    LINKER.set(None);
+    Some(
+        TemplateData {
+            filename: Some(filename.to_path_buf()),
+            template: source.to_string(),
+            nodes: nodes.ok()?,
+            engine: engine.clone(),
+        }
+        .into(),
+    )
+}
+
+/// Counts the temporary files written by this process, to name them apart.
+static PARTIAL_FILES: AtomicU64 = AtomicU64::new(0);
+
+/// A temporary path next to `path` that no other call, in this or another
+/// process, writes to at the same time.
+fn partial_path(path: &Path) -> PathBuf {
+    let count = PARTIAL_FILES.fetch_add(1, Ordering::Relaxed);
+    path.with_extension(format!("{}.{count}.tmp", std::process::id()))
+}
+
+/// Write the compiled form of `template`, loaded as `origin`, to `dir`.
+pub fn store(dir: &Path, template: &Template, origin: &str) -> std::io::Result<()> {
+    let Some(filename) = template.filename.as_deref() else {
+        return Err(std::io::Error::other(
+            "Only templates loaded from a file can be compiled",
+        ));
+    };
+    let nodes = serde_json::value::to_raw_value(&template.nodes)?;
+    let compiled = CompiledTemplate {
+        version: Cow::Borrowed(env!("CARGO_PKG_VERSION")),
+        origin: Cow::Borrowed(origin),
+        source: Cow::Borrowed(&template.template),
+        humanize_libraries: humanize_libraries(&template.engine),
+        nodes: &nodes,
+    };
+    std::fs::create_dir_all(dir)?;
+    let path = compiled_path(dir, filename, origin);
+    // Write to a temporary file first, so a reader never sees half a template.
+    let partial = partial_path(&path);
+    let written = std::fs::write(&partial, serde_json::to_vec(&compiled)?)
+        .and_then(|()| std::fs::rename(&partial, path));
+    if written.is_err() {
+        let _ = std::fs::remove_file(partial);
+    }
+    written
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+    use std::ffi::CStr;
+
+    use pyo3::types::{PyDict, PyDictMethods, PyList};
+
+    use crate::filters::FilterType;
+    use crate::parse::Tag;
+
+    const SOURCE: &str = "{% for user in users reversed %}{{ user.name|upper }},\n{% empty %}\"None\"{% endfor %}{% if users.1 %}{% include 'basic.txt' %}{% endif %}";
+
+    fn setup(name: &str) -> (PathBuf, Arc<Engine>) {
+        setup_with_libraries(name, HashMap::new())
+    }
+
+    fn setup_with_libraries(
+        name: &str,
+        libraries: HashMap<String, Py<PyAny>>,
+    ) -> (PathBuf, Arc<Engine>) {
+        let dir = std::env::temp_dir().join(format!("dtl-compiled-{}-{name}", std::process::id()));
+        let mut engine = Engine::empty();
+        engine.template_cache_dir = Some(dir.clone());
+        engine.libraries = libraries;
+        (dir, Arc::new(engine))
+    }
+
+    /// A stand-in for a `django.template.Library`, with a filter and a tag
+    /// laid out like Django's `simple_tag`.
+    const LIBRARY: &CStr = c"
+def simple_tag(func):
+    defaults = None
+    function_name = func.__name__
+    kwonly = []
+    kwonly_defaults = None
+    params = ['name']
+    takes_context = False
+    varargs = None
+    varkw = None
+
+    def compile_func(parser, token):
+        return (
+            defaults, func, function_name, kwonly, kwonly_defaults, params,
+            takes_context, varargs, varkw,
+        )
+
+    return compile_func
+
+def double(value):
+    return value * 2
+
+def greet(name):
+    return f'Hello {name}'
+
+class Library:
+    filters = {'double': double}
+    tags = {'greet': simple_tag(greet)}
+
+library = Library()
+";
+
+    #[test]
+    fn test_store_and_load() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let (dir, engine) = setup("store");
+            let filename = Path::new("users.html");
+            let template = Template::new(
+                py,
+                SOURCE,
+                filename.to_path_buf(),
+                "users.html",
+                engine.clone(),
+            )
+            .unwrap();
+            store(&dir, &template, "users.html").unwrap();
+
+            let loaded = load(&dir, SOURCE, filename, "users.html", &engine).unwrap();
+            assert_eq!(loaded.nodes, template.nodes);
+            assert_eq!(loaded.filename.as_deref(), Some(filename));
+
+            let user = PyDict::new(py);
+            user.set_item("name", "lily").unwrap();
+            let context = PyDict::new(py);
+            context
+                .set_item("users", PyList::new(py, [user]).unwrap())
+                .unwrap();
+            assert_eq!(
+                loaded
+                    .py_render(py, Some(context.into_any()), None)
+                    .unwrap(),
+                "LILY,\n"
+            );
+
+            std::fs::remove_dir_all(dir).unwrap();
+        });
+    }
+
+    #[test]
+    fn test_load_changed_source() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let (dir, engine) = setup("changed");
+            let filename = Path::new("users.html");
+            let template = Template::new(
+                py,
+                SOURCE,
+                filename.to_path_buf(),
+                "users.html",
+                engine.clone(),
+            )
+            .unwrap();
+            store(&dir, &template, "users.html").unwrap();
+
+            assert!(load(&dir, "{{ users }}", filename, "users.html", &engine).is_none());
+            assert!(load(&dir, SOURCE, filename, "other.html", &engine).is_none());
+            assert!(load(&dir, SOURCE, Path::new("other.html"), "users.html", &engine).is_none());
+
+            std::fs::remove_dir_all(dir).unwrap();
+        });
+    }
+
+    #[test]
+    fn test_float_round_trip() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let (dir, engine) = setup("float");
+            // serde_json parses this one ULP off unless `float_roundtrip` is enabled.
+            let source = "{{ 0.0017866971117175877 }}{{ user|default:0.0017866971117175877 }}{% if user == 0.0017866971117175877 %}{% endif %}";
+            let filename = Path::new("floats.html");
+            let template = Template::new(
+                py,
+                source,
+                filename.to_path_buf(),
+                "floats.html",
+                engine.clone(),
+            )
+            .unwrap();
+            store(&dir, &template, "floats.html").unwrap();
+
+            let loaded = load(&dir, source, filename, "floats.html", &engine).unwrap();
+            assert_eq!(loaded.nodes, template.nodes);
+
+            std::fs::remove_dir_all(dir).unwrap();
+        });
+    }
+
+    #[test]
+    fn test_compiled_path_is_stable() {
+        let path = compiled_path(
+            Path::new("compiled"),
+            Path::new("templates/users.html"),
+            "users.html",
+        );
+        assert_eq!(path, Path::new("compiled/2ab93022a550b2fd.json"));
+    }
+
+    #[test]
+    fn test_partial_paths_differ() {
+        let path = Path::new("compiled/2ab93022a550b2fd.json");
+        let first = partial_path(path);
+        let second = partial_path(path);
+        assert_ne!(first, second);
+        assert_eq!(first.parent(), path.parent());
+    }
+
+    #[test]
+    fn test_store_from_string() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let (dir, engine) = setup("from-string");
+            let template = Template::new_from_string(py, SOURCE.to_string(), engine).unwrap();
+            let error = store(&dir, &template, "users.html").unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                "Only templates loaded from a file can be compiled"
+            );
+        });
+    }
+
+    #[test]
+    fn test_relink_library() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let globals = PyDict::new(py);
+            py.run(LIBRARY, Some(&globals), None).unwrap();
+            let library = globals.get_item("library").unwrap().unwrap();
+            let double = globals.get_item("double").unwrap().unwrap();
+            let greet = globals.get_item("greet").unwrap().unwrap();
+            let libraries = HashMap::from([("custom".to_string(), library.unbind())]);
+            let (dir, engine) = setup_with_libraries("relink", libraries);
+            let source = "{% load custom %}{{ user|double }}{% greet user %}";
+            let filename = Path::new("custom.html");
+            let template = Template::new(
+                py,
+                source,
+                filename.to_path_buf(),
+                "custom.html",
+                engine.clone(),
+            )
+            .unwrap();
+            store(&dir, &template, "custom.html").unwrap();
+
+            let loaded = load(&dir, source, filename, "custom.html", &engine).unwrap();
+            let [
+                TokenTree::Filter(filter),
+                TokenTree::Tag(Tag::SimpleTag(tag)),
+            ] = loaded.nodes.as_slice()
+            else {
+                panic!("Unexpected nodes: {:?}", loaded.nodes);
+            };
+            let FilterType::External(external) = &filter.filter else {
+                panic!("Unexpected filter: {:?}", filter.filter);
+            };
+            assert_eq!(external.library, "custom");
+            assert_eq!(external.name, "double");
+            assert!(external.filter.bind(py).is(&double));
+            assert_eq!(tag.library, "custom");
+            assert_eq!(tag.name, "greet");
+            assert!(tag.func.bind(py).is(&greet));
+
+            // The compiled template can't be used without the library.
+            let (_, other_engine) = setup("relink");
+            assert!(load(&dir, source, filename, "custom.html", &other_engine).is_none());
+
+            std::fs::remove_dir_all(dir).unwrap();
+        });
+    }
+}
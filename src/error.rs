@@ -1,6 +1,11 @@
+use html_escape::encode_quoted_attribute;
 use miette::{Diagnostic, LabeledSpan, SourceSpan, miette};
-use pyo3::exceptions::PyKeyError;
+use pyo3::exceptions::{
+    PyKeyError, PyUnicodeDecodeError, PyUnicodeEncodeError, PyUnicodeTranslateError,
+};
+use pyo3::intern;
 use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
 use thiserror::Error;
 
 use crate::path::RelativePathError;
@@ -92,19 +97,34 @@ impl KeyErrorMessage {
     }
 }
 
+/// Attribute storing the span of the template element that raised an annotated error.
+///
+/// This mirrors Django's `_culprit_node` and is used to build `template_debug`.
+const CULPRIT_AT: &str = "_culprit_at";
+
+/// Number of lines shown around the failing line on Django's debug page.
+const CONTEXT_LINES: usize = 10;
+
 pub trait AnnotatePyErr {
     fn annotate(self, py: Python<'_>, at: At, label: &str, template: TemplateString<'_>) -> Self;
 }
 
 impl AnnotatePyErr for PyErr {
     fn annotate(self, py: Python<'_>, at: At, label: &str, template: TemplateString<'_>) -> Self {
+        // These can't be constructed from a single message, so keep the original.
+        if self.is_instance_of::<PyUnicodeDecodeError>(py)
+            || self.is_instance_of::<PyUnicodeEncodeError>(py)
+            || self.is_instance_of::<PyUnicodeTranslateError>(py)
+        {
+            return self;
+        }
         let message = miette!(
             labels = vec![LabeledSpan::at(at, label)],
             "{}",
             self.value(py),
         )
         .with_source_code(template.0.to_string());
-        if self.is_instance_of::<PyKeyError>(py) {
+        let err = if self.is_instance_of::<PyKeyError>(py) {
             let message = format!("{message:?}");
             // Python converts the message to `repr(message)` for KeyError.
             // When annotating, this is unhelpful, so we work around this by defining a custom
@@ -115,6 +135,95 @@ impl AnnotatePyErr for PyErr {
         } else {
             let err_type = self.get_type(py);
             Self::from_type(err_type, format!("{message:?}"))
+        };
+        let _ = err.value(py).setattr(intern!(py, CULPRIT_AT), at);
+        err
+    }
+}
+
+/// Find the span of a Python error previously annotated with `AnnotatePyErr::annotate`.
+pub fn culprit_at(py: Python<'_>, err: &PyErr) -> Option<At> {
+    err.value(py)
+        .getattr_opt(intern!(py, CULPRIT_AT))
+        .ok()
+        .flatten()?
+        .extract()
+        .ok()
+}
+
+/// Find the span of the primary label of a diagnostic.
+pub fn diagnostic_at(diagnostic: &impl Diagnostic) -> Option<At> {
+    let label = diagnostic.labels()?.next()?;
+    Some((label.offset(), label.len()))
+}
+
+/// Build the debug information Django's technical 500 page expects in
+/// `exception.template_debug`.
+///
+/// Django works with character offsets, so the byte offsets in `at` are converted.
+///
+/// See <https://github.com/django/django/blob/stable/5.2.x/django/template/base.py#L207>
+pub fn get_exception_info<'py>(
+    py: Python<'py>,
+    source: &str,
+    name: &str,
+    at: At,
+    message: &str,
+) -> PyResult<Bound<'py, PyDict>> {
+    let (start, len) = at;
+    let start_byte = start.min(source.len());
+    let end_byte = (start + len).min(source.len());
+    let start = source[..start_byte].chars().count();
+    let end = start + source[start_byte..end_byte].chars().count();
+
+    let chars: Vec<char> = source.chars().collect();
+    let slice = |from: usize, to: usize| -> String {
+        let text: String = chars[from.min(chars.len())..to.min(chars.len())]
+            .iter()
+            .collect();
+        encode_quoted_attribute(&text).into_owned()
+    };
+    let mut breaks = vec![0];
+    breaks.extend(
+        chars
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c == '\n')
+            .map(|(index, _)| index + 1),
+    );
+    breaks.push(chars.len() + 1);
+
+    let mut line = 0;
+    let mut upto = 0;
+    let (mut before, mut during, mut after) = (String::new(), String::new(), String::new());
+    let mut source_lines = Vec::with_capacity(breaks.len());
+    for (num, next) in breaks.into_iter().enumerate() {
+        if start >= upto && end <= next {
+            line = num;
+            before = slice(upto, start);
+            during = slice(start, end);
+            after = slice(end, next);
         }
+        source_lines.push((num, slice(upto, next)));
+        upto = next;
     }
+    let total = source_lines.len();
+    let top = line.saturating_sub(CONTEXT_LINES).max(1);
+    let bottom = total.min(line + 1 + CONTEXT_LINES);
+    let source_lines = PyList::new(py, source_lines.get(top..bottom).unwrap_or_default())?;
+
+    let info = PyDict::new(py);
+    info.set_item("message", message)?;
+    info.set_item("source_lines", source_lines)?;
+    info.set_item("before", before)?;
+    info.set_item("during", during)?;
+    info.set_item("after", after)?;
+    info.set_item("top", top)?;
+    info.set_item("bottom", bottom)?;
+    info.set_item("total", total)?;
+    info.set_item("line", line)?;
+    info.set_item("name", name)?;
+    info.set_item("start", start)?;
+    info.set_item("end", end)?;
+    Ok(info)
 }
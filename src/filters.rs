@@ -1,14 +1,18 @@
 use std::sync::Arc;
 
 use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
 
+use crate::compiled::ExternalFilterRef;
+use crate::render::stringformat::StringFormat;
 use crate::types::Argument;
 use dtl_lexer::types::At;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum FilterType {
     Add(AddFilter),
     AddSlashes(AddSlashesFilter),
+    Apnumber(ApnumberFilter),
     Capfirst(CapfirstFilter),
     Center(CenterFilter),
     Cut(CutFilter),
@@ -16,26 +20,66 @@ pub enum FilterType {
     DefaultIfNone(DefaultIfNoneFilter),
     DivisibleBy(DivisibleByFilter),
     Date(DateFilter),
+    Dictsort(DictsortFilter),
+    DictsortReversed(DictsortReversedFilter),
     Escape(EscapeFilter),
     Escapejs(EscapejsFilter),
+    Escapeseq(EscapeseqFilter),
     External(ExternalFilter),
+    Filesizeformat(FilesizeformatFilter),
+    First(FirstFilter),
+    Floatformat(FloatformatFilter),
     ForceEscape(ForceEscapeFilter),
+    GetDigit(GetDigitFilter),
+    Intcomma(IntcommaFilter),
+    Intword(IntwordFilter),
+    Iriencode(IriencodeFilter),
+    Join(JoinFilter),
+    JsonScript(JsonScriptFilter),
     Last(LastFilter),
+    Linebreaks(LinebreaksFilter),
+    Linebreaksbr(LinebreaksbrFilter),
+    Linenumbers(LinenumbersFilter),
+    Ljust(LjustFilter),
     Lower(LowerFilter),
+    MakeList(MakeListFilter),
     Length(LengthFilter),
+    Naturalday(NaturaldayFilter),
+    Naturaltime(NaturaltimeFilter),
+    Ordinal(OrdinalFilter),
+    Phone2numeric(Phone2numericFilter),
+    Pluralize(PluralizeFilter),
+    Pprint(PprintFilter),
+    Random(RandomFilter),
+    Rjust(RjustFilter),
     Safe(SafeFilter),
+    Safeseq(SafeseqFilter),
+    Slice(SliceFilter),
     Slugify(SlugifyFilter),
+    Stringformat(StringformatFilter),
+    Striptags(StriptagsFilter),
+    Time(TimeFilter),
+    Timesince(TimesinceFilter),
+    Timeuntil(TimeuntilFilter),
     Title(TitleFilter),
+    Truncatechars(TruncatecharsFilter),
+    TruncatecharsHtml(TruncatecharsHtmlFilter),
+    Truncatewords(TruncatewordsFilter),
+    TruncatewordsHtml(TruncatewordsHtmlFilter),
+    UnorderedList(UnorderedListFilter),
     Upper(UpperFilter),
+    Urlencode(UrlencodeFilter),
+    Urlize(UrlizeFilter),
+    Urlizetrunc(UrlizetruncFilter),
     Wordcount(WordcountFilter),
     Wordwrap(WordwrapFilter),
     Yesno(YesnoFilter),
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AddSlashesFilter;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AddFilter {
     pub argument: Argument,
 }
@@ -46,10 +90,10 @@ impl AddFilter {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CapfirstFilter;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct CenterFilter {
     pub argument: Argument,
 }
@@ -60,7 +104,29 @@ impl CenterFilter {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LjustFilter {
+    pub argument: Argument,
+}
+
+impl LjustFilter {
+    pub fn new(argument: Argument) -> Self {
+        Self { argument }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RjustFilter {
+    pub argument: Argument,
+}
+
+impl RjustFilter {
+    pub fn new(argument: Argument) -> Self {
+        Self { argument }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct CutFilter {
     pub argument: Argument,
 }
@@ -71,7 +137,29 @@ impl CutFilter {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DictsortFilter {
+    pub argument: Argument,
+}
+
+impl DictsortFilter {
+    pub fn new(argument: Argument) -> Self {
+        Self { argument }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DictsortReversedFilter {
+    pub argument: Argument,
+}
+
+impl DictsortReversedFilter {
+    pub fn new(argument: Argument) -> Self {
+        Self { argument }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct DefaultFilter {
     pub argument: Argument,
     pub at: At,
@@ -83,7 +171,7 @@ impl DefaultFilter {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct DefaultIfNoneFilter {
     pub argument: Argument,
 }
@@ -94,7 +182,7 @@ impl DefaultIfNoneFilter {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct DateFilter {
     pub argument: Option<Argument>,
     pub at: At,
@@ -106,22 +194,81 @@ impl DateFilter {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TimeFilter {
+    pub argument: Option<Argument>,
+    pub at: At,
+}
+
+impl TimeFilter {
+    pub fn new(argument: Option<Argument>, at: At) -> Self {
+        Self { argument, at }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TimesinceFilter {
+    pub argument: Option<Argument>,
+    pub at: At,
+}
+
+impl TimesinceFilter {
+    pub fn new(argument: Option<Argument>, at: At) -> Self {
+        Self { argument, at }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TimeuntilFilter {
+    pub argument: Option<Argument>,
+    pub at: At,
+}
+
+impl TimeuntilFilter {
+    pub fn new(argument: Option<Argument>, at: At) -> Self {
+        Self { argument, at }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EscapeFilter;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EscapejsFilter;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EscapeseqFilter {
+    pub at: At,
+}
+
+impl EscapeseqFilter {
+    pub fn new(at: At) -> Self {
+        Self { at }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(into = "ExternalFilterRef", try_from = "ExternalFilterRef")]
 pub struct ExternalFilter {
     pub filter: Arc<Py<PyAny>>,
+    /// The library the filter was loaded from.
+    pub library: String,
+    /// The name the filter is registered under in its library.
+    pub name: String,
     pub argument: Option<Argument>,
 }
 
 impl ExternalFilter {
-    pub fn new(filter: Py<PyAny>, argument: Option<Argument>) -> Self {
+    pub fn new(
+        filter: Py<PyAny>,
+        library: String,
+        name: String,
+        argument: Option<Argument>,
+    ) -> Self {
         Self {
             filter: Arc::new(filter),
+            library,
+            name,
             argument,
         }
     }
@@ -133,11 +280,151 @@ impl PartialEq for ExternalFilter {
         // equality comparison between two `Py` smart pointers.
         //
         // We only use `eq` in tests, so this concession is acceptable here.
-        self.argument.eq(&other.argument) && Arc::ptr_eq(&self.filter, &other.filter)
+        self.argument.eq(&other.argument)
+            && self.library == other.library
+            && self.name == other.name
+            && Arc::ptr_eq(&self.filter, &other.filter)
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FloatformatFilter {
+    pub argument: Option<Argument>,
+    pub at: At,
+}
+
+impl FloatformatFilter {
+    pub fn new(argument: Option<Argument>, at: At) -> Self {
+        Self { argument, at }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilesizeformatFilter {
+    pub at: At,
+}
+
+impl FilesizeformatFilter {
+    pub fn new(at: At) -> Self {
+        Self { at }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FirstFilter {
+    pub at: At,
+}
+
+impl FirstFilter {
+    pub fn new(at: At) -> Self {
+        Self { at }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GetDigitFilter {
+    pub argument: Argument,
+    pub at: At,
+}
+
+impl GetDigitFilter {
+    pub fn new(argument: Argument, at: At) -> Self {
+        Self { argument, at }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApnumberFilter {
+    pub at: At,
+}
+
+impl ApnumberFilter {
+    pub fn new(at: At) -> Self {
+        Self { at }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IntcommaFilter {
+    pub argument: Option<Argument>,
+    pub at: At,
+}
+
+impl IntcommaFilter {
+    pub fn new(argument: Option<Argument>, at: At) -> Self {
+        Self { argument, at }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntwordFilter {
+    pub at: At,
+}
+
+impl IntwordFilter {
+    pub fn new(at: At) -> Self {
+        Self { at }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NaturaldayFilter {
+    pub argument: Option<Argument>,
+    pub at: At,
+}
+
+impl NaturaldayFilter {
+    pub fn new(argument: Option<Argument>, at: At) -> Self {
+        Self { argument, at }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NaturaltimeFilter {
+    pub at: At,
+}
+
+impl NaturaltimeFilter {
+    pub fn new(at: At) -> Self {
+        Self { at }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrdinalFilter {
+    pub at: At,
+}
+
+impl OrdinalFilter {
+    pub fn new(at: At) -> Self {
+        Self { at }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JoinFilter {
+    pub argument: Argument,
+}
+
+impl JoinFilter {
+    pub fn new(argument: Argument) -> Self {
+        Self { argument }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JsonScriptFilter {
+    pub argument: Option<Argument>,
+    pub at: At,
+}
+
+impl JsonScriptFilter {
+    pub fn new(argument: Option<Argument>, at: At) -> Self {
+        Self { argument, at }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LastFilter {
     pub at: (usize, usize),
 }
@@ -148,31 +435,199 @@ impl LastFilter {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinebreaksFilter;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinebreaksbrFilter;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinenumbersFilter;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ForceEscapeFilter;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IriencodeFilter;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Phone2numericFilter;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PprintFilter;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LowerFilter;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LengthFilter;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SafeFilter;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SafeseqFilter {
+    pub at: At,
+}
+
+impl SafeseqFilter {
+    pub fn new(at: At) -> Self {
+        Self { at }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SlugifyFilter;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TitleFilter;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TruncatecharsFilter {
+    pub argument: Argument,
+}
+
+impl TruncatecharsFilter {
+    pub fn new(argument: Argument) -> Self {
+        Self { argument }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TruncatecharsHtmlFilter {
+    pub argument: Argument,
+}
+
+impl TruncatecharsHtmlFilter {
+    pub fn new(argument: Argument) -> Self {
+        Self { argument }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TruncatewordsFilter {
+    pub argument: Argument,
+}
+
+impl TruncatewordsFilter {
+    pub fn new(argument: Argument) -> Self {
+        Self { argument }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TruncatewordsHtmlFilter {
+    pub argument: Argument,
+}
+
+impl TruncatewordsHtmlFilter {
+    pub fn new(argument: Argument) -> Self {
+        Self { argument }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MakeListFilter;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PluralizeFilter {
+    pub argument: Option<Argument>,
+    pub at: At,
+}
+
+impl PluralizeFilter {
+    pub fn new(argument: Option<Argument>, at: At) -> Self {
+        Self { argument, at }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RandomFilter {
+    pub at: At,
+}
+
+impl RandomFilter {
+    pub fn new(at: At) -> Self {
+        Self { at }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SliceFilter {
+    pub argument: Argument,
+}
+
+impl SliceFilter {
+    pub fn new(argument: Argument) -> Self {
+        Self { argument }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StringformatFilter {
+    pub argument: Argument,
+    /// The format parsed from a literal argument, when it can be formatted
+    /// natively.
+    pub format: Option<StringFormat>,
+    pub at: At,
+}
+
+impl StringformatFilter {
+    pub fn new(argument: Argument, format: Option<StringFormat>, at: At) -> Self {
+        Self {
+            argument,
+            format,
+            at,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StriptagsFilter;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnorderedListFilter {
+    pub at: At,
+}
+
+impl UnorderedListFilter {
+    pub fn new(at: At) -> Self {
+        Self { at }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct UpperFilter;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct UrlencodeFilter {
+    pub argument: Option<Argument>,
+}
+
+impl UrlencodeFilter {
+    pub fn new(argument: Option<Argument>) -> Self {
+        Self { argument }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UrlizeFilter;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct UrlizetruncFilter {
+    pub argument: Argument,
+}
+
+impl UrlizetruncFilter {
+    pub fn new(argument: Argument) -> Self {
+        Self { argument }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WordcountFilter;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct WordwrapFilter {
     pub argument: Argument,
 }
@@ -183,7 +638,7 @@ impl WordwrapFilter {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct YesnoFilter {
     pub at: At,
     pub argument: Option<Argument>,
@@ -195,7 +650,7 @@ impl YesnoFilter {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct DivisibleByFilter {
     pub at: At,
     pub argument: Argument,
@@ -16,9 +16,11 @@ macro_rules! todo {
     }};
 }
 
+mod compiled;
 mod error;
 mod filters;
 mod loaders;
+mod optimise;
 mod parse;
 mod path;
 pub mod render;
@@ -1,12 +1,12 @@
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
 
 use cached::macros::cached;
 use encoding_rs::Encoding;
 use pyo3::exceptions::PyUnicodeError;
 use pyo3::prelude::*;
-use pyo3::sync::PyOnceLock;
+use pyo3::sync::{MutexExt, PyOnceLock, RwLockExt};
 use sugar_path::SugarPath;
 
 use crate::template::django_rusty_templates::{Engine, Template};
@@ -48,6 +48,34 @@ fn get_app_template_dir(path: Bound<'_, PyAny>, dirname: &str) -> PyResult<Optio
     Ok(None)
 }
 
+/// The names of the templates in `directory` and its subdirectories.
+fn template_names_in(directory: &Path) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut pending = vec![directory.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&current) else {
+            continue;
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            let Ok(relative) = path.strip_prefix(directory) else {
+                continue;
+            };
+            let parts: Option<Vec<_>> = relative
+                .components()
+                .map(|component| component.as_os_str().to_str())
+                .collect();
+            if let Some(parts) = parts {
+                names.push(parts.join("/"));
+            }
+        }
+    }
+    names
+}
+
 #[cached(
     max_size = 128,  // Cache size
     key = "String",  // Use owned String as key
@@ -112,6 +140,13 @@ impl FileSystemLoader {
         }
         Err(LoaderError { tried })
     }
+
+    fn template_names(&self) -> Vec<String> {
+        self.dirs
+            .iter()
+            .flat_map(|dir| template_names_in(dir))
+            .collect()
+    }
 }
 #[derive(Debug)]
 pub struct AppDirsLoader {
@@ -136,10 +171,24 @@ impl AppDirsLoader {
         let filesystem_loader = FileSystemLoader::from_pathbuf(dirs, self.encoding);
         filesystem_loader.get_template(py, template_name, engine)
     }
+
+    fn template_names(&self, py: Python<'_>) -> PyResult<Vec<String>> {
+        let dirs = get_app_template_dirs(py, "templates")?;
+        Ok(FileSystemLoader::from_pathbuf(dirs, self.encoding).template_names())
+    }
 }
+/// The cached result of looking up one template name.
+#[derive(Debug, Default)]
+struct CacheEntry {
+    result: OnceLock<Result<Template, LoaderError>>,
+    /// Held while the template is first loaded, so other lookups of the same
+    /// name wait for it instead of loading it again.
+    loading: Mutex<()>,
+}
+
 #[derive(Debug)]
 pub struct CachedLoader {
-    cache: HashMap<String, Result<Template, LoaderError>>,
+    cache: RwLock<HashMap<String, Arc<CacheEntry>>>,
     pub loaders: Vec<Loader>,
 }
 
@@ -147,38 +196,73 @@ impl CachedLoader {
     pub fn new(loaders: Vec<Loader>) -> Self {
         Self {
             loaders,
-            cache: HashMap::new(),
+            cache: RwLock::new(HashMap::new()),
         }
     }
 
+    fn entry(&self, py: Python<'_>, template_name: &str) -> Arc<CacheEntry> {
+        let cache = self
+            .cache
+            .read_py_attached(py)
+            .expect("RwLock should not be poisoned");
+        if let Some(entry) = cache.get(template_name) {
+            return entry.clone();
+        }
+        drop(cache);
+        self.cache
+            .write_py_attached(py)
+            .expect("RwLock should not be poisoned")
+            .entry(template_name.to_string())
+            .or_default()
+            .clone()
+    }
+
     fn get_template(
-        &mut self,
+        &self,
         py: Python<'_>,
         template_name: &str,
         engine: Arc<Engine>,
     ) -> Result<PyResult<Template>, LoaderError> {
-        match self.cache.get(template_name) {
-            Some(Ok(template)) => Ok(Ok((*template).clone())),
-            Some(Err(e)) => Err(e.clone()),
-            None => {
-                let mut tried = Vec::new();
-                for loader in &mut self.loaders {
-                    match loader.get_template(py, template_name, engine.clone()) {
-                        Ok(Ok(template)) => {
-                            self.cache
-                                .insert(template_name.to_string(), Ok(template.clone()));
-                            return Ok(Ok(template));
-                        }
-                        Ok(Err(e)) => return Ok(Err(e)),
-                        Err(mut e) => tried.append(&mut e.tried),
-                    }
+        let entry = self.entry(py, template_name);
+        if let Some(result) = entry.result.get() {
+            return result.clone().map(Ok);
+        }
+        let _loading = entry
+            .loading
+            .lock_py_attached(py)
+            .expect("Mutex should not be poisoned");
+        // Another thread may have loaded the template while we waited.
+        if let Some(result) = entry.result.get() {
+            return result.clone().map(Ok);
+        }
+        let mut tried = Vec::new();
+        for loader in &self.loaders {
+            match loader.get_template(py, template_name, engine.clone()) {
+                Ok(Ok(template)) => {
+                    entry
+                        .result
+                        .set(Ok(template.clone()))
+                        .expect("Only the thread holding the loading lock sets the result");
+                    return Ok(Ok(template));
                 }
-                let error = LoaderError { tried };
-                self.cache
-                    .insert(template_name.to_string(), Err(error.clone()));
-                Err(error)
+                Ok(Err(e)) => return Ok(Err(e)),
+                Err(mut e) => tried.append(&mut e.tried),
             }
         }
+        let error = LoaderError { tried };
+        entry
+            .result
+            .set(Err(error.clone()))
+            .expect("Only the thread holding the loading lock sets the result");
+        Err(error)
+    }
+
+    fn template_names(&self, py: Python<'_>) -> PyResult<Vec<String>> {
+        let mut names = Vec::new();
+        for loader in &self.loaders {
+            names.append(&mut loader.template_names(py)?);
+        }
+        Ok(names)
     }
 }
 #[derive(Debug)]
@@ -215,6 +299,10 @@ impl LocMemLoader {
             })
         }
     }
+
+    fn template_names(&self) -> Vec<String> {
+        self.templates.keys().cloned().collect()
+    }
 }
 #[derive(Debug)]
 pub struct ExternalLoader {}
@@ -243,7 +331,7 @@ pub enum Loader {
 
 impl Loader {
     pub fn get_template(
-        &mut self,
+        &self,
         py: Python<'_>,
         template_name: &str,
         engine: Arc<Engine>,
@@ -256,6 +344,17 @@ impl Loader {
             Self::External(loader) => loader.get_template(py, template_name, engine),
         }
     }
+
+    /// The names of the templates this loader can find.
+    pub fn template_names(&self, py: Python<'_>) -> PyResult<Vec<String>> {
+        Ok(match self {
+            Self::FileSystem(loader) => loader.template_names(),
+            Self::AppDirs(loader) => loader.template_names(py)?,
+            Self::Cached(loader) => loader.template_names(py)?,
+            Self::LocMem(loader) => loader.template_names(),
+            Self::External(_) => Vec::new(),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -300,7 +399,51 @@ mod tests {
             expected.push("tests/templates/basic.txt");
             #[cfg(windows)]
             expected.push("tests\\templates\\basic.txt");
-            assert_eq!(template.filename.unwrap(), expected);
+            assert_eq!(template.filename.as_deref().unwrap(), expected);
+        });
+    }
+
+    #[test]
+    fn test_filesystem_loader_template_names() {
+        let loader =
+            FileSystemLoader::new(vec![PathBuf::from("tests/templates")], encoding_rs::UTF_8);
+        let names = loader.template_names();
+
+        assert!(names.contains(&"basic.txt".to_string()));
+        assert!(names.contains(&"nested/adjacent.txt".to_string()));
+        assert!(!names.iter().any(|name| name == "nested"));
+    }
+
+    #[test]
+    fn test_cached_loader_template_names() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let loader = Loader::Cached(CachedLoader::new(vec![
+                Loader::LocMem(LocMemLoader::new(HashMap::from([(
+                    "index.html".to_string(),
+                    "index".to_string(),
+                )]))),
+                Loader::FileSystem(FileSystemLoader::new(
+                    vec![PathBuf::from("tests/templates/nested")],
+                    encoding_rs::UTF_8,
+                )),
+            ]));
+            let mut names = loader.template_names(py).unwrap();
+            names.sort_unstable();
+
+            assert_eq!(
+                names,
+                vec![
+                    "adjacent.txt",
+                    "index.html",
+                    "invalid.txt",
+                    "outside_hierarchy.txt",
+                    "outside_hierarchy2.txt",
+                    "relative.txt",
+                    "relative2.txt",
+                ]
+            );
         });
     }
 
@@ -365,10 +508,11 @@ mod tests {
 
         Python::attach(|py| {
             // Helper to check cache contents
-            let verify_cache = |cache: &HashMap<String, Result<Template, LoaderError>>,
+            let verify_cache = |cache: &RwLock<HashMap<String, Arc<CacheEntry>>>,
                                 key: &str,
                                 expected_path: &Path| {
-                if let Some(Ok(cached_template)) = cache.get(key) {
+                let cache = cache.read().unwrap();
+                if let Some(Ok(cached_template)) = cache.get(key).and_then(|e| e.result.get()) {
                     assert_eq!(cached_template.filename.as_ref().unwrap(), expected_path);
                 } else {
                     panic!("Expected '{key}' to be in cache.");
@@ -382,7 +526,7 @@ mod tests {
                 FileSystemLoader::new(vec![PathBuf::from("tests/templates")], encoding_rs::UTF_8);
 
             // Wrap the FileSystemLoader in a CachedLoader
-            let mut cached_loader = CachedLoader::new(vec![Loader::FileSystem(filesystem_loader)]);
+            let cached_loader = CachedLoader::new(vec![Loader::FileSystem(filesystem_loader)]);
 
             // Load a template via the CachedLoader
             let template = cached_loader
@@ -397,23 +541,26 @@ mod tests {
             expected_path.push("tests/templates/basic.txt");
             #[cfg(windows)]
             expected_path.push("tests\\templates\\basic.txt");
-            assert_eq!(template.filename.unwrap(), expected_path);
+            assert_eq!(template.filename.as_deref().unwrap(), expected_path);
 
             // Verify the cache state after first load
-            assert_eq!(cached_loader.cache.len(), 1);
+            assert_eq!(cached_loader.cache.read().unwrap().len(), 1);
             verify_cache(&cached_loader.cache, "basic.txt", &expected_path);
 
             // Load the same template again via the CachedLoader
-            let template = cached_loader
+            let cached_template = cached_loader
                 .get_template(py, "basic.txt", engine)
                 .expect("Failed to load template")
                 .expect("Template file could not be read");
 
             // Verify the template filename again
-            assert_eq!(template.filename.unwrap(), expected_path);
+            assert_eq!(cached_template.filename.as_deref().unwrap(), expected_path);
+
+            // Verify the parsed template is shared rather than copied
+            assert!(std::ptr::eq(&*template, &*cached_template));
 
             // Verify the cache state remains consistent
-            assert_eq!(cached_loader.cache.len(), 1);
+            assert_eq!(cached_loader.cache.read().unwrap().len(), 1);
             verify_cache(&cached_loader.cache, "basic.txt", &expected_path);
         });
     }
@@ -427,7 +574,7 @@ mod tests {
             let filesystem_loader =
                 FileSystemLoader::new(vec![PathBuf::from("tests/templates")], encoding_rs::UTF_8);
 
-            let mut cached_loader = CachedLoader::new(vec![Loader::FileSystem(filesystem_loader)]);
+            let cached_loader = CachedLoader::new(vec![Loader::FileSystem(filesystem_loader)]);
             let error = cached_loader
                 .get_template(py, "missing.txt", engine.clone())
                 .unwrap_err();
@@ -445,11 +592,17 @@ mod tests {
             };
             assert_eq!(error, expected_err);
 
-            let cache = &cached_loader.cache;
+            let cache = cached_loader.cache.read().unwrap();
             assert_eq!(
-                cache.get("missing.txt").unwrap().as_ref().unwrap_err(),
+                cache["missing.txt"]
+                    .result
+                    .get()
+                    .unwrap()
+                    .as_ref()
+                    .unwrap_err(),
                 &expected_err
             );
+            drop(cache);
 
             let error = cached_loader
                 .get_template(py, "missing.txt", engine)
@@ -458,6 +611,38 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_cached_loader_threads() {
+        Python::initialize();
+
+        let engine = Arc::new(Engine::empty());
+        let filesystem_loader =
+            FileSystemLoader::new(vec![PathBuf::from("tests/templates")], encoding_rs::UTF_8);
+        let cached_loader = CachedLoader::new(vec![Loader::FileSystem(filesystem_loader)]);
+
+        let templates: Vec<Template> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| {
+                    scope.spawn(|| {
+                        Python::attach(|py| {
+                            cached_loader
+                                .get_template(py, "basic.txt", engine.clone())
+                                .unwrap()
+                                .unwrap()
+                        })
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        // Every thread gets the same parsed template
+        for template in &templates {
+            assert!(std::ptr::eq(&**template, &*templates[0]));
+        }
+        assert_eq!(cached_loader.cache.read().unwrap().len(), 1);
+    }
+
     #[test]
     fn test_cached_loader_invalid_encoding() {
         Python::initialize();
@@ -467,7 +652,7 @@ mod tests {
             let filesystem_loader =
                 FileSystemLoader::new(vec![PathBuf::from("tests/templates")], encoding_rs::UTF_8);
 
-            let mut cached_loader = CachedLoader::new(vec![Loader::FileSystem(filesystem_loader)]);
+            let cached_loader = CachedLoader::new(vec![Loader::FileSystem(filesystem_loader)]);
             let error = cached_loader
                 .get_template(py, "invalid.txt", engine)
                 .unwrap()
@@ -504,7 +689,10 @@ mod tests {
                 .unwrap()
                 .unwrap();
             assert_eq!(template.template, "index".to_string());
-            assert_eq!(template.filename.unwrap(), PathBuf::from("index.html"));
+            assert_eq!(
+                template.filename.as_deref().unwrap(),
+                PathBuf::from("index.html")
+            );
         });
     }
 
@@ -551,7 +739,7 @@ mod tests {
             expected.push("tests/templates/basic.txt");
             #[cfg(windows)]
             expected.push("tests\\templates\\basic.txt");
-            assert_eq!(template.filename.unwrap(), expected);
+            assert_eq!(template.filename.as_deref().unwrap(), expected);
         });
     }
 
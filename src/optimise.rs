@@ -0,0 +1,350 @@
+//! An optimisation pass over the nodes of a parsed template.
+//!
+//! This merges adjacent text, renders filters applied to literals when their
+//! output can't depend on the context, and removes nodes which never render
+//! anything. Nodes which could raise an error are left unchanged, so their
+//! error spans still point into the template source.
+
+use std::collections::HashMap;
+
+use num_traits::Zero;
+use pyo3::prelude::*;
+
+use dtl_lexer::types::TemplateString;
+
+use crate::filters::FilterType;
+use crate::parse::{Filter, IfCondition, Tag, TagElement, TokenTree};
+use crate::render::types::{Content, Context};
+use crate::render::{Resolve, ResolveFailures};
+use crate::types::{Argument, ArgumentType, Constant};
+
+pub fn optimise(
+    py: Python<'_>,
+    template: TemplateString<'_>,
+    nodes: Vec<TokenTree>,
+    detach: bool,
+) -> Vec<TokenTree> {
+    let nodes = fold(py, template, nodes);
+    // Merging text doesn't need the interpreter, so it can run detached.
+    match detach {
+        true => py.detach(|| merge(template, nodes)),
+        false => merge(template, nodes),
+    }
+}
+
+/// Remove nodes which never render anything and render constant filters.
+fn fold(py: Python<'_>, template: TemplateString<'_>, nodes: Vec<TokenTree>) -> Vec<TokenTree> {
+    let mut folded = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        match node {
+            TokenTree::Tag(Tag::Comment(_) | Tag::Load) => {}
+            TokenTree::Tag(Tag::If {
+                condition,
+                truthy,
+                falsey,
+            }) => match constant_condition(&condition, template) {
+                Some(true) => folded.extend(fold(py, template, truthy)),
+                Some(false) => folded.extend(fold(py, template, falsey.unwrap_or_default())),
+                None => folded.push(TokenTree::Tag(Tag::If {
+                    condition,
+                    truthy: fold(py, template, truthy),
+                    falsey: falsey.map(|falsey| fold(py, template, falsey)),
+                })),
+            },
+            TokenTree::Tag(Tag::For(mut for_tag)) => {
+                for_tag.body = fold(py, template, for_tag.body);
+                for_tag.empty = for_tag.empty.map(|empty| fold(py, template, empty));
+                folded.push(TokenTree::Tag(Tag::For(for_tag)));
+            }
+            TokenTree::Tag(Tag::Autoescape { enabled, nodes }) => {
+                folded.push(TokenTree::Tag(Tag::Autoescape {
+                    enabled,
+                    nodes: fold(py, template, nodes),
+                }));
+            }
+            TokenTree::Tag(Tag::SimpleBlockTag(mut simple_tag)) => {
+                simple_tag.nodes = fold(py, template, simple_tag.nodes);
+                folded.push(TokenTree::Tag(Tag::SimpleBlockTag(simple_tag)));
+            }
+            TokenTree::Filter(filter) => match fold_filter(py, template, &filter) {
+                Some(constant) => folded.push(TokenTree::Constant(constant)),
+                None => folded.push(TokenTree::Filter(filter)),
+            },
+            node => folded.push(node),
+        }
+    }
+    folded
+}
+
+/// Merge adjacent nodes whose output is constant.
+fn merge(template: TemplateString<'_>, nodes: Vec<TokenTree>) -> Vec<TokenTree> {
+    let mut merged = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        let node = match node {
+            TokenTree::Tag(Tag::If {
+                condition,
+                truthy,
+                falsey,
+            }) => TokenTree::Tag(Tag::If {
+                condition,
+                truthy: merge(template, truthy),
+                falsey: falsey.map(|falsey| merge(template, falsey)),
+            }),
+            TokenTree::Tag(Tag::For(mut for_tag)) => {
+                for_tag.body = merge(template, for_tag.body);
+                for_tag.empty = for_tag.empty.map(|empty| merge(template, empty));
+                TokenTree::Tag(Tag::For(for_tag))
+            }
+            TokenTree::Tag(Tag::Autoescape { enabled, nodes }) => TokenTree::Tag(Tag::Autoescape {
+                enabled,
+                nodes: merge(template, nodes),
+            }),
+            TokenTree::Tag(Tag::SimpleBlockTag(mut simple_tag)) => {
+                simple_tag.nodes = merge(template, simple_tag.nodes);
+                TokenTree::Tag(Tag::SimpleBlockTag(simple_tag))
+            }
+            node => node,
+        };
+        push(&mut merged, node, template);
+    }
+    merged
+}
+
+/// Push `node`, merging it with the previous node if both are constant.
+fn push(nodes: &mut Vec<TokenTree>, node: TokenTree, template: TemplateString<'_>) {
+    if let Some(last) = nodes.last_mut()
+        && let Some(next) = constant(&node, template)
+        && let Some(mut previous) = constant(last, template)
+    {
+        previous.push(&next);
+        *last = TokenTree::Constant(previous);
+    } else {
+        nodes.push(node);
+    }
+}
+
+/// The output of `node` if it is the same whatever the context.
+fn constant(node: &TokenTree, template: TemplateString<'_>) -> Option<Constant> {
+    let text = match node {
+        TokenTree::Constant(constant) => return Some(constant.clone()),
+        TokenTree::Text(text) => template.content(text.at).to_string(),
+        TokenTree::Int(n) => n.to_string(),
+        TokenTree::Float(f) => f.to_string(),
+        TokenTree::Tag(Tag::TemplateTag(template_tag)) => template_tag.output().to_string(),
+        _ => return None,
+    };
+    Some(Constant::new(text))
+}
+
+/// Evaluate an `{% if %}` condition made only of literals, like `{% if False %}`.
+fn constant_condition(condition: &IfCondition, template: TemplateString<'_>) -> Option<bool> {
+    match condition {
+        IfCondition::Variable(TagElement::Int(n)) => Some(!n.is_zero()),
+        IfCondition::Variable(TagElement::Float(f)) => Some(!f.is_zero()),
+        IfCondition::Variable(TagElement::Text(text)) => {
+            Some(!template.content(text.at).is_empty())
+        }
+        IfCondition::Variable(TagElement::Variable(variable)) => {
+            match template.content(variable.at) {
+                "True" => Some(true),
+                "False" | "None" => Some(false),
+                _ => None,
+            }
+        }
+        IfCondition::Not(inner) => constant_condition(inner, template).map(|inner| !inner),
+        IfCondition::And(inner) => {
+            Some(constant_condition(&inner.0, template)? && constant_condition(&inner.1, template)?)
+        }
+        IfCondition::Or(inner) => {
+            Some(constant_condition(&inner.0, template)? || constant_condition(&inner.1, template)?)
+        }
+        _ => None,
+    }
+}
+
+fn is_literal(argument: &Argument) -> bool {
+    matches!(
+        argument.argument_type,
+        ArgumentType::Text(_) | ArgumentType::Int(_) | ArgumentType::Float(_)
+    )
+}
+
+/// Whether `filter` and its argument only depend on a literal input.
+fn is_pure(filter: &Filter) -> bool {
+    let left = match &filter.left {
+        TagElement::Text(_) | TagElement::Int(_) | TagElement::Float(_) => true,
+        TagElement::Filter(left) => is_pure(left),
+        _ => false,
+    };
+    left && match &filter.filter {
+        FilterType::AddSlashes(_)
+        | FilterType::Capfirst(_)
+        | FilterType::Escape(_)
+        | FilterType::Escapejs(_)
+        | FilterType::ForceEscape(_)
+        | FilterType::Iriencode(_)
+        | FilterType::Length(_)
+        | FilterType::Linebreaksbr(_)
+        | FilterType::Lower(_)
+        | FilterType::Safe(_)
+        | FilterType::Slugify(_)
+        | FilterType::Striptags(_)
+        | FilterType::Title(_)
+        | FilterType::Upper(_)
+        | FilterType::Wordcount(_) => true,
+        FilterType::Add(filter) => is_literal(&filter.argument),
+        FilterType::Center(filter) => is_literal(&filter.argument),
+        FilterType::Cut(filter) => is_literal(&filter.argument),
+        FilterType::Ljust(filter) => is_literal(&filter.argument),
+        FilterType::Rjust(filter) => is_literal(&filter.argument),
+        FilterType::Urlencode(filter) => filter.argument.as_ref().is_none_or(is_literal),
+        _ => false,
+    }
+}
+
+/// Render `filter` now if its output can't depend on the context.
+///
+/// Any error is left to be raised when the template is rendered.
+fn fold_filter(py: Python<'_>, template: TemplateString<'_>, filter: &Filter) -> Option<Constant> {
+    if !is_pure(filter) {
+        return None;
+    }
+    let text = render_constant(py, template, filter, true)?;
+    let unescaped = render_constant(py, template, filter, false)?;
+    Some(Constant {
+        unescaped: (unescaped != text).then_some(unescaped),
+        text,
+    })
+}
+
+fn render_constant(
+    py: Python<'_>,
+    template: TemplateString<'_>,
+    filter: &Filter,
+    autoescape: bool,
+) -> Option<String> {
+    let mut context = Context::new(HashMap::new(), None, autoescape);
+    let content = filter
+        .resolve(py, template, &mut context, ResolveFailures::Raise)
+        .ok()??;
+    match content {
+        // Rendering Python objects can depend on the active language.
+        Content::Py(_) => None,
+        content => Some(content.render(&context).ok()?.into_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::parse::Parser;
+    use crate::render::Render;
+    use crate::template::django_rusty_templates::Engine;
+
+    fn parse_optimised(py: Python<'_>, template: TemplateString<'_>) -> Vec<TokenTree> {
+        let mut parser = Parser::new(py, template, Engine::empty().into(), None);
+        let nodes = parser.parse().unwrap();
+        optimise(py, template, nodes, false)
+    }
+
+    fn render(
+        py: Python<'_>,
+        template: TemplateString<'_>,
+        nodes: &Vec<TokenTree>,
+        autoescape: bool,
+    ) -> String {
+        let mut context = Context::new(HashMap::new(), None, autoescape);
+        nodes.render_to_string(py, template, &mut context).unwrap()
+    }
+
+    #[test]
+    fn test_merge_text() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let template = TemplateString(
+                "a{# comment #}b{% comment %}c{% endcomment %}{{ 1 }}{% templatetag openblock %}",
+            );
+            let nodes = parse_optimised(py, template);
+            assert_eq!(
+                nodes,
+                vec![TokenTree::Constant(Constant::new("ab1{%".to_string()))]
+            );
+        });
+    }
+
+    #[test]
+    fn test_single_text_unchanged() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let template = TemplateString("a{{ b }}c");
+            let nodes = parse_optimised(py, template);
+            assert!(matches!(nodes[0], TokenTree::Text(_)));
+            assert!(matches!(nodes[1], TokenTree::Variable(_)));
+            assert!(matches!(nodes[2], TokenTree::Text(_)));
+        });
+    }
+
+    #[test]
+    fn test_fold_filter() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let template =
+                TemplateString(r#"<{{ 123|cut:"2"|rjust:"4"|length }}{{ 1.5|ljust:"4" }}>"#);
+            let nodes = parse_optimised(py, template);
+            assert_eq!(
+                nodes,
+                vec![TokenTree::Constant(Constant::new("<41.5 >".to_string()))]
+            );
+            assert_eq!(render(py, template, &nodes, true), "<41.5 >");
+        });
+    }
+
+    #[test]
+    fn test_fold_filter_skips_variables() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let template = TemplateString("{{ 1|add:b }}{{ a|upper }}{{ 1|date }}");
+            let nodes = parse_optimised(py, template);
+            assert_eq!(nodes.len(), 3);
+            assert!(
+                nodes
+                    .iter()
+                    .all(|node| matches!(node, TokenTree::Filter(_)))
+            );
+        });
+    }
+
+    #[test]
+    fn test_fold_filter_keeps_errors() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let template = TemplateString(r#"{{ 1|center:"b" }}"#);
+            let nodes = parse_optimised(py, template);
+            assert!(matches!(nodes[0], TokenTree::Filter(_)));
+        });
+    }
+
+    #[test]
+    fn test_prune_if() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let template = TemplateString(
+                "a{% if False %}b{% elif not 0 and 'x' %}c{% else %}d{% endif %}e{% if x %}f{% endif %}",
+            );
+            let nodes = parse_optimised(py, template);
+            assert_eq!(nodes.len(), 2);
+            assert_eq!(
+                nodes[0],
+                TokenTree::Constant(Constant::new("ace".to_string()))
+            );
+            assert!(matches!(nodes[1], TokenTree::Tag(Tag::If { .. })));
+        });
+    }
+}
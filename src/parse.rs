@@ -1,6 +1,7 @@
 use dtl_lexer::DelimitedToken;
 use num_traits::Zero;
 use std::borrow::Cow;
+use std::cell::Cell;
 use std::collections::{HashMap, HashSet};
 use std::iter::Peekable;
 use std::sync::Arc;
@@ -10,34 +11,76 @@ use miette::{Diagnostic, SourceSpan};
 use num_bigint::BigInt;
 use pyo3::intern;
 use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::filters::AddFilter;
 use crate::filters::AddSlashesFilter;
+use crate::filters::ApnumberFilter;
 use crate::filters::CapfirstFilter;
 use crate::filters::CenterFilter;
 use crate::filters::CutFilter;
 use crate::filters::DateFilter;
 use crate::filters::DefaultFilter;
 use crate::filters::DefaultIfNoneFilter;
+use crate::filters::DictsortFilter;
+use crate::filters::DictsortReversedFilter;
 use crate::filters::DivisibleByFilter;
 use crate::filters::EscapeFilter;
 use crate::filters::EscapejsFilter;
+use crate::filters::EscapeseqFilter;
 use crate::filters::ExternalFilter;
+use crate::filters::FilesizeformatFilter;
 use crate::filters::FilterType;
+use crate::filters::FirstFilter;
+use crate::filters::FloatformatFilter;
 use crate::filters::ForceEscapeFilter;
+use crate::filters::GetDigitFilter;
+use crate::filters::IntcommaFilter;
+use crate::filters::IntwordFilter;
+use crate::filters::IriencodeFilter;
+use crate::filters::JoinFilter;
+use crate::filters::JsonScriptFilter;
 use crate::filters::LastFilter;
 use crate::filters::LengthFilter;
+use crate::filters::LinebreaksFilter;
+use crate::filters::LinebreaksbrFilter;
+use crate::filters::LinenumbersFilter;
+use crate::filters::LjustFilter;
 use crate::filters::LowerFilter;
+use crate::filters::MakeListFilter;
+use crate::filters::NaturaldayFilter;
+use crate::filters::NaturaltimeFilter;
+use crate::filters::OrdinalFilter;
+use crate::filters::Phone2numericFilter;
+use crate::filters::PluralizeFilter;
+use crate::filters::PprintFilter;
+use crate::filters::RandomFilter;
+use crate::filters::RjustFilter;
 use crate::filters::SafeFilter;
+use crate::filters::SafeseqFilter;
+use crate::filters::SliceFilter;
 use crate::filters::SlugifyFilter;
+use crate::filters::StringformatFilter;
+use crate::filters::StriptagsFilter;
+use crate::filters::TimeFilter;
+use crate::filters::TimesinceFilter;
+use crate::filters::TimeuntilFilter;
 use crate::filters::TitleFilter;
+use crate::filters::TruncatecharsFilter;
+use crate::filters::TruncatecharsHtmlFilter;
+use crate::filters::TruncatewordsFilter;
+use crate::filters::TruncatewordsHtmlFilter;
+use crate::filters::UnorderedListFilter;
 use crate::filters::UpperFilter;
+use crate::filters::UrlencodeFilter;
+use crate::filters::UrlizeFilter;
+use crate::filters::UrlizetruncFilter;
 use crate::filters::WordcountFilter;
 use crate::filters::WordwrapFilter;
 use crate::filters::YesnoFilter;
 use dtl_lexer::common::{LexerError, get_all_at, text_content_at, translated_text_content_at};
-use dtl_lexer::core::{Lexer, TokenType};
+use dtl_lexer::core::{Lexer, Token, TokenType};
 use dtl_lexer::tag::autoescape::{AutoescapeEnabled, AutoescapeError, lex_autoescape_argument};
 use dtl_lexer::tag::common::{TagElementToken, TagElementTokenType};
 use dtl_lexer::tag::forloop::{ForLexer, ForLexerError, ForLexerInError, ForTokenType};
@@ -62,31 +105,34 @@ use dtl_lexer::variable::{
 };
 use dtl_lexer::{START_TAG_LEN, TemplateContent};
 
+use crate::compiled::{IncludeRef, SimpleBlockTagRef, SimpleTagRef};
 use crate::path::{RelativePathError, construct_relative_path};
+use crate::render::stringformat::StringFormat;
 use crate::template::django_rusty_templates::Engine;
 use crate::types::Argument;
 use crate::types::ArgumentType;
+use crate::types::Constant;
 use crate::types::ForVariable;
 use crate::types::ForVariableName;
 
 use crate::types::Text;
 use crate::types::TranslatedText;
-use dtl_lexer::types::Variable;
+use crate::types::Variable;
 
 trait Parse<R> {
     fn parse(&self, parser: &Parser) -> Result<R, ParseError>;
 }
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Lorem {
     pub count: TagElement,
     pub method: LoremMethod,
     pub common: bool,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Comment;
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct CsrfToken;
 
 impl Parse<Argument> for ArgumentToken {
@@ -119,7 +165,7 @@ impl Parse<Argument> for ArgumentToken {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum TagElement {
     Int(BigInt),
     Float(f64),
@@ -137,7 +183,41 @@ fn unexpected_argument(filter: &'static str, right: Argument) -> ParseError {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+/// The filters of `django.contrib.humanize`, which are implemented natively.
+const HUMANIZE_FILTERS: [&str; 6] = [
+    "apnumber",
+    "intcomma",
+    "intword",
+    "naturalday",
+    "naturaltime",
+    "ordinal",
+];
+
+fn humanize_filter(name: &str, at: At, right: Option<Argument>) -> Result<FilterType, ParseError> {
+    Ok(match name {
+        "apnumber" => match right {
+            Some(right) => return Err(unexpected_argument("apnumber", right)),
+            None => FilterType::Apnumber(ApnumberFilter::new(at)),
+        },
+        "intcomma" => FilterType::Intcomma(IntcommaFilter::new(right, at)),
+        "intword" => match right {
+            Some(right) => return Err(unexpected_argument("intword", right)),
+            None => FilterType::Intword(IntwordFilter::new(at)),
+        },
+        "naturalday" => FilterType::Naturalday(NaturaldayFilter::new(right, at)),
+        "naturaltime" => match right {
+            Some(right) => return Err(unexpected_argument("naturaltime", right)),
+            None => FilterType::Naturaltime(NaturaltimeFilter::new(at)),
+        },
+        "ordinal" => match right {
+            Some(right) => return Err(unexpected_argument("ordinal", right)),
+            None => FilterType::Ordinal(OrdinalFilter::new(at)),
+        },
+        _ => unreachable!("Only humanize filters are loaded natively"),
+    })
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Filter {
     pub at: At,
     pub all_at: At,
@@ -182,6 +262,14 @@ impl Filter {
                 Some(right) => FilterType::DefaultIfNone(DefaultIfNoneFilter::new(right)),
                 None => return Err(ParseError::MissingArgument { at: at.into() }),
             },
+            "dictsort" => match right {
+                Some(right) => FilterType::Dictsort(DictsortFilter::new(right)),
+                None => return Err(ParseError::MissingArgument { at: at.into() }),
+            },
+            "dictsortreversed" => match right {
+                Some(right) => FilterType::DictsortReversed(DictsortReversedFilter::new(right)),
+                None => return Err(ParseError::MissingArgument { at: at.into() }),
+            },
             "divisibleby" => match right {
                 Some(right) => {
                     if let ArgumentType::Int(ref n) = right.argument_type
@@ -204,14 +292,56 @@ impl Filter {
                 Some(right) => return Err(unexpected_argument("escapejs", right)),
                 None => FilterType::Escapejs(EscapejsFilter),
             },
+            "escapeseq" => match right {
+                Some(right) => return Err(unexpected_argument("escapeseq", right)),
+                None => FilterType::Escapeseq(EscapeseqFilter::new(at)),
+            },
+            "filesizeformat" => match right {
+                Some(right) => return Err(unexpected_argument("filesizeformat", right)),
+                None => FilterType::Filesizeformat(FilesizeformatFilter::new(at)),
+            },
+            "first" => match right {
+                Some(right) => return Err(unexpected_argument("first", right)),
+                None => FilterType::First(FirstFilter::new(at)),
+            },
+            "floatformat" => FilterType::Floatformat(FloatformatFilter::new(right, at)),
+            "get_digit" => match right {
+                Some(right) => FilterType::GetDigit(GetDigitFilter::new(right, at)),
+                None => return Err(ParseError::MissingArgument { at: at.into() }),
+            },
             "force_escape" => match right {
                 Some(right) => return Err(unexpected_argument("force_escape", right)),
                 None => FilterType::ForceEscape(ForceEscapeFilter),
             },
+            "iriencode" => match right {
+                Some(right) => return Err(unexpected_argument("iriencode", right)),
+                None => FilterType::Iriencode(IriencodeFilter),
+            },
+            "join" => match right {
+                Some(right) => FilterType::Join(JoinFilter::new(right)),
+                None => return Err(ParseError::MissingArgument { at: at.into() }),
+            },
+            "json_script" => FilterType::JsonScript(JsonScriptFilter::new(right, at)),
             "last" => match right {
                 Some(right) => return Err(unexpected_argument("last", right)),
                 None => FilterType::Last(LastFilter::new(at)),
             },
+            "linebreaks" => match right {
+                Some(right) => return Err(unexpected_argument("linebreaks", right)),
+                None => FilterType::Linebreaks(LinebreaksFilter),
+            },
+            "linebreaksbr" => match right {
+                Some(right) => return Err(unexpected_argument("linebreaksbr", right)),
+                None => FilterType::Linebreaksbr(LinebreaksbrFilter),
+            },
+            "linenumbers" => match right {
+                Some(right) => return Err(unexpected_argument("linenumbers", right)),
+                None => FilterType::Linenumbers(LinenumbersFilter),
+            },
+            "ljust" => match right {
+                Some(right) => FilterType::Ljust(LjustFilter::new(right)),
+                None => return Err(ParseError::MissingArgument { at: at.into() }),
+            },
             "lower" => match right {
                 Some(right) => return Err(unexpected_argument("lower", right)),
                 None => FilterType::Lower(LowerFilter),
@@ -220,22 +350,99 @@ impl Filter {
                 Some(right) => return Err(unexpected_argument("length", right)),
                 None => FilterType::Length(LengthFilter),
             },
+            "make_list" => match right {
+                Some(right) => return Err(unexpected_argument("make_list", right)),
+                None => FilterType::MakeList(MakeListFilter),
+            },
+            "phone2numeric" => match right {
+                Some(right) => return Err(unexpected_argument("phone2numeric", right)),
+                None => FilterType::Phone2numeric(Phone2numericFilter),
+            },
+            "pluralize" => FilterType::Pluralize(PluralizeFilter::new(right, at)),
+            "pprint" => match right {
+                Some(right) => return Err(unexpected_argument("pprint", right)),
+                None => FilterType::Pprint(PprintFilter),
+            },
+            "random" => match right {
+                Some(right) => return Err(unexpected_argument("random", right)),
+                None => FilterType::Random(RandomFilter::new(at)),
+            },
+            "rjust" => match right {
+                Some(right) => FilterType::Rjust(RjustFilter::new(right)),
+                None => return Err(ParseError::MissingArgument { at: at.into() }),
+            },
             "safe" => match right {
                 Some(right) => return Err(unexpected_argument("safe", right)),
                 None => FilterType::Safe(SafeFilter),
             },
+            "safeseq" => match right {
+                Some(right) => return Err(unexpected_argument("safeseq", right)),
+                None => FilterType::Safeseq(SafeseqFilter::new(at)),
+            },
+            "slice" => match right {
+                Some(right) => FilterType::Slice(SliceFilter::new(right)),
+                None => return Err(ParseError::MissingArgument { at: at.into() }),
+            },
             "slugify" => match right {
                 Some(right) => return Err(unexpected_argument("slugify", right)),
                 None => FilterType::Slugify(SlugifyFilter),
             },
+            "stringformat" => match right {
+                Some(right) => {
+                    let format = match right.argument_type {
+                        ArgumentType::Text(text) => {
+                            StringFormat::parse(parser.template.content(text.at))
+                        }
+                        _ => None,
+                    };
+                    FilterType::Stringformat(StringformatFilter::new(right, format, at))
+                }
+                None => return Err(ParseError::MissingArgument { at: at.into() }),
+            },
+            "striptags" => match right {
+                Some(right) => return Err(unexpected_argument("striptags", right)),
+                None => FilterType::Striptags(StriptagsFilter),
+            },
+            "time" => FilterType::Time(TimeFilter::new(right, at)),
+            "timesince" => FilterType::Timesince(TimesinceFilter::new(right, at)),
+            "timeuntil" => FilterType::Timeuntil(TimeuntilFilter::new(right, at)),
             "title" => match right {
                 Some(right) => return Err(unexpected_argument("title", right)),
                 None => FilterType::Title(TitleFilter),
             },
+            "truncatechars" => match right {
+                Some(right) => FilterType::Truncatechars(TruncatecharsFilter::new(right)),
+                None => return Err(ParseError::MissingArgument { at: at.into() }),
+            },
+            "truncatechars_html" => match right {
+                Some(right) => FilterType::TruncatecharsHtml(TruncatecharsHtmlFilter::new(right)),
+                None => return Err(ParseError::MissingArgument { at: at.into() }),
+            },
+            "truncatewords" => match right {
+                Some(right) => FilterType::Truncatewords(TruncatewordsFilter::new(right)),
+                None => return Err(ParseError::MissingArgument { at: at.into() }),
+            },
+            "truncatewords_html" => match right {
+                Some(right) => FilterType::TruncatewordsHtml(TruncatewordsHtmlFilter::new(right)),
+                None => return Err(ParseError::MissingArgument { at: at.into() }),
+            },
+            "unordered_list" => match right {
+                Some(right) => return Err(unexpected_argument("unordered_list", right)),
+                None => FilterType::UnorderedList(UnorderedListFilter::new(at)),
+            },
             "upper" => match right {
                 Some(right) => return Err(unexpected_argument("upper", right)),
                 None => FilterType::Upper(UpperFilter),
             },
+            "urlencode" => FilterType::Urlencode(UrlencodeFilter::new(right)),
+            "urlize" => match right {
+                Some(right) => return Err(unexpected_argument("urlize", right)),
+                None => FilterType::Urlize(UrlizeFilter),
+            },
+            "urlizetrunc" => match right {
+                Some(right) => FilterType::Urlizetrunc(UrlizetruncFilter::new(right)),
+                None => return Err(ParseError::MissingArgument { at: at.into() }),
+            },
             "wordcount" => match right {
                 Some(right) => return Err(unexpected_argument("wordcount", right)),
                 None => FilterType::Wordcount(WordcountFilter),
@@ -245,17 +452,22 @@ impl Filter {
                 None => return Err(ParseError::MissingArgument { at: at.into() }),
             },
             "yesno" => FilterType::Yesno(YesnoFilter::new(at, right)),
+            humanize if parser.humanize_filters.contains(humanize) => {
+                humanize_filter(humanize, at, right)?
+            }
             external => {
-                let external = match parser.external_filters.get(external) {
-                    Some(external) => external.clone().unbind(),
-                    None => {
-                        return Err(ParseError::InvalidFilter {
-                            at: at.into(),
-                            filter: external.to_string(),
-                        });
-                    }
+                let Some((library, filter)) = parser.external_filters.get(external) else {
+                    return Err(ParseError::InvalidFilter {
+                        at: at.into(),
+                        filter: external.to_string(),
+                    });
                 };
-                FilterType::External(ExternalFilter::new(external, right))
+                FilterType::External(ExternalFilter::new(
+                    filter.clone().unbind(),
+                    library.clone(),
+                    external.to_string(),
+                    right,
+                ))
             }
         };
         Ok(Self {
@@ -373,7 +585,7 @@ fn parse_include_template_token(
     })
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Url {
     pub at: At,
     pub view_name: TagElement,
@@ -382,7 +594,7 @@ pub struct Url {
     pub asvar: Option<String>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum IfCondition {
     Variable(TagElement),
     And(Box<(Self, Self)>),
@@ -525,13 +737,13 @@ impl IfConditionOperatorMethods for IfConditionOperator {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ForIterable {
     pub iterable: TagElement,
     pub at: At,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ForNames {
     pub names: Vec<String>,
     pub at: At,
@@ -611,29 +823,33 @@ fn parse_for_loop(
     ))
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct For {
     pub iterable: ForIterable,
     pub variables: ForNames,
     pub reversed: bool,
+    /// Whether the body uses `forloop.revcounter`, `forloop.last` or similar, so
+    /// the length of the loop must be known before the first iteration.
+    pub uses_length: bool,
     pub body: Vec<TokenTree>,
     pub empty: Option<Vec<TokenTree>>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RelativePath {
     pub at: At,
     pub path: String,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum IncludeTemplateName {
     Text(Text),
     Variable(TagElement),
     Relative(RelativePath),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(into = "IncludeRef", try_from = "IncludeRef")]
 pub struct Include {
     pub template_name: IncludeTemplateName,
     pub origin: Option<String>,
@@ -656,9 +872,14 @@ impl PartialEq for Include {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(into = "SimpleTagRef", try_from = "SimpleTagRef")]
 pub struct SimpleTag {
     pub func: Arc<Py<PyAny>>,
+    /// The library the tag was loaded from.
+    pub library: String,
+    /// The name the tag is registered under in its library.
+    pub name: String,
     pub at: At,
     pub takes_context: bool,
     pub args: Vec<TagElement>,
@@ -673,6 +894,8 @@ impl PartialEq for SimpleTag {
         //
         // We only use `eq` in tests, so this concession is acceptable here.
         self.at == other.at
+            && self.library == other.library
+            && self.name == other.name
             && self.takes_context == other.takes_context
             && self.args == other.args
             && self.kwargs == other.kwargs
@@ -681,9 +904,14 @@ impl PartialEq for SimpleTag {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(into = "SimpleBlockTagRef", try_from = "SimpleBlockTagRef")]
 pub struct SimpleBlockTag {
     pub func: Arc<Py<PyAny>>,
+    /// The library the tag was loaded from.
+    pub library: String,
+    /// The name the tag is registered under in its library.
+    pub name: String,
     pub nodes: Vec<TokenTree>,
     pub at: At,
     pub takes_context: bool,
@@ -699,6 +927,8 @@ impl PartialEq for SimpleBlockTag {
         //
         // We only use `eq` in tests, so this concession is acceptable here.
         self.at == other.at
+            && self.library == other.library
+            && self.name == other.name
             && self.takes_context == other.takes_context
             && self.args == other.args
             && self.kwargs == other.kwargs
@@ -708,19 +938,19 @@ impl PartialEq for SimpleBlockTag {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Now {
     pub format: String,
     pub asvar: Option<At>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct FirstOf {
     pub vars: Vec<TagElement>,
     pub asvar: Option<String>,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Tag {
     Autoescape {
         enabled: AutoescapeEnabled,
@@ -786,10 +1016,11 @@ impl EndTag {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum TokenTree {
     Text(Text),
     TranslatedText(Text),
+    Constant(Constant),
     Int(BigInt),
     Float(f64),
     Tag(Tag),
@@ -1166,6 +1397,8 @@ impl LoadLibrary for LoadToken {
 #[derive(Debug, Clone)]
 struct SimpleTagContext<'py> {
     func: Bound<'py, PyAny>,
+    library: String,
+    name: String,
     function_name: String,
     takes_context: bool,
     params: Vec<String>,
@@ -1189,12 +1422,15 @@ enum TagContext<'py> {
 pub struct Parser<'t, 'py> {
     py: Python<'py>,
     template: TemplateString<'t>,
-    lexer: Lexer<'t>,
+    lexer: std::vec::IntoIter<Token>,
     engine: Arc<Engine>,
     origin: Option<&'t str>,
     external_tags: HashMap<String, TagContext<'py>>,
-    external_filters: HashMap<String, Bound<'py, PyAny>>,
-    forloop_depth: usize,
+    /// The loaded filters by name, with the library each came from.
+    external_filters: HashMap<String, (String, Bound<'py, PyAny>)>,
+    humanize_filters: HashSet<&'static str>,
+    /// Whether each enclosing `{% for %}` loop uses its length, innermost last.
+    forloops: Vec<Cell<bool>>,
 }
 
 impl<'t, 'py> Parser<'t, 'py> {
@@ -1204,15 +1440,21 @@ impl<'t, 'py> Parser<'t, 'py> {
         engine: Arc<Engine>,
         origin: Option<&'t str>,
     ) -> Self {
+        // Lexing doesn't need the interpreter, so it can run detached.
+        let tokens: Vec<Token> = match engine.detach {
+            true => py.detach(|| Lexer::new(template).collect()),
+            false => Lexer::new(template).collect(),
+        };
         Self {
             py,
             template,
-            lexer: Lexer::new(template),
+            lexer: tokens.into_iter(),
             engine,
             origin,
             external_tags: HashMap::new(),
             external_filters: HashMap::new(),
-            forloop_depth: 0,
+            humanize_filters: HashSet::new(),
+            forloops: Vec::new(),
         }
     }
 
@@ -1222,15 +1464,20 @@ impl<'t, 'py> Parser<'t, 'py> {
         template: TemplateString<'t>,
         external_filters: HashMap<String, Bound<'py, PyAny>>,
     ) -> Self {
+        let external_filters = external_filters
+            .into_iter()
+            .map(|(name, filter)| (name, (String::new(), filter)))
+            .collect();
         Self {
             py,
             template,
-            lexer: Lexer::new(template),
+            lexer: Lexer::new(template).collect::<Vec<_>>().into_iter(),
             engine: Engine::empty().into(),
             origin: None,
             external_tags: HashMap::new(),
             external_filters,
-            forloop_depth: 0,
+            humanize_filters: HashSet::new(),
+            forloops: Vec::new(),
         }
     }
 
@@ -1310,14 +1557,14 @@ impl<'t, 'py> Parser<'t, 'py> {
 
     fn parse_for_variable(&self, at: At) -> Either<Variable, ForVariable> {
         let mut parts = self.template.content(at).split('.');
-        if self.forloop_depth == 0
+        if self.forloops.is_empty()
             || parts
                 .next()
                 .expect("a variable can always be split into at least one part")
                 .trim()
                 != "forloop"
         {
-            return Either::Left(Variable::new(at));
+            return Either::Left(Variable::new(self.py, self.template, at));
         }
         let Some(part) = parts.next_back() else {
             return Either::Right(ForVariable {
@@ -1334,21 +1581,22 @@ impl<'t, 'py> Parser<'t, 'py> {
             "first" => ForVariableName::First,
             "last" => ForVariableName::Last,
             "parentloop" => ForVariableName::Object,
-            _ => return Either::Left(Variable::new(at)),
+            _ => return Either::Left(Variable::new(self.py, self.template, at)),
         };
         let parts: Vec<_> = parts.collect();
         for part in &parts {
             if part.trim() != "parentloop" {
-                return Either::Left(Variable::new(at));
+                return Either::Left(Variable::new(self.py, self.template, at));
             }
         }
         let mut parent_count = parts.len();
         if variant == ForVariableName::Object {
             parent_count += 1;
         }
-        if parent_count > self.forloop_depth {
-            return Either::Left(Variable::new(at));
+        if parent_count > self.forloops.len() {
+            return Either::Left(Variable::new(self.py, self.template, at));
         }
+        self.mark_forloop_length(&variant, parent_count);
         Either::Right(ForVariable {
             variant,
             parent_count,
@@ -1356,6 +1604,24 @@ impl<'t, 'py> Parser<'t, 'py> {
         })
     }
 
+    /// Record which enclosing loops need their length to resolve `variant`.
+    fn mark_forloop_length(&self, variant: &ForVariableName, parent_count: usize) {
+        match variant {
+            ForVariableName::RevCounter | ForVariableName::RevCounter0 | ForVariableName::Last => {
+                if let Some(index) = self.forloops.len().checked_sub(parent_count + 1) {
+                    self.forloops[index].set(true);
+                }
+            }
+            // Rendering `forloop` shows the length of every enclosing loop.
+            ForVariableName::Object => {
+                for forloop in &self.forloops {
+                    forloop.set(true);
+                }
+            }
+            ForVariableName::Counter | ForVariableName::Counter0 | ForVariableName::First => {}
+        }
+    }
+
     fn parse_variable(
         &self,
         variable: &str,
@@ -1717,6 +1983,8 @@ impl<'t, 'py> Parser<'t, 'py> {
         let (args, kwargs, target_var) = self.parse_custom_tag_parts(parts, context)?;
         let tag = SimpleTag {
             func: context.func.clone().unbind().into(),
+            library: context.library.clone(),
+            name: context.name.clone(),
             at,
             takes_context: context.takes_context,
             args,
@@ -1742,6 +2010,8 @@ impl<'t, 'py> Parser<'t, 'py> {
         )?;
         let tag = SimpleBlockTag {
             func: context.func.clone().unbind().into(),
+            library: context.library.clone(),
+            name: context.name.clone(),
             nodes,
             at,
             takes_context: context.takes_context,
@@ -1759,15 +2029,16 @@ impl<'t, 'py> Parser<'t, 'py> {
             && self.template.content(prev.at) == "from"
         {
             let library = last.load_library(self.py, &self.engine.libraries, self.template)?;
+            let library_name = self.template.content(last.at);
+            let humanize = self.is_humanize(last.at);
             let filters = self.get_filters(library)?;
             let tags = self.get_tags(library)?;
             for token in rev {
                 let content = self.template.content(token.at);
                 if let Some(filter) = filters.get(content) {
-                    self.external_filters
-                        .insert(content.to_string(), filter.clone());
+                    self.load_filter(library_name, content.to_string(), filter.clone(), humanize);
                 } else if let Some(tag) = tags.get(content) {
-                    self.load_tag(token.at, content, tag)?;
+                    self.load_tag(token.at, library_name, content, tag)?;
                 } else {
                     return Err(ParseError::MissingFilterTag {
                         library: self.template.content(last.at).to_string(),
@@ -1782,20 +2053,57 @@ impl<'t, 'py> Parser<'t, 'py> {
         }
         for token in tokens {
             let library = token.load_library(self.py, &self.engine.libraries, self.template)?;
+            let library_name = self.template.content(token.at);
+            let humanize = self.is_humanize(token.at);
             let filters = self.get_filters(library)?;
             let tags = self.get_tags(library)?;
-            self.external_filters.extend(filters);
+            for (name, filter) in filters {
+                self.load_filter(library_name, name, filter, humanize);
+            }
             for (name, tag) in &tags {
-                self.load_tag(at, name, tag)?;
+                self.load_tag(at, library_name, name, tag)?;
             }
         }
         Ok(TokenTree::Tag(Tag::Load))
     }
 
+    /// Whether the library named at `at` is `django.contrib.humanize`.
+    fn is_humanize(&self, at: At) -> bool {
+        self.engine
+            .humanize_libraries
+            .contains(self.template.content(at))
+    }
+
+    /// Make a loaded filter available, preferring the native implementation
+    /// of the `django.contrib.humanize` filters.
+    fn load_filter(
+        &mut self,
+        library: &str,
+        name: String,
+        filter: Bound<'py, PyAny>,
+        humanize: bool,
+    ) {
+        match HUMANIZE_FILTERS
+            .iter()
+            .find(|native| humanize && **native == name)
+        {
+            Some(native) => {
+                self.external_filters.remove(&name);
+                self.humanize_filters.insert(native);
+            }
+            None => {
+                self.humanize_filters.remove(name.as_str());
+                self.external_filters
+                    .insert(name, (library.to_string(), filter));
+            }
+        }
+    }
+
     #[allow(clippy::too_many_lines)]
     fn load_tag(
         &mut self,
         at: At,
+        library: &str,
         name: &str,
         tag: &Bound<'py, PyAny>,
     ) -> Result<(), PyParseError> {
@@ -1860,6 +2168,8 @@ impl<'t, 'py> Parser<'t, 'py> {
                     end_tag_name,
                     context: SimpleTagContext {
                         func,
+                        library: library.to_string(),
+                        name: name.to_string(),
                         function_name,
                         takes_context,
                         params,
@@ -1899,6 +2209,8 @@ impl<'t, 'py> Parser<'t, 'py> {
                 };
                 TagContext::Simple(SimpleTagContext {
                     func,
+                    library: library.to_string(),
+                    name: name.to_string(),
                     function_name,
                     takes_context,
                     params,
@@ -2027,6 +2339,13 @@ impl<'t, 'py> Parser<'t, 'py> {
                 return Err(ParseError::MissingKeywordArgument { at: with_at.into() });
             }
         }
+        // Unless it is isolated, the included template can render the `forloop`
+        // of every enclosing loop.
+        if only.is_none() {
+            for forloop in &self.forloops {
+                forloop.set(true);
+            }
+        }
         let include = Include {
             template_name,
             origin: self.origin.map(ToString::to_string),
@@ -2087,14 +2406,18 @@ impl<'t, 'py> Parser<'t, 'py> {
     }
 
     fn parse_for(&mut self, at: At, parts: TagParts) -> Result<TokenTree, PyParseError> {
-        self.forloop_depth += 1;
+        self.forloops.push(Cell::new(false));
         let (iterable, variables, reversed) = parse_for_loop(self, parts, at)?;
         let (nodes, end_tag) = self.parse_until(
             vec![EndTagType::Empty, EndTagType::EndFor],
             "for".into(),
             at,
         )?;
-        self.forloop_depth -= 1;
+        let uses_length = self
+            .forloops
+            .pop()
+            .expect("The loop was pushed above")
+            .get();
         let empty = match end_tag {
             EndTag {
                 at,
@@ -2115,6 +2438,7 @@ impl<'t, 'py> Parser<'t, 'py> {
             iterable,
             variables,
             reversed,
+            uses_length,
             body: nodes,
             empty,
         })))
@@ -2231,8 +2555,8 @@ mod tests {
             let template = TemplateString("{{ foo }}");
             let mut parser = Parser::new(py, template, Engine::empty().into(), None);
             let nodes = parser.parse().unwrap();
-            let variable = Variable { at: (3, 3) };
-            assert_eq!(nodes, vec![TokenTree::Variable(variable)]);
+            let variable = Variable::new(py, template, (3, 3));
+            assert_eq!(nodes, vec![TokenTree::Variable(variable.clone())]);
             assert_eq!(
                 variable.parts(template).collect::<Vec<_>>(),
                 vec![("foo", (3, 3))]
@@ -2248,8 +2572,8 @@ mod tests {
             let template = TemplateString("{{ foo.bar.baz }}");
             let mut parser = Parser::new(py, template, Engine::empty().into(), None);
             let nodes = parser.parse().unwrap();
-            let variable = Variable { at: (3, 11) };
-            assert_eq!(nodes, vec![TokenTree::Variable(variable)]);
+            let variable = Variable::new(py, template, (3, 11));
+            assert_eq!(nodes, vec![TokenTree::Variable(variable.clone())]);
             assert_eq!(
                 variable.parts(template).collect::<Vec<_>>(),
                 vec![("foo", (3, 3)), ("bar", (7, 3)), ("baz", (11, 3))]
@@ -2269,15 +2593,17 @@ mod tests {
 
             assert_eq!(nodes.len(), 1);
 
-            let foo = Variable { at: (3, 3) };
+            let foo = Variable::new(py, template, (3, 3));
             let external = get_external_filter(&nodes[0]);
             assert!(external.is_none(py));
             let bar = TokenTree::Filter(Box::new(Filter {
                 at: (7, 3),
                 all_at: (3, 7),
-                left: TagElement::Variable(foo),
+                left: TagElement::Variable(foo.clone()),
                 filter: FilterType::External(ExternalFilter {
                     filter: external,
+                    library: String::new(),
+                    name: "bar".to_string(),
                     argument: None,
                 }),
             }));
@@ -2321,7 +2647,7 @@ mod tests {
             let nodes = parser.parse().unwrap();
             assert_eq!(nodes.len(), 1);
 
-            let foo = TagElement::Variable(Variable { at: (3, 3) });
+            let foo = TagElement::Variable(Variable::new(py, template.into(), (3, 3)));
             let external = get_external_filter_tag_element(&nodes[0]);
             assert!(external.is_none(py));
             let bar = TagElement::Filter(Box::new(Filter {
@@ -2330,6 +2656,8 @@ mod tests {
                 left: foo,
                 filter: FilterType::External(ExternalFilter {
                     filter: external,
+                    library: String::new(),
+                    name: "bar".to_string(),
                     argument: None,
                 }),
             }));
@@ -2341,6 +2669,8 @@ mod tests {
                 left: bar,
                 filter: FilterType::External(ExternalFilter {
                     filter: external,
+                    library: String::new(),
+                    name: "baz".to_string(),
                     argument: None,
                 }),
             }));
@@ -2359,8 +2689,8 @@ mod tests {
             let nodes = parser.parse().unwrap();
             assert_eq!(nodes.len(), 1);
 
-            let foo = TagElement::Variable(Variable { at: (3, 3) });
-            let baz = Variable { at: (11, 3) };
+            let foo = TagElement::Variable(Variable::new(py, template, (3, 3)));
+            let baz = Variable::new(py, template, (11, 3));
             let external = get_external_filter(&nodes[0]);
             assert!(external.is_none(py));
             let bar = TokenTree::Filter(Box::new(Filter {
@@ -2369,9 +2699,11 @@ mod tests {
                 left: foo,
                 filter: FilterType::External(ExternalFilter {
                     filter: external,
+                    library: String::new(),
+                    name: "bar".to_string(),
                     argument: Some(Argument {
                         at: (11, 3),
-                        argument_type: ArgumentType::Variable(baz),
+                        argument_type: ArgumentType::Variable(baz.clone()),
                     }),
                 }),
             }));
@@ -2393,7 +2725,7 @@ mod tests {
             let mut parser = Parser::new_with_filters(py, template, filters);
             let nodes = parser.parse().unwrap();
 
-            let foo = TagElement::Variable(Variable { at: (3, 3) });
+            let foo = TagElement::Variable(Variable::new(py, template, (3, 3)));
             let baz = Text::new((12, 3));
             let external = get_external_filter(&nodes[0]);
             assert!(external.is_none(py));
@@ -2403,6 +2735,8 @@ mod tests {
                 left: foo,
                 filter: FilterType::External(ExternalFilter {
                     filter: external,
+                    library: String::new(),
+                    name: "bar".to_string(),
                     argument: Some(Argument {
                         at: (11, 5),
                         argument_type: ArgumentType::Text(baz),
@@ -2424,7 +2758,7 @@ mod tests {
             let mut parser = Parser::new_with_filters(py, template, filters);
             let nodes = parser.parse().unwrap();
 
-            let foo = TagElement::Variable(Variable { at: (3, 3) });
+            let foo = TagElement::Variable(Variable::new(py, template, (3, 3)));
             let baz = TranslatedText::new((14, 3));
             let external = get_external_filter(&nodes[0]);
             assert!(external.is_none(py));
@@ -2434,6 +2768,8 @@ mod tests {
                 left: foo,
                 filter: FilterType::External(ExternalFilter {
                     filter: external,
+                    library: String::new(),
+                    name: "bar".to_string(),
                     argument: Some(Argument {
                         at: (11, 8),
                         argument_type: ArgumentType::TranslatedText(baz),
@@ -2455,7 +2791,7 @@ mod tests {
             let mut parser = Parser::new_with_filters(py, template.into(), filters);
             let nodes = parser.parse().unwrap();
 
-            let foo = TagElement::Variable(Variable { at: (3, 3) });
+            let foo = TagElement::Variable(Variable::new(py, template.into(), (3, 3)));
             let num = Argument {
                 at: (11, 5),
                 argument_type: ArgumentType::Float(5.2e3),
@@ -2468,6 +2804,8 @@ mod tests {
                 left: foo,
                 filter: FilterType::External(ExternalFilter {
                     filter: external,
+                    library: String::new(),
+                    name: "bar".to_string(),
                     argument: Some(num),
                 }),
             }));
@@ -2485,7 +2823,7 @@ mod tests {
             let mut parser = Parser::new_with_filters(py, template.into(), filters);
             let nodes = parser.parse().unwrap();
 
-            let foo = TagElement::Variable(Variable { at: (3, 3) });
+            let foo = TagElement::Variable(Variable::new(py, template.into(), (3, 3)));
             let num = Argument {
                 at: (11, 2),
                 argument_type: ArgumentType::Int(99.into()),
@@ -2498,6 +2836,8 @@ mod tests {
                 left: foo,
                 filter: FilterType::External(ExternalFilter {
                     filter: external,
+                    library: String::new(),
+                    name: "bar".to_string(),
                     argument: Some(num),
                 }),
             }));
@@ -2515,7 +2855,7 @@ mod tests {
             let mut parser = Parser::new_with_filters(py, template.into(), filters);
             let nodes = parser.parse().unwrap();
 
-            let foo = TagElement::Variable(Variable { at: (3, 3) });
+            let foo = TagElement::Variable(Variable::new(py, template.into(), (3, 3)));
             let num = Argument {
                 at: (11, 17),
                 argument_type: ArgumentType::Int("99999999999999999".parse::<BigInt>().unwrap()),
@@ -2528,6 +2868,8 @@ mod tests {
                 left: foo,
                 filter: FilterType::External(ExternalFilter {
                     filter: external,
+                    library: String::new(),
+                    name: "bar".to_string(),
                     argument: Some(num),
                 }),
             }));
@@ -2582,8 +2924,8 @@ mod tests {
             let mut parser = Parser::new(py, template, Engine::empty().into(), None);
             let nodes = parser.parse().unwrap();
 
-            let foo = TagElement::Variable(Variable { at: (3, 3) });
-            let baz = Variable { at: (15, 3) };
+            let foo = TagElement::Variable(Variable::new(py, template, (3, 3)));
+            let baz = Variable::new(py, template, (15, 3));
             let bar = TokenTree::Filter(Box::new(Filter {
                 at: (7, 7),
                 all_at: (3, 11),
@@ -2591,7 +2933,7 @@ mod tests {
                 filter: FilterType::Default(DefaultFilter::new(
                     Argument {
                         at: (15, 3),
-                        argument_type: ArgumentType::Variable(baz),
+                        argument_type: ArgumentType::Variable(baz.clone()),
                     },
                     (7, 7),
                 )),
@@ -2734,7 +3076,7 @@ mod tests {
 
             let url = TokenTree::Tag(Tag::Url(Url {
                 at: (0, 24),
-                view_name: TagElement::Variable(Variable { at: (7, 14) }),
+                view_name: TagElement::Variable(Variable::new(py, template.into(), (7, 14))),
                 args: vec![],
                 kwargs: vec![],
                 asvar: None,
@@ -2753,7 +3095,7 @@ mod tests {
             let mut parser = Parser::new(py, template.into(), Engine::empty().into(), None);
             let nodes = parser.parse().unwrap();
 
-            let some_view_name = TagElement::Variable(Variable { at: (7, 14) });
+            let some_view_name = TagElement::Variable(Variable::new(py, template.into(), (7, 14)));
             let home = Text { at: (31, 4) };
             let default = Box::new(Filter {
                 at: (22, 7),
@@ -2823,13 +3165,13 @@ mod tests {
 
             let url = TokenTree::Tag(Tag::Url(Url {
                 at: (0, 66),
-                view_name: TagElement::Variable(Variable { at: (7, 14) }),
+                view_name: TagElement::Variable(Variable::new(py, template.into(), (7, 14))),
                 args: vec![
                     TagElement::Text(Text { at: (23, 3) }),
                     TagElement::Filter(Box::new(Filter {
                         at: (32, 7),
                         all_at: (28, 11),
-                        left: TagElement::Variable(Variable { at: (28, 3) }),
+                        left: TagElement::Variable(Variable::new(py, template.into(), (28, 3))),
                         filter: FilterType::Default(DefaultFilter::new(
                             Argument {
                                 at: (40, 6),
@@ -2861,7 +3203,7 @@ mod tests {
 
             let url = TokenTree::Tag(Tag::Url(Url {
                 at: (0, 44),
-                view_name: TagElement::Variable(Variable { at: (7, 14) }),
+                view_name: TagElement::Variable(Variable::new(py, template.into(), (7, 14))),
                 args: vec![],
                 kwargs: vec![
                     ("foo".to_string(), TagElement::Text(Text { at: (27, 3) })),
@@ -2885,7 +3227,7 @@ mod tests {
 
             let url = TokenTree::Tag(Tag::Url(Url {
                 at: (0, 42),
-                view_name: TagElement::Variable(Variable { at: (7, 14) }),
+                view_name: TagElement::Variable(Variable::new(py, template.into(), (7, 14))),
                 args: vec![TagElement::Text(Text { at: (23, 3) })],
                 kwargs: vec![],
                 asvar: Some("some_url".to_string()),
@@ -2906,7 +3248,7 @@ mod tests {
 
             let url = TokenTree::Tag(Tag::Url(Url {
                 at: (0, 46),
-                view_name: TagElement::Variable(Variable { at: (7, 14) }),
+                view_name: TagElement::Variable(Variable::new(py, template.into(), (7, 14))),
                 args: vec![],
                 kwargs: vec![("foo".to_string(), TagElement::Text(Text { at: (27, 3) }))],
                 asvar: Some("some_url".to_string()),
@@ -2927,11 +3269,11 @@ mod tests {
 
             let url = TokenTree::Tag(Tag::Url(Url {
                 at: (0, 39),
-                view_name: TagElement::Variable(Variable { at: (7, 14) }),
+                view_name: TagElement::Variable(Variable::new(py, template.into(), (7, 14))),
                 args: vec![
                     TagElement::Text(Text { at: (23, 3) }),
-                    TagElement::Variable(Variable { at: (28, 3) }),
-                    TagElement::Variable(Variable { at: (32, 4) }),
+                    TagElement::Variable(Variable::new(py, template.into(), (28, 3))),
+                    TagElement::Variable(Variable::new(py, template.into(), (32, 4))),
                 ],
                 kwargs: vec![],
                 asvar: None,
@@ -2980,8 +3322,18 @@ mod tests {
                 FilterType::Lower(LowerFilter)
             );
             assert_ne!(
-                FilterType::External(ExternalFilter::new(py.None(), None)),
-                FilterType::External(ExternalFilter::new(py.None(), None))
+                FilterType::External(ExternalFilter::new(
+                    py.None(),
+                    String::new(),
+                    "foo".to_string(),
+                    None
+                )),
+                FilterType::External(ExternalFilter::new(
+                    py.None(),
+                    String::new(),
+                    "foo".to_string(),
+                    None
+                ))
             );
             assert_ne!(
                 FilterType::Lower(LowerFilter),
@@ -3007,6 +3359,8 @@ mod tests {
             assert_eq!(
                 SimpleTag {
                     func: func.clone(),
+                    library: "custom_tags".to_string(),
+                    name: "foo".to_string(),
                     at,
                     takes_context,
                     args: Vec::new(),
@@ -3015,6 +3369,8 @@ mod tests {
                 },
                 SimpleTag {
                     func,
+                    library: "custom_tags".to_string(),
+                    name: "foo".to_string(),
                     at,
                     takes_context,
                     args: Vec::new(),
@@ -3036,6 +3392,8 @@ mod tests {
             assert_eq!(
                 SimpleBlockTag {
                     func: func.clone(),
+                    library: "custom_tags".to_string(),
+                    name: "foo".to_string(),
                     at,
                     takes_context,
                     args: Vec::new(),
@@ -3045,6 +3403,8 @@ mod tests {
                 },
                 SimpleBlockTag {
                     func,
+                    library: "custom_tags".to_string(),
+                    name: "foo".to_string(),
                     at,
                     takes_context,
                     args: Vec::new(),
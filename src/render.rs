@@ -1,11 +1,20 @@
 pub mod common;
+pub mod dateformat;
 pub mod filters;
+pub mod html;
+pub mod humanize;
+pub mod json;
 pub mod lorem;
+pub mod numberformat;
+pub mod stream;
+pub mod stringformat;
 pub mod tags;
+pub mod truncate;
 pub mod types;
+pub mod url;
+pub mod urlize;
 
-use std::borrow::Cow;
-
+use pyo3::exceptions::PyUnicodeDecodeError;
 use pyo3::prelude::*;
 
 use dtl_lexer::types::TemplateString;
@@ -14,7 +23,7 @@ use crate::error::PyRenderError;
 use types::{Content, Context};
 
 pub type ResolveResult<'t, 'py> = Result<Option<Content<'t, 'py>>, PyRenderError>;
-pub type RenderResult<'t> = Result<Cow<'t, str>, PyRenderError>;
+pub type RenderResult = Result<(), PyRenderError>;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ResolveFailures {
@@ -24,7 +33,7 @@ pub enum ResolveFailures {
 
 /// Trait for resolving a template element into content suitable for
 /// further processing by another template element.
-trait Resolve {
+pub trait Resolve {
     fn resolve<'t, 'py>(
         &self,
         py: Python<'py>,
@@ -36,13 +45,29 @@ trait Resolve {
 
 /// Trait for rendering a template element into content suitable for
 /// output in the completely processed template.
+///
+/// Elements append to a single `output` buffer shared by the whole tree,
+/// so rendering a template doesn't allocate a string for every node.
 pub trait Render {
-    fn render<'t>(
+    fn render(
         &self,
         py: Python<'_>,
-        template: TemplateString<'t>,
+        template: TemplateString<'_>,
         context: &mut Context,
-    ) -> RenderResult<'t>;
+        output: &mut String,
+    ) -> RenderResult;
+
+    /// Render into a new string.
+    fn render_to_string(
+        &self,
+        py: Python<'_>,
+        template: TemplateString<'_>,
+        context: &mut Context,
+    ) -> Result<String, PyRenderError> {
+        let mut output = String::new();
+        self.render(py, template, context, &mut output)?;
+        Ok(output)
+    }
 }
 
 /// Trait for evaluating an expression in a boolean context
@@ -77,16 +102,23 @@ impl<T> Render for T
 where
     T: Resolve,
 {
-    fn render<'t>(
+    fn render(
         &self,
         py: Python<'_>,
-        template: TemplateString<'t>,
+        template: TemplateString<'_>,
         context: &mut Context,
-    ) -> RenderResult<'t> {
-        match self.resolve(py, template, context, ResolveFailures::Raise)? {
-            Some(content) => Ok(content.render(context)?),
-            None => Ok(Cow::Borrowed("")),
+        output: &mut String,
+    ) -> RenderResult {
+        match self.resolve(py, template, context, ResolveFailures::Raise) {
+            Ok(Some(content)) => content.render_to(py, context, output)?,
+            Ok(None) => {}
+            // Like Django, fail quietly when unicode conversion fails for
+            // reasons out of our control.
+            Err(PyRenderError::PyErr(error))
+                if error.is_instance_of::<PyUnicodeDecodeError>(py) => {}
+            Err(error) => return Err(error),
         }
+        Ok(())
     }
 }
 
@@ -94,18 +126,17 @@ impl<T> Render for Vec<T>
 where
     T: Render,
 {
-    fn render<'t>(
+    fn render(
         &self,
         py: Python<'_>,
-        template: TemplateString<'t>,
+        template: TemplateString<'_>,
         context: &mut Context,
-    ) -> RenderResult<'t> {
-        Ok(Cow::Owned(
-            self.iter()
-                .map(|node| node.render(py, template, context))
-                .collect::<Result<Vec<_>, _>>()?
-                .join(""),
-        ))
+        output: &mut String,
+    ) -> RenderResult {
+        for node in self {
+            node.render(py, template, context, output)?;
+        }
+        Ok(())
     }
 }
 
@@ -113,15 +144,16 @@ impl<T> Render for Option<T>
 where
     T: Render,
 {
-    fn render<'t>(
+    fn render(
         &self,
         py: Python<'_>,
-        template: TemplateString<'t>,
+        template: TemplateString<'_>,
         context: &mut Context,
-    ) -> RenderResult<'t> {
-        Ok(match self {
-            Some(inner) => inner.render(py, template, context)?,
-            None => Cow::Borrowed(""),
-        })
+        output: &mut String,
+    ) -> RenderResult {
+        match self {
+            Some(inner) => inner.render(py, template, context, output),
+            None => Ok(()),
+        }
     }
 }
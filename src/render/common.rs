@@ -3,7 +3,7 @@ use std::borrow::Cow;
 use pyo3::intern;
 use pyo3::prelude::*;
 use pyo3::sync::PyOnceLock;
-use pyo3::types::PyString;
+use pyo3::types::{PyDict, PyList, PyString, PyTuple};
 
 use dtl_lexer::types::TemplateString;
 
@@ -15,11 +15,15 @@ use crate::types::Argument;
 use crate::types::ArgumentType;
 use crate::types::ForVariable;
 use crate::types::ForVariableName;
+use crate::types::Lookup;
 use crate::types::Text;
 use crate::types::TranslatedText;
-use dtl_lexer::types::Variable;
+use crate::types::Variable;
 
 static GETTEXT: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static PGETTEXT: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static NGETTEXT: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static NPGETTEXT: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
 
 /// Helper function to translate a string using Django's gettext
 pub fn gettext(py: Python<'_>, text: &str) -> PyResult<String> {
@@ -27,6 +31,34 @@ pub fn gettext(py: Python<'_>, text: &str) -> PyResult<String> {
     get_text.call1((text,))?.extract::<String>()
 }
 
+/// Helper function to translate a string with a context using Django's pgettext
+pub fn pgettext(py: Python<'_>, context: &str, text: &str) -> PyResult<String> {
+    let pgettext = PGETTEXT.import(py, "django.utils.translation", "pgettext")?;
+    pgettext.call1((context, text))?.extract::<String>()
+}
+
+/// Helper function to translate a string with plural forms using Django's ngettext
+pub fn ngettext(py: Python<'_>, singular: &str, plural: &str, number: i64) -> PyResult<String> {
+    let ngettext = NGETTEXT.import(py, "django.utils.translation", "ngettext")?;
+    ngettext
+        .call1((singular, plural, number))?
+        .extract::<String>()
+}
+
+/// Helper function to translate a string with a context and plural forms using Django's npgettext
+pub fn npgettext(
+    py: Python<'_>,
+    context: &str,
+    singular: &str,
+    plural: &str,
+    number: i64,
+) -> PyResult<String> {
+    let npgettext = NPGETTEXT.import(py, "django.utils.translation", "npgettext")?;
+    npgettext
+        .call1((context, singular, plural, number))?
+        .extract::<String>()
+}
+
 fn has_truthy_attr(variable: &Bound<'_, PyAny>, attr: &Bound<'_, PyString>) -> PyResult<bool> {
     match variable.getattr(attr) {
         Ok(attr) if attr.is_truthy()? => Ok(true),
@@ -34,6 +66,17 @@ fn has_truthy_attr(variable: &Bound<'_, PyAny>, attr: &Bound<'_, PyString>) -> P
     }
 }
 
+/// Look up `part` on `variable` as a key, falling back to an attribute.
+pub fn get_item_or_attr<'py>(
+    variable: &Bound<'py, PyAny>,
+    part: &str,
+) -> PyResult<Bound<'py, PyAny>> {
+    match variable.get_item(part) {
+        Ok(variable) => Ok(variable),
+        Err(_) => variable.getattr(part),
+    }
+}
+
 fn resolve_callable(variable: Bound<'_, PyAny>) -> PyResult<Option<Bound<'_, PyAny>>> {
     if !variable.is_callable() {
         return Ok(Some(variable));
@@ -48,6 +91,25 @@ fn resolve_callable(variable: Bound<'_, PyAny>) -> PyResult<Option<Bound<'_, PyA
     Ok(Some(variable.call0()?))
 }
 
+/// Look up `lookup` directly on an exact `dict`, `list` or `tuple`.
+///
+/// These can't customise item or attribute access, so a successful lookup
+/// here gives the same result as Django's lookup order. `None` means the
+/// general lookup is needed.
+fn builtin_lookup<'py>(variable: &Bound<'py, PyAny>, lookup: &Lookup) -> Option<Bound<'py, PyAny>> {
+    if let Ok(dict) = variable.cast_exact::<PyDict>() {
+        return dict.get_item(lookup.key.bind(variable.py())).ok().flatten();
+    }
+    let index = lookup.index?;
+    if let Ok(list) = variable.cast_exact::<PyList>() {
+        return list.get_item(index).ok();
+    }
+    if let Ok(tuple) = variable.cast_exact::<PyTuple>() {
+        return tuple.get_item(index).ok();
+    }
+    None
+}
+
 impl Resolve for Variable {
     fn resolve<'t, 'py>(
         &self,
@@ -56,8 +118,7 @@ impl Resolve for Variable {
         context: &mut Context,
         failures: ResolveFailures,
     ) -> ResolveResult<'t, 'py> {
-        let mut parts = self.parts(template);
-        let (first, mut object_at) = parts.next().expect("Variable names cannot be empty");
+        let (first, mut object_at) = self.first(template);
         let Some(variable) = context.get(first) else {
             return Ok(None);
         };
@@ -67,25 +128,26 @@ impl Resolve for Variable {
             return Ok(None);
         };
 
-        for (part, key_at) in parts {
-            variable = match variable.get_item(part) {
-                Ok(variable) => variable,
-                Err(_) => match variable.getattr(part) {
+        for lookup in self.lookups.iter() {
+            let key = lookup.key.bind(py);
+            variable = match builtin_lookup(&variable, lookup) {
+                Some(variable) => variable,
+                None => match variable.get_item(key).or_else(|_| variable.getattr(key)) {
                     Ok(variable) => variable,
                     Err(_) => {
-                        let Ok(int) = part.parse::<usize>() else {
+                        let Some(index) = lookup.index else {
                             return match failures {
                                 ResolveFailures::Raise => Err(RenderError::VariableDoesNotExist {
-                                    key: part.to_string(),
+                                    key: template.content(lookup.at).to_string(),
                                     object: variable.str()?.to_string(),
-                                    key_at: key_at.into(),
+                                    key_at: lookup.at.into(),
                                     object_at: Some(object_at.into()),
                                 }
                                 .into()),
                                 ResolveFailures::IgnoreVariableDoesNotExist => Ok(None),
                             };
                         };
-                        match variable.get_item(int) {
+                        match variable.get_item(index) {
                             Ok(variable) => variable,
                             Err(_) => todo!(),
                         }
@@ -98,7 +160,7 @@ impl Resolve for Variable {
                 Some(variable) => variable,
                 None => return Ok(None),
             };
-            object_at.1 += key_at.1 + 1;
+            object_at.1 += lookup.at.1 + 1;
         }
         Ok(Some(Content::Py(variable)))
     }
@@ -118,10 +180,19 @@ impl Resolve for ForVariable {
         Ok(Some(match self.variant {
             ForVariableName::Counter => Content::Int(for_loop.counter().into()),
             ForVariableName::Counter0 => Content::Int(for_loop.counter0().into()),
-            ForVariableName::RevCounter => Content::Int(for_loop.rev_counter().into()),
-            ForVariableName::RevCounter0 => Content::Int(for_loop.rev_counter0().into()),
+            ForVariableName::RevCounter => match for_loop.rev_counter() {
+                Some(rev_counter) => Content::Int(rev_counter.into()),
+                None => return Ok(None),
+            },
+            ForVariableName::RevCounter0 => match for_loop.rev_counter0() {
+                Some(rev_counter0) => Content::Int(rev_counter0.into()),
+                None => return Ok(None),
+            },
             ForVariableName::First => Content::Bool(for_loop.first()),
-            ForVariableName::Last => Content::Bool(for_loop.last()),
+            ForVariableName::Last => match for_loop.last() {
+                Some(last) => Content::Bool(last),
+                None => return Ok(None),
+            },
             ForVariableName::Object => {
                 let content = Cow::Owned(context.render_for_loop(py, self.parent_count));
                 let content = match context.autoescape {
@@ -245,21 +316,32 @@ impl Evaluate for TagElement {
 }
 
 impl Render for TokenTree {
-    fn render<'t>(
+    fn render(
         &self,
         py: Python<'_>,
-        template: TemplateString<'t>,
+        template: TemplateString<'_>,
         context: &mut Context,
-    ) -> RenderResult<'t> {
+        output: &mut String,
+    ) -> RenderResult {
         match self {
-            Self::Text(text) => text.render(py, template, context),
+            Self::Text(text) => text.render(py, template, context, output),
             Self::TranslatedText(_text) => todo!(),
-            Self::Int(n) => Ok(n.to_string().into()),
-            Self::Float(f) => Ok(f.to_string().into()),
-            Self::Tag(tag) => tag.render(py, template, context),
-            Self::Variable(variable) => variable.render(py, template, context),
-            Self::ForVariable(variable) => variable.render(py, template, context),
-            Self::Filter(filter) => filter.render(py, template, context),
+            Self::Constant(constant) => {
+                output.push_str(constant.output(context.autoescape));
+                Ok(())
+            }
+            Self::Int(n) => {
+                output.push_str(&n.to_string());
+                Ok(())
+            }
+            Self::Float(f) => {
+                output.push_str(&f.to_string());
+                Ok(())
+            }
+            Self::Tag(tag) => tag.render(py, template, context, output),
+            Self::Variable(variable) => variable.render(py, template, context, output),
+            Self::ForVariable(variable) => variable.render(py, template, context, output),
+            Self::Filter(filter) => filter.render(py, template, context, output),
         }
     }
 }
@@ -281,9 +363,11 @@ mod tests {
             let context = HashMap::from([("name".to_string(), name.unbind())]);
             let mut context = Context::new(context, None, false);
             let template = TemplateString("{{ name }}");
-            let variable = Variable::new((3, 4));
+            let variable = Variable::new(py, template, (3, 4));
 
-            let rendered = variable.render(py, template, &mut context).unwrap();
+            let rendered = variable
+                .render_to_string(py, template, &mut context)
+                .unwrap();
             assert_eq!(rendered, "Lily");
         });
     }
@@ -299,9 +383,11 @@ mod tests {
             let context = HashMap::from([("data".to_string(), data.into_any().unbind())]);
             let mut context = Context::new(context, None, false);
             let template = TemplateString("{{ data.name }}");
-            let variable = Variable::new((3, 9));
+            let variable = Variable::new(py, template, (3, 9));
 
-            let rendered = variable.render(py, template, &mut context).unwrap();
+            let rendered = variable
+                .render_to_string(py, template, &mut context)
+                .unwrap();
             assert_eq!(rendered, "Lily");
         });
     }
@@ -316,9 +402,11 @@ mod tests {
             let context = HashMap::from([("names".to_string(), names.into_any().unbind())]);
             let mut context = Context::new(context, None, false);
             let template = TemplateString("{{ names.0 }}");
-            let variable = Variable::new((3, 7));
+            let variable = Variable::new(py, template, (3, 7));
 
-            let rendered = variable.render(py, template, &mut context).unwrap();
+            let rendered = variable
+                .render_to_string(py, template, &mut context)
+                .unwrap();
             assert_eq!(rendered, "Lily");
         });
     }
@@ -345,13 +433,55 @@ user = User('Lily')
             let context = locals.extract().unwrap();
             let mut context = Context::new(context, None, false);
             let template = TemplateString("{{ user.name }}");
-            let variable = Variable::new((3, 9));
+            let variable = Variable::new(py, template, (3, 9));
 
-            let rendered = variable.render(py, template, &mut context).unwrap();
+            let rendered = variable
+                .render_to_string(py, template, &mut context)
+                .unwrap();
             assert_eq!(rendered, "Lily");
         });
     }
 
+    #[test]
+    fn test_render_builtin_lookups() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let locals = PyDict::new(py);
+            py.run(
+                c"
+from collections import defaultdict
+
+data = {'users': [('Lily', 'Jacob')], '0': 'zero', 0: 'int zero'}
+counts = defaultdict(int)
+",
+                None,
+                Some(&locals),
+            )
+            .unwrap();
+
+            let context = locals.extract().unwrap();
+            let mut context = Context::new(context, None, false);
+            let template = TemplateString(
+                "{{ data.users.0.1 }} {{ data.0 }} {{ data.items }} {{ counts.missing }}",
+            );
+            let variables = [(3, 14), (24, 6), (37, 10), (54, 14)];
+
+            let rendered: Vec<_> = variables
+                .into_iter()
+                .map(|at| {
+                    Variable::new(py, template, at)
+                        .render_to_string(py, template, &mut context)
+                        .unwrap()
+                })
+                .collect();
+            assert_eq!(rendered[0], "Jacob");
+            assert_eq!(rendered[1], "zero");
+            assert!(rendered[2].starts_with("dict_items("));
+            assert_eq!(rendered[3], "0");
+        });
+    }
+
     #[test]
     fn test_render_html_autoescape() {
         Python::initialize();
@@ -361,9 +491,9 @@ user = User('Lily')
             let context = HashMap::from([("html".to_string(), html)]);
             let mut context = Context::new(context, None, true);
             let template = TemplateString("{{ html }}");
-            let html = Variable::new((3, 4));
+            let html = Variable::new(py, template, (3, 4));
 
-            let rendered = html.render(py, template, &mut context).unwrap();
+            let rendered = html.render_to_string(py, template, &mut context).unwrap();
             assert_eq!(rendered, "&lt;p&gt;Hello World!&lt;/p&gt;");
         });
     }
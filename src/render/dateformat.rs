@@ -0,0 +1,1219 @@
+//! Date and time formatting matching `django.utils.dateformat`, and the
+//! elapsed time descriptions of `django.utils.timesince`.
+
+use std::borrow::Cow;
+use std::cell::OnceCell;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use pyo3::exceptions::{
+    PyAttributeError, PyKeyError, PyNotImplementedError, PyTypeError, PyValueError,
+};
+use pyo3::intern;
+use pyo3::prelude::*;
+use pyo3::sync::PyOnceLock;
+use pyo3::types::{
+    PyDate, PyDateAccess, PyDateTime, PyDict, PyInt, PyString, PyTime, PyTimeAccess, PyType,
+    PyTzInfo, PyTzInfoAccess,
+};
+
+use crate::render::common::{gettext, ngettext, npgettext};
+
+static GET_FORMAT: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static GET_LANGUAGE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static GET_DEFAULT_TIMEZONE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static GET_CURRENT_TIMEZONE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static TEMPLATE_LOCALTIME: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static TIMESINCE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static SETTING_CHANGED_RECEIVER: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+
+/// The translated names and formats of each language used so far.
+static DATE_LOCALES: Mutex<Vec<(Option<String>, Arc<DateLocale>)>> = Mutex::new(Vec::new());
+
+/// The characters with a meaning in a date format string.
+const FORMAT_CHARS: &str = "aAbcdDeEfFgGhHiIjlLmMnNoOPrsStTUuwWyYzZ";
+
+/// The format characters supported by Django's `TimeFormat`.
+const TIME_CHARS: &str = "aAefgGhHiOPsTuZ";
+
+/// Named formats cached by `DateLocale`.
+const DATE_FORMATS: [&str; 7] = [
+    "DATE_FORMAT",
+    "DATETIME_FORMAT",
+    "SHORT_DATE_FORMAT",
+    "SHORT_DATETIME_FORMAT",
+    "YEAR_MONTH_FORMAT",
+    "MONTH_DAY_FORMAT",
+    "TIME_FORMAT",
+];
+
+/// The other names in Django's `FORMAT_SETTINGS`, which are rarely used as
+/// date formats and so are looked up with `get_format` every time.
+const OTHER_FORMATS: [&str; 7] = [
+    "DECIMAL_SEPARATOR",
+    "THOUSAND_SEPARATOR",
+    "NUMBER_GROUPING",
+    "FIRST_DAY_OF_WEEK",
+    "DATE_INPUT_FORMATS",
+    "TIME_INPUT_FORMATS",
+    "DATETIME_INPUT_FORMATS",
+];
+
+const MONTHS_DAYS: [u8; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+const WEEKDAYS_RFC: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const MONTHS_RFC: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// The units of `timesince` below a month, in seconds.
+const TIME_CHUNKS: [i64; 4] = [60 * 60 * 24 * 7, 60 * 60 * 24, 60 * 60, 60];
+
+const TIME_STRINGS: [(&str, &str); 6] = [
+    ("%(num)d year", "%(num)d years"),
+    ("%(num)d month", "%(num)d months"),
+    ("%(num)d week", "%(num)d weeks"),
+    ("%(num)d day", "%(num)d days"),
+    ("%(num)d hour", "%(num)d hours"),
+    ("%(num)d minute", "%(num)d minutes"),
+];
+
+/// Translated month and weekday names, time of day words and named formats
+/// for one language.
+pub struct DateLocale {
+    months: Vec<String>,
+    months_3: Vec<String>,
+    months_3_title: Vec<String>,
+    months_alt: Vec<String>,
+    months_ap: Vec<String>,
+    weekdays: Vec<String>,
+    weekdays_abbr: Vec<String>,
+    am: String,
+    pm: String,
+    am_upper: String,
+    pm_upper: String,
+    midnight: String,
+    noon: String,
+    formats: Vec<String>,
+}
+
+impl DateLocale {
+    fn new(py: Python<'_>) -> PyResult<Self> {
+        let dates = py.import(intern!(py, "django.utils.dates"))?;
+        let names = |name: &str, keys: std::ops::RangeInclusive<u8>| {
+            let names = dates.getattr(name)?;
+            keys.map(|key| names.get_item(key)?.str()?.extract())
+                .collect::<PyResult<Vec<String>>>()
+        };
+        let months_3 = names("MONTHS_3", 1..=12)?;
+        let months_3_title = months_3
+            .iter()
+            .map(|month| {
+                PyString::new(py, month)
+                    .call_method0(intern!(py, "title"))?
+                    .extract()
+            })
+            .collect::<PyResult<_>>()?;
+        let get_format = GET_FORMAT.import(py, "django.utils.formats", "get_format")?;
+        let formats = DATE_FORMATS
+            .iter()
+            .map(|name| get_format.call1((name,))?.str()?.extract())
+            .collect::<PyResult<_>>()?;
+        Ok(Self {
+            months: names("MONTHS", 1..=12)?,
+            months_3,
+            months_3_title,
+            months_alt: names("MONTHS_ALT", 1..=12)?,
+            months_ap: names("MONTHS_AP", 1..=12)?,
+            weekdays: names("WEEKDAYS", 0..=6)?,
+            weekdays_abbr: names("WEEKDAYS_ABBR", 0..=6)?,
+            am: gettext(py, "a.m.")?,
+            pm: gettext(py, "p.m.")?,
+            am_upper: gettext(py, "AM")?,
+            pm_upper: gettext(py, "PM")?,
+            midnight: gettext(py, "midnight")?,
+            noon: gettext(py, "noon")?,
+            formats,
+        })
+    }
+
+    /// Look up a named format like Django's `get_format`, returning any other
+    /// name unchanged.
+    pub fn format<'a>(&'a self, py: Python<'_>, name: &'a str) -> PyResult<Cow<'a, str>> {
+        if let Some(index) = DATE_FORMATS.iter().position(|format| *format == name) {
+            return Ok(Cow::Borrowed(&self.formats[index]));
+        }
+        if OTHER_FORMATS.contains(&name) {
+            let get_format = GET_FORMAT.import(py, "django.utils.formats", "get_format")?;
+            return Ok(Cow::Owned(get_format.call1((name,))?.str()?.extract()?));
+        }
+        Ok(Cow::Borrowed(name))
+    }
+}
+
+fn date_locales() -> std::sync::MutexGuard<'static, Vec<(Option<String>, Arc<DateLocale>)>> {
+    DATE_LOCALES.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Forget the cached locales when a setting changes, like Django's
+/// `reset_format_cache`.
+#[pyfunction]
+#[pyo3(signature = (**_kwargs))]
+fn clear_date_locales(_kwargs: Option<&Bound<'_, PyDict>>) {
+    date_locales().clear();
+}
+
+fn connect_setting_changed(py: Python<'_>) -> PyResult<()> {
+    SETTING_CHANGED_RECEIVER.get_or_try_init(py, || {
+        let receiver = wrap_pyfunction!(clear_date_locales, py)?;
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("weak", false)?;
+        kwargs.set_item("dispatch_uid", "django_rusty_templates.date_locales")?;
+        py.import("django.core.signals")?
+            .getattr("setting_changed")?
+            .call_method("connect", (&receiver,), Some(&kwargs))?;
+        Ok::<_, PyErr>(receiver.into_any().unbind())
+    })?;
+    Ok(())
+}
+
+/// The `DateLocale` of the active language, built on first use.
+pub fn date_locale(py: Python<'_>) -> PyResult<Arc<DateLocale>> {
+    let get_language = GET_LANGUAGE.import(py, "django.utils.translation", "get_language")?;
+    let language: Option<String> = get_language.call0()?.extract()?;
+    let cached = date_locales()
+        .iter()
+        .find(|(cached, _)| *cached == language)
+        .map(|(_, locale)| Arc::clone(locale));
+    if let Some(locale) = cached {
+        return Ok(locale);
+    }
+    connect_setting_changed(py)?;
+    let locale = Arc::new(DateLocale::new(py)?);
+    let mut locales = date_locales();
+    locales.retain(|(cached, _)| *cached != language);
+    locales.push((language, Arc::clone(&locale)));
+    Ok(locale)
+}
+
+/// Convert an aware datetime to the current time zone, like Django's
+/// `template_localtime`.
+pub fn template_localtime<'py>(
+    value: Bound<'py, PyAny>,
+    use_tz: Option<bool>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let is_aware = match value.cast::<PyDateTime>() {
+        Ok(datetime) => datetime.get_tzinfo().is_some(),
+        Err(_) => false,
+    };
+    if !is_aware || use_tz == Some(false) {
+        return Ok(value);
+    }
+    let py = value.py();
+    let template_localtime =
+        TEMPLATE_LOCALTIME.import(py, "django.utils.timezone", "template_localtime")?;
+    template_localtime.call1((value, use_tz))
+}
+
+fn default_timezone(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+    let get_default_timezone =
+        GET_DEFAULT_TIMEZONE.import(py, "django.utils.timezone", "get_default_timezone")?;
+    get_default_timezone.call0()
+}
+
+fn current_timezone(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+    let get_current_timezone =
+        GET_CURRENT_TIMEZONE.import(py, "django.utils.timezone", "get_current_timezone")?;
+    get_current_timezone.call0()
+}
+
+fn is_naive(value: &Bound<'_, PyAny>) -> PyResult<bool> {
+    Ok(value
+        .call_method0(intern!(value.py(), "utcoffset"))?
+        .is_none())
+}
+
+fn is_leap(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if is_leap(year) => 29,
+        month => MONTHS_DAYS[usize::from(month) - 1],
+    }
+}
+
+/// The number of the day counting from 0001-01-01 as 1, like
+/// `date.toordinal`.
+pub fn ordinal(year: i32, month: u8, day: u8) -> i32 {
+    let before = year - 1;
+    let days_before_month: i32 = (1..month)
+        .map(|month| i32::from(days_in_month(year, month)))
+        .sum();
+    before * 365 + before / 4 - before / 100 + before / 400 + days_before_month + i32::from(day)
+}
+
+/// The day of the week with Monday as 0, like `date.weekday`.
+fn weekday(year: i32, month: u8, day: u8) -> usize {
+    usize::try_from((ordinal(year, month, day) + 6) % 7).expect("Ordinals are positive")
+}
+
+fn iso_week1_monday(year: i32) -> i32 {
+    let first_day = ordinal(year, 1, 1);
+    let first_weekday = (first_day + 6) % 7;
+    match first_weekday > 3 {
+        true => first_day - first_weekday + 7,
+        false => first_day - first_weekday,
+    }
+}
+
+/// The ISO year and week number, like `date.isocalendar`.
+fn iso_calendar(year: i32, month: u8, day: u8) -> (i32, i32) {
+    let today = ordinal(year, month, day);
+    let week = (today - iso_week1_monday(year)).div_euclid(7);
+    if week < 0 {
+        (
+            year - 1,
+            (today - iso_week1_monday(year - 1)).div_euclid(7) + 1,
+        )
+    } else if week >= 52 && today >= iso_week1_monday(year + 1) {
+        (year + 1, 1)
+    } else {
+        (year, week + 1)
+    }
+}
+
+fn name(names: &[String], index: u8, key: usize) -> PyResult<&str> {
+    names
+        .get(key)
+        .map(String::as_str)
+        .ok_or_else(|| PyKeyError::new_err(index))
+}
+
+fn month_name(names: &[String], month: u8) -> PyResult<&str> {
+    name(names, month, usize::from(month).wrapping_sub(1))
+}
+
+fn twelve_hour(hour: u8) -> u8 {
+    match hour % 12 {
+        0 => 12,
+        hour => hour,
+    }
+}
+
+/// Append `text` with backslash escapes removed.
+fn push_unescaped(output: &mut String, text: &str) {
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('\\', Some(&next)) if next != '\n' => {
+                output.push(next);
+                chars.next();
+            }
+            _ => output.push(c),
+        }
+    }
+}
+
+/// Append a UTC offset like `strftime("%z")`.
+fn push_utc_offset(output: &mut String, offset: &Bound<'_, PyAny>) -> PyResult<()> {
+    if offset.is_none() {
+        return Ok(());
+    }
+    let (seconds, microseconds) = offset_microseconds(offset)?;
+    let total = seconds * 1_000_000 + microseconds;
+    output.push(if total < 0 { '-' } else { '+' });
+    let (seconds, microseconds) = (total.abs() / 1_000_000, total.abs() % 1_000_000);
+    write!(output, "{:02}{:02}", seconds / 3600, seconds / 60 % 60)
+        .expect("Writing to a String cannot fail");
+    if seconds % 60 != 0 || microseconds != 0 {
+        write!(output, "{:02}", seconds % 60).expect("Writing to a String cannot fail");
+    }
+    if microseconds != 0 {
+        write!(output, ".{microseconds:06}").expect("Writing to a String cannot fail");
+    }
+    Ok(())
+}
+
+/// The seconds and microseconds of a `timedelta`.
+fn offset_microseconds(offset: &Bound<'_, PyAny>) -> PyResult<(i64, i64)> {
+    let py = offset.py();
+    let days: i64 = offset.getattr(intern!(py, "days"))?.extract()?;
+    let seconds: i64 = offset.getattr(intern!(py, "seconds"))?.extract()?;
+    let microseconds: i64 = offset.getattr(intern!(py, "microseconds"))?.extract()?;
+    Ok((days * 86400 + seconds, microseconds))
+}
+
+/// Fast access to the fields of the exact `datetime` types.
+enum Fields<'py> {
+    Date(Bound<'py, PyDate>),
+    DateTime(Bound<'py, PyDateTime>),
+    Time(Bound<'py, PyTime>),
+    Other,
+}
+
+/// A `date`, `time` or `datetime` being formatted, like Django's
+/// `DateFormat` and `TimeFormat`.
+pub struct DateFormat<'a, 'py> {
+    value: &'a Bound<'py, PyAny>,
+    fields: Fields<'py>,
+    locale: &'a DateLocale,
+    timezone: OnceCell<Option<Bound<'py, PyAny>>>,
+}
+
+impl<'a, 'py> DateFormat<'a, 'py> {
+    pub fn new(value: &'a Bound<'py, PyAny>, locale: &'a DateLocale) -> Self {
+        let fields = if let Ok(datetime) = value.cast_exact::<PyDateTime>() {
+            Fields::DateTime(datetime.clone())
+        } else if let Ok(date) = value.cast_exact::<PyDate>() {
+            Fields::Date(date.clone())
+        } else if let Ok(time) = value.cast_exact::<PyTime>() {
+            Fields::Time(time.clone())
+        } else {
+            Fields::Other
+        };
+        Self {
+            value,
+            fields,
+            locale,
+            timezone: OnceCell::new(),
+        }
+    }
+
+    /// Format the value like Django's `dateformat.format`, or like
+    /// `dateformat.time_format` when `time_only` is set.
+    pub fn format(&self, format: &str, time_only: bool) -> PyResult<String> {
+        let mut output = String::new();
+        let mut literal = String::new();
+        let mut previous = None;
+        for c in format.chars() {
+            let is_format_char = FORMAT_CHARS.contains(c) && previous != Some('\\');
+            previous = Some(c);
+            if !is_format_char {
+                literal.push(c);
+                continue;
+            }
+            push_unescaped(&mut output, &literal);
+            literal.clear();
+            if matches!(self.fields, Fields::Date(_)) && TIME_CHARS.contains(c) {
+                return Err(PyTypeError::new_err(format!(
+                    "The format for date objects may not contain time-related format specifiers (found '{c}')."
+                )));
+            }
+            if time_only && !TIME_CHARS.contains(c) {
+                return Err(PyAttributeError::new_err(format!(
+                    "'TimeFormat' object has no attribute '{c}'"
+                )));
+            }
+            self.push_format_char(&mut output, c)?;
+        }
+        push_unescaped(&mut output, &literal);
+        Ok(output)
+    }
+
+    fn attribute<T>(&self, name: &str) -> PyResult<T>
+    where
+        T: for<'b> FromPyObject<'b, 'py, Error = PyErr>,
+    {
+        self.value.getattr(name)?.extract()
+    }
+
+    fn year(&self) -> PyResult<i32> {
+        match &self.fields {
+            Fields::Date(date) => Ok(date.get_year()),
+            Fields::DateTime(datetime) => Ok(datetime.get_year()),
+            _ => self.attribute("year"),
+        }
+    }
+
+    fn month(&self) -> PyResult<u8> {
+        match &self.fields {
+            Fields::Date(date) => Ok(date.get_month()),
+            Fields::DateTime(datetime) => Ok(datetime.get_month()),
+            _ => self.attribute("month"),
+        }
+    }
+
+    fn day(&self) -> PyResult<u8> {
+        match &self.fields {
+            Fields::Date(date) => Ok(date.get_day()),
+            Fields::DateTime(datetime) => Ok(datetime.get_day()),
+            _ => self.attribute("day"),
+        }
+    }
+
+    fn hour(&self) -> PyResult<u8> {
+        match &self.fields {
+            Fields::DateTime(datetime) => Ok(datetime.get_hour()),
+            Fields::Time(time) => Ok(time.get_hour()),
+            _ => self.attribute("hour"),
+        }
+    }
+
+    fn minute(&self) -> PyResult<u8> {
+        match &self.fields {
+            Fields::DateTime(datetime) => Ok(datetime.get_minute()),
+            Fields::Time(time) => Ok(time.get_minute()),
+            _ => self.attribute("minute"),
+        }
+    }
+
+    fn second(&self) -> PyResult<u8> {
+        match &self.fields {
+            Fields::DateTime(datetime) => Ok(datetime.get_second()),
+            Fields::Time(time) => Ok(time.get_second()),
+            _ => self.attribute("second"),
+        }
+    }
+
+    fn microsecond(&self) -> PyResult<u32> {
+        match &self.fields {
+            Fields::DateTime(datetime) => Ok(datetime.get_microsecond()),
+            Fields::Time(time) => Ok(time.get_microsecond()),
+            _ => self.attribute("microsecond"),
+        }
+    }
+
+    /// The date as stored by `date`, which its methods like `weekday` use
+    /// even when a subclass overrides the `year`, `month` or `day` properties.
+    fn stored_date(&self, method: &str) -> PyResult<(i32, u8, u8)> {
+        match self.value.cast::<PyDate>() {
+            Ok(date) => Ok((date.get_year(), date.get_month(), date.get_day())),
+            Err(_) => Err(PyAttributeError::new_err(format!(
+                "'{}' object has no attribute '{method}'",
+                self.value.get_type().name()?
+            ))),
+        }
+    }
+
+    /// The time zone used by the time zone format characters, like the
+    /// `timezone` attribute of Django's `TimeFormat`.
+    fn timezone(&self) -> PyResult<Option<&Bound<'py, PyAny>>> {
+        if let Some(timezone) = self.timezone.get() {
+            return Ok(timezone.as_ref());
+        }
+        let timezone = self.find_timezone()?;
+        Ok(self.timezone.get_or_init(|| timezone).as_ref())
+    }
+
+    fn find_timezone(&self) -> PyResult<Option<Bound<'py, PyAny>>> {
+        if !self.value.is_instance_of::<PyDateTime>() {
+            return Ok(None);
+        }
+        let py = self.value.py();
+        let timezone = match is_naive(self.value)? {
+            true => default_timezone(py)?,
+            false => self.value.getattr(intern!(py, "tzinfo"))?,
+        };
+        // Django leaves out the time zone of ambiguous or imaginary datetimes.
+        let fold = self.value.getattr(intern!(py, "fold"))?.is_truthy()?;
+        let kwargs = PyDict::new(py);
+        kwargs.set_item(intern!(py, "fold"), u8::from(!fold))?;
+        let folded = self
+            .value
+            .call_method(intern!(py, "replace"), (), Some(&kwargs))?;
+        let utcoffset = intern!(py, "utcoffset");
+        let offset = timezone.call_method1(utcoffset, (self.value,))?;
+        let folded_offset = timezone.call_method1(utcoffset, (folded,))?;
+        match folded_offset.ne(offset)? {
+            true => Ok(None),
+            false => Ok(Some(timezone)),
+        }
+    }
+
+    fn offset_seconds(&self, timezone: &Bound<'py, PyAny>) -> PyResult<i64> {
+        let offset = timezone.call_method1(intern!(self.value.py(), "utcoffset"), (self.value,))?;
+        Ok(offset_microseconds(&offset)?.0)
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn push_format_char(&self, output: &mut String, c: char) -> PyResult<()> {
+        let py = self.value.py();
+        let locale = self.locale;
+        match c {
+            'a' => output.push_str(match self.hour()? > 11 {
+                true => &locale.pm,
+                false => &locale.am,
+            }),
+            'A' => output.push_str(match self.hour()? > 11 {
+                true => &locale.pm_upper,
+                false => &locale.am_upper,
+            }),
+            'b' => output.push_str(month_name(&locale.months_3, self.month()?)?),
+            'c' => self.push_isoformat(output)?,
+            'd' => write!(output, "{:02}", self.day()?).expect("Writing to a String cannot fail"),
+            'D' => {
+                let (year, month, day) = self.stored_date("weekday")?;
+                output.push_str(&locale.weekdays_abbr[weekday(year, month, day)]);
+            }
+            'e' => self.push_timezone_name(output)?,
+            'E' => output.push_str(month_name(&locale.months_alt, self.month()?)?),
+            'f' => self.push_hour_minute(output)?,
+            'F' => output.push_str(month_name(&locale.months, self.month()?)?),
+            'g' => write!(output, "{}", twelve_hour(self.hour()?))
+                .expect("Writing to a String cannot fail"),
+            'G' => write!(output, "{}", self.hour()?).expect("Writing to a String cannot fail"),
+            'h' => write!(output, "{:02}", twelve_hour(self.hour()?))
+                .expect("Writing to a String cannot fail"),
+            'H' => write!(output, "{:02}", self.hour()?).expect("Writing to a String cannot fail"),
+            'i' => {
+                write!(output, "{:02}", self.minute()?).expect("Writing to a String cannot fail");
+            }
+            'I' => {
+                if let Some(timezone) = self.timezone()? {
+                    let dst = timezone.call_method1(intern!(py, "dst"), (self.value,))?;
+                    output.push(if dst.is_truthy()? { '1' } else { '0' });
+                }
+            }
+            'j' => write!(output, "{}", self.day()?).expect("Writing to a String cannot fail"),
+            'l' => {
+                let (year, month, day) = self.stored_date("weekday")?;
+                output.push_str(&locale.weekdays[weekday(year, month, day)]);
+            }
+            'L' => output.push_str(match is_leap(self.year()?) {
+                true => "True",
+                false => "False",
+            }),
+            'm' => write!(output, "{:02}", self.month()?).expect("Writing to a String cannot fail"),
+            'M' => output.push_str(month_name(&locale.months_3_title, self.month()?)?),
+            'n' => write!(output, "{}", self.month()?).expect("Writing to a String cannot fail"),
+            'N' => output.push_str(month_name(&locale.months_ap, self.month()?)?),
+            'o' => {
+                let (year, month, day) = self.stored_date("isocalendar")?;
+                write!(output, "{}", iso_calendar(year, month, day).0)
+                    .expect("Writing to a String cannot fail");
+            }
+            'O' => {
+                if let Some(timezone) = self.timezone()? {
+                    let seconds = self.offset_seconds(timezone)?;
+                    let sign = if seconds < 0 { '-' } else { '+' };
+                    let seconds = seconds.abs();
+                    write!(
+                        output,
+                        "{sign}{:02}{:02}",
+                        seconds / 3600,
+                        seconds / 60 % 60
+                    )
+                    .expect("Writing to a String cannot fail");
+                }
+            }
+            'P' => match (self.hour()?, self.minute()?) {
+                (0, 0) => output.push_str(&locale.midnight),
+                (12, 0) => output.push_str(&locale.noon),
+                (hour, _) => {
+                    self.push_hour_minute(output)?;
+                    output.push(' ');
+                    output.push_str(match hour > 11 {
+                        true => &locale.pm,
+                        false => &locale.am,
+                    });
+                }
+            },
+            'r' => self.push_rfc5322(output)?,
+            's' => {
+                write!(output, "{:02}", self.second()?).expect("Writing to a String cannot fail")
+            }
+            'S' => output.push_str(match self.day()? {
+                11..=13 => "th",
+                day => match day % 10 {
+                    1 => "st",
+                    2 => "nd",
+                    3 => "rd",
+                    _ => "th",
+                },
+            }),
+            't' => {
+                let (year, month) = (self.year()?, self.month()?);
+                if !(1..=12).contains(&month) {
+                    return Err(PyValueError::new_err(format!(
+                        "bad month number {month}; must be 1-12"
+                    )));
+                }
+                write!(output, "{}", days_in_month(year, month))
+                    .expect("Writing to a String cannot fail");
+            }
+            'T' => {
+                if let Some(timezone) = self.timezone()? {
+                    output.push_str(
+                        &timezone
+                            .call_method1(intern!(py, "tzname"), (self.value,))?
+                            .str()?
+                            .to_cow()?,
+                    );
+                }
+            }
+            'u' => write!(output, "{:06}", self.microsecond()?)
+                .expect("Writing to a String cannot fail"),
+            'U' => self.push_timestamp(output)?,
+            'w' => {
+                let (year, month, day) = self.stored_date("weekday")?;
+                write!(output, "{}", (weekday(year, month, day) + 1) % 7)
+                    .expect("Writing to a String cannot fail");
+            }
+            'W' => {
+                let (year, month, day) = self.stored_date("isocalendar")?;
+                write!(output, "{}", iso_calendar(year, month, day).1)
+                    .expect("Writing to a String cannot fail");
+            }
+            'y' => write!(output, "{:02}", self.year()?.rem_euclid(100))
+                .expect("Writing to a String cannot fail"),
+            'Y' => write!(output, "{:04}", self.year()?).expect("Writing to a String cannot fail"),
+            'z' => {
+                let (year, month, day) = self.stored_date("timetuple")?;
+                write!(
+                    output,
+                    "{}",
+                    ordinal(year, month, day) - ordinal(year, 1, 1) + 1
+                )
+                .expect("Writing to a String cannot fail");
+            }
+            'Z' => {
+                if let Some(timezone) = self.timezone()? {
+                    write!(output, "{}", self.offset_seconds(timezone)?)
+                        .expect("Writing to a String cannot fail");
+                }
+            }
+            _ => unreachable!("Only format characters are formatted"),
+        }
+        Ok(())
+    }
+
+    /// Append the hour, and the minutes if there are any, like Django's `f`.
+    fn push_hour_minute(&self, output: &mut String) -> PyResult<()> {
+        let hour = twelve_hour(self.hour()?);
+        match self.minute()? {
+            0 => write!(output, "{hour}"),
+            minute => write!(output, "{hour}:{minute:02}"),
+        }
+        .expect("Writing to a String cannot fail");
+        Ok(())
+    }
+
+    fn push_timezone_name(&self, output: &mut String) -> PyResult<()> {
+        match self.timezone()? {
+            Some(timezone) if timezone.is_truthy()? => {}
+            _ => return Ok(()),
+        }
+        let py = self.value.py();
+        if !self.value.getattr(intern!(py, "tzinfo"))?.is_truthy()? {
+            return Ok(());
+        }
+        match self.value.call_method0(intern!(py, "tzname")) {
+            Ok(name) if name.is_truthy()? => output.push_str(&name.str()?.to_cow()?),
+            Ok(_) => {}
+            Err(error) if error.is_instance_of::<PyNotImplementedError>(py) => {}
+            Err(error) => return Err(error),
+        }
+        Ok(())
+    }
+
+    fn push_isoformat(&self, output: &mut String) -> PyResult<()> {
+        let py = self.value.py();
+        match &self.fields {
+            Fields::Date(date) => write!(
+                output,
+                "{:04}-{:02}-{:02}",
+                date.get_year(),
+                date.get_month(),
+                date.get_day()
+            )
+            .expect("Writing to a String cannot fail"),
+            Fields::DateTime(datetime) if datetime.get_tzinfo().is_none() => {
+                write!(
+                    output,
+                    "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+                    datetime.get_year(),
+                    datetime.get_month(),
+                    datetime.get_day(),
+                    datetime.get_hour(),
+                    datetime.get_minute(),
+                    datetime.get_second()
+                )
+                .expect("Writing to a String cannot fail");
+                if datetime.get_microsecond() != 0 {
+                    write!(output, ".{:06}", datetime.get_microsecond())
+                        .expect("Writing to a String cannot fail");
+                }
+            }
+            Fields::Time(time) if time.get_tzinfo().is_none() => {
+                write!(
+                    output,
+                    "{:02}:{:02}:{:02}",
+                    time.get_hour(),
+                    time.get_minute(),
+                    time.get_second()
+                )
+                .expect("Writing to a String cannot fail");
+                if time.get_microsecond() != 0 {
+                    write!(output, ".{:06}", time.get_microsecond())
+                        .expect("Writing to a String cannot fail");
+                }
+            }
+            _ => output.push_str(
+                &self
+                    .value
+                    .call_method0(intern!(py, "isoformat"))?
+                    .str()?
+                    .to_cow()?,
+            ),
+        }
+        Ok(())
+    }
+
+    /// Append the value as an aware datetime in RFC 5322 format, like
+    /// `email.utils.format_datetime`.
+    fn push_rfc5322(&self, output: &mut String) -> PyResult<()> {
+        let py = self.value.py();
+        let value = match self.value.cast::<PyDateTime>() {
+            Ok(datetime) if is_naive(datetime)? => {
+                let timezone = match self.timezone()? {
+                    Some(timezone) => timezone.clone(),
+                    None => current_timezone(py)?,
+                };
+                let kwargs = PyDict::new(py);
+                kwargs.set_item(intern!(py, "tzinfo"), timezone)?;
+                datetime
+                    .call_method(intern!(py, "replace"), (), Some(&kwargs))?
+                    .cast_into::<PyDateTime>()?
+            }
+            Ok(datetime) => datetime.clone(),
+            Err(_) => {
+                let (year, month, day) = (self.year()?, self.month()?, self.day()?);
+                let timezone = default_timezone(py)?;
+                PyDateTime::new(
+                    py,
+                    year,
+                    month,
+                    day,
+                    0,
+                    0,
+                    0,
+                    0,
+                    Some(timezone.cast::<PyTzInfo>()?),
+                )?
+            }
+        };
+        let (year, month, day) = (value.get_year(), value.get_month(), value.get_day());
+        write!(
+            output,
+            "{}, {:02} {} {:04} {:02}:{:02}:{:02} ",
+            WEEKDAYS_RFC[weekday(year, month, day)],
+            day,
+            MONTHS_RFC[usize::from(month) - 1],
+            year,
+            value.get_hour(),
+            value.get_minute(),
+            value.get_second()
+        )
+        .expect("Writing to a String cannot fail");
+        match value.get_tzinfo() {
+            Some(_) => push_utc_offset(output, &value.call_method0(intern!(py, "utcoffset"))?)?,
+            None => output.push_str("-0000"),
+        }
+        Ok(())
+    }
+
+    fn push_timestamp(&self, output: &mut String) -> PyResult<()> {
+        let py = self.value.py();
+        let value = match self.value.is_instance_of::<PyDateTime>() {
+            true => self.value.clone(),
+            false => {
+                let (year, month, day) = (self.year()?, self.month()?, self.day()?);
+                PyDateTime::new(py, year, month, day, 0, 0, 0, 0, None)?.into_any()
+            }
+        };
+        let timestamp = value.call_method0(intern!(py, "timestamp"))?;
+        let timestamp = PyType::new::<PyInt>(py).call1((timestamp,))?;
+        output.push_str(&timestamp.str()?.to_cow()?);
+        Ok(())
+    }
+}
+
+/// A datetime as the naive fields used by Python's datetime arithmetic, with
+/// its `tzinfo`.
+struct Moment<'py> {
+    year: i32,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+    microsecond: u32,
+    tzinfo: Option<Bound<'py, PyAny>>,
+}
+
+impl<'py> Moment<'py> {
+    /// Convert a `date` or `datetime`, with dates at midnight.
+    fn new(value: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(datetime) = value.cast::<PyDateTime>() {
+            return Ok(Self {
+                year: datetime.get_year(),
+                month: datetime.get_month(),
+                day: datetime.get_day(),
+                hour: datetime.get_hour(),
+                minute: datetime.get_minute(),
+                second: datetime.get_second(),
+                microsecond: datetime.get_microsecond(),
+                tzinfo: datetime.get_tzinfo().map(Bound::into_any),
+            });
+        }
+        let py = value.py();
+        Ok(Self {
+            year: value.getattr(intern!(py, "year"))?.extract()?,
+            month: value.getattr(intern!(py, "month"))?.extract()?,
+            day: value.getattr(intern!(py, "day"))?.extract()?,
+            hour: 0,
+            minute: 0,
+            second: 0,
+            microsecond: 0,
+            tzinfo: None,
+        })
+    }
+
+    fn to_py(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDateTime>> {
+        let tzinfo = match &self.tzinfo {
+            Some(tzinfo) => Some(tzinfo.cast::<PyTzInfo>()?),
+            None => None,
+        };
+        PyDateTime::new(
+            py,
+            self.year,
+            self.month,
+            self.day,
+            self.hour,
+            self.minute,
+            self.second,
+            self.microsecond,
+            tzinfo,
+        )
+    }
+
+    /// The UTC offset in microseconds, or `None` if the datetime is naive.
+    fn utcoffset(&self, py: Python<'py>) -> PyResult<Option<i64>> {
+        if self.tzinfo.is_none() {
+            return Ok(None);
+        }
+        let offset = self.to_py(py)?.call_method0(intern!(py, "utcoffset"))?;
+        if offset.is_none() {
+            return Ok(None);
+        }
+        let (seconds, microseconds) = offset_microseconds(&offset)?;
+        Ok(Some(seconds * 1_000_000 + microseconds))
+    }
+
+    fn naive_microseconds(&self) -> i64 {
+        let days = i64::from(ordinal(self.year, self.month, self.day));
+        let seconds =
+            i64::from(self.hour) * 3600 + i64::from(self.minute) * 60 + i64::from(self.second);
+        (days * 86400 + seconds) * 1_000_000 + i64::from(self.microsecond)
+    }
+
+    fn time(&self) -> (u8, u8, u8, u32) {
+        (self.hour, self.minute, self.second, self.microsecond)
+    }
+
+    /// Subtract `other` in microseconds, like subtracting datetimes.
+    fn sub(&self, other: &Self, py: Python<'py>) -> PyResult<i64> {
+        let naive = self.naive_microseconds() - other.naive_microseconds();
+        match (&self.tzinfo, &other.tzinfo) {
+            (None, None) => return Ok(naive),
+            (Some(tzinfo), Some(other)) if tzinfo.is(other) => return Ok(naive),
+            _ => {}
+        }
+        match (self.utcoffset(py)?, other.utcoffset(py)?) {
+            (Some(offset), Some(other_offset)) => Ok(naive - offset + other_offset),
+            (None, None) => Ok(naive),
+            _ => Err(PyTypeError::new_err(
+                "can't subtract offset-naive and offset-aware datetimes",
+            )),
+        }
+    }
+}
+
+/// Divide floats like Python's `//`.
+fn float_floor_div(dividend: f64, divisor: f64) -> f64 {
+    let modulo = dividend % divisor;
+    let mut div = (dividend - modulo) / divisor;
+    if modulo != 0.0 && (divisor < 0.0) != (modulo < 0.0) {
+        div -= 1.0;
+    }
+    if div == 0.0 {
+        return 0.0_f64.copysign(dividend / divisor);
+    }
+    let floor = div.floor();
+    match div - floor > 0.5 {
+        true => floor + 1.0,
+        false => floor,
+    }
+}
+
+fn time_string(
+    py: Python<'_>,
+    index: usize,
+    count: i64,
+    context: Option<&str>,
+) -> PyResult<String> {
+    let (singular, plural) = TIME_STRINGS[index];
+    let time_string = match context {
+        Some(context) => npgettext(py, context, singular, plural, count)?,
+        None => ngettext(py, singular, plural, count)?,
+    };
+    Ok(time_string
+        .replace("%(num)d", &count.to_string())
+        .replace(' ', "\u{a0}"))
+}
+
+/// Describe the time between `value` and `now`, like Django's `timesince`,
+/// or the time until `value` when `reversed` is set.
+///
+/// The units are translated with `context` when given, like the
+/// `time_strings` used by `django.contrib.humanize`. Values other than dates
+/// are passed to Django's `timesince`.
+pub fn timesince(
+    value: &Bound<'_, PyAny>,
+    now: Option<&Bound<'_, PyAny>>,
+    reversed: bool,
+    depth: usize,
+    context: Option<&str>,
+) -> PyResult<String> {
+    let py = value.py();
+    let is_date = |value: &Bound<'_, PyAny>| value.is_instance_of::<PyDate>();
+    if !is_date(value) || !now.is_none_or(is_date) {
+        let timesince = TIMESINCE.import(py, "django.utils.timesince", "timesince")?;
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("reversed", reversed)?;
+        kwargs.set_item("depth", depth)?;
+        return timesince.call((value, now), Some(&kwargs))?.extract();
+    }
+    if depth == 0 {
+        return Err(PyValueError::new_err("depth must be greater than 0."));
+    }
+    let mut d = Moment::new(value)?;
+    let mut now = match now {
+        Some(now) => Moment::new(now)?,
+        None => {
+            let tzinfo = match d.utcoffset(py)? {
+                Some(_) => d.tzinfo.clone(),
+                None => None,
+            };
+            let now = PyType::new::<PyDateTime>(py).call_method1(intern!(py, "now"), (tzinfo,))?;
+            Moment::new(&now)?
+        }
+    };
+    if now.utcoffset(py)?.is_some() && d.utcoffset(py)?.is_none() {
+        d.tzinfo.clone_from(&now.tzinfo);
+    }
+    if reversed {
+        std::mem::swap(&mut d, &mut now);
+    }
+
+    // Ignore microseconds.
+    let since = now.sub(&d, py)?.div_euclid(1_000_000);
+    if since <= 0 {
+        return time_string(py, 5, 0, context);
+    }
+
+    let mut total_months =
+        (i64::from(now.year) - i64::from(d.year)) * 12 + i64::from(now.month) - i64::from(d.month);
+    if d.day > now.day || (d.day == now.day && d.time() > now.time()) {
+        total_months -= 1;
+    }
+    let (years, months) = (total_months.div_euclid(12), total_months.rem_euclid(12));
+
+    // Measure the rest from a "pivot" shifted from d by the years and months.
+    let remaining = if years != 0 || months != 0 {
+        let mut pivot_year = i64::from(d.year) + years;
+        let mut pivot_month = i64::from(d.month) + months;
+        if pivot_month > 12 {
+            pivot_month -= 12;
+            pivot_year += 1;
+        }
+        if !(1..=9999).contains(&pivot_year) {
+            return Err(PyValueError::new_err(format!(
+                "year {pivot_year} is out of range"
+            )));
+        }
+        let pivot_month = u8::try_from(pivot_month).expect("Months are between 1 and 12");
+        let pivot = Moment {
+            year: i32::try_from(pivot_year).expect("Years are between 1 and 9999"),
+            month: pivot_month,
+            day: d.day.min(MONTHS_DAYS[usize::from(pivot_month) - 1]),
+            hour: d.hour,
+            minute: d.minute,
+            second: d.second,
+            microsecond: 0,
+            tzinfo: d.tzinfo.clone(),
+        };
+        now.sub(&pivot, py)?
+    } else {
+        now.sub(&d, py)?
+    };
+
+    // Python counts the rest as float seconds, which is exact for the less
+    // than a month remaining.
+    #[allow(clippy::cast_precision_loss)]
+    let mut remaining_time = remaining as f64 / 1_000_000.0;
+    let mut partials = vec![years, months];
+    for chunk in TIME_CHUNKS {
+        #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+        let count = float_floor_div(remaining_time, chunk as f64) as i64;
+        partials.push(count);
+        #[allow(clippy::cast_precision_loss)]
+        let chunk_time = (chunk * count) as f64;
+        remaining_time -= chunk_time;
+    }
+
+    let Some(first) = partials.iter().position(|partial| *partial != 0) else {
+        return time_string(py, 5, 0, context);
+    };
+    let parts = partials[first..]
+        .iter()
+        .take(depth)
+        .take_while(|partial| **partial != 0)
+        .enumerate()
+        .map(|(index, partial)| time_string(py, first + index, *partial, context))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(parts.join(&gettext(py, ", ")?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ordinal() {
+        assert_eq!(ordinal(1, 1, 1), 1);
+        assert_eq!(ordinal(2000, 3, 1), 730_180);
+        assert_eq!(weekday(2024, 1, 1), 0);
+        assert_eq!(weekday(2008, 1, 6), 6);
+    }
+
+    #[test]
+    fn test_iso_calendar() {
+        assert_eq!(iso_calendar(2008, 12, 29), (2009, 1));
+        assert_eq!(iso_calendar(2010, 1, 3), (2009, 53));
+        assert_eq!(iso_calendar(2024, 6, 15), (2024, 24));
+    }
+
+    #[test]
+    fn test_iso_calendar_matches_python() {
+        Python::initialize();
+        Python::attach(|py| {
+            let date = PyType::new::<PyDate>(py);
+            for (year, month, day) in [(1, 1, 1), (2004, 12, 31), (2005, 1, 2), (9999, 12, 31)] {
+                let expected: (i32, i32, i32) = date
+                    .call1((year, month, day))
+                    .unwrap()
+                    .call_method0("isocalendar")
+                    .unwrap()
+                    .extract()
+                    .unwrap();
+                assert_eq!(iso_calendar(year, month, day), (expected.0, expected.1));
+            }
+        });
+    }
+
+    fn english() -> DateLocale {
+        let strings = |names: &[&str]| names.iter().map(ToString::to_string).collect();
+        let months = [
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ];
+        let months_3 = MONTHS_RFC.map(str::to_lowercase);
+        DateLocale {
+            months: strings(&months),
+            months_3: months_3.to_vec(),
+            months_3_title: strings(&MONTHS_RFC),
+            months_alt: strings(&months),
+            months_ap: strings(&MONTHS_RFC).into_iter().map(|m| m + ".").collect(),
+            weekdays: strings(&[
+                "Monday",
+                "Tuesday",
+                "Wednesday",
+                "Thursday",
+                "Friday",
+                "Saturday",
+                "Sunday",
+            ]),
+            weekdays_abbr: strings(&WEEKDAYS_RFC),
+            am: "a.m.".to_string(),
+            pm: "p.m.".to_string(),
+            am_upper: "AM".to_string(),
+            pm_upper: "PM".to_string(),
+            midnight: "midnight".to_string(),
+            noon: "noon".to_string(),
+            formats: strings(&DATE_FORMATS),
+        }
+    }
+
+    #[test]
+    fn test_format() {
+        Python::initialize();
+        Python::attach(|py| {
+            let locale = english();
+            let value = PyDateTime::new(py, 2008, 1, 6, 15, 5, 9, 120, None)
+                .unwrap()
+                .into_any();
+            let format = DateFormat::new(&value, &locale);
+            assert_eq!(
+                format
+                    .format("d D j l S w z W F m M n N y Y L o b t", false)
+                    .unwrap(),
+                "06 Sun 6 Sunday th 0 6 1 January 01 Jan 1 Jan. 08 2008 True 2008 jan 31"
+            );
+            assert_eq!(
+                format.format("a A P f g G h H i s u c", false).unwrap(),
+                "p.m. PM 3:05 p.m. 3:05 3 15 03 15 05 09 000120 2008-01-06T15:05:09.000120"
+            );
+            assert_eq!(
+                format.format("jS \\o\\f F", false).unwrap(),
+                "6th of January"
+            );
+        });
+    }
+
+    #[test]
+    fn test_format_errors() {
+        Python::initialize();
+        Python::attach(|py| {
+            let locale = english();
+            let date = PyDate::new(py, 2008, 1, 6).unwrap().into_any();
+            let error = DateFormat::new(&date, &locale)
+                .format("Y H", false)
+                .unwrap_err();
+            assert!(error.is_instance_of::<PyTypeError>(py));
+            let time = PyTime::new(py, 0, 0, 0, 0, None).unwrap().into_any();
+            let format = DateFormat::new(&time, &locale);
+            assert_eq!(format.format("P", true).unwrap(), "midnight");
+            let error = format.format("Y", true).unwrap_err();
+            assert!(error.is_instance_of::<PyAttributeError>(py));
+            let error = format.format("Y", false).unwrap_err();
+            assert!(error.is_instance_of::<PyAttributeError>(py));
+        });
+    }
+
+    #[test]
+    fn test_push_unescaped() {
+        let mut output = String::new();
+        push_unescaped(&mut output, "\\o\\f \\\\ \\\n end\\");
+        assert_eq!(output, "of \\ \\\n end\\");
+    }
+
+    #[test]
+    fn test_float_floor_div() {
+        assert_eq!(float_floor_div(7.5, 2.0), 3.0);
+        assert_eq!(float_floor_div(-7.5, 2.0), -4.0);
+        assert_eq!(float_floor_div(604_799.999_999, 604_800.0), 0.0);
+    }
+}
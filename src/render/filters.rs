@@ -1,30 +1,72 @@
 use std::borrow::Cow;
 
-use html_escape::encode_quoted_attribute_to_string;
-use num_traits::{ToPrimitive, Zero};
+use html_escape::{encode_quoted_attribute, encode_quoted_attribute_to_string};
+use num_bigint::{BigInt, ToBigInt};
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use pyo3::exceptions::{
+    PyAttributeError, PyException, PyIndexError, PyOverflowError, PyRecursionError, PyTypeError,
+    PyValueError,
+};
+use pyo3::intern;
 use pyo3::prelude::*;
 use pyo3::sync::PyOnceLock;
-use pyo3::types::PyType;
-use pyo3::types::{PyDate, PyDateTime, PyTime};
+use pyo3::types::{
+    PyBool, PyDict, PyFloat, PyInt, PyList, PyRange, PySlice, PyString, PyTuple, PyType,
+};
+use pyo3::types::{PyDate, PyTime};
+use rand::RngExt;
 
 use crate::error::{AnnotatePyErr, PyRenderError, RenderError};
 use crate::filters::{
-    AddFilter, AddSlashesFilter, CapfirstFilter, CenterFilter, CutFilter, DateFilter,
-    DefaultFilter, DefaultIfNoneFilter, DivisibleByFilter, EscapeFilter, EscapejsFilter,
-    ExternalFilter, FilterType, ForceEscapeFilter, LastFilter, LengthFilter, LowerFilter,
-    SafeFilter, SlugifyFilter, TitleFilter, UpperFilter, WordcountFilter, WordwrapFilter,
-    YesnoFilter,
+    AddFilter, AddSlashesFilter, ApnumberFilter, CapfirstFilter, CenterFilter, CutFilter,
+    DateFilter, DefaultFilter, DefaultIfNoneFilter, DictsortFilter, DictsortReversedFilter,
+    DivisibleByFilter, EscapeFilter, EscapejsFilter, EscapeseqFilter, ExternalFilter,
+    FilesizeformatFilter, FilterType, FirstFilter, FloatformatFilter, ForceEscapeFilter,
+    GetDigitFilter, IntcommaFilter, IntwordFilter, IriencodeFilter, JoinFilter, JsonScriptFilter,
+    LastFilter, LengthFilter, LinebreaksFilter, LinebreaksbrFilter, LinenumbersFilter, LjustFilter,
+    LowerFilter, MakeListFilter, NaturaldayFilter, NaturaltimeFilter, OrdinalFilter,
+    Phone2numericFilter, PluralizeFilter, PprintFilter, RandomFilter, RjustFilter, SafeFilter,
+    SafeseqFilter, SliceFilter, SlugifyFilter, StringformatFilter, StriptagsFilter, TimeFilter,
+    TimesinceFilter, TimeuntilFilter, TitleFilter, TruncatecharsFilter, TruncatecharsHtmlFilter,
+    TruncatewordsFilter, TruncatewordsHtmlFilter, UnorderedListFilter, UpperFilter,
+    UrlencodeFilter, UrlizeFilter, UrlizetruncFilter, WordcountFilter, WordwrapFilter, YesnoFilter,
 };
 use crate::parse::Filter;
-use crate::render::common::gettext;
+use crate::render::common::{get_item_or_attr, gettext, ngettext, pgettext};
+use crate::render::dateformat::{DateFormat, date_locale, template_localtime, timesince};
+use crate::render::humanize;
+use crate::render::json;
+use crate::render::numberformat::{
+    NumberFormat, ParsedDecimal, floatformat, format_decimal, format_float, format_number,
+};
+use crate::render::stringformat::{FormatValue, StringFormat, float_repr};
+use crate::render::truncate::{
+    truncate_chars, truncate_chars_html, truncate_words, truncate_words_html,
+};
 use crate::render::types::{AsBorrowedContent, Content, ContentString, Context, IntoOwnedContent};
+use crate::render::url::quote;
+use crate::render::urlize::Urlizer;
 use crate::render::{Resolve, ResolveFailures, ResolveResult};
-use dtl_lexer::types::TemplateString;
+use crate::types::Argument;
+use crate::utils::PyResultMethods;
+use dtl_lexer::types::{At, TemplateString, Variable};
 use unicode_normalization::UnicodeNormalization;
 
 static SAFEDATA: PyOnceLock<Py<PyType>> = PyOnceLock::new();
 static GET_FORMAT: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
-static DATE_FORMAT: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static DECIMAL: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static GET_LANGUAGE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static SETTINGS: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static SORTED: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static PFORMAT: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static HUMANIZE_INTCOMMA: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static STRIP_TAGS: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static GENERATOR_TYPE: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static NUMBER_FORMAT: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static MARK_SAFE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static SAFE_STRING: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+static JSON_DUMPS: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static DJANGO_JSON_ENCODER: PyOnceLock<Py<PyType>> = PyOnceLock::new();
 
 impl Resolve for Filter {
     fn resolve<'t, 'py>(
@@ -38,24 +80,65 @@ impl Resolve for Filter {
         match &self.filter {
             FilterType::Add(filter) => filter.resolve(left, py, template, context),
             FilterType::AddSlashes(filter) => filter.resolve(left, py, template, context),
+            FilterType::Apnumber(filter) => filter.resolve(left, py, template, context),
             FilterType::Capfirst(filter) => filter.resolve(left, py, template, context),
             FilterType::Center(filter) => filter.resolve(left, py, template, context),
             FilterType::Cut(filter) => filter.resolve(left, py, template, context),
             FilterType::Default(filter) => filter.resolve(left, py, template, context),
             FilterType::DefaultIfNone(filter) => filter.resolve(left, py, template, context),
+            FilterType::Dictsort(filter) => filter.resolve(left, py, template, context),
+            FilterType::DictsortReversed(filter) => filter.resolve(left, py, template, context),
             FilterType::DivisibleBy(filter) => filter.resolve(left, py, template, context),
             FilterType::Date(filter) => filter.resolve(left, py, template, context),
             FilterType::Escape(filter) => filter.resolve(left, py, template, context),
             FilterType::Escapejs(filter) => filter.resolve(left, py, template, context),
+            FilterType::Escapeseq(filter) => filter.resolve(left, py, template, context),
             FilterType::External(filter) => filter.resolve(left, py, template, context),
+            FilterType::Filesizeformat(filter) => filter.resolve(left, py, template, context),
+            FilterType::First(filter) => filter.resolve(left, py, template, context),
+            FilterType::Floatformat(filter) => filter.resolve(left, py, template, context),
             FilterType::ForceEscape(filter) => filter.resolve(left, py, template, context),
+            FilterType::GetDigit(filter) => filter.resolve(left, py, template, context),
+            FilterType::Intcomma(filter) => filter.resolve(left, py, template, context),
+            FilterType::Intword(filter) => filter.resolve(left, py, template, context),
+            FilterType::Iriencode(filter) => filter.resolve(left, py, template, context),
+            FilterType::Join(filter) => filter.resolve(left, py, template, context),
+            FilterType::JsonScript(filter) => filter.resolve(left, py, template, context),
             FilterType::Last(filter) => filter.resolve(left, py, template, context),
+            FilterType::Linebreaks(filter) => filter.resolve(left, py, template, context),
+            FilterType::Linebreaksbr(filter) => filter.resolve(left, py, template, context),
+            FilterType::Linenumbers(filter) => filter.resolve(left, py, template, context),
+            FilterType::Ljust(filter) => filter.resolve(left, py, template, context),
             FilterType::Lower(filter) => filter.resolve(left, py, template, context),
+            FilterType::MakeList(filter) => filter.resolve(left, py, template, context),
             FilterType::Length(filter) => filter.resolve(left, py, template, context),
+            FilterType::Naturalday(filter) => filter.resolve(left, py, template, context),
+            FilterType::Naturaltime(filter) => filter.resolve(left, py, template, context),
+            FilterType::Ordinal(filter) => filter.resolve(left, py, template, context),
+            FilterType::Phone2numeric(filter) => filter.resolve(left, py, template, context),
+            FilterType::Pluralize(filter) => filter.resolve(left, py, template, context),
+            FilterType::Pprint(filter) => filter.resolve(left, py, template, context),
+            FilterType::Random(filter) => filter.resolve(left, py, template, context),
+            FilterType::Rjust(filter) => filter.resolve(left, py, template, context),
             FilterType::Safe(filter) => filter.resolve(left, py, template, context),
+            FilterType::Safeseq(filter) => filter.resolve(left, py, template, context),
+            FilterType::Slice(filter) => filter.resolve(left, py, template, context),
             FilterType::Slugify(filter) => filter.resolve(left, py, template, context),
+            FilterType::Stringformat(filter) => filter.resolve(left, py, template, context),
+            FilterType::Striptags(filter) => filter.resolve(left, py, template, context),
+            FilterType::Time(filter) => filter.resolve(left, py, template, context),
+            FilterType::Timesince(filter) => filter.resolve(left, py, template, context),
+            FilterType::Timeuntil(filter) => filter.resolve(left, py, template, context),
             FilterType::Title(filter) => filter.resolve(left, py, template, context),
+            FilterType::Truncatechars(filter) => filter.resolve(left, py, template, context),
+            FilterType::TruncatecharsHtml(filter) => filter.resolve(left, py, template, context),
+            FilterType::Truncatewords(filter) => filter.resolve(left, py, template, context),
+            FilterType::TruncatewordsHtml(filter) => filter.resolve(left, py, template, context),
+            FilterType::UnorderedList(filter) => filter.resolve(left, py, template, context),
             FilterType::Upper(filter) => filter.resolve(left, py, template, context),
+            FilterType::Urlencode(filter) => filter.resolve(left, py, template, context),
+            FilterType::Urlize(filter) => filter.resolve(left, py, template, context),
+            FilterType::Urlizetrunc(filter) => filter.resolve(left, py, template, context),
             FilterType::Wordcount(filter) => filter.resolve(left, py, template, context),
             FilterType::Wordwrap(filter) => filter.resolve(left, py, template, context),
             FilterType::Yesno(filter) => filter.resolve(left, py, template, context),
@@ -149,6 +232,58 @@ impl ResolveFilter for CapfirstFilter {
     }
 }
 
+/// Where `pad` puts the content within the padding.
+#[derive(Clone, Copy)]
+enum Justify {
+    Left,
+    Center,
+    Right,
+}
+
+/// Pad `content` with spaces to `width` characters, like Python's
+/// `str.ljust`, `str.center` and `str.rjust`.
+fn pad(content: Cow<'_, str>, width: usize, justify: Justify) -> Cow<'_, str> {
+    let len = content.chars().count();
+    if width <= len {
+        return content;
+    }
+    let margin = width - len;
+    let left = match justify {
+        Justify::Left => 0,
+        // Python gives the extra space to the left when both are odd.
+        Justify::Center => margin / 2 + (margin & width & 1),
+        Justify::Right => margin,
+    };
+    let mut padded = String::with_capacity(content.len() + margin);
+    padded.push_str(&" ".repeat(left));
+    padded.push_str(&content);
+    padded.push_str(&" ".repeat(margin - left));
+    Cow::Owned(padded)
+}
+
+/// Resolve the content and width of `ljust`, `center` or `rjust`, then pad
+/// the content keeping its safety.
+fn resolve_pad<'t, 'py>(
+    variable: Option<Content<'t, 'py>>,
+    argument: &Argument,
+    justify: Justify,
+    py: Python<'py>,
+    template: TemplateString<'t>,
+    context: &mut Context,
+) -> ResolveResult<'t, 'py> {
+    let Some(content) = variable else {
+        return Ok(Some("".as_content()));
+    };
+    let content = content.resolve_string(context)?;
+    let width = argument
+        .resolve(py, template, context, ResolveFailures::Raise)?
+        .expect("missing argument in context should already have raised")
+        .resolve_usize(argument.at)?;
+    Ok(Some(
+        content.map_content(|content| pad(content, width, justify)),
+    ))
+}
+
 impl ResolveFilter for CenterFilter {
     fn resolve<'t, 'py>(
         &self,
@@ -157,36 +292,52 @@ impl ResolveFilter for CenterFilter {
         template: TemplateString<'t>,
         context: &mut Context,
     ) -> ResolveResult<'t, 'py> {
-        let Some(content) = variable else {
-            return Ok(Some("".as_content()));
-        };
-        let content = content.render(context)?;
-        let arg = self
-            .argument
-            .resolve(py, template, context, ResolveFailures::Raise)?
-            .expect("missing argument in context should already have raised");
-
-        let size = arg.resolve_usize(self.argument.at)?;
-
-        if size <= content.len() {
-            return Ok(Some(content.into_content()));
-        }
-        let round_up = size % 2 == 0 && content.len() % 2 != 0;
-        let right = if round_up {
-            // If the size is even and the content length is odd, we need to adjust the centering
-            (size - content.len()).div_ceil(2)
-        } else {
-            (size - content.len()) / 2
-        };
-        let left = size - content.len() - right;
-
-        let mut centered = String::with_capacity(size);
+        resolve_pad(
+            variable,
+            &self.argument,
+            Justify::Center,
+            py,
+            template,
+            context,
+        )
+    }
+}
 
-        centered.push_str(&" ".repeat(left));
-        centered.push_str(&content);
-        centered.push_str(&" ".repeat(right));
+impl ResolveFilter for LjustFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        resolve_pad(
+            variable,
+            &self.argument,
+            Justify::Left,
+            py,
+            template,
+            context,
+        )
+    }
+}
 
-        Ok(Some(centered.into_content()))
+impl ResolveFilter for RjustFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        resolve_pad(
+            variable,
+            &self.argument,
+            Justify::Right,
+            py,
+            template,
+            context,
+        )
     }
 }
 
@@ -272,6 +423,44 @@ impl ResolveFilter for DefaultIfNoneFilter {
     }
 }
 
+/// Resolve the format argument of `date` or `time`, which falls back to the
+/// default format when missing or falsy.
+fn date_format_argument(
+    argument: Option<&Argument>,
+    py: Python<'_>,
+    template: TemplateString<'_>,
+    context: &mut Context,
+) -> Result<Option<String>, PyRenderError> {
+    let Some(argument) = argument else {
+        return Ok(None);
+    };
+    let format = argument
+        .resolve(py, template, context, ResolveFailures::Raise)?
+        .expect("missing argument in context should already have raised");
+    match format.to_bool()? {
+        true => Ok(Some(
+            format.resolve_string(context)?.into_raw().into_owned(),
+        )),
+        false => Ok(None),
+    }
+}
+
+/// Format a date or time in the active language, like Django's
+/// `formats.date_format` and `formats.time_format`.
+fn format_date(
+    value: Bound<'_, PyAny>,
+    format: Option<&str>,
+    default: &str,
+    time_only: bool,
+    use_tz: Option<bool>,
+) -> PyResult<String> {
+    let py = value.py();
+    let value = template_localtime(value, use_tz)?;
+    let locale = date_locale(py)?;
+    let format = locale.format(py, format.unwrap_or(default))?;
+    DateFormat::new(&value, &locale).format(&format, time_only)
+}
+
 impl ResolveFilter for DateFilter {
     fn resolve<'t, 'py>(
         &self,
@@ -283,43 +472,271 @@ impl ResolveFilter for DateFilter {
         let Some(value) = variable else {
             return Ok(Some("".as_content()));
         };
+        let value = value.to_py(py);
+        if !value.is_instance_of::<PyDate>() && !value.is_instance_of::<PyTime>() {
+            return Ok(Some("".as_content()));
+        }
+        let format = date_format_argument(self.argument.as_ref(), py, template, context)?;
+        match format_date(
+            value,
+            format.as_deref(),
+            "DATE_FORMAT",
+            false,
+            context.use_tz,
+        ) {
+            Ok(formatted) => Ok(Some(Content::String(ContentString::HtmlUnsafe(
+                Cow::Owned(formatted),
+            )))),
+            Err(error) if error.is_instance_of::<PyAttributeError>(py) => Ok(Some("".as_content())),
+            Err(error) => Err(error.annotate(py, self.at, "here", template).into()),
+        }
+    }
+}
 
-        let fmt = match &self.argument {
-            Some(arg) => arg
-                .resolve(py, template, context, ResolveFailures::Raise)?
-                .expect("missing argument in context should already have raised")
-                .to_py(py),
-            None => {
-                let get_format = GET_FORMAT.import(py, "django.utils.formats", "get_format")?;
-                get_format.call1(("DATE_FORMAT",))?
-            }
+impl ResolveFilter for TimeFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let Some(value) = variable else {
+            return Ok(Some("".as_content()));
         };
-
         let value = value.to_py(py);
-
-        let is_valid = value.is_instance_of::<PyDate>()
-            || value.is_instance_of::<PyTime>()
-            || value.is_instance_of::<PyDateTime>();
-
-        if !is_valid {
+        if !value.is_instance_of::<PyDate>() && !value.is_instance_of::<PyTime>() {
             return Ok(Some("".as_content()));
         }
+        let format = date_format_argument(self.argument.as_ref(), py, template, context)?;
+        match format_date(
+            value,
+            format.as_deref(),
+            "TIME_FORMAT",
+            true,
+            context.use_tz,
+        ) {
+            Ok(formatted) => Ok(Some(Content::String(ContentString::HtmlUnsafe(
+                Cow::Owned(formatted),
+            )))),
+            Err(error)
+                if error.is_instance_of::<PyAttributeError>(py)
+                    || error.is_instance_of::<PyTypeError>(py) =>
+            {
+                Ok(Some("".as_content()))
+            }
+            Err(error) => Err(error.annotate(py, self.at, "here", template).into()),
+        }
+    }
+}
+
+/// Describe the time since or until a date, like Django's `timesince` and
+/// `timeuntil` filters.
+fn resolve_timesince<'t, 'py>(
+    variable: Option<Content<'t, 'py>>,
+    argument: Option<&Argument>,
+    reversed: bool,
+    at: At,
+    py: Python<'py>,
+    template: TemplateString<'t>,
+    context: &mut Context,
+) -> ResolveResult<'t, 'py> {
+    let Some(value) = variable else {
+        return Ok(Some("".as_content()));
+    };
+    if !value.to_bool()? {
+        return Ok(Some("".as_content()));
+    }
+    let now = match argument {
+        Some(argument) => {
+            let now = argument
+                .resolve(py, template, context, ResolveFailures::Raise)?
+                .expect("missing argument in context should already have raised");
+            match now.to_bool()? {
+                true => Some(now.to_py(py)),
+                false => None,
+            }
+        }
+        None => None,
+    };
+    match timesince(&value.to_py(py), now.as_ref(), reversed, 2, None) {
+        Ok(result) => Ok(Some(Content::String(ContentString::HtmlUnsafe(
+            Cow::Owned(result),
+        )))),
+        Err(error)
+            if error.is_instance_of::<PyValueError>(py)
+                || error.is_instance_of::<PyTypeError>(py) =>
+        {
+            Ok(Some("".as_content()))
+        }
+        Err(error) => Err(error.annotate(py, at, "here", template).into()),
+    }
+}
 
-        let date_format_fn = DATE_FORMAT.import(py, "django.utils.dateformat", "format")?;
+impl ResolveFilter for TimesinceFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let argument = self.argument.as_ref();
+        resolve_timesince(variable, argument, false, self.at, py, template, context)
+    }
+}
 
-        let formatted = match date_format_fn.call1((value, fmt)) {
-            Ok(res) => res,
-            Err(e) => {
-                if e.is_instance_of::<pyo3::exceptions::PyAttributeError>(py) {
-                    return Ok(Some("".as_content()));
+impl ResolveFilter for TimeuntilFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let argument = self.argument.as_ref();
+        resolve_timesince(variable, argument, true, self.at, py, template, context)
+    }
+}
+
+/// How `dictsort` finds the sort key of each item, like Django's
+/// `_property_resolver`.
+enum PropertyResolver<'py> {
+    /// Look up a number-like argument with `item[argument]`.
+    Item(Bound<'py, PyAny>),
+    /// Follow a dotted path of keys and attributes.
+    Path(String),
+}
+
+impl<'py> PropertyResolver<'py> {
+    /// Returns `None` for private paths, which Django forbids.
+    fn new(argument: Bound<'py, PyAny>) -> PyResult<Option<Self>> {
+        let py = argument.py();
+        match PyType::new::<PyFloat>(py).call1((&argument,)) {
+            Ok(_) => Ok(Some(Self::Item(argument))),
+            Err(error) if error.is_instance_of::<PyValueError>(py) => {
+                let path = argument.extract::<String>()?;
+                if path.is_empty() {
+                    return Err(PyIndexError::new_err("string index out of range"));
                 }
-                return Err(PyRenderError::PyErr(
-                    e.annotate(py, self.at, "here", template),
-                ));
+                if path.starts_with('_') || path.contains("._") {
+                    return Ok(None);
+                }
+                Ok(Some(Self::Path(path)))
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    fn resolve(&self, item: Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        match self {
+            Self::Item(argument) => item.get_item(argument),
+            Self::Path(path) => {
+                let variable = Variable::new((0, path.len()));
+                let parts = variable.parts(TemplateString(path));
+                // Python's `str.split` keeps a trailing empty part.
+                let trailing = path.ends_with('.').then_some("");
+                parts
+                    .map(|(part, _)| part)
+                    .chain(trailing)
+                    .try_fold(item, |item, part| get_item_or_attr(&item, part))
             }
+        }
+    }
+}
+
+/// Sort `value` like Django's `dictsort`, returning `None` where Django
+/// fails silently.
+fn dictsort<'py>(
+    value: &Bound<'py, PyAny>,
+    argument: Bound<'py, PyAny>,
+    reverse: bool,
+) -> PyResult<Option<Bound<'py, PyList>>> {
+    let py = value.py();
+    let sort = || {
+        let Some(resolver) = PropertyResolver::new(argument)? else {
+            return Err(PyAttributeError::new_err(
+                "Access to private variables is forbidden.",
+            ));
         };
+        let items = value.try_iter()?.collect::<PyResult<Vec<_>>>()?;
+        let keys = items
+            .iter()
+            .map(|item| resolver.resolve(item.clone()))
+            .collect::<PyResult<Vec<_>>>()?;
+        // Sort the indices with Python's `sorted`, so keys are compared
+        // exactly as Django compares them.
+        let keys = PyList::new(py, keys)?;
+        let kwargs = PyDict::new(py);
+        kwargs.set_item(
+            intern!(py, "key"),
+            keys.getattr(intern!(py, "__getitem__"))?,
+        )?;
+        kwargs.set_item(intern!(py, "reverse"), reverse)?;
+        let order = SORTED.import(py, "builtins", "sorted")?.call(
+            (PyRange::new(py, 0, items.len().cast_signed())?,),
+            Some(&kwargs),
+        )?;
+        let sorted = order
+            .try_iter()?
+            .map(|index| Ok(items[index?.extract::<usize>()?].clone()))
+            .collect::<PyResult<Vec<_>>>()?;
+        PyList::new(py, sorted)
+    };
+    match sort() {
+        Ok(sorted) => Ok(Some(sorted)),
+        Err(error)
+            if error.is_instance_of::<PyAttributeError>(py)
+                || error.is_instance_of::<PyTypeError>(py) =>
+        {
+            Ok(None)
+        }
+        Err(error) => Err(error),
+    }
+}
+
+fn resolve_dictsort<'t, 'py>(
+    argument: &Argument,
+    variable: Option<Content<'t, 'py>>,
+    reverse: bool,
+    py: Python<'py>,
+    template: TemplateString<'t>,
+    context: &mut Context,
+) -> ResolveResult<'t, 'py> {
+    let value = variable.unwrap_or_else(|| "".as_content()).to_py(py);
+    let key = argument
+        .resolve(py, template, context, ResolveFailures::Raise)?
+        .expect("missing argument in context should already have raised")
+        .to_py(py);
+    match dictsort(&value, key, reverse)
+        .map_err(|error| error.annotate(py, argument.at, "here", template))?
+    {
+        Some(sorted) => Ok(Some(Content::Py(sorted.into_any()))),
+        None => Ok(Some("".as_content())),
+    }
+}
+
+impl ResolveFilter for DictsortFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        resolve_dictsort(&self.argument, variable, false, py, template, context)
+    }
+}
 
-        Ok(Some(Content::Py(formatted)))
+impl ResolveFilter for DictsortReversedFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        resolve_dictsort(&self.argument, variable, true, py, template, context)
     }
 }
 
@@ -468,6 +885,43 @@ impl ResolveFilter for EscapejsFilter {
     }
 }
 
+/// Build a list from the items of `value`, like Django's `safeseq` and
+/// `escapeseq` do.
+fn map_items<'py>(
+    value: Option<Content<'_, 'py>>,
+    py: Python<'py>,
+    f: impl Fn(Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyList>> {
+    let value = match value {
+        Some(value) => value.to_py(py),
+        None => PyString::new(py, "").into_any(),
+    };
+    let items = value
+        .try_iter()?
+        .map(|item| f(item?))
+        .collect::<PyResult<Vec<_>>>()?;
+    PyList::new(py, items)
+}
+
+impl ResolveFilter for EscapeseqFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        _context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let safe_string = SAFE_STRING.import(py, "django.utils.safestring", "SafeString")?;
+        let escaped = map_items(variable, py, |item| {
+            safe_string.call1((conditional_escape(&item)?,))
+        });
+        match escaped {
+            Ok(escaped) => Ok(Some(Content::Py(escaped.into_any()))),
+            Err(error) => Err(error.annotate(py, self.at, "here", template).into()),
+        }
+    }
+}
+
 impl ResolveFilter for ExternalFilter {
     fn resolve<'t, 'py>(
         &self,
@@ -489,26 +943,230 @@ impl ResolveFilter for ExternalFilter {
     }
 }
 
-impl ResolveFilter for ForceEscapeFilter {
-    fn resolve<'t, 'py>(
+/// Parse a string like Python's `decimal.Decimal`, using Python itself for input
+/// outside the ASCII subset handled natively.
+fn parse_decimal(py: Python<'_>, value: &str) -> PyResult<Option<ParsedDecimal>> {
+    if let Some(decimal) = ParsedDecimal::parse(value) {
+        return Ok(Some(decimal));
+    }
+    if value.is_ascii() && !value.contains('_') {
+        return Ok(None);
+    }
+    let decimal = DECIMAL.import(py, "decimal", "Decimal")?;
+    Ok(match decimal.call1((value,)) {
+        Ok(decimal) => ParsedDecimal::parse(&decimal.str()?.to_cow()?),
+        Err(_) => None,
+    })
+}
+
+/// Refuse to print an integer with more digits than Python's
+/// `sys.get_int_max_str_digits()` allows, like `"%d" % int(d)` in Django.
+fn check_int_max_str_digits(py: Python<'_>, digits: u64) -> PyResult<()> {
+    // Python never sets the limit below 640 digits.
+    if digits <= 640 {
+        return Ok(());
+    }
+    let sys = py.import(intern!(py, "sys"))?;
+    let Some(get_limit) = sys.getattr_opt(intern!(py, "get_int_max_str_digits"))? else {
+        return Ok(());
+    };
+    let limit: u64 = get_limit.call0()?.extract()?;
+    if limit == 0 || digits <= limit {
+        return Ok(());
+    }
+    Err(PyValueError::new_err(format!(
+        "Exceeds the limit ({limit} digits) for integer string conversion; use sys.set_int_max_str_digits() to increase the limit"
+    )))
+}
+
+/// Convert the precision argument of `floatformat` with Python's `int()`.
+///
+/// Returns `None` where Django would return the input unchanged.
+fn floatformat_precision(argument: &Content<'_, '_>, py: Python<'_>) -> PyResult<Option<i64>> {
+    let int = |value: Bound<'_, PyAny>| -> PyResult<Option<i64>> {
+        match PyType::new::<PyInt>(py).call1((value,)) {
+            Ok(precision) => Ok(precision.extract().ok()),
+            Err(error) if error.is_instance_of::<PyValueError>(py) => Ok(None),
+            Err(error) => Err(error),
+        }
+    };
+    Ok(match argument {
+        Content::Int(precision) => precision.to_i64(),
+        Content::Bool(precision) => Some(i64::from(*precision)),
+        Content::Float(precision) if precision.is_finite() => precision.trunc().to_i64(),
+        Content::Float(_) => None,
+        Content::String(precision) => match precision.as_raw().trim().parse() {
+            Ok(precision) => Some(precision),
+            Err(_) => int(argument.to_py(py))?,
+        },
+        Content::Py(precision) => int(precision.clone())?,
+    })
+}
+
+impl FloatformatFilter {
+    /// Handle the `g` (grouping) and `u` (unlocalized) suffixes of a string argument.
+    fn argument<'t, 'py>(
         &self,
-        variable: Option<Content<'t, 'py>>,
         py: Python<'py>,
         template: TemplateString<'t>,
         context: &mut Context,
-    ) -> ResolveResult<'t, 'py> {
-        let variable = match variable {
-            // We want to re-run the escape filter even if the content has already been resolved to safe HTML
-            Some(Content::String(ContentString::HtmlSafe(c))) => {
-                Some(Content::String(ContentString::String(c)))
+    ) -> Result<(Option<Content<'t, 'py>>, bool, bool), PyRenderError> {
+        let Some(argument) = &self.argument else {
+            return Ok((None, false, true));
+        };
+        let content = argument
+            .resolve(py, template, context, ResolveFailures::Raise)?
+            .expect("missing argument in context should already have raised");
+        let raw = match &content {
+            Content::String(content) => content.as_raw().to_string(),
+            Content::Py(content) if content.is_instance_of::<PyString>() => {
+                content.extract::<String>()?
             }
-            other => other,
+            _ => return Ok((Some(content), false, true)),
         };
-        EscapeFilter.resolve(variable, py, template, context)
+        if raw.is_empty() {
+            return Err(PyIndexError::new_err("string index out of range")
+                .annotate(py, argument.at, "here", template)
+                .into());
+        }
+        let (stripped, force_grouping, use_l10n) =
+            if let Some(stripped) = raw.strip_suffix("gu").or_else(|| raw.strip_suffix("ug")) {
+                (stripped, true, false)
+            } else if let Some(stripped) = raw.strip_suffix('g') {
+                (stripped, true, true)
+            } else if let Some(stripped) = raw.strip_suffix('u') {
+                (stripped, false, false)
+            } else {
+                return Ok((Some(content), false, true));
+            };
+        let precision = match stripped {
+            "" => None,
+            stripped => Some(stripped.to_string().into_content()),
+        };
+        Ok((precision, force_grouping, use_l10n))
     }
 }
 
-impl ResolveFilter for LastFilter {
+/// Look up the locale formats used by `django.utils.formats.number_format`.
+fn number_format_locale(
+    py: Python<'_>,
+    use_l10n: bool,
+    force_grouping: bool,
+) -> PyResult<(String, Option<Vec<usize>>, String)> {
+    let get_format = GET_FORMAT.import(py, "django.utils.formats", "get_format")?;
+    let lang = match use_l10n {
+        true => GET_LANGUAGE
+            .import(py, "django.utils.translation", "get_language")?
+            .call0()?,
+        false => py.None().into_bound(py),
+    };
+    let format = |name: &str| {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("use_l10n", use_l10n)?;
+        get_format.call((name, &lang), Some(&kwargs))
+    };
+    let decimal_separator = format("DECIMAL_SEPARATOR")?.extract()?;
+
+    let settings = SETTINGS.import(py, "django.conf", "settings")?;
+    let use_thousand_separator = settings
+        .getattr(intern!(py, "USE_THOUSAND_SEPARATOR"))?
+        .is_truthy()?;
+    if !(use_l10n && use_thousand_separator || force_grouping) {
+        return Ok((decimal_separator, None, String::new()));
+    }
+    let grouping = format("NUMBER_GROUPING")?;
+    let grouping = match grouping.extract::<usize>() {
+        Ok(0) => return Ok((decimal_separator, None, String::new())),
+        Ok(grouping) => vec![grouping, 0],
+        Err(_) => grouping.extract()?,
+    };
+    let thousand_separator = format("THOUSAND_SEPARATOR")?.extract()?;
+    Ok((decimal_separator, Some(grouping), thousand_separator))
+}
+
+/// The result of a filter which isn't `is_safe`, so Django escapes it even
+/// when its input was safe.
+fn unsafe_result<'t>(content: &ContentString<'_>, result: String) -> ContentString<'t> {
+    match content {
+        ContentString::String(_) => ContentString::String(Cow::Owned(result)),
+        ContentString::HtmlSafe(_) | ContentString::HtmlUnsafe(_) => {
+            ContentString::HtmlUnsafe(Cow::Owned(result))
+        }
+    }
+}
+
+/// Escape a Python object unless it is marked as safe, like Django's
+/// `conditional_escape`.
+fn conditional_escape(value: &Bound<'_, PyAny>) -> PyResult<String> {
+    let py = value.py();
+    match value
+        .getattr(intern!(py, "__html__"))
+        .ok_or_isinstance_of::<PyAttributeError>(py)?
+    {
+        Ok(html) => html.call0()?.extract(),
+        Err(_) => Ok(encode_quoted_attribute(&value.str()?.extract::<String>()?).into_owned()),
+    }
+}
+
+/// Resolve content which is a Rust or Python string, so filters can handle
+/// it without calling back into Python.
+fn as_content_string<'t, 'py>(
+    content: Content<'t, 'py>,
+    context: &Context,
+) -> PyResult<Result<ContentString<'t>, Content<'t, 'py>>> {
+    match content {
+        Content::String(content) => Ok(Ok(content)),
+        Content::Py(object) if object.is_instance_of::<PyString>() => {
+            Ok(Ok(Content::Py(object).resolve_string(context)?))
+        }
+        content => Ok(Err(content)),
+    }
+}
+
+/// Format a positive number of bytes like Django's `filesizeformat`, before
+/// replacing spaces with non-breaking spaces.
+fn filesize(py: Python<'_>, bytes: &BigInt) -> PyResult<String> {
+    const UNITS: [&str; 5] = ["%s KB", "%s MB", "%s GB", "%s TB", "%s PB"];
+    if let Some(bytes) = bytes.to_i64()
+        && bytes < 1024
+    {
+        let value = ngettext(py, "%(size)d byte", "%(size)d bytes", bytes)?;
+        return Ok(value.replace("%(size)d", &bytes.to_string()));
+    }
+    let unit = ((bytes.bits() - 1) / 10).min(5);
+    let value = bytes.to_f64().unwrap_or(f64::INFINITY)
+        / 1024f64.powi(i32::try_from(unit).expect("unit is at most 5"));
+    if !value.is_finite() {
+        return Err(PyOverflowError::new_err(
+            "integer division result too large for a float",
+        ));
+    }
+    let number = if value < 1e16 {
+        let rounded = format!("{value:.1}");
+        let (integer, fraction) = rounded
+            .split_once('.')
+            .expect("formatted with one decimal place");
+        let (decimal_separator, grouping, thousand_separator) =
+            number_format_locale(py, true, false)?;
+        let format = NumberFormat {
+            decimal_separator: &decimal_separator,
+            grouping,
+            thousand_separator: &thousand_separator,
+        };
+        format_number(false, integer, fraction, &format)
+    } else {
+        // Django formats these via `Decimal`, possibly in scientific notation.
+        let number_format = NUMBER_FORMAT.import(py, "django.utils.formats", "number_format")?;
+        let rounded = value
+            .into_pyobject(py)?
+            .call_method1(intern!(py, "__round__"), (1,))?;
+        number_format.call1((rounded, 1))?.extract()?
+    };
+    let unit = usize::try_from(unit).expect("unit is at most 5") - 1;
+    Ok(gettext(py, UNITS[unit])?.replace("%s", &number))
+}
+
+impl ResolveFilter for FilesizeformatFilter {
     fn resolve<'t, 'py>(
         &self,
         variable: Option<Content<'t, 'py>>,
@@ -516,54 +1174,922 @@ impl ResolveFilter for LastFilter {
         template: TemplateString<'t>,
         _context: &mut Context,
     ) -> ResolveResult<'t, 'py> {
-        let content = match variable {
-            Some(content) => content,
-            None => return Ok(Some("".as_content())),
-        };
-
-        let obj = content.to_py(py);
-
-        match obj.get_item(-1) {
-            Ok(last) => Ok(Some(Content::Py(last))),
-            Err(err) => {
-                if err.is_instance_of::<pyo3::exceptions::PyIndexError>(py) {
-                    Ok(Some("".as_content()))
-                } else {
-                    let error = err.annotate(py, self.at, "here", template);
-                    Err(error.into())
-                }
+        let bytes = match variable.map(|value| int_argument(value, py)) {
+            Some(Ok(bytes)) => bytes,
+            Some(Err(error))
+                if !(error.is_instance_of::<PyTypeError>(py)
+                    || error.is_instance_of::<PyValueError>(py)) =>
+            {
+                return Err(error.annotate(py, self.at, "here", template).into());
             }
-        }
+            None | Some(Err(_)) => BigInt::zero(),
+        };
+        let value = match filesize(py, &bytes.abs()) {
+            Ok(value) => value,
+            Err(error) => return Err(error.annotate(py, self.at, "here", template).into()),
+        };
+        let value = match bytes.is_negative() {
+            true => format!("-{value}"),
+            false => value,
+        };
+        Ok(Some(Content::String(ContentString::HtmlUnsafe(
+            Cow::Owned(value.replace(' ', "\u{a0}")),
+        ))))
     }
 }
 
-impl ResolveFilter for LowerFilter {
+impl ResolveFilter for GetDigitFilter {
     fn resolve<'t, 'py>(
         &self,
         variable: Option<Content<'t, 'py>>,
-        _py: Python<'py>,
-        _template: TemplateString<'t>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
         context: &mut Context,
     ) -> ResolveResult<'t, 'py> {
-        let content = match variable {
-            Some(content) => content
-                .resolve_string(context)?
-                .map_content(|content| Cow::Owned(content.to_lowercase())),
-            None => "".as_content(),
+        let value = variable.unwrap_or_else(|| "".as_content());
+        let position = self
+            .argument
+            .resolve(py, template, context, ResolveFailures::Raise)?
+            .expect("missing argument in context should already have raised");
+        // Invalid input is returned unchanged, but only a `ValueError` counts
+        // as invalid.
+        let position = match int_argument(position, py) {
+            Ok(position) => position,
+            Err(error) if error.is_instance_of::<PyValueError>(py) => return Ok(Some(value)),
+            Err(error) => {
+                return Err(error
+                    .annotate(py, self.argument.at, "here", template)
+                    .into());
+            }
         };
-        Ok(Some(content))
+        let number = match &value {
+            Content::Int(number) => Ok(number.clone()),
+            value => int_argument(Content::Py(value.to_py(py)), py),
+        };
+        let number = match number {
+            Ok(number) => number,
+            Err(error) if error.is_instance_of::<PyValueError>(py) => return Ok(Some(value)),
+            Err(error) => return Err(error.annotate(py, self.at, "here", template).into()),
+        };
+        if position < BigInt::one() {
+            return Ok(Some(value));
+        }
+        let digits = number.to_string();
+        let digit = match position.to_usize() {
+            Some(position) if position <= digits.len() => {
+                digits.as_bytes()[digits.len() - position]
+            }
+            _ => return Ok(Some(Content::Int(BigInt::zero()))),
+        };
+        match digit {
+            b'-' => Err(
+                PyValueError::new_err("invalid literal for int() with base 10: '-'")
+                    .annotate(py, self.at, "here", template)
+                    .into(),
+            ),
+            digit => Ok(Some(Content::Int(BigInt::from(digit - b'0')))),
+        }
     }
 }
 
-impl ResolveFilter for LengthFilter {
+impl ResolveFilter for FirstFilter {
     fn resolve<'t, 'py>(
         &self,
         variable: Option<Content<'t, 'py>>,
-        _py: Python<'py>,
-        _template: TemplateString<'t>,
-        _context: &mut Context,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
     ) -> ResolveResult<'t, 'py> {
-        let len = match variable {
+        let Some(content) = variable else {
+            return Ok(Some("".as_content()));
+        };
+        let content = match as_content_string(content, context)? {
+            Ok(content) => {
+                let first = content.as_raw().chars().next().map(String::from);
+                return Ok(Some(Content::String(unsafe_result(
+                    &content,
+                    first.unwrap_or_default(),
+                ))));
+            }
+            Err(content) => content,
+        };
+        match content.to_py(py).get_item(0) {
+            Ok(first) => Ok(Some(Content::Py(first))),
+            Err(error) if error.is_instance_of::<PyIndexError>(py) => Ok(Some("".as_content())),
+            Err(error) => Err(error.annotate(py, self.at, "here", template).into()),
+        }
+    }
+}
+
+impl ResolveFilter for FloatformatFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let (argument, force_grouping, use_l10n) = self.argument(py, template, context)?;
+        let Some(value) = variable else {
+            return Ok(Some("".as_content()));
+        };
+
+        let (input, safe) = match &value {
+            Content::String(content) => (
+                content.as_raw().to_string(),
+                matches!(content, ContentString::HtmlSafe(_)),
+            ),
+            Content::Py(content) => {
+                #[allow(non_snake_case)]
+                let SafeData = SAFEDATA.import(py, "django.utils.safestring", "SafeData")?;
+                (content.str()?.extract()?, content.is_instance(SafeData)?)
+            }
+            Content::Float(_) => (value.to_py(py).str()?.extract()?, false),
+            Content::Int(content) => (content.to_string(), false),
+            Content::Bool(content) => (if *content { "True" } else { "False" }.to_string(), false),
+        };
+        let unchanged = |input: String| -> ResolveResult<'t, 'py> {
+            Ok(Some(Content::String(match safe {
+                true => ContentString::HtmlSafe(Cow::Owned(input)),
+                false => ContentString::HtmlUnsafe(Cow::Owned(input)),
+            })))
+        };
+
+        let decimal = match parse_decimal(py, &input)? {
+            Some(decimal) => decimal,
+            None => {
+                // Django falls back to `Decimal(str(float(text)))`.
+                let float = match &value {
+                    Content::Py(_) | Content::Bool(_) => {
+                        match PyType::new::<PyFloat>(py).call1((value.to_py(py),)) {
+                            Ok(float) => float,
+                            Err(error)
+                                if error.is_instance_of::<PyValueError>(py)
+                                    || error.is_instance_of::<PyTypeError>(py) =>
+                            {
+                                return Ok(Some("".as_content()));
+                            }
+                            Err(error) => {
+                                return Err(error.annotate(py, self.at, "here", template).into());
+                            }
+                        }
+                    }
+                    _ => return Ok(Some("".as_content())),
+                };
+                match ParsedDecimal::parse(&float.str()?.to_cow()?) {
+                    Some(decimal) => decimal,
+                    None => return Ok(Some("".as_content())),
+                }
+            }
+        };
+        let precision = match &argument {
+            Some(argument) => match floatformat_precision(argument, py) {
+                Ok(Some(precision)) => precision,
+                Ok(None) => return unchanged(input),
+                Err(error) => {
+                    let at = self
+                        .argument
+                        .as_ref()
+                        .map_or(self.at, |argument| argument.at);
+                    return Err(error.annotate(py, at, "here", template).into());
+                }
+            },
+            None => -1,
+        };
+        let ParsedDecimal::Finite(decimal) = decimal else {
+            return unchanged(input);
+        };
+
+        if precision <= 0
+            && !decimal.has_fraction()
+            && let Err(error) = check_int_max_str_digits(py, decimal.integer_len())
+        {
+            return Err(error.annotate(py, self.at, "here", template).into());
+        }
+        let (decimal_separator, grouping, thousand_separator) =
+            number_format_locale(py, use_l10n, force_grouping)?;
+        let format = NumberFormat {
+            decimal_separator: &decimal_separator,
+            grouping,
+            thousand_separator: &thousand_separator,
+        };
+        let formatted = floatformat(&decimal, precision, &format);
+        Ok(Some(Content::String(ContentString::HtmlSafe(Cow::Owned(
+            formatted,
+        )))))
+    }
+}
+
+impl ResolveFilter for ForceEscapeFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let variable = match variable {
+            // We want to re-run the escape filter even if the content has already been resolved to safe HTML
+            Some(Content::String(ContentString::HtmlSafe(c))) => {
+                Some(Content::String(ContentString::String(c)))
+            }
+            other => other,
+        };
+        EscapeFilter.resolve(variable, py, template, context)
+    }
+}
+
+/// Convert letters to the digits they share a key with on a phone, like
+/// Django's `phone2numeric`.
+fn phone2numeric(phone: &str) -> String {
+    phone
+        .to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'a'..='c' => '2',
+            'd'..='f' => '3',
+            'g'..='i' => '4',
+            'j'..='l' => '5',
+            'm'..='o' => '6',
+            'p'..='s' => '7',
+            't'..='v' => '8',
+            'w'..='z' => '9',
+            c => c,
+        })
+        .collect()
+}
+
+impl ResolveFilter for Phone2numericFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        _py: Python<'py>,
+        _template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let content = match variable {
+            Some(content) => content
+                .resolve_string(context)?
+                .map_content(|content| Cow::Owned(phone2numeric(&content))),
+            None => "".as_content(),
+        };
+        Ok(Some(content))
+    }
+}
+
+impl ResolveFilter for PprintFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        _template: TemplateString<'t>,
+        _context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let value = variable.unwrap_or_else(|| "".as_content());
+        let safe = is_safe_data(&value, py)?;
+        let pformat = PFORMAT.import(py, "pprint", "pformat")?;
+        let formatted = match pformat.call1((value.to_py(py),)) {
+            Ok(formatted) => formatted.extract::<String>()?,
+            Err(error) if error.is_instance_of::<PyException>(py) => format!(
+                "Error in formatting: {}: {}",
+                error.get_type(py).name()?,
+                error.value(py).str()?,
+            ),
+            Err(error) => return Err(error.into()),
+        };
+        Ok(Some(safe_result(safe, formatted)))
+    }
+}
+
+/// Whether content is marked as safe, like `isinstance(value, SafeData)`.
+fn is_safe_data(content: &Content<'_, '_>, py: Python<'_>) -> PyResult<bool> {
+    Ok(match content {
+        Content::String(content) => matches!(content, ContentString::HtmlSafe(_)),
+        Content::Py(content) => {
+            #[allow(non_snake_case)]
+            let SafeData = SAFEDATA.import(py, "django.utils.safestring", "SafeData")?;
+            content.is_instance(SafeData)?
+        }
+        Content::Float(_) | Content::Int(_) | Content::Bool(_) => false,
+    })
+}
+
+/// The result of a filter which is `is_safe`, so Django marks it as safe
+/// when its input was safe.
+fn safe_result<'t, 'py>(safe: bool, result: String) -> Content<'t, 'py> {
+    Content::String(match safe {
+        true => ContentString::HtmlSafe(Cow::Owned(result)),
+        false => ContentString::HtmlUnsafe(Cow::Owned(result)),
+    })
+}
+
+/// Convert the value of a `django.contrib.humanize` filter like Python's
+/// `int`, returning `None` if this raises a `TypeError` or `ValueError`.
+fn humanize_int(
+    value: &Content<'_, '_>,
+    py: Python<'_>,
+    at: At,
+    template: TemplateString<'_>,
+) -> Result<Option<BigInt>, PyRenderError> {
+    let int = match value {
+        Content::Int(n) => return Ok(Some(n.clone())),
+        Content::Bool(b) => return Ok(Some(u8::from(*b).into())),
+        Content::Float(f) if f.is_finite() => return Ok(f.trunc().to_bigint()),
+        value => PyType::new::<PyInt>(py).call1((value.to_py(py),)),
+    };
+    match int {
+        Ok(int) => Ok(Some(int.extract()?)),
+        Err(error)
+            if error.is_instance_of::<PyTypeError>(py)
+                || error.is_instance_of::<PyValueError>(py) =>
+        {
+            Ok(None)
+        }
+        Err(error) => Err(error.annotate(py, at, "here", template).into()),
+    }
+}
+
+impl ResolveFilter for ApnumberFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        _context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let value = variable.unwrap_or_else(|| "".as_content());
+        let Some(int) = humanize_int(&value, py, self.at, template)? else {
+            return Ok(Some(value));
+        };
+        Ok(Some(match humanize::apnumber(py, &int)? {
+            Some(word) => safe_result(is_safe_data(&value, py)?, word),
+            None => Content::Int(int),
+        }))
+    }
+}
+
+impl ResolveFilter for IntcommaFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let use_l10n = match &self.argument {
+            Some(argument) => argument
+                .resolve(py, template, context, ResolveFailures::Raise)?
+                .expect("missing argument in context should already have raised")
+                .to_bool()?,
+            None => true,
+        };
+        let value = variable.unwrap_or_else(|| "".as_content());
+        let safe = is_safe_data(&value, py)?;
+        if use_l10n {
+            let number = match &value {
+                Content::Float(float) => Some((false, float_repr(*float))),
+                Content::Py(value) if value.is_instance_of::<PyFloat>() => {
+                    Some((false, value.str()?.extract::<String>()?))
+                }
+                Content::Py(value)
+                    if value.is_instance(DECIMAL.import(py, "decimal", "Decimal")?)? =>
+                {
+                    Some((true, value.str()?.extract::<String>()?))
+                }
+                _ => None,
+            };
+            if let Some((decimal, number)) = number {
+                let (decimal_separator, grouping, thousand_separator) =
+                    number_format_locale(py, true, true)?;
+                let format = NumberFormat {
+                    decimal_separator: &decimal_separator,
+                    grouping,
+                    thousand_separator: &thousand_separator,
+                };
+                let formatted = match ParsedDecimal::parse(&number) {
+                    Some(ParsedDecimal::Finite(number)) if decimal => {
+                        Some(format_decimal(&number, &format))
+                    }
+                    _ if !decimal => format_float(&number, &format),
+                    _ => None,
+                };
+                let formatted = match formatted {
+                    Some(formatted) => formatted,
+                    // Django's `number_format` raises for infinite and NaN
+                    // decimals and for floats whose `str()` can't be converted
+                    // to a decimal, so let it report those.
+                    None => {
+                        let number_format =
+                            NUMBER_FORMAT.import(py, "django.utils.formats", "number_format")?;
+                        let kwargs = PyDict::new(py);
+                        kwargs.set_item("use_l10n", true)?;
+                        kwargs.set_item("force_grouping", true)?;
+                        number_format
+                            .call((value.to_py(py),), Some(&kwargs))
+                            .map_err(|error| error.annotate(py, self.at, "here", template))?
+                            .extract()?
+                    }
+                };
+                return Ok(Some(safe_result(safe, formatted)));
+            }
+            if let Some(int) = humanize_int(&value, py, self.at, template)? {
+                let (decimal_separator, grouping, thousand_separator) =
+                    number_format_locale(py, true, true)?;
+                let format = NumberFormat {
+                    decimal_separator: &decimal_separator,
+                    grouping,
+                    thousand_separator: &thousand_separator,
+                };
+                let digits = int.magnitude().to_string();
+                let formatted = format_number(int.is_negative(), &digits, "", &format);
+                return Ok(Some(safe_result(safe, formatted)));
+            }
+        }
+        let text = match &value {
+            Content::String(content) => content.as_raw().to_string(),
+            value => value.to_py(py).str()?.extract::<String>()?,
+        };
+        let formatted = match humanize::intcomma(&text) {
+            Some(formatted) => formatted,
+            None => {
+                // Django's regular expressions also match non-ASCII digits,
+                // which only Python's `re` classifies exactly, so leave text
+                // starting with them to Django's `intcomma`.
+                let intcomma = HUMANIZE_INTCOMMA.import(
+                    py,
+                    "django.contrib.humanize.templatetags.humanize",
+                    "intcomma",
+                )?;
+                intcomma.call1((value.to_py(py), false))?.extract()?
+            }
+        };
+        Ok(Some(safe_result(safe, formatted)))
+    }
+}
+
+impl ResolveFilter for IntwordFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        _context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let value = variable.unwrap_or_else(|| "".as_content());
+        let Some(int) = humanize_int(&value, py, self.at, template)? else {
+            return Ok(Some(value));
+        };
+        let Some(intword) = humanize::intword(py, &int)? else {
+            return Ok(Some(Content::Int(int)));
+        };
+        let ParsedDecimal::Finite(decimal) = ParsedDecimal::parse(&float_repr(intword.value))
+            .expect("A float repr is a valid decimal")
+        else {
+            unreachable!("The scaled value is less than 1000")
+        };
+        let (decimal_separator, grouping, thousand_separator) =
+            number_format_locale(py, true, false)?;
+        let format = NumberFormat {
+            decimal_separator: &decimal_separator,
+            grouping,
+            thousand_separator: &thousand_separator,
+        };
+        let number = floatformat(&decimal, 1, &format);
+        let formatted =
+            ngettext(py, intword.unit, intword.unit, intword.count)?.replace("%(value)s", &number);
+        Ok(Some(Content::String(ContentString::HtmlUnsafe(
+            Cow::Owned(formatted),
+        ))))
+    }
+}
+
+impl ResolveFilter for NaturaldayFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let format = date_format_argument(self.argument.as_ref(), py, template, context)?;
+        let Some(value) = variable else {
+            return Ok(Some("".as_content()));
+        };
+        let naturalday = || {
+            let value = template_localtime(value.to_py(py), context.use_tz)?;
+            let Some((date, days)) = humanize::day_offset(&value)? else {
+                return Ok(Content::Py(value));
+            };
+            let text = match days {
+                0 => gettext(py, "today")?,
+                1 => gettext(py, "tomorrow")?,
+                -1 => gettext(py, "yesterday")?,
+                _ => match format_date(
+                    date.into_any(),
+                    format.as_deref(),
+                    "DATE_FORMAT",
+                    false,
+                    context.use_tz,
+                ) {
+                    Ok(formatted) => formatted,
+                    Err(error) if error.is_instance_of::<PyAttributeError>(py) => String::new(),
+                    Err(error) => return Err(error),
+                },
+            };
+            Ok(Content::String(ContentString::HtmlUnsafe(Cow::Owned(text))))
+        };
+        match naturalday() {
+            Ok(content) => Ok(Some(content)),
+            Err(error) => Err(error.annotate(py, self.at, "here", template).into()),
+        }
+    }
+}
+
+impl ResolveFilter for NaturaltimeFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        _context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let Some(value) = variable else {
+            return Ok(Some("".as_content()));
+        };
+        let date = value.to_py(py);
+        if !date.is_instance_of::<PyDate>() {
+            return Ok(Some(value));
+        }
+        match humanize::naturaltime(&date) {
+            Ok(text) => Ok(Some(Content::String(ContentString::HtmlUnsafe(
+                Cow::Owned(text),
+            )))),
+            Err(error) => Err(error.annotate(py, self.at, "here", template).into()),
+        }
+    }
+}
+
+impl ResolveFilter for OrdinalFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        _context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let value = variable.unwrap_or_else(|| "".as_content());
+        let Some(int) = humanize_int(&value, py, self.at, template)? else {
+            return Ok(Some(value));
+        };
+        Ok(Some(Content::String(ContentString::HtmlSafe(Cow::Owned(
+            humanize::ordinal(py, &int)?,
+        )))))
+    }
+}
+
+impl ResolveFilter for IriencodeFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        _py: Python<'py>,
+        _template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let content = match variable {
+            Some(content) => content
+                .resolve_string(context)?
+                .map_content(|content| Cow::Owned(quote(&content, "/#%[]=:;$&()+,!?*@'~"))),
+            None => "".as_content(),
+        };
+        Ok(Some(content))
+    }
+}
+
+/// Collect the items of `content` for the `join` filter, escaping them if
+/// `autoescape` is set. Without escaping, every item must be a string.
+fn join_items(content: &Content<'_, '_>, autoescape: bool) -> PyResult<Vec<String>> {
+    let object = match content {
+        Content::String(content) => {
+            return Ok(content
+                .as_raw()
+                .chars()
+                .map(|c| match autoescape {
+                    true => encode_quoted_attribute(c.encode_utf8(&mut [0; 4])).into_owned(),
+                    false => c.to_string(),
+                })
+                .collect());
+        }
+        Content::Py(object) => object,
+        Content::Int(_) | Content::Float(_) | Content::Bool(_) => {
+            return Err(PyTypeError::new_err("can only join an iterable"));
+        }
+    };
+    object
+        .try_iter()?
+        .map(|item| {
+            let item = item?;
+            match autoescape {
+                true => conditional_escape(&item),
+                false => match item.cast::<PyString>() {
+                    Ok(item) => item.extract(),
+                    Err(_) => Err(PyTypeError::new_err("sequence item: expected str instance")),
+                },
+            }
+        })
+        .collect()
+}
+
+impl ResolveFilter for JoinFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let content = variable.unwrap_or_else(|| "".as_content());
+        let separator = self
+            .argument
+            .resolve(py, template, context, ResolveFailures::Raise)?
+            .expect("missing argument in context should already have raised");
+        let joined = match (context.autoescape, separator) {
+            (true, separator) => {
+                let separator = separator.resolve_string(context)?.content();
+                join_items(&content, true).map(|items| items.join(&separator))
+            }
+            (false, Content::String(separator)) => {
+                join_items(&content, false).map(|items| items.join(separator.as_raw()))
+            }
+            (false, Content::Py(separator)) if separator.is_instance_of::<PyString>() => {
+                let separator = separator.extract::<String>()?;
+                join_items(&content, false).map(|items| items.join(&separator))
+            }
+            (false, separator) => separator
+                .to_py(py)
+                .call_method1(intern!(py, "join"), (content.to_py(py),))
+                .and_then(|joined| joined.str()?.extract()),
+        };
+        match joined {
+            Ok(joined) => Ok(Some(Content::String(ContentString::HtmlSafe(Cow::Owned(
+                joined,
+            ))))),
+            // Django fails silently if the value isn't iterable.
+            Err(error) if error.is_instance_of::<PyTypeError>(py) => Ok(Some(content)),
+            Err(error) => Err(error
+                .annotate(py, self.argument.at, "here", template)
+                .into()),
+        }
+    }
+}
+
+impl JsonScriptFilter {
+    /// Resolve the element id, escaped unless it is safe, or `None` if it
+    /// is falsy.
+    fn element_id<'t>(
+        &self,
+        py: Python<'_>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> Result<Option<Cow<'t, str>>, PyRenderError> {
+        let Some(argument) = &self.argument else {
+            return Ok(None);
+        };
+        let element_id = argument
+            .resolve(py, template, context, ResolveFailures::Raise)?
+            .expect("missing argument in context should already have raised");
+        let truthy = match element_id.to_bool() {
+            Ok(truthy) => truthy,
+            Err(error) => return Err(error.annotate(py, argument.at, "here", template).into()),
+        };
+        if !truthy {
+            return Ok(None);
+        }
+        Ok(Some(match element_id {
+            // Django marks literal arguments as safe.
+            Content::String(element_id) => element_id.into_raw(),
+            Content::Py(element_id) => match conditional_escape(&element_id) {
+                Ok(element_id) => Cow::Owned(element_id),
+                Err(error) => {
+                    return Err(error.annotate(py, argument.at, "here", template).into());
+                }
+            },
+            element_id => element_id.resolve_string(context)?.into_raw(),
+        }))
+    }
+}
+
+impl ResolveFilter for JsonScriptFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let element_id = self.element_id(py, template, context)?;
+        let value = match variable {
+            Some(value) => value.to_py(py),
+            None => PyString::new(py, "").into_any(),
+        };
+        let json = match json::dumps(&value, true) {
+            Ok(Some(json)) => Ok(json),
+            Ok(None) => (|| {
+                let dumps = JSON_DUMPS.import(py, "json", "dumps")?;
+                let encoder = DJANGO_JSON_ENCODER.import(
+                    py,
+                    "django.core.serializers.json",
+                    "DjangoJSONEncoder",
+                )?;
+                let kwargs = PyDict::new(py);
+                kwargs.set_item(intern!(py, "cls"), encoder)?;
+                let json = dumps.call((value,), Some(&kwargs))?;
+                Ok(json::escape_script(json.cast::<PyString>()?.to_str()?))
+            })(),
+            Err(error) => Err(error),
+        };
+        let json = match json {
+            Ok(json) => json,
+            Err(error) => return Err(error.annotate(py, self.at, "here", template).into()),
+        };
+        let script = match element_id {
+            Some(element_id) => {
+                format!(r#"<script id="{element_id}" type="application/json">{json}</script>"#)
+            }
+            None => format!(r#"<script type="application/json">{json}</script>"#),
+        };
+        Ok(Some(Content::String(ContentString::HtmlSafe(Cow::Owned(
+            script,
+        )))))
+    }
+}
+
+impl ResolveFilter for LastFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        _context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let content = match variable {
+            Some(content) => content,
+            None => return Ok(Some("".as_content())),
+        };
+
+        let obj = content.to_py(py);
+
+        match obj.get_item(-1) {
+            Ok(last) => Ok(Some(Content::Py(last))),
+            Err(err) => {
+                if err.is_instance_of::<pyo3::exceptions::PyIndexError>(py) {
+                    Ok(Some("".as_content()))
+                } else {
+                    let error = err.annotate(py, self.at, "here", template);
+                    Err(error.into())
+                }
+            }
+        }
+    }
+}
+
+/// Whether a filter with `needs_autoescape` should escape its input, which
+/// Django skips for safe strings.
+fn needs_escape(content: &ContentString<'_>, context: &Context) -> bool {
+    context.autoescape && !matches!(content, ContentString::HtmlSafe(_))
+}
+
+/// Convert all newlines to `\n`, like Django's `normalize_newlines`.
+fn normalize_newlines(text: &str) -> Cow<'_, str> {
+    match text.contains('\r') {
+        true => Cow::Owned(text.replace("\r\n", "\n").replace('\r', "\n")),
+        false => Cow::Borrowed(text),
+    }
+}
+
+/// Split text into paragraphs separated by two or more newlines.
+fn split_paragraphs(text: &str) -> Vec<&str> {
+    let mut paragraphs = Vec::new();
+    let mut rest = text;
+    while let Some(index) = rest.find("\n\n") {
+        paragraphs.push(&rest[..index]);
+        rest = rest[index..].trim_start_matches('\n');
+    }
+    paragraphs.push(rest);
+    paragraphs
+}
+
+fn escape_if(text: &str, autoescape: bool) -> Cow<'_, str> {
+    match autoescape {
+        true => encode_quoted_attribute(text),
+        false => Cow::Borrowed(text),
+    }
+}
+
+impl ResolveFilter for LinebreaksFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        _py: Python<'py>,
+        _template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let content = match variable {
+            Some(content) => content.resolve_string(context)?,
+            None => ContentString::String(Cow::Borrowed("")),
+        };
+        let autoescape = needs_escape(&content, context);
+        let text = normalize_newlines(content.as_raw());
+        let paragraphs = split_paragraphs(&text)
+            .into_iter()
+            .map(|paragraph| {
+                format!(
+                    "<p>{}</p>",
+                    escape_if(paragraph, autoescape).replace('\n', "<br>")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        Ok(Some(Content::String(ContentString::HtmlSafe(Cow::Owned(
+            paragraphs,
+        )))))
+    }
+}
+
+impl ResolveFilter for LinebreaksbrFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        _py: Python<'py>,
+        _template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let Some(content) = variable else {
+            return Ok(Some("".as_content()));
+        };
+        let content = content.resolve_string(context)?;
+        let autoescape = needs_escape(&content, context);
+        let text = normalize_newlines(content.as_raw());
+        let text = escape_if(&text, autoescape).replace('\n', "<br>");
+        Ok(Some(Content::String(ContentString::HtmlSafe(Cow::Owned(
+            text,
+        )))))
+    }
+}
+
+impl ResolveFilter for LinenumbersFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        _py: Python<'py>,
+        _template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let content = match variable {
+            Some(content) => content.resolve_string(context)?,
+            None => ContentString::String(Cow::Borrowed("")),
+        };
+        let autoescape = needs_escape(&content, context);
+        let lines: Vec<&str> = content.as_raw().split('\n').collect();
+        let width = lines.len().to_string().len();
+        let numbered = lines
+            .into_iter()
+            .enumerate()
+            .map(|(index, line)| format!("{:0width$}. {}", index + 1, escape_if(line, autoescape)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Ok(Some(Content::String(ContentString::HtmlSafe(Cow::Owned(
+            numbered,
+        )))))
+    }
+}
+
+impl ResolveFilter for LowerFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        _py: Python<'py>,
+        _template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let content = match variable {
+            Some(content) => content
+                .resolve_string(context)?
+                .map_content(|content| Cow::Owned(content.to_lowercase())),
+            None => "".as_content(),
+        };
+        Ok(Some(content))
+    }
+}
+
+impl ResolveFilter for LengthFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        _py: Python<'py>,
+        _template: TemplateString<'t>,
+        _context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let len = match variable {
             None => 0,
             Some(Content::String(s)) => s.as_raw().chars().count(),
             Some(Content::Py(obj)) => obj.len().unwrap_or(0),
@@ -574,6 +2100,133 @@ impl ResolveFilter for LengthFilter {
     }
 }
 
+impl ResolveFilter for MakeListFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        _template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let content = match variable {
+            Some(content) => content.resolve_string(context)?,
+            None => ContentString::String(Cow::Borrowed("")),
+        };
+        let list = PyList::new(py, content.as_raw().chars().map(String::from))?;
+        Ok(Some(Content::Py(list.into_any())))
+    }
+}
+
+/// Whether `value` counts as one for `pluralize`, or `None` if Django would
+/// return an empty string.
+fn is_singular(value: &Content<'_, '_>, py: Python<'_>) -> PyResult<Option<bool>> {
+    let value = match value {
+        Content::Int(n) => return Ok(Some(n.is_one())),
+        Content::Float(f) => return Ok(Some(*f == 1.0)),
+        Content::Bool(b) => return Ok(Some(*b)),
+        Content::String(s) => match s.as_raw().trim().parse::<f64>() {
+            Ok(f) => return Ok(Some(f == 1.0)),
+            Err(_) => value.to_py(py),
+        },
+        Content::Py(value) => value.clone(),
+    };
+    match PyType::new::<PyFloat>(py).call1((&value,)) {
+        Ok(float) => Ok(Some(float.extract::<f64>()? == 1.0)),
+        Err(error) if error.is_instance_of::<PyValueError>(py) => Ok(None),
+        // The value isn't a string or a number, but may have a length.
+        Err(error) if error.is_instance_of::<PyTypeError>(py) => match value.len() {
+            Ok(len) => Ok(Some(len == 1)),
+            Err(error) if error.is_instance_of::<PyTypeError>(py) => Ok(None),
+            Err(error) => Err(error),
+        },
+        Err(error) => Err(error),
+    }
+}
+
+impl ResolveFilter for PluralizeFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let argument = match &self.argument {
+            Some(argument) => argument
+                .resolve(py, template, context, ResolveFailures::Raise)?
+                .expect("missing argument in context should already have raised")
+                .resolve_string_strict(context, argument.at.into())?
+                .into_raw(),
+            None => Cow::Borrowed("s"),
+        };
+        let (singular, plural) = match argument.split_once(',') {
+            None => ("", argument.as_ref()),
+            Some((_, plural)) if plural.contains(',') => return Ok(Some("".as_content())),
+            Some(bits) => bits,
+        };
+        let is_one = match variable.map(|value| is_singular(&value, py)) {
+            Some(Ok(Some(is_one))) => is_one,
+            None | Some(Ok(None)) => return Ok(Some("".as_content())),
+            Some(Err(error)) => return Err(error.annotate(py, self.at, "here", template).into()),
+        };
+        let suffix = match is_one {
+            true => singular,
+            false => plural,
+        };
+        Ok(Some(Content::String(match context.autoescape {
+            true => ContentString::HtmlUnsafe(Cow::Owned(suffix.to_string())),
+            false => ContentString::String(Cow::Owned(suffix.to_string())),
+        })))
+    }
+}
+
+impl ResolveFilter for RandomFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let Some(content) = variable else {
+            return Ok(Some("".as_content()));
+        };
+        let mut rng = rand::rng();
+        let content = match as_content_string(content, context)? {
+            Ok(content) => {
+                let len = content.as_raw().chars().count();
+                if len == 0 {
+                    return Ok(Some("".as_content()));
+                }
+                let index = rng.random_range(0..len);
+                return Ok(Some(content.map_content(|content| {
+                    Cow::Owned(
+                        content
+                            .chars()
+                            .nth(index)
+                            .expect("index is within the string")
+                            .to_string(),
+                    )
+                })));
+            }
+            Err(content) => content,
+        };
+        let object = content.to_py(py);
+        let mut choice = || {
+            let len = object.len()?;
+            if len == 0 {
+                return Ok(None);
+            }
+            object.get_item(rng.random_range(0..len)).map(Some)
+        };
+        match choice() {
+            Ok(Some(choice)) => Ok(Some(Content::Py(choice))),
+            Ok(None) => Ok(Some("".as_content())),
+            Err(error) => Err(error.annotate(py, self.at, "here", template).into()),
+        }
+    }
+}
+
 impl ResolveFilter for SafeFilter {
     fn resolve<'t, 'py>(
         &self,
@@ -601,27 +2254,171 @@ impl ResolveFilter for SafeFilter {
     }
 }
 
-/// Convert spaces or repeated dashes to single dashes.
-/// Remove characters that aren't ascii alphanumerics, underscores, or hyphens.
-/// Convert to lowercase. Also strip leading and trailing whitespace, dashes, and underscores.
-///
-/// See https://github.com/django/django/blob/stable/5.2.x/django/utils/text.py#L453
-pub fn slugify(content: Cow<str>) -> Cow<str> {
-    let mut slug = String::with_capacity(content.len());
-
-    // treat start as if preceded by dash to strip leading dashes
-    let mut prev_dash = true;
-    for c in content.as_ref().nfkd().filter(|c| c.is_ascii()) {
-        if c.is_ascii_alphanumeric() || c == '_' {
-            slug.push(c.to_ascii_lowercase());
-            prev_dash = false;
-        } else if (c.is_whitespace() || c == '-') && !prev_dash {
-            slug.push('-');
-            prev_dash = true;
+/// Convert spaces or repeated dashes to single dashes.
+/// Remove characters that aren't ascii alphanumerics, underscores, or hyphens.
+/// Convert to lowercase. Also strip leading and trailing whitespace, dashes, and underscores.
+///
+/// See https://github.com/django/django/blob/stable/5.2.x/django/utils/text.py#L453
+pub fn slugify(content: Cow<str>) -> Cow<str> {
+    let mut slug = String::with_capacity(content.len());
+
+    // treat start as if preceded by dash to strip leading dashes
+    let mut prev_dash = true;
+    for c in content.as_ref().nfkd().filter(|c| c.is_ascii()) {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            slug.push(c.to_ascii_lowercase());
+            prev_dash = false;
+        } else if (c.is_whitespace() || c == '-') && !prev_dash {
+            slug.push('-');
+            prev_dash = true;
+        }
+    }
+    slug.truncate(slug.trim_end_matches(['-', '_']).len());
+    Cow::Owned(slug)
+}
+
+/// Parse the argument of the `slice` filter into the arguments of Python's
+/// `slice`, returning `None` if Django would fail silently.
+fn slice_bits(argument: &str, py: Python<'_>) -> PyResult<Option<[Option<BigInt>; 3]>> {
+    let mut bits = Vec::new();
+    for bit in argument.split(':') {
+        if bit.is_empty() {
+            bits.push(None);
+            continue;
+        }
+        let digits = bit.strip_prefix(['-', '+']).unwrap_or(bit);
+        if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+            bits.push(Some(bit.parse().expect("bit is a valid integer")));
+            continue;
+        }
+        match PyType::new::<PyInt>(py).call1((bit,)) {
+            Ok(bit) => bits.push(Some(bit.extract()?)),
+            Err(error) if error.is_instance_of::<PyValueError>(py) => return Ok(None),
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(match bits.as_mut_slice() {
+        [stop] => Some([None, stop.take(), None]),
+        [start, stop] => Some([start.take(), stop.take(), None]),
+        [_, _, Some(step)] if step.is_zero() => None,
+        [start, stop, step] => Some([start.take(), stop.take(), step.take()]),
+        _ => None,
+    })
+}
+
+/// The indices selected by slicing a sequence of length `len`, like
+/// `slice.indices`.
+fn slice_indices(len: usize, bits: &[Option<BigInt>; 3]) -> Vec<usize> {
+    let clamp = |n: &BigInt| {
+        n.to_isize().unwrap_or(match n.is_negative() {
+            true => isize::MIN,
+            false => isize::MAX,
+        })
+    };
+    let len = isize::try_from(len).expect("a string is never longer than isize::MAX");
+    let step = bits[2]
+        .as_ref()
+        .map_or(1, |step| clamp(step).max(-isize::MAX));
+    let adjust = |bound: &Option<BigInt>, default: isize| match bound.as_ref().map(clamp) {
+        None => default,
+        Some(bound) if bound < 0 => match bound.saturating_add(len) {
+            bound if bound < 0 => {
+                if step < 0 {
+                    -1
+                } else {
+                    0
+                }
+            }
+            bound => bound,
+        },
+        Some(bound) if bound >= len => {
+            if step < 0 {
+                len - 1
+            } else {
+                len
+            }
+        }
+        Some(bound) => bound,
+    };
+    let (start, stop) = match step < 0 {
+        true => (adjust(&bits[0], len - 1), adjust(&bits[1], -1)),
+        false => (adjust(&bits[0], 0), adjust(&bits[1], len)),
+    };
+    let mut indices = Vec::new();
+    let mut index = start;
+    while (step > 0 && index < stop) || (step < 0 && index > stop) {
+        indices.push(index.unsigned_abs());
+        index = match index.checked_add(step) {
+            Some(index) => index,
+            None => break,
+        };
+    }
+    indices
+}
+
+impl ResolveFilter for SafeseqFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        _context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let mark_safe = MARK_SAFE.import(py, "django.utils.safestring", "mark_safe")?;
+        match map_items(variable, py, |item| mark_safe.call1((item,))) {
+            Ok(safe) => Ok(Some(Content::Py(safe.into_any()))),
+            Err(error) => Err(error.annotate(py, self.at, "here", template).into()),
+        }
+    }
+}
+
+impl ResolveFilter for SliceFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let content = variable.unwrap_or_else(|| "".as_content());
+        let argument = self
+            .argument
+            .resolve(py, template, context, ResolveFailures::Raise)?
+            .expect("missing argument in context should already have raised")
+            .resolve_string(context)?;
+        let Some(bits) = slice_bits(argument.as_raw(), py)
+            .map_err(|error| error.annotate(py, self.argument.at, "here", template))?
+        else {
+            return Ok(Some(content));
+        };
+        let content = match as_content_string(content, context)? {
+            Ok(content) => {
+                let chars: Vec<char> = content.as_raw().chars().collect();
+                let sliced = slice_indices(chars.len(), &bits)
+                    .into_iter()
+                    .map(|index| chars[index])
+                    .collect();
+                return Ok(Some(content.map_content(|_| Cow::Owned(sliced))));
+            }
+            Err(content) => content,
+        };
+        let sliced = py
+            .get_type::<PySlice>()
+            .call1(PyTuple::new(py, bits)?)
+            .and_then(|slice| content.to_py(py).get_item(slice));
+        match sliced {
+            Ok(sliced) => Ok(Some(Content::Py(sliced))),
+            Err(error)
+                if error.is_instance_of::<PyTypeError>(py)
+                    || error.is_instance_of::<PyValueError>(py) =>
+            {
+                Ok(Some(content))
+            }
+            Err(error) => Err(error
+                .annotate(py, self.argument.at, "here", template)
+                .into()),
         }
     }
-    slug.truncate(slug.trim_end_matches(['-', '_']).len());
-    Cow::Owned(slug)
 }
 
 impl ResolveFilter for SlugifyFilter {
@@ -656,6 +2453,123 @@ impl ResolveFilter for SlugifyFilter {
     }
 }
 
+/// Format `value` natively, or return `None` to let Python format it.
+fn stringformat_native(format: &StringFormat, value: &Content<'_, '_>) -> PyResult<Option<String>> {
+    Ok(match value {
+        Content::Int(n) => format.format(FormatValue::Int(n)),
+        Content::Float(f) => format.format(FormatValue::Float(*f)),
+        Content::Bool(b) => format.format(FormatValue::Bool(*b)),
+        Content::String(s) => format.format(FormatValue::Str(s.as_raw())),
+        Content::Py(value) => {
+            if value.is_exact_instance_of::<PyBool>() {
+                format.format(FormatValue::Bool(value.is_truthy()?))
+            } else if value.is_exact_instance_of::<PyInt>() {
+                format.format(FormatValue::Int(&value.extract()?))
+            } else if value.is_exact_instance_of::<PyFloat>() {
+                format.format(FormatValue::Float(value.extract()?))
+            } else if let Ok(value) = value.cast_exact::<PyString>() {
+                format.format(FormatValue::Str(&value.to_cow()?))
+            } else {
+                None
+            }
+        }
+    })
+}
+
+impl ResolveFilter for StringformatFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let argument = self
+            .argument
+            .resolve(py, template, context, ResolveFailures::Raise)?
+            .expect("missing argument in context should already have raised")
+            .resolve_string(context)?
+            .into_raw();
+        let value = match variable {
+            Some(Content::Py(value)) if value.is_instance_of::<PyTuple>() => {
+                Content::Py(value.str()?.into_any())
+            }
+            Some(value) => value,
+            None => "".as_content(),
+        };
+        let safe = match &value {
+            Content::String(content) => matches!(content, ContentString::HtmlSafe(_)),
+            Content::Py(value) => {
+                #[allow(non_snake_case)]
+                let SafeData = SAFEDATA.import(py, "django.utils.safestring", "SafeData")?;
+                value.is_instance(SafeData)?
+            }
+            Content::Int(_) | Content::Float(_) | Content::Bool(_) => false,
+        };
+
+        let parsed;
+        let format = match &self.format {
+            Some(format) => Some(format),
+            None => {
+                parsed = StringFormat::parse(&argument);
+                parsed.as_ref()
+            }
+        };
+        let native = match format {
+            Some(format) => stringformat_native(format, &value)?,
+            None => None,
+        };
+        let formatted = match native {
+            Some(formatted) => formatted,
+            None => match PyString::new(py, &format!("%{argument}")).rem(value.to_py(py)) {
+                Ok(formatted) => formatted.extract()?,
+                Err(error)
+                    if error.is_instance_of::<PyValueError>(py)
+                        || error.is_instance_of::<PyTypeError>(py) =>
+                {
+                    String::new()
+                }
+                Err(error) => return Err(error.annotate(py, self.at, "here", template).into()),
+            },
+        };
+        Ok(Some(Content::String(match safe {
+            true => ContentString::HtmlSafe(Cow::Owned(formatted)),
+            false => ContentString::HtmlUnsafe(Cow::Owned(formatted)),
+        })))
+    }
+}
+
+impl ResolveFilter for StriptagsFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        _template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let Some(content) = variable else {
+            return Ok(Some("".as_content()));
+        };
+        let content = content.resolve_string(context)?;
+        let text = content.as_raw();
+        if !(text.contains('<') && text.contains('>')) {
+            return Ok(Some(Content::String(content)));
+        }
+        // Text without both `<` and `>` cannot contain a tag and is returned
+        // as is. Anything else goes through Django's `strip_tags`, which
+        // repeatedly feeds the text to `html.parser` until no more tags are
+        // removed. That parser's treatment of comments, `<script>` contents
+        // and malformed markup changes between Python releases, so a Rust
+        // port could not match Django on every supported version; deferring
+        // keeps the output identical to the Python interpreter in use.
+        let stripped = STRIP_TAGS
+            .import(py, "django.utils.html", "strip_tags")?
+            .call1((text.as_ref(),))?
+            .extract::<String>()?;
+        Ok(Some(content.map_content(|_| Cow::Owned(stripped))))
+    }
+}
+
 impl ResolveFilter for TitleFilter {
     fn resolve<'t, 'py>(
         &self,
@@ -701,6 +2615,222 @@ impl ResolveFilter for TitleFilter {
     }
 }
 
+/// Convert a filter argument to an integer like Python's `int`.
+fn int_argument(argument: Content<'_, '_>, py: Python<'_>) -> PyResult<BigInt> {
+    match argument {
+        Content::Int(n) => Ok(n),
+        Content::Bool(b) => Ok(u8::from(b).into()),
+        argument => PyType::new::<PyInt>(py)
+            .call1((argument.to_py(py),))?
+            .extract(),
+    }
+}
+
+/// Convert a length argument like Python's `int`, returning `None` if this
+/// raises a `ValueError`, which Django's filters catch to return their input
+/// unchanged.
+fn length_argument(argument: Content<'_, '_>, py: Python<'_>) -> PyResult<Option<usize>> {
+    let length = match int_argument(argument, py) {
+        Ok(length) => length,
+        Err(error) if error.is_instance_of::<PyValueError>(py) => return Ok(None),
+        Err(error) => return Err(error),
+    };
+    Ok(Some(match length.to_usize() {
+        Some(length) => length,
+        None if length.is_negative() => 0,
+        None => usize::MAX,
+    }))
+}
+
+/// The default truncation text used by `Truncator.chars`.
+fn truncation_text(py: Python<'_>) -> PyResult<String> {
+    pgettext(
+        py,
+        "String to return when truncating text",
+        "%(truncated_text)s…",
+    )
+}
+
+fn resolve_truncate<'t, 'py>(
+    argument: &Argument,
+    variable: Option<Content<'t, 'py>>,
+    py: Python<'py>,
+    template: TemplateString<'t>,
+    context: &mut Context,
+    truncate: impl FnOnce(&str, usize) -> PyResult<String>,
+) -> ResolveResult<'t, 'py> {
+    let Some(content) = variable else {
+        return Ok(Some("".as_content()));
+    };
+    let text = content.resolve_string(context)?;
+    let length = argument
+        .resolve(py, template, context, ResolveFailures::Raise)?
+        .expect("missing argument in context should already have raised");
+    let Some(length) = length_argument(length, py)
+        .map_err(|error| error.annotate(py, argument.at, "here", template))?
+    else {
+        return Ok(Some(Content::String(text)));
+    };
+    let truncated = truncate(text.as_raw(), length)?;
+    Ok(Some(text.map_content(|_| Cow::Owned(truncated))))
+}
+
+impl ResolveFilter for TruncatecharsFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        resolve_truncate(
+            &self.argument,
+            variable,
+            py,
+            template,
+            context,
+            |text, length| Ok(truncate_chars(text, length, &truncation_text(py)?)),
+        )
+    }
+}
+
+impl ResolveFilter for TruncatecharsHtmlFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        resolve_truncate(
+            &self.argument,
+            variable,
+            py,
+            template,
+            context,
+            |text, length| Ok(truncate_chars_html(text, length, &truncation_text(py)?)),
+        )
+    }
+}
+
+impl ResolveFilter for TruncatewordsFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        resolve_truncate(
+            &self.argument,
+            variable,
+            py,
+            template,
+            context,
+            |text, length| Ok(truncate_words(text, length, " …")),
+        )
+    }
+}
+
+impl ResolveFilter for TruncatewordsHtmlFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        resolve_truncate(
+            &self.argument,
+            variable,
+            py,
+            template,
+            context,
+            |text, length| Ok(truncate_words_html(text, length, " …")),
+        )
+    }
+}
+
+/// How deeply `unordered_list` may nest before raising a `RecursionError`,
+/// standing in for Python's recursion limit.
+const MAX_LIST_DEPTH: usize = 500;
+
+/// Whether `unordered_list` treats an item as a nested list.
+fn is_sublist(item: &Bound<'_, PyAny>) -> PyResult<bool> {
+    if item.is_instance_of::<PyList>() || item.is_instance_of::<PyTuple>() {
+        return Ok(true);
+    }
+    let py = item.py();
+    item.is_instance(GENERATOR_TYPE.import(py, "types", "GeneratorType")?)
+}
+
+/// Format `items` as HTML list items, like the `list_formatter` helper of
+/// Django's `unordered_list`.
+fn list_formatter(items: &Bound<'_, PyAny>, tabs: usize, context: &Context) -> PyResult<String> {
+    if tabs > MAX_LIST_DEPTH {
+        return Err(PyRecursionError::new_err(
+            "maximum recursion depth exceeded",
+        ));
+    }
+    let indent = "\t".repeat(tabs);
+    let format_item = |item: Bound<'_, PyAny>, children: Option<Bound<'_, PyAny>>| {
+        let sublist = match children {
+            Some(children) if children.is_truthy()? => format!(
+                "\n{indent}<ul>\n{}\n{indent}</ul>\n{indent}",
+                list_formatter(&children, tabs + 1, context)?
+            ),
+            _ => String::new(),
+        };
+        let item = match context.autoescape {
+            true => conditional_escape(&item)?,
+            false => item.str()?.extract()?,
+        };
+        Ok::<_, PyErr>(format!("{indent}<li>{item}{sublist}</li>"))
+    };
+
+    let mut output = Vec::new();
+    let mut items = items.try_iter()?;
+    let Some(mut item) = items.next().transpose()? else {
+        return Ok(String::new());
+    };
+    loop {
+        let Some(next_item) = items.next().transpose()? else {
+            output.push(format_item(item, None)?);
+            break;
+        };
+        if is_sublist(&next_item)? {
+            output.push(format_item(item, Some(next_item))?);
+            match items.next().transpose()? {
+                Some(following) => item = following,
+                None => break,
+            }
+        } else {
+            output.push(format_item(item, None)?);
+            item = next_item;
+        }
+    }
+    Ok(output.join("\n"))
+}
+
+impl ResolveFilter for UnorderedListFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let Some(content) = variable else {
+            return Ok(Some("".as_content()));
+        };
+        let list = list_formatter(&content.to_py(py), 1, context)
+            .map_err(|error| error.annotate(py, self.at, "here", template))?;
+        Ok(Some(Content::String(ContentString::HtmlSafe(Cow::Owned(
+            list,
+        )))))
+    }
+}
+
 impl ResolveFilter for UpperFilter {
     fn resolve<'t, 'py>(
         &self,
@@ -720,6 +2850,91 @@ impl ResolveFilter for UpperFilter {
     }
 }
 
+impl ResolveFilter for UrlencodeFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let Some(variable) = variable else {
+            return Ok(Some("".as_content()));
+        };
+        let safe = match &self.argument {
+            Some(arg) => arg
+                .resolve(py, template, context, ResolveFailures::Raise)?
+                .expect("missing argument in context should already have raised")
+                .resolve_string_strict(context, arg.at.into())?
+                .into_raw(),
+            None => Cow::Borrowed("/"),
+        };
+        let content = variable.resolve_string(context)?;
+        let quoted = quote(content.as_raw(), &safe);
+        Ok(Some(Content::String(unsafe_result(&content, quoted))))
+    }
+}
+
+/// Convert text URLs and email addresses in `content` into links.
+fn urlize<'t, 'py>(
+    content: Content<'t, 'py>,
+    trim_url_limit: Option<isize>,
+    py: Python<'py>,
+    context: &Context,
+) -> ResolveResult<'t, 'py> {
+    let content = content.resolve_string(context)?;
+    let urlizer = Urlizer {
+        trim_url_limit,
+        nofollow: true,
+        autoescape: context.autoescape,
+        safe_input: matches!(content, ContentString::HtmlSafe(_)),
+    };
+    let urlized = urlizer.urlize(py, content.as_raw())?;
+    Ok(Some(Content::String(ContentString::HtmlSafe(Cow::Owned(
+        urlized,
+    )))))
+}
+
+impl ResolveFilter for UrlizeFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        _template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        match variable {
+            Some(content) => urlize(content, None, py, context),
+            None => Ok(Some("".as_content())),
+        }
+    }
+}
+
+impl ResolveFilter for UrlizetruncFilter {
+    fn resolve<'t, 'py>(
+        &self,
+        variable: Option<Content<'t, 'py>>,
+        py: Python<'py>,
+        template: TemplateString<'t>,
+        context: &mut Context,
+    ) -> ResolveResult<'t, 'py> {
+        let limit = self
+            .argument
+            .resolve(py, template, context, ResolveFailures::Raise)?
+            .expect("missing argument in context should already have raised");
+        let limit = int_argument(limit, py)
+            .map_err(|error| error.annotate(py, self.argument.at, "here", template))?;
+        let limit = limit.to_isize().unwrap_or(match limit.is_negative() {
+            true => isize::MIN,
+            false => isize::MAX,
+        });
+        match variable {
+            Some(content) => urlize(content, Some(limit), py, context),
+            None => Ok(Some("".as_content())),
+        }
+    }
+}
+
 impl ResolveFilter for WordcountFilter {
     fn resolve<'t, 'py>(
         &self,
@@ -887,8 +3102,9 @@ mod tests {
     use crate::parse::TagElement;
     use crate::render::Render;
     use crate::template::django_rusty_templates::{Engine, Template};
-    use crate::types::{Argument, ArgumentType, Text};
+    use crate::types::{ArgumentType, Text};
 
+    use proptest::prelude::*;
     use pyo3::types::{PyDict, PyString};
     static MARK_SAFE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
 
@@ -906,7 +3122,7 @@ mod tests {
         Ok(safe_string)
     }
 
-    use dtl_lexer::types::Variable;
+    use crate::types::Variable;
     use std::collections::HashMap;
 
     #[test]
@@ -918,7 +3134,7 @@ mod tests {
             let context = HashMap::from([("name".to_string(), name.unbind())]);
             let mut context = Context::new(context, None, false);
             let template = TemplateString("{{ name|default:'Bryony' }}");
-            let variable = Variable::new((3, 4));
+            let variable = Variable::new(py, template, (3, 4));
             let filter = Filter {
                 at: (8, 7),
                 all_at: (3, 12),
@@ -932,7 +3148,7 @@ mod tests {
                 )),
             };
 
-            let rendered = filter.render(py, template, &mut context).unwrap();
+            let rendered = filter.render_to_string(py, template, &mut context).unwrap();
             assert_eq!(rendered, "Lily");
         });
     }
@@ -1136,7 +3352,7 @@ mod tests {
             let context = HashMap::from([("quotes".to_string(), name.unbind())]);
             let mut context = Context::new(context, None, false);
             let template = TemplateString("{{ quotes|addslashes }}");
-            let variable = Variable::new((3, 6));
+            let variable = Variable::new(py, template, (3, 6));
             let filter = Filter {
                 at: (10, 10),
                 all_at: (3, 17),
@@ -1144,7 +3360,7 @@ mod tests {
                 filter: FilterType::AddSlashes(AddSlashesFilter),
             };
 
-            let rendered = filter.render(py, template, &mut context).unwrap();
+            let rendered = filter.render_to_string(py, template, &mut context).unwrap();
             assert_eq!(rendered, r"\'hello\'");
         });
     }
@@ -1246,6 +3462,31 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_pad_matches_python() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            for text in ["", "a", "ab", "café", "odd", "even"] {
+                let string = PyString::new(py, text);
+                for width in 0..9 {
+                    for (justify, method) in [
+                        (Justify::Left, "ljust"),
+                        (Justify::Center, "center"),
+                        (Justify::Right, "rjust"),
+                    ] {
+                        let expected: String = string
+                            .call_method1(method, (width,))
+                            .unwrap()
+                            .extract()
+                            .unwrap();
+                        assert_eq!(pad(Cow::Borrowed(text), width, justify), expected);
+                    }
+                }
+            }
+        });
+    }
+
     #[test]
     fn test_render_filter_center_no_variable() {
         Python::initialize();
@@ -1289,7 +3530,7 @@ mod tests {
             let context = HashMap::new();
             let mut context = Context::new(context, None, false);
             let template = TemplateString("{{ name|default:'Bryony' }}");
-            let variable = Variable::new((3, 4));
+            let variable = Variable::new(py, template, (3, 4));
             let filter = Filter {
                 at: (8, 7),
                 all_at: (3, 12),
@@ -1303,7 +3544,7 @@ mod tests {
                 )),
             };
 
-            let rendered = filter.render(py, template, &mut context).unwrap();
+            let rendered = filter.render_to_string(py, template, &mut context).unwrap();
             assert_eq!(rendered, "Bryony");
         });
     }
@@ -1316,7 +3557,7 @@ mod tests {
             let context = HashMap::new();
             let mut context = Context::new(context, None, false);
             let template = TemplateString("{{ count|default:12}}");
-            let variable = Variable::new((3, 5));
+            let variable = Variable::new(py, template, (3, 5));
             let filter = Filter {
                 at: (9, 7),
                 all_at: (3, 12),
@@ -1330,7 +3571,7 @@ mod tests {
                 )),
             };
 
-            let rendered = filter.render(py, template, &mut context).unwrap();
+            let rendered = filter.render_to_string(py, template, &mut context).unwrap();
             assert_eq!(rendered, "12");
         });
     }
@@ -1343,7 +3584,7 @@ mod tests {
             let context = HashMap::new();
             let mut context = Context::new(context, None, false);
             let template = TemplateString("{{ count|default:3.5}}");
-            let variable = Variable::new((3, 5));
+            let variable = Variable::new(py, template, (3, 5));
             let filter = Filter {
                 at: (9, 7),
                 all_at: (3, 12),
@@ -1357,7 +3598,7 @@ mod tests {
                 )),
             };
 
-            let rendered = filter.render(py, template, &mut context).unwrap();
+            let rendered = filter.render_to_string(py, template, &mut context).unwrap();
             assert_eq!(rendered, "3.5");
         });
     }
@@ -1371,7 +3612,7 @@ mod tests {
             let context = HashMap::from([("me".to_string(), me.unbind())]);
             let mut context = Context::new(context, None, false);
             let template = TemplateString("{{ name|default:me}}");
-            let variable = Variable::new((3, 4));
+            let variable = Variable::new(py, template, (3, 4));
             let filter = Filter {
                 at: (8, 7),
                 all_at: (3, 11),
@@ -1379,13 +3620,13 @@ mod tests {
                 filter: FilterType::Default(DefaultFilter::new(
                     Argument {
                         at: (16, 2),
-                        argument_type: ArgumentType::Variable(Variable::new((16, 2))),
+                        argument_type: ArgumentType::Variable(Variable::new(py, template, (16, 2))),
                     },
                     (8, 7),
                 )),
             };
 
-            let rendered = filter.render(py, template, &mut context).unwrap();
+            let rendered = filter.render_to_string(py, template, &mut context).unwrap();
             assert_eq!(rendered, "Lily");
         });
     }
@@ -1399,7 +3640,7 @@ mod tests {
             let context = HashMap::from([("name".to_string(), name.unbind())]);
             let mut context = Context::new(context, None, false);
             let template = TemplateString("{{ name|lower }}");
-            let variable = Variable::new((3, 4));
+            let variable = Variable::new(py, template, (3, 4));
             let filter = Filter {
                 at: (8, 5),
                 all_at: (3, 10),
@@ -1407,7 +3648,7 @@ mod tests {
                 filter: FilterType::Lower(LowerFilter),
             };
 
-            let rendered = filter.render(py, template, &mut context).unwrap();
+            let rendered = filter.render_to_string(py, template, &mut context).unwrap();
             assert_eq!(rendered, "lily");
         });
     }
@@ -1420,7 +3661,7 @@ mod tests {
             let context = HashMap::new();
             let mut context = Context::new(context, None, false);
             let template = TemplateString("{{ name|lower }}");
-            let variable = Variable::new((3, 4));
+            let variable = Variable::new(py, template, (3, 4));
             let filter = Filter {
                 at: (8, 5),
                 all_at: (3, 10),
@@ -1428,7 +3669,7 @@ mod tests {
                 filter: FilterType::Lower(LowerFilter),
             };
 
-            let rendered = filter.render(py, template, &mut context).unwrap();
+            let rendered = filter.render_to_string(py, template, &mut context).unwrap();
             assert_eq!(rendered, "");
         });
     }
@@ -1441,7 +3682,7 @@ mod tests {
             let context = HashMap::new();
             let mut context = Context::new(context, None, false);
             let template = TemplateString("{{ name|default:'Bryony'|lower }}");
-            let variable = Variable::new((3, 4));
+            let variable = Variable::new(py, template, (3, 4));
             let default = Filter {
                 at: (8, 7),
                 all_at: (3, 12),
@@ -1461,7 +3702,7 @@ mod tests {
                 filter: FilterType::Lower(LowerFilter),
             };
 
-            let rendered = lower.render(py, template, &mut context).unwrap();
+            let rendered = lower.render_to_string(py, template, &mut context).unwrap();
             assert_eq!(rendered, "bryony");
         });
     }
@@ -1475,7 +3716,7 @@ mod tests {
             let context = HashMap::from([("name".to_string(), name.unbind())]);
             let mut context = Context::new(context, None, false);
             let template = TemplateString("{{ name|upper }}");
-            let variable = Variable::new((3, 4));
+            let variable = Variable::new(py, template, (3, 4));
             let filter = Filter {
                 at: (8, 5),
                 all_at: (3, 10),
@@ -1483,7 +3724,7 @@ mod tests {
                 filter: FilterType::Upper(UpperFilter),
             };
 
-            let rendered = filter.render(py, template, &mut context).unwrap();
+            let rendered = filter.render_to_string(py, template, &mut context).unwrap();
             assert_eq!(rendered, "FOO");
         });
     }
@@ -1496,7 +3737,7 @@ mod tests {
             let context = HashMap::new();
             let mut context = Context::new(context, None, false);
             let template = TemplateString("{{ name|upper }}");
-            let variable = Variable::new((3, 4));
+            let variable = Variable::new(py, template, (3, 4));
             let filter = Filter {
                 at: (8, 5),
                 all_at: (3, 10),
@@ -1504,8 +3745,131 @@ mod tests {
                 filter: FilterType::Upper(UpperFilter),
             };
 
-            let rendered = filter.render(py, template, &mut context).unwrap();
+            let rendered = filter.render_to_string(py, template, &mut context).unwrap();
             assert_eq!(rendered, "");
         });
     }
+
+    fn setup_django(py: Python<'_>) {
+        let os_module = PyModule::import(py, "os").unwrap();
+        let environ = os_module.getattr("environ").unwrap();
+        environ
+            .call_method(
+                "setdefault",
+                ("DJANGO_SETTINGS_MODULE", "tests.settings"),
+                None,
+            )
+            .unwrap();
+        let django_module = PyModule::import(py, "django").unwrap();
+        django_module.call_method0("setup").unwrap();
+    }
+
+    fn assert_floatformat_matches_django(
+        py: Python<'_>,
+        value: &Bound<'_, PyAny>,
+        argument: &str,
+    ) -> Result<(), TestCaseError> {
+        setup_django(py);
+        let floatformat = py
+            .import("django.template.defaultfilters")
+            .unwrap()
+            .getattr("floatformat")
+            .unwrap();
+        let expected: String = floatformat
+            .call1((value, argument))
+            .unwrap()
+            .extract()
+            .unwrap();
+
+        let engine = Arc::new(Engine::empty());
+        let template_string = "{{ value|floatformat:argument }}".to_string();
+        let template = Template::new_from_string(py, template_string, engine).unwrap();
+        let context = PyDict::new(py);
+        context.set_item("value", value).unwrap();
+        context.set_item("argument", argument).unwrap();
+        let rendered = template
+            .py_render(py, Some(context.into_any()), None)
+            .unwrap();
+        prop_assert_eq!(rendered, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_floatformat_string_matches_django() {
+        Python::initialize();
+        proptest!(|(value in r"-?[0-9]{1,20}(\.[0-9]{0,12})?(e-?[0-9])?", argument in r"-?[0-9]{0,2}(g|u|gu|ug)?")| {
+            prop_assume!(!argument.is_empty());
+            Python::attach(|py| {
+                let value = PyString::new(py, &value).into_any();
+                assert_floatformat_matches_django(py, &value, &argument)
+            })?;
+        });
+    }
+
+    #[test]
+    fn test_floatformat_float_matches_django() {
+        Python::initialize();
+        proptest!(|(value: f64, argument in r"-?[0-9]{1,2}")| {
+            Python::attach(|py| {
+                let value = value.into_pyobject(py).unwrap().into_any();
+                assert_floatformat_matches_django(py, &value, &argument)
+            })?;
+        });
+    }
+
+    #[test]
+    fn test_slice_indices_matches_python() {
+        Python::initialize();
+        let bound = proptest::option::of(-12i32..12);
+        proptest!(|(len in 0usize..10, start in bound.clone(), stop in bound, step in proptest::option::of(prop_oneof![-4i32..0, 1i32..4]))| {
+            Python::attach(|py| {
+                let bits = [start, stop, step].map(|bit| bit.map(BigInt::from));
+                let slice = py
+                    .get_type::<PySlice>()
+                    .call1(PyTuple::new(py, bits.clone()).unwrap())
+                    .unwrap();
+                let expected: Vec<usize> = py
+                    .eval(c"lambda s, n: list(range(*s.indices(n)))", None, None)
+                    .unwrap()
+                    .call1((slice, len))
+                    .unwrap()
+                    .extract()
+                    .unwrap();
+                prop_assert_eq!(slice_indices(len, &bits), expected);
+                Ok(())
+            })?;
+        });
+    }
+
+    #[test]
+    fn test_dictsort() {
+        Python::initialize();
+        Python::attach(|py| {
+            let value = py
+                .eval(
+                    c"[{'a': 1, 'b': {'c': 'x'}}, {'a': 0, 'b': {'c': 'z'}}, {'a': 1, 'b': {'c': 'y'}}]",
+                    None,
+                    None,
+                )
+                .unwrap();
+            let sorted = |argument: &str, reverse: bool| {
+                let argument = PyString::new(py, argument).into_any();
+                dictsort(&value, argument, reverse)
+                    .unwrap()
+                    .map(|sorted| sorted.str().unwrap().to_string())
+            };
+            assert_eq!(
+                sorted("a", true).unwrap(),
+                "[{'a': 1, 'b': {'c': 'x'}}, {'a': 1, 'b': {'c': 'y'}}, {'a': 0, 'b': {'c': 'z'}}]"
+            );
+            assert_eq!(
+                sorted("b.c", false).unwrap(),
+                "[{'a': 1, 'b': {'c': 'x'}}, {'a': 1, 'b': {'c': 'y'}}, {'a': 0, 'b': {'c': 'z'}}]"
+            );
+            assert_eq!(sorted("b._c", false), None);
+            assert_eq!(sorted("b.", false), None);
+            assert_eq!(sorted("missing", false), None);
+            assert!(dictsort(&value, PyString::new(py, "").into_any(), false).is_err());
+        });
+    }
 }
@@ -0,0 +1,202 @@
+//! HTML helpers matching Python's `html` module.
+
+use std::borrow::Cow;
+
+use html_escape::NAMED_ENTITIES;
+
+/// Entities which may be used without a trailing semicolon, from the keys of
+/// `html.entities.html5` without one.
+const LEGACY_ENTITIES: [&str; 106] = [
+    "AElig", "AMP", "Aacute", "Acirc", "Agrave", "Aring", "Atilde", "Auml", "COPY", "Ccedil",
+    "ETH", "Eacute", "Ecirc", "Egrave", "Euml", "GT", "Iacute", "Icirc", "Igrave", "Iuml", "LT",
+    "Ntilde", "Oacute", "Ocirc", "Ograve", "Oslash", "Otilde", "Ouml", "QUOT", "REG", "THORN",
+    "Uacute", "Ucirc", "Ugrave", "Uuml", "Yacute", "aacute", "acirc", "acute", "aelig", "agrave",
+    "amp", "aring", "atilde", "auml", "brvbar", "ccedil", "cedil", "cent", "copy", "curren", "deg",
+    "divide", "eacute", "ecirc", "egrave", "eth", "euml", "frac12", "frac14", "frac34", "gt",
+    "iacute", "icirc", "iexcl", "igrave", "iquest", "iuml", "laquo", "lt", "macr", "micro",
+    "middot", "nbsp", "not", "ntilde", "oacute", "ocirc", "ograve", "ordf", "ordm", "oslash",
+    "otilde", "ouml", "para", "plusmn", "pound", "quot", "raquo", "reg", "sect", "shy", "sup1",
+    "sup2", "sup3", "szlig", "thorn", "times", "uacute", "ucirc", "ugrave", "uml", "uuml",
+    "yacute", "yen", "yuml",
+];
+
+/// Replacements for numeric character references to invalid code points.
+fn invalid_charref(number: u32) -> Option<char> {
+    Some(match number {
+        0x00 => '\u{fffd}',
+        0x0d => '\r',
+        0x80 => '\u{20ac}',
+        0x81 => '\u{81}',
+        0x82 => '\u{201a}',
+        0x83 => '\u{0192}',
+        0x84 => '\u{201e}',
+        0x85 => '\u{2026}',
+        0x86 => '\u{2020}',
+        0x87 => '\u{2021}',
+        0x88 => '\u{02c6}',
+        0x89 => '\u{2030}',
+        0x8a => '\u{0160}',
+        0x8b => '\u{2039}',
+        0x8c => '\u{0152}',
+        0x8d => '\u{8d}',
+        0x8e => '\u{017d}',
+        0x8f => '\u{8f}',
+        0x90 => '\u{90}',
+        0x91 => '\u{2018}',
+        0x92 => '\u{2019}',
+        0x93 => '\u{201c}',
+        0x94 => '\u{201d}',
+        0x95 => '\u{2022}',
+        0x96 => '\u{2013}',
+        0x97 => '\u{2014}',
+        0x98 => '\u{02dc}',
+        0x99 => '\u{2122}',
+        0x9a => '\u{0161}',
+        0x9b => '\u{203a}',
+        0x9c => '\u{0153}',
+        0x9d => '\u{9d}',
+        0x9e => '\u{017e}',
+        0x9f => '\u{0178}',
+        _ => return None,
+    })
+}
+
+fn is_invalid_codepoint(number: u32) -> bool {
+    matches!(number, 0x1..=0x8 | 0xb | 0xe..=0x1f | 0x7f..=0x9f | 0xfdd0..=0xfdef)
+        || number & 0xfffe == 0xfffe
+}
+
+fn named_entity(name: &str) -> Option<&'static str> {
+    NAMED_ENTITIES
+        .binary_search_by(|(entity, _)| entity.cmp(&name.as_bytes()))
+        .ok()
+        .map(|index| NAMED_ENTITIES[index].1)
+}
+
+fn numeric_charref(number: Option<u32>, output: &mut String) {
+    match number {
+        Some(number) => match invalid_charref(number) {
+            Some(c) => output.push(c),
+            None if (0xd800..=0xdfff).contains(&number) || number > 0x0010_ffff => {
+                output.push('\u{fffd}');
+            }
+            None if is_invalid_codepoint(number) => {}
+            None => output.push(char::from_u32(number).expect("a valid code point")),
+        },
+        None => output.push('\u{fffd}'),
+    }
+}
+
+/// Replace the character reference at the start of `text` (just after the
+/// `&`), returning the number of bytes consumed.
+fn replace_charref(text: &str, output: &mut String) -> usize {
+    let bytes = text.as_bytes();
+    if let Some(numeric) = text.strip_prefix('#') {
+        let (digits, radix, prefix) = match numeric.strip_prefix(['x', 'X']) {
+            Some(hex) => (hex, 16, 2),
+            None => (numeric, 10, 1),
+        };
+        let len = digits
+            .bytes()
+            .take_while(|b| match radix {
+                16 => b.is_ascii_hexdigit(),
+                _ => b.is_ascii_digit(),
+            })
+            .count();
+        if len == 0 {
+            output.push('&');
+            return 0;
+        }
+        numeric_charref(u32::from_str_radix(&digits[..len], radix).ok(), output);
+        let end = prefix + len;
+        return end + usize::from(bytes.get(end) == Some(&b';'));
+    }
+
+    let name_len = text
+        .char_indices()
+        .take(32)
+        .take_while(|(_, c)| !matches!(c, '\t' | '\n' | '\x0c' | ' ' | '<' | '&' | '#' | ';'))
+        .last()
+        .map_or(0, |(index, c)| index + c.len_utf8());
+    if name_len == 0 {
+        output.push('&');
+        return 0;
+    }
+    let name = &text[..name_len];
+    let has_semicolon = bytes.get(name_len) == Some(&b';');
+    let end = name_len + usize::from(has_semicolon);
+    let entity = match has_semicolon {
+        true => named_entity(name),
+        false if LEGACY_ENTITIES.contains(&name) => named_entity(name),
+        false => None,
+    };
+    if let Some(entity) = entity {
+        output.push_str(entity);
+        return end;
+    }
+    // Find the longest legacy entity the reference starts with.
+    let reference = &text[..end];
+    for (prefix_len, _) in reference
+        .char_indices()
+        .skip(2)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+    {
+        let prefix = &reference[..prefix_len];
+        if LEGACY_ENTITIES.contains(&prefix) {
+            output.push_str(named_entity(prefix).expect("legacy entities are named"));
+            output.push_str(&reference[prefix_len..]);
+            return end;
+        }
+    }
+    output.push('&');
+    output.push_str(reference);
+    end
+}
+
+/// Convert all named and numeric character references to the corresponding
+/// Unicode characters, like Python's `html.unescape`.
+pub fn unescape(text: &str) -> Cow<'_, str> {
+    let Some(first) = text.find('&') else {
+        return Cow::Borrowed(text);
+    };
+    let mut output = String::with_capacity(text.len());
+    output.push_str(&text[..first]);
+    let mut rest = &text[first..];
+    while let Some(amp) = rest.find('&') {
+        output.push_str(&rest[..amp]);
+        rest = &rest[amp + 1..];
+        let consumed = replace_charref(rest, &mut output);
+        rest = &rest[consumed..];
+    }
+    output.push_str(rest);
+    Cow::Owned(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unescape() {
+        let cases = [
+            ("no entities", "no entities"),
+            ("&amp;&lt;&gt;&quot;&#x27;", "&<>\"'"),
+            ("&amp", "&"),
+            ("&ampx", "&x"),
+            ("&copy=3", "©=3"),
+            ("&notit;", "¬it;"),
+            ("&notin;", "∉"),
+            ("&unknown;", "&unknown;"),
+            ("&#65;&#x42;&#X43", "ABC"),
+            ("&#0;&#x80;&#xd800;&#1;", "\u{fffd}€\u{fffd}"),
+            ("&#99999999999;", "\u{fffd}"),
+            ("& &# &#x; &;", "& &# &#x; &;"),
+            ("&acirc;&Acirc", "âÂ"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(unescape(text), expected, "{text}");
+        }
+    }
+}
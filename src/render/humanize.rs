@@ -0,0 +1,264 @@
+//! The filters of `django.contrib.humanize`.
+//!
+//! See <https://github.com/django/django/blob/stable/5.2.x/django/contrib/humanize/templatetags/humanize.py>
+
+use num_bigint::BigInt;
+use num_traits::{Euclid, Signed, ToPrimitive};
+use pyo3::intern;
+use pyo3::prelude::*;
+use pyo3::types::{PyDate, PyDateAccess, PyDateTime, PyDelta, PyDeltaAccess, PyType, PyTzInfo};
+
+use crate::render::common::{gettext, ngettext, pgettext};
+use crate::render::dateformat::{ordinal as day_ordinal, timesince};
+
+const ORDINALS: [(&str, &str); 10] = [
+    ("ordinal 0", "{}th"),
+    ("ordinal 1", "{}st"),
+    ("ordinal 2", "{}nd"),
+    ("ordinal 3", "{}rd"),
+    ("ordinal 4", "{}th"),
+    ("ordinal 5", "{}th"),
+    ("ordinal 6", "{}th"),
+    ("ordinal 7", "{}th"),
+    ("ordinal 8", "{}th"),
+    ("ordinal 9", "{}th"),
+];
+
+const AP_NUMBERS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// The powers of ten named by `intword`, with their translatable strings.
+const INTWORD_CONVERTERS: [(u32, &str); 11] = [
+    (6, "%(value)s million"),
+    (9, "%(value)s billion"),
+    (12, "%(value)s trillion"),
+    (15, "%(value)s quadrillion"),
+    (18, "%(value)s quintillion"),
+    (21, "%(value)s sextillion"),
+    (24, "%(value)s septillion"),
+    (27, "%(value)s octillion"),
+    (30, "%(value)s nonillion"),
+    (33, "%(value)s decillion"),
+    (100, "%(value)s googol"),
+];
+
+/// Integers below this are exactly representable as a float.
+const MAX_EXACT_FLOAT: i64 = 1 << 53;
+
+/// Format an integer as an ordinal, like `1st`, `2nd` or `11th`.
+pub fn ordinal(py: Python<'_>, value: &BigInt) -> PyResult<String> {
+    let last_two = value
+        .rem_euclid(&BigInt::from(100))
+        .to_usize()
+        .expect("The remainder is less than 100");
+    let template = match last_two {
+        11..=13 => pgettext(py, "ordinal 11, 12, 13", "{}th")?,
+        _ => {
+            let (context, template) = ORDINALS[last_two % 10];
+            pgettext(py, context, template)?
+        }
+    };
+    Ok(template.replace("{}", &value.to_string()))
+}
+
+/// Spell out the numbers one to nine, Associated Press style.
+pub fn apnumber(py: Python<'_>, value: &BigInt) -> PyResult<Option<String>> {
+    match value.to_usize() {
+        Some(value @ 1..=9) => Ok(Some(gettext(py, AP_NUMBERS[value - 1])?)),
+        _ => Ok(None),
+    }
+}
+
+/// Add commas between each group of three digits at the start of `text`,
+/// like `intcomma` when localisation is disabled.
+///
+/// Returns `None` if `text` starts with non-ASCII digits, which Django's
+/// regular expressions also match.
+pub fn intcomma(text: &str) -> Option<String> {
+    let unsigned = text.strip_prefix('-').unwrap_or(text);
+    let digits = unsigned
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(unsigned.len());
+    if unsigned[digits..]
+        .chars()
+        .next()
+        .is_some_and(|c| !c.is_ascii() && c.is_numeric())
+    {
+        return None;
+    }
+    if digits == 0 {
+        return Some(text.to_string());
+    }
+    let sign = &text[..text.len() - unsigned.len()];
+    let (integer, rest) = unsigned.split_at(digits);
+    let mut result = String::with_capacity(text.len() + digits / 3);
+    result.push_str(sign);
+    for (index, digit) in integer.chars().enumerate() {
+        if index != 0 && (digits - index).is_multiple_of(3) {
+            result.push(',');
+        }
+        result.push(digit);
+    }
+    result.push_str(rest);
+    Some(result)
+}
+
+/// A large integer scaled down to its `intword` unit.
+pub struct Intword {
+    /// The value divided by the unit, to be formatted with one decimal place.
+    pub value: f64,
+    /// The translatable string for the unit.
+    pub unit: &'static str,
+    /// The number which chooses the plural form of the unit.
+    pub count: i64,
+}
+
+/// Scale `value` to the unit used by `intword`, or return `None` when it
+/// is displayed unchanged.
+pub fn intword(py: Python<'_>, value: &BigInt) -> PyResult<Option<Intword>> {
+    let abs_value = value.abs();
+    if abs_value < BigInt::from(1_000_000) {
+        return Ok(None);
+    }
+    for (exponent, unit) in INTWORD_CONVERTERS {
+        let large_number = BigInt::from(10).pow(exponent);
+        if abs_value >= &large_number * 1000 {
+            continue;
+        }
+        let value = match (value.to_i64(), large_number.to_f64()) {
+            // Both are exact, so the division is correctly rounded like Python's.
+            #[allow(clippy::cast_precision_loss)]
+            (Some(small), Some(divisor)) if small.abs() < MAX_EXACT_FLOAT => small as f64 / divisor,
+            _ => value
+                .into_pyobject(py)?
+                .call_method1(intern!(py, "__truediv__"), (large_number,))?
+                .extract()?,
+        };
+        return Ok(Some(Intword {
+            value,
+            unit,
+            count: round_away_from_one(value).abs(),
+        }));
+    }
+    Ok(None)
+}
+
+/// Round towards the closest integer away from one, like Django's
+/// `round_away_from_one`.
+fn round_away_from_one(value: f64) -> i64 {
+    let shifted = value - 1.0;
+    let rounded = match shifted.is_sign_negative() {
+        true => shifted.floor(),
+        false => shifted.ceil(),
+    };
+    #[allow(clippy::cast_possible_truncation)]
+    let rounded = rounded as i64;
+    rounded + 1
+}
+
+/// The number of days from today to the date of `value`, which has the
+/// `year`, `month` and `day` attributes of a date, along with that date.
+///
+/// Returns `None` if `value` is missing one of these attributes.
+pub fn day_offset<'py>(value: &Bound<'py, PyAny>) -> PyResult<Option<(Bound<'py, PyDate>, i32)>> {
+    let py = value.py();
+    let tzinfo = match value.getattr_opt(intern!(py, "tzinfo"))? {
+        Some(tzinfo) => tzinfo,
+        None => py.None().into_bound(py),
+    };
+    let date =
+        if value.is_exact_instance_of::<PyDate>() || value.is_exact_instance_of::<PyDateTime>() {
+            let value = value.cast::<PyDate>()?;
+            PyDate::new(py, value.get_year(), value.get_month(), value.get_day())?
+        } else {
+            let (Some(year), Some(month), Some(day)) = (
+                value.getattr_opt(intern!(py, "year"))?,
+                value.getattr_opt(intern!(py, "month"))?,
+                value.getattr_opt(intern!(py, "day"))?,
+            ) else {
+                return Ok(None);
+            };
+            PyType::new::<PyDate>(py)
+                .call1((year, month, day))?
+                .cast_into::<PyDate>()?
+        };
+    let now = PyType::new::<PyDateTime>(py).call_method1(intern!(py, "now"), (tzinfo,))?;
+    let now = now.cast::<PyDate>()?;
+    let days = day_ordinal(date.get_year(), date.get_month(), date.get_day())
+        - day_ordinal(now.get_year(), now.get_month(), now.get_day());
+    Ok(Some((date, days)))
+}
+
+/// Describe how long ago or until `value` is, like Django's `naturaltime`.
+pub fn naturaltime(value: &Bound<'_, PyAny>) -> PyResult<String> {
+    let py = value.py();
+    let timezone = match value.call_method0(intern!(py, "utcoffset"))?.is_none() {
+        true => py.None().into_bound(py),
+        false => PyTzInfo::utc(py)?.to_owned().into_any(),
+    };
+    let now = PyType::new::<PyDateTime>(py).call_method1(intern!(py, "now"), (timezone,))?;
+    let past = value.lt(&now)?;
+    let delta = match past {
+        true => now.sub(value)?,
+        false => value.sub(&now)?,
+    };
+    let delta = delta.cast_into::<PyDelta>()?;
+    if delta.get_days() != 0 {
+        let (context, text) = match past {
+            true => ("naturaltime-past", "%(delta)s ago"),
+            false => ("naturaltime-future", "%(delta)s from now"),
+        };
+        let delta = timesince(value, Some(&now), !past, 2, Some(context))?;
+        return Ok(gettext(py, text)?.replace("%(delta)s", &delta));
+    }
+    let seconds = i64::from(delta.get_seconds());
+    let (singular, plural, count) = match (seconds, past) {
+        (0, _) => return gettext(py, "now"),
+        (1..60, true) => ("a second ago", "%(count)s\u{a0}seconds ago", seconds),
+        (1..60, false) => (
+            "a second from now",
+            "%(count)s\u{a0}seconds from now",
+            seconds,
+        ),
+        (60..3600, true) => ("a minute ago", "%(count)s\u{a0}minutes ago", seconds / 60),
+        (60..3600, false) => (
+            "a minute from now",
+            "%(count)s\u{a0}minutes from now",
+            seconds / 60,
+        ),
+        (_, true) => ("an hour ago", "%(count)s\u{a0}hours ago", seconds / 3600),
+        (_, false) => (
+            "an hour from now",
+            "%(count)s\u{a0}hours from now",
+            seconds / 3600,
+        ),
+    };
+    Ok(ngettext(py, singular, plural, count)?.replace("%(count)s", &count.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intcomma() {
+        assert_eq!(intcomma("100").unwrap(), "100");
+        assert_eq!(intcomma("1000").unwrap(), "1,000");
+        assert_eq!(intcomma("-123456.78").unwrap(), "-123,456.78");
+        assert_eq!(intcomma("-123").unwrap(), "-123");
+        assert_eq!(intcomma("1234567abc").unwrap(), "1,234,567abc");
+        assert_eq!(intcomma("abc").unwrap(), "abc");
+        assert_eq!(intcomma("-").unwrap(), "-");
+        assert_eq!(intcomma("12\u{661}"), None);
+    }
+
+    #[test]
+    fn test_round_away_from_one() {
+        assert_eq!(round_away_from_one(1.0), 1);
+        assert_eq!(round_away_from_one(1.5), 2);
+        assert_eq!(round_away_from_one(0.5), 0);
+        assert_eq!(round_away_from_one(-1.5), -2);
+        assert_eq!(round_away_from_one(999.9), 1000);
+    }
+}
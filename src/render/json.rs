@@ -0,0 +1,239 @@
+//! JSON serialisation matching `json.dumps` with its default arguments, for
+//! the plain Python types which don't need a custom encoder.
+
+use std::fmt::Write;
+
+use num_bigint::BigInt;
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple};
+
+use crate::render::stringformat::float_repr;
+
+/// Containers nested deeper than this are left to Python, which detects
+/// circular references.
+const MAX_DEPTH: usize = 100;
+
+/// Integers with more bits than this may exceed Python's limit on the number
+/// of digits in an `int` to `str` conversion.
+const MAX_INT_BITS: u64 = 14_000;
+
+/// Escape a string for use in JSON, like `json.dumps` with `ensure_ascii`.
+///
+/// When `script_safe` is set, `<`, `>` and `&` are also escaped, like
+/// Django's `json_script`.
+pub fn push_json_string(json: &mut String, text: &str, script_safe: bool) {
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            '\u{8}' => json.push_str("\\b"),
+            '\u{c}' => json.push_str("\\f"),
+            '<' if script_safe => json.push_str("\\u003C"),
+            '>' if script_safe => json.push_str("\\u003E"),
+            '&' if script_safe => json.push_str("\\u0026"),
+            ' '..='~' => json.push(c),
+            _ => {
+                let mut units = [0; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    write!(json, "\\u{unit:04x}").expect("Writing to a String cannot fail");
+                }
+            }
+        }
+    }
+    json.push('"');
+}
+
+/// Escape `<`, `>` and `&` in JSON produced by Python, like Django's
+/// `json_script`.
+pub fn escape_script(json: &str) -> String {
+    json.replace('<', "\\u003C")
+        .replace('>', "\\u003E")
+        .replace('&', "\\u0026")
+}
+
+fn push_float(json: &mut String, float: f64) {
+    if float.is_nan() {
+        json.push_str("NaN");
+    } else if float.is_infinite() {
+        json.push_str(match float.is_sign_negative() {
+            true => "-Infinity",
+            false => "Infinity",
+        });
+    } else {
+        json.push_str(&float_repr(float));
+    }
+}
+
+/// Serialise a dictionary key, or return `None` if it isn't a plain type.
+fn push_key(json: &mut String, key: &Bound<'_, PyAny>, script_safe: bool) -> PyResult<Option<()>> {
+    if let Ok(key) = key.cast::<PyString>() {
+        let Ok(key) = key.to_str() else {
+            return Ok(None);
+        };
+        push_json_string(json, key, script_safe);
+    } else if key.is_exact_instance_of::<PyBool>() {
+        json.push_str(match key.is_truthy()? {
+            true => "\"true\"",
+            false => "\"false\"",
+        });
+    } else if key.is_none() {
+        json.push_str("\"null\"");
+    } else if key.is_exact_instance_of::<PyInt>() {
+        let key: BigInt = key.extract()?;
+        if key.bits() > MAX_INT_BITS {
+            return Ok(None);
+        }
+        write!(json, "\"{key}\"").expect("Writing to a String cannot fail");
+    } else if key.is_exact_instance_of::<PyFloat>() {
+        json.push('"');
+        push_float(json, key.extract()?);
+        json.push('"');
+    } else {
+        return Ok(None);
+    }
+    Ok(Some(()))
+}
+
+fn push_value(
+    json: &mut String,
+    value: &Bound<'_, PyAny>,
+    script_safe: bool,
+    depth: usize,
+) -> PyResult<Option<()>> {
+    if depth > MAX_DEPTH {
+        return Ok(None);
+    }
+    if let Ok(value) = value.cast::<PyString>() {
+        let Ok(value) = value.to_str() else {
+            return Ok(None);
+        };
+        push_json_string(json, value, script_safe);
+    } else if value.is_none() {
+        json.push_str("null");
+    } else if value.is_exact_instance_of::<PyBool>() {
+        json.push_str(match value.is_truthy()? {
+            true => "true",
+            false => "false",
+        });
+    } else if value.is_exact_instance_of::<PyInt>() {
+        let value: BigInt = value.extract()?;
+        if value.bits() > MAX_INT_BITS {
+            return Ok(None);
+        }
+        write!(json, "{value}").expect("Writing to a String cannot fail");
+    } else if value.is_exact_instance_of::<PyFloat>() {
+        push_float(json, value.extract()?);
+    } else if let Ok(dict) = value.cast_exact::<PyDict>() {
+        json.push('{');
+        for (index, (key, value)) in dict.iter().enumerate() {
+            if index > 0 {
+                json.push_str(", ");
+            }
+            if push_key(json, &key, script_safe)?.is_none() {
+                return Ok(None);
+            }
+            json.push_str(": ");
+            if push_value(json, &value, script_safe, depth + 1)?.is_none() {
+                return Ok(None);
+            }
+        }
+        json.push('}');
+    } else if value.is_exact_instance_of::<PyList>() || value.is_exact_instance_of::<PyTuple>() {
+        json.push('[');
+        for (index, item) in value.try_iter()?.enumerate() {
+            if index > 0 {
+                json.push_str(", ");
+            }
+            if push_value(json, &item?, script_safe, depth + 1)?.is_none() {
+                return Ok(None);
+            }
+        }
+        json.push(']');
+    } else {
+        return Ok(None);
+    }
+    Ok(Some(()))
+}
+
+/// Serialise `value` like `json.dumps`, or return `None` if it contains a
+/// type which needs Python's encoder.
+pub fn dumps(value: &Bound<'_, PyAny>, script_safe: bool) -> PyResult<Option<String>> {
+    let mut json = String::new();
+    Ok(push_value(&mut json, value, script_safe, 0)?.map(|()| json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+
+    fn python_dumps(value: &Bound<'_, PyAny>) -> String {
+        let json = value.py().import("json").unwrap();
+        json.call_method1("dumps", (value,))
+            .unwrap()
+            .extract()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_dumps() {
+        Python::initialize();
+        Python::attach(|py| {
+            let value = py
+                .eval(
+                    c"{'a': [1, 2.5, None, True], 1: 'é\\n\"<>&', None: (), 1.5: {}, False: float('nan')}",
+                    None,
+                    None,
+                )
+                .unwrap();
+            let json = dumps(&value, false).unwrap().unwrap();
+            assert_eq!(json, python_dumps(&value));
+            assert_eq!(dumps(&value, true).unwrap().unwrap(), escape_script(&json));
+        });
+    }
+
+    #[test]
+    fn test_dumps_unsupported() {
+        Python::initialize();
+        Python::attach(|py| {
+            for value in [
+                c"{(1, 2): 3}",
+                c"[1, {2}]",
+                c"__import__('decimal').Decimal('1.5')",
+                c"'\\ud800'",
+            ] {
+                let value = py.eval(value, None, None).unwrap();
+                assert_eq!(dumps(&value, false).unwrap(), None);
+            }
+            let value = py
+                .eval(c"(lambda l: l.append(l) or l)([])", None, None)
+                .unwrap();
+            assert_eq!(dumps(&value, false).unwrap(), None);
+        });
+    }
+
+    proptest! {
+        #[test]
+        fn test_string_matches_python(text in "\\PC*") {
+            Python::initialize();
+            Python::attach(|py| {
+                let value = PyString::new(py, &text).into_any();
+                assert_eq!(dumps(&value, false).unwrap().unwrap(), python_dumps(&value));
+            });
+        }
+
+        #[test]
+        fn test_float_matches_python(float in any::<f64>()) {
+            Python::initialize();
+            Python::attach(|py| {
+                let value = PyFloat::new(py, float).into_any();
+                assert_eq!(dumps(&value, false).unwrap().unwrap(), python_dumps(&value));
+            });
+        }
+    }
+}
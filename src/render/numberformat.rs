@@ -0,0 +1,448 @@
+//! Exact decimal arithmetic and number formatting matching Python's `decimal`
+//! module and `django.utils.numberformat`.
+
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+/// A finite decimal number, `(-1) ** negative * coefficient * 10 ** exponent`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Decimal {
+    pub negative: bool,
+    pub coefficient: BigUint,
+    pub exponent: i64,
+}
+
+/// The result of parsing a string like Python's `decimal.Decimal`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParsedDecimal {
+    Finite(Decimal),
+    Infinite,
+    NaN,
+}
+
+impl ParsedDecimal {
+    /// Parse the ASCII subset of the syntax accepted by `decimal.Decimal`.
+    ///
+    /// Returns `None` for invalid input, and also for non-ASCII input or input
+    /// containing underscores, which callers can normalise with Python first.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim_ascii();
+        if !value.is_ascii() || value.contains('_') {
+            return None;
+        }
+        let (negative, unsigned) = match value.as_bytes().first()? {
+            b'-' => (true, &value[1..]),
+            b'+' => (false, &value[1..]),
+            _ => (false, value),
+        };
+        let lower = unsigned.to_ascii_lowercase();
+        if lower == "inf" || lower == "infinity" {
+            return Some(Self::Infinite);
+        }
+        for prefix in ["nan", "snan"] {
+            if let Some(payload) = lower.strip_prefix(prefix) {
+                return payload
+                    .bytes()
+                    .all(|b| b.is_ascii_digit())
+                    .then_some(Self::NaN);
+            }
+        }
+
+        let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+            Some(index) => {
+                let exponent = &unsigned[index + 1..];
+                let digits = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+                if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                (&unsigned[..index], exponent.parse::<i64>().ok()?)
+            }
+            None => (unsigned, 0),
+        };
+        let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        if integer.is_empty() && fraction.is_empty() {
+            return None;
+        }
+        let all_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if !all_digits(integer) || !all_digits(fraction) {
+            return None;
+        }
+        let digits = format!("{integer}{fraction}");
+        let coefficient = BigUint::parse_bytes(digits.as_bytes(), 10)?;
+        let exponent = exponent.checked_sub(i64::try_from(fraction.len()).ok()?)?;
+        Some(Self::Finite(Decimal {
+            negative,
+            coefficient,
+            exponent,
+        }))
+    }
+}
+
+/// Add one to a string of decimal digits.
+fn increment(digits: &mut String) {
+    let mut bytes = std::mem::take(digits).into_bytes();
+    let carried = bytes.iter_mut().rev().all(|digit| match digit {
+        b'9' => {
+            *digit = b'0';
+            true
+        }
+        _ => {
+            *digit += 1;
+            false
+        }
+    });
+    if carried {
+        bytes.insert(0, b'1');
+    }
+    *digits = String::from_utf8(bytes).expect("Only ASCII digits were changed");
+}
+
+/// Split `digits`, scaled by `10 ** -places`, into integer and fractional digits.
+fn split_fraction(mut digits: String, places: usize) -> (String, String) {
+    if digits.len() <= places {
+        digits.insert_str(0, &"0".repeat(places + 1 - digits.len()));
+    }
+    let fraction = digits.split_off(digits.len() - places);
+    (digits, fraction)
+}
+
+// The digits are worked on as strings, so an exponent far from the digits
+// costs no more than the output it produces.
+impl Decimal {
+    /// The number of digits in the integer part, like `len(str(abs(int(d))))`.
+    pub fn integer_len(&self) -> u64 {
+        if self.coefficient.is_zero() {
+            return 1;
+        }
+        let digits = self.coefficient.to_string().len() as u64;
+        match self.exponent {
+            exponent if exponent >= 0 => digits + exponent.unsigned_abs(),
+            exponent => digits.saturating_sub(exponent.unsigned_abs()).max(1),
+        }
+    }
+
+    /// Whether the number has a non-zero fractional part.
+    pub fn has_fraction(&self) -> bool {
+        if self.exponent >= 0 {
+            return false;
+        }
+        let digits = self.coefficient.to_string();
+        let places = usize::try_from(self.exponent.unsigned_abs()).unwrap_or(usize::MAX);
+        let fraction = &digits[digits.len().saturating_sub(places)..];
+        fraction.bytes().any(|digit| digit != b'0')
+    }
+
+    /// The integer part as a string, like `"%d" % int(d)` in Python.
+    pub fn integer_string(&self) -> String {
+        if self.coefficient.is_zero() {
+            return "0".to_string();
+        }
+        let mut integer = self.coefficient.to_string();
+        if self.exponent >= 0 {
+            integer.push_str(&"0".repeat(self.exponent.unsigned_abs() as usize));
+        } else {
+            let places = usize::try_from(self.exponent.unsigned_abs()).unwrap_or(usize::MAX);
+            integer.truncate(integer.len().saturating_sub(places));
+            if integer.is_empty() {
+                return "0".to_string();
+            }
+        }
+        match self.negative {
+            true => format!("-{integer}"),
+            false => integer,
+        }
+    }
+
+    /// Round to `places` decimal places using `ROUND_HALF_UP`, like
+    /// `Decimal.quantize(Decimal(1).scaleb(-places), ROUND_HALF_UP)`, and
+    /// split the result into a sign, integer digits and fractional digits.
+    ///
+    /// Negative zero loses its sign, as in Django's `floatformat`.
+    pub fn round_parts(&self, places: usize) -> (bool, String, String) {
+        let mut digits = self.coefficient.to_string();
+        let target = -i64::try_from(places).unwrap_or(i64::MAX);
+        if self.exponent >= target {
+            let zeros = self.exponent.abs_diff(target) as usize;
+            digits.push_str(&"0".repeat(zeros));
+        } else {
+            // Drop the digits past `places`, rounding on the first one dropped.
+            let dropped = usize::try_from(self.exponent.abs_diff(target)).unwrap_or(usize::MAX);
+            if dropped > digits.len() {
+                digits = "0".to_string();
+            } else {
+                let round_up = digits.as_bytes()[digits.len() - dropped] >= b'5';
+                digits.truncate(digits.len() - dropped);
+                if digits.is_empty() {
+                    digits.push('0');
+                }
+                if round_up {
+                    increment(&mut digits);
+                }
+            }
+        }
+        let negative = self.negative && digits.bytes().any(|digit| digit != b'0');
+        let (integer, fraction) = split_fraction(digits, places);
+        (negative, integer, fraction)
+    }
+}
+
+/// Locale information used when formatting a number.
+pub struct NumberFormat<'a> {
+    pub decimal_separator: &'a str,
+    /// Group sizes from `NUMBER_GROUPING`, or `None` when not grouping.
+    pub grouping: Option<Vec<usize>>,
+    pub thousand_separator: &'a str,
+}
+
+/// Format the parts of a number like `django.utils.numberformat.format`.
+///
+/// See <https://github.com/django/django/blob/stable/5.2.x/django/utils/numberformat.py>
+pub fn format_number(
+    negative: bool,
+    integer: &str,
+    fraction: &str,
+    format: &NumberFormat<'_>,
+) -> String {
+    let mut formatted = String::with_capacity(integer.len() + fraction.len() + 8);
+    if negative {
+        formatted.push('-');
+    }
+    match &format.grouping {
+        Some(grouping) => {
+            let mut intervals = grouping.iter().copied();
+            let mut active_interval = intervals.next().unwrap_or(0);
+            let separator: Vec<char> = format.thousand_separator.chars().rev().collect();
+            let mut grouped = Vec::with_capacity(integer.len() * 2);
+            let mut count = 0;
+            for digit in integer.chars().rev() {
+                if count != 0 && count == active_interval {
+                    if let Some(interval) = intervals.next()
+                        && interval != 0
+                    {
+                        active_interval = interval;
+                    }
+                    grouped.extend(&separator);
+                    count = 0;
+                }
+                grouped.push(digit);
+                count += 1;
+            }
+            formatted.extend(grouped.into_iter().rev());
+        }
+        None => formatted.push_str(integer),
+    }
+    if !fraction.is_empty() {
+        formatted.push_str(format.decimal_separator);
+        formatted.push_str(fraction);
+    }
+    formatted
+}
+
+/// Decimals with more digits than this, counting the exponent, are formatted
+/// in scientific notation by Django to avoid building huge strings.
+const MAX_PLAIN_DIGITS: u64 = 200;
+
+/// Format a decimal like `django.utils.numberformat.format` without
+/// `decimal_pos`, which formats it with `"{:f}"` or, when it is very long,
+/// `"{:e}"`.
+pub fn format_decimal(value: &Decimal, format: &NumberFormat<'_>) -> String {
+    let mut digits = value.coefficient.to_string();
+    if value.exponent.unsigned_abs() + digits.len() as u64 > MAX_PLAIN_DIGITS {
+        let exponent = value.exponent + (digits.len() as i64 - 1);
+        let fraction = digits.split_off(1);
+        let coefficient = format_number(value.negative, &digits, &fraction, format);
+        return format!("{coefficient}e{exponent:+}");
+    }
+    let (integer, fraction) = match usize::try_from(value.exponent) {
+        Ok(zeros) => {
+            if !value.coefficient.is_zero() {
+                digits.push_str(&"0".repeat(zeros));
+            }
+            (digits, String::new())
+        }
+        Err(_) => split_fraction(digits, value.exponent.unsigned_abs() as usize),
+    };
+    format_number(value.negative, &integer, &fraction, format)
+}
+
+/// Format a float, given as its `str()`, like `django.utils.numberformat.format`
+/// without `decimal_pos`.
+///
+/// Returns `None` if the text uses scientific notation but is not a valid
+/// decimal, which Django would fail to convert.
+pub fn format_float(text: &str, format: &NumberFormat<'_>) -> Option<String> {
+    if text.contains(['e', 'E']) {
+        // Django treats floats in scientific notation as decimals.
+        return match ParsedDecimal::parse(text)? {
+            ParsedDecimal::Finite(decimal) => Some(format_decimal(&decimal, format)),
+            ParsedDecimal::Infinite | ParsedDecimal::NaN => None,
+        };
+    }
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, text),
+    };
+    let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    Some(format_number(negative, integer, fraction, format))
+}
+
+/// Format a decimal number like Django's `floatformat` filter, given an
+/// already parsed precision argument.
+///
+/// See <https://github.com/django/django/blob/stable/5.2.x/django/template/defaultfilters.py#L96>
+pub fn floatformat(value: &Decimal, precision: i64, format: &NumberFormat<'_>) -> String {
+    if !value.has_fraction() && precision <= 0 {
+        let integer = value.integer_string();
+        let (negative, integer) = match integer.strip_prefix('-') {
+            Some(integer) => (true, integer),
+            None => (false, integer.as_str()),
+        };
+        return format_number(negative, integer, "", format);
+    }
+    let places = usize::try_from(precision.unsigned_abs()).unwrap_or(usize::MAX);
+    let (negative, integer, fraction) = value.round_parts(places);
+    format_number(negative, &integer, &fraction, format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAIN: NumberFormat = NumberFormat {
+        decimal_separator: ".",
+        grouping: None,
+        thousand_separator: ",",
+    };
+
+    fn decimal(value: &str) -> Decimal {
+        match ParsedDecimal::parse(value) {
+            Some(ParsedDecimal::Finite(decimal)) => decimal,
+            other => panic!("{value} parsed as {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_decimal() {
+        assert_eq!(
+            decimal(" -1.50e2 "),
+            Decimal {
+                negative: true,
+                coefficient: BigUint::from(150u8),
+                exponent: 0,
+            }
+        );
+        assert_eq!(decimal(".5").exponent, -1);
+        assert_eq!(decimal("5.").exponent, 0);
+        assert_eq!(
+            ParsedDecimal::parse("-Infinity"),
+            Some(ParsedDecimal::Infinite)
+        );
+        assert_eq!(ParsedDecimal::parse("nan12"), Some(ParsedDecimal::NaN));
+        assert_eq!(ParsedDecimal::parse("sNaN"), Some(ParsedDecimal::NaN));
+        assert_eq!(ParsedDecimal::parse("."), None);
+        assert_eq!(ParsedDecimal::parse("1e"), None);
+        assert_eq!(ParsedDecimal::parse("abc"), None);
+        assert_eq!(ParsedDecimal::parse("1_000"), None);
+        assert_eq!(ParsedDecimal::parse(""), None);
+    }
+
+    #[test]
+    fn test_floatformat() {
+        let cases = [
+            ("34.23234", -1, "34.2"),
+            ("34.00000", -1, "34"),
+            ("34.26000", -1, "34.3"),
+            ("34.23234", 3, "34.232"),
+            ("34.00000", 3, "34.000"),
+            ("34.26000", 3, "34.260"),
+            ("34.23234", -3, "34.232"),
+            ("34.00000", -3, "34"),
+            ("0.5", 0, "1"),
+            ("-0.5", 0, "-1"),
+            ("-0.01", -1, "0.0"),
+            ("0.0000001", 2, "0.00"),
+            ("1E+3", 2, "1000.00"),
+            ("-1E+3", -2, "-1000"),
+            ("2.5", 0, "3"),
+            ("1.005", 2, "1.01"),
+        ];
+        for (value, precision, expected) in cases {
+            assert_eq!(
+                floatformat(&decimal(value), precision, &PLAIN),
+                expected,
+                "{value}|floatformat:{precision}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_floatformat_huge_exponents() {
+        let cases = [
+            ("1e-999999999", 2, "0.00"),
+            ("-1e-999999999", 2, "0.00"),
+            ("1e-999999999", -1, "0.0"),
+            ("5e-3", 2, "0.01"),
+            ("999e-3", 2, "1.00"),
+            ("1e30", 2, "1000000000000000000000000000000.00"),
+        ];
+        for (value, precision, expected) in cases {
+            assert_eq!(
+                floatformat(&decimal(value), precision, &PLAIN),
+                expected,
+                "{value}|floatformat:{precision}"
+            );
+        }
+
+        let huge = decimal("1e999999999");
+        assert!(!huge.has_fraction());
+        assert_eq!(huge.integer_len(), 1_000_000_000);
+        let tiny = decimal("-1e-999999999");
+        assert!(tiny.has_fraction());
+        assert_eq!(tiny.integer_len(), 1);
+        assert_eq!(tiny.integer_string(), "0");
+    }
+
+    #[test]
+    fn test_format_number_grouping() {
+        let format = NumberFormat {
+            decimal_separator: ",",
+            grouping: Some(vec![3, 2, 0]),
+            thousand_separator: ".",
+        };
+        assert_eq!(
+            format_number(true, "123456789", "50", &format),
+            "-12.34.56.789,50"
+        );
+        let format = NumberFormat {
+            decimal_separator: ".",
+            grouping: Some(vec![3, 0]),
+            thousand_separator: ",",
+        };
+        assert_eq!(format_number(false, "1234567", "", &format), "1,234,567");
+        assert_eq!(format_number(false, "123", "", &format), "123");
+    }
+
+    #[test]
+    fn test_format_decimal_and_float() {
+        let format = NumberFormat {
+            decimal_separator: ".",
+            grouping: Some(vec![3, 0]),
+            thousand_separator: ",",
+        };
+        assert_eq!(format_decimal(&decimal("1E+5"), &format), "100,000");
+        assert_eq!(format_decimal(&decimal("1234.5"), &format), "1,234.5");
+        assert_eq!(format_decimal(&decimal("0E+5"), &format), "0");
+        assert_eq!(format_decimal(&decimal("-0E-3"), &format), "-0.000");
+        assert_eq!(format_decimal(&decimal("1.5E-7"), &format), "0.00000015");
+        assert_eq!(format_decimal(&decimal("100E+300"), &format), "1.00e+302");
+        assert_eq!(format_decimal(&decimal("-1.5E-250"), &format), "-1.5e-250");
+
+        let float = |text| format_float(text, &format);
+        assert_eq!(float("-1234.5").as_deref(), Some("-1,234.5"));
+        assert_eq!(float("-0.0").as_deref(), Some("-0.0"));
+        assert_eq!(float("1e+16").as_deref(), Some("10,000,000,000,000,000"));
+        assert_eq!(float("1.5e-05").as_deref(), Some("0.000015"));
+        assert_eq!(float("inf").as_deref(), Some("inf"));
+        assert_eq!(float("1e"), None);
+    }
+}
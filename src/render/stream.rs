@@ -0,0 +1,275 @@
+//! Render a template a chunk at a time, for `Template.stream`.
+//!
+//! Rendering a node normally renders everything inside it, so the nodes which
+//! hold other nodes (`{% for %}`, `{% if %}`, `{% autoescape %}` and
+//! `{% include %}`) are instead entered one level at a time using a stack of
+//! frames. This lets a chunk be returned after any node or loop iteration.
+
+use pyo3::prelude::*;
+
+use dtl_lexer::types::TemplateString;
+
+use super::tags::{ForState, IncludeState, IncludeTemplate};
+use super::types::Context;
+use super::{Evaluate, Render};
+use crate::error::PyRenderError;
+use crate::parse::{Tag, TokenTree};
+use crate::template::django_rusty_templates::Template;
+
+/// A list of nodes held by another node.
+#[derive(Clone, Copy)]
+enum Branch {
+    Autoescape,
+    IfTrue,
+    IfFalse,
+    ForBody,
+    ForEmpty,
+}
+
+/// What to do once every node of a frame is rendered.
+enum Exit {
+    Nothing,
+    /// Restore the previous autoescape setting.
+    Autoescape(bool),
+    /// Start the next iteration of the loop.
+    For(ForState),
+    /// Restore the context of the including template.
+    Include(IncludeState),
+}
+
+/// A list of nodes being rendered.
+struct Frame {
+    /// The included template the nodes belong to, or `None` for the streamed template.
+    template: Option<Template>,
+    /// The index of the node and the branch taken at each level to reach the nodes.
+    path: Vec<(usize, Branch)>,
+    /// The next node to render.
+    index: usize,
+    exit: Exit,
+}
+
+fn branch_nodes(node: &TokenTree, branch: Branch) -> &[TokenTree] {
+    match (node, branch) {
+        (TokenTree::Tag(Tag::Autoescape { nodes, .. }), Branch::Autoescape) => nodes,
+        (TokenTree::Tag(Tag::If { truthy, .. }), Branch::IfTrue) => truthy,
+        (TokenTree::Tag(Tag::If { falsey, .. }), Branch::IfFalse) => {
+            falsey.as_deref().unwrap_or_default()
+        }
+        (TokenTree::Tag(Tag::For(for_tag)), Branch::ForBody) => &for_tag.body,
+        (TokenTree::Tag(Tag::For(for_tag)), Branch::ForEmpty) => {
+            for_tag.empty.as_deref().unwrap_or_default()
+        }
+        _ => unreachable!("The path was built from the same nodes"),
+    }
+}
+
+fn find_nodes<'a>(mut nodes: &'a [TokenTree], path: &[(usize, Branch)]) -> &'a [TokenTree] {
+    for &(index, branch) in path {
+        nodes = branch_nodes(&nodes[index], branch);
+    }
+    nodes
+}
+
+/// The progress of streaming a template.
+pub struct Stream {
+    frames: Vec<Frame>,
+    output: String,
+}
+
+impl Default for Stream {
+    fn default() -> Self {
+        Self {
+            frames: vec![Frame {
+                template: None,
+                path: Vec::new(),
+                index: 0,
+                exit: Exit::Nothing,
+            }],
+            output: String::new(),
+        }
+    }
+}
+
+impl Stream {
+    /// Render `root` until at least `chunk_size` bytes are ready, returning
+    /// `None` once the whole template has been rendered.
+    pub fn next_chunk(
+        &mut self,
+        py: Python<'_>,
+        root: &Template,
+        context: &mut Context,
+        chunk_size: usize,
+    ) -> PyResult<Option<String>> {
+        loop {
+            match self.step(py, root, context) {
+                Ok(true) => {}
+                Ok(false) if self.output.is_empty() => return Ok(None),
+                Ok(false) => return Ok(Some(std::mem::take(&mut self.output))),
+                Err(error) => {
+                    let error = self.render_error(py, root, error);
+                    // Report the render error rather than any raised while unwinding.
+                    let _ = self.unwind(py, context);
+                    return Err(error);
+                }
+            }
+            if self.output.len() >= chunk_size.max(1) {
+                return Ok(Some(std::mem::take(&mut self.output)));
+            }
+        }
+    }
+
+    /// Render the next node or leave the current frame, returning `false`
+    /// when there is nothing left to render.
+    fn step(
+        &mut self,
+        py: Python<'_>,
+        root: &Template,
+        context: &mut Context,
+    ) -> Result<bool, PyRenderError> {
+        let Some(frame) = self.frames.last_mut() else {
+            return Ok(false);
+        };
+        let included = frame.template.clone();
+        let template = included.as_ref().unwrap_or(root);
+        let source = TemplateString(&template.template);
+        let nodes = find_nodes(&template.nodes, &frame.path);
+        let Some(node) = nodes.get(frame.index) else {
+            let frame = self.frames.pop().expect("There is a current frame");
+            self.exit(frame, py, template, context)?;
+            return Ok(true);
+        };
+        let index = frame.index;
+        frame.index += 1;
+        match node {
+            TokenTree::Tag(Tag::Autoescape { enabled, .. }) => {
+                let autoescape = context.autoescape;
+                context.autoescape = enabled.into();
+                self.enter(index, Branch::Autoescape, Exit::Autoescape(autoescape));
+            }
+            TokenTree::Tag(Tag::If { condition, .. }) => {
+                let branch = match condition.evaluate(py, source, context).unwrap_or(false) {
+                    true => Branch::IfTrue,
+                    false => Branch::IfFalse,
+                };
+                self.enter(index, branch, Exit::Nothing);
+            }
+            TokenTree::Tag(Tag::For(for_tag)) => match for_tag.start(py, source, context)? {
+                None => self.enter(index, Branch::ForEmpty, Exit::Nothing),
+                Some(mut state) => {
+                    if for_tag.next_iteration(&mut state, py, source, context)? {
+                        self.enter(index, Branch::ForBody, Exit::For(state));
+                    }
+                }
+            },
+            TokenTree::Tag(Tag::Include(include)) => {
+                let (include_template, scope) = include.enter(py, source, context)?;
+                match include_template {
+                    IncludeTemplate::Template(included) => self.frames.push(Frame {
+                        template: Some(included),
+                        path: Vec::new(),
+                        index: 0,
+                        exit: Exit::Include(scope),
+                    }),
+                    callable => {
+                        let rendered = callable.render(
+                            py,
+                            context,
+                            include.template_at(),
+                            source,
+                            &mut self.output,
+                        );
+                        scope.exit(py, context)?;
+                        rendered?;
+                    }
+                }
+            }
+            node => node.render(py, source, context, &mut self.output)?,
+        }
+        Ok(true)
+    }
+
+    /// Start rendering the nodes in `branch` of the current frame's node at `index`.
+    fn enter(&mut self, index: usize, branch: Branch, exit: Exit) {
+        let frame = self.frames.last().expect("There is a current frame");
+        let mut path = frame.path.clone();
+        path.push((index, branch));
+        self.frames.push(Frame {
+            template: frame.template.clone(),
+            path,
+            index: 0,
+            exit,
+        });
+    }
+
+    fn exit(
+        &mut self,
+        frame: Frame,
+        py: Python<'_>,
+        template: &Template,
+        context: &mut Context,
+    ) -> Result<(), PyRenderError> {
+        match frame.exit {
+            Exit::Nothing => {}
+            Exit::Autoescape(autoescape) => context.autoescape = autoescape,
+            Exit::Include(scope) => scope.exit(py, context)?,
+            Exit::For(mut state) => {
+                context.increment_for_loop();
+                let (index, _) = *frame.path.last().expect("A loop body is inside a node");
+                let nodes = find_nodes(&template.nodes, &frame.path[..frame.path.len() - 1]);
+                let TokenTree::Tag(Tag::For(for_tag)) = &nodes[index] else {
+                    unreachable!("The path leads to a for loop")
+                };
+                let source = TemplateString(&template.template);
+                if for_tag.next_iteration(&mut state, py, source, context)? {
+                    self.frames.push(Frame {
+                        exit: Exit::For(state),
+                        index: 0,
+                        ..frame
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop rendering, undoing the context changes of the frames still being
+    /// rendered, innermost first.
+    pub fn unwind(&mut self, py: Python<'_>, context: &mut Context) -> PyResult<()> {
+        self.output.clear();
+        let mut result = Ok(());
+        while let Some(frame) = self.frames.pop() {
+            let exited = match frame.exit {
+                Exit::Nothing => Ok(()),
+                Exit::Autoescape(autoescape) => {
+                    context.autoescape = autoescape;
+                    Ok(())
+                }
+                Exit::For(state) => {
+                    state.leave(context);
+                    Ok(())
+                }
+                Exit::Include(scope) => scope.exit(py, context),
+            };
+            result = result.and(exited);
+        }
+        result
+    }
+
+    /// Convert `error` like `Template::render` does, including for each
+    /// included template it was raised within.
+    fn render_error(&self, py: Python<'_>, root: &Template, error: PyRenderError) -> PyErr {
+        let error = self
+            .frames
+            .iter()
+            .rev()
+            .filter(|frame| matches!(frame.exit, Exit::Include(_)))
+            .fold(error, |error, frame| {
+                let template = frame
+                    .template
+                    .as_ref()
+                    .expect("An included frame has a template");
+                template.render_error(py, error).into()
+            });
+        root.render_error(py, error)
+    }
+}
@@ -0,0 +1,468 @@
+//! printf-style formatting matching Python's `%` operator on strings, for the
+//! subset of format specifications used with Django's `stringformat` filter.
+//!
+//! Anything outside this subset is left for Python to format, so callers can
+//! fall back to `("%" + arg) % value` whenever [`StringFormat::parse`] or
+//! [`StringFormat::format`] return `None`.
+
+use num_bigint::BigInt;
+use num_traits::{FromPrimitive, Signed, ToPrimitive};
+use serde::{Deserialize, Serialize};
+
+/// Widths and precisions above this are left to Python, which raises
+/// `ValueError` for sizes it cannot allocate.
+const MAX_SIZE: usize = 10_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+enum Conversion {
+    /// `d`, `i` and `u`
+    Decimal,
+    /// `o`
+    Octal,
+    /// `x` and `X`
+    Hex { upper: bool },
+    /// `e` and `E`
+    Exponent { upper: bool },
+    /// `f` and `F`
+    Fixed { upper: bool },
+    /// `g` and `G`
+    General { upper: bool },
+    /// `s`
+    Str,
+}
+
+/// A parsed `%` conversion specification followed by literal text.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StringFormat {
+    left_adjust: bool,
+    sign: Option<char>,
+    alternate: bool,
+    zero_pad: bool,
+    width: usize,
+    precision: Option<usize>,
+    conversion: Conversion,
+    suffix: String,
+}
+
+/// A value to format, already converted from its Python type.
+#[derive(Clone, Copy, Debug)]
+pub enum FormatValue<'a> {
+    Bool(bool),
+    Int(&'a BigInt),
+    Float(f64),
+    Str(&'a str),
+}
+
+fn parse_number(spec: &str) -> (Option<usize>, &str) {
+    let end = spec
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(spec.len());
+    match end {
+        0 => (None, spec),
+        _ => (spec[..end].parse().ok(), &spec[end..]),
+    }
+}
+
+impl StringFormat {
+    /// Parse the argument of `stringformat`, which Django prefixes with `%`.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let mut left_adjust = false;
+        let mut sign = None;
+        let mut alternate = false;
+        let mut zero_pad = false;
+        let mut rest = spec;
+        loop {
+            match rest.as_bytes().first()? {
+                b'-' => left_adjust = true,
+                b'+' => sign = Some('+'),
+                b' ' => {
+                    if sign.is_none() {
+                        sign = Some(' ');
+                    }
+                }
+                b'#' => alternate = true,
+                b'0' => zero_pad = true,
+                _ => break,
+            }
+            rest = &rest[1..];
+        }
+        let (width, rest) = parse_number(rest);
+        let width = width.unwrap_or(0);
+        let (precision, rest) = match rest.strip_prefix('.') {
+            Some(rest) => {
+                let (precision, rest) = parse_number(rest);
+                (Some(precision.unwrap_or(0)), rest)
+            }
+            None => (None, rest),
+        };
+        if width > MAX_SIZE || precision.is_some_and(|precision| precision > MAX_SIZE) {
+            return None;
+        }
+        let rest = rest.trim_start_matches(['h', 'l', 'L']);
+        let mut chars = rest.chars();
+        let conversion = match chars.next()? {
+            'd' | 'i' | 'u' => Conversion::Decimal,
+            'o' => Conversion::Octal,
+            'x' => Conversion::Hex { upper: false },
+            'X' => Conversion::Hex { upper: true },
+            'e' => Conversion::Exponent { upper: false },
+            'E' => Conversion::Exponent { upper: true },
+            'f' => Conversion::Fixed { upper: false },
+            'F' => Conversion::Fixed { upper: true },
+            'g' => Conversion::General { upper: false },
+            'G' => Conversion::General { upper: true },
+            's' => Conversion::Str,
+            _ => return None,
+        };
+        let suffix = chars.as_str();
+        if suffix.contains('%') {
+            return None;
+        }
+        Some(Self {
+            left_adjust,
+            sign,
+            alternate,
+            zero_pad,
+            width,
+            precision,
+            conversion,
+            suffix: suffix.to_string(),
+        })
+    }
+
+    /// Format `value`, or return `None` if Python should handle it instead,
+    /// either because it would raise an error or because it isn't supported.
+    pub fn format(&self, value: FormatValue<'_>) -> Option<String> {
+        let mut formatted = match self.conversion {
+            Conversion::Str => {
+                let text = match value {
+                    FormatValue::Bool(true) => "True".to_string(),
+                    FormatValue::Bool(false) => "False".to_string(),
+                    FormatValue::Int(int) => int.to_string(),
+                    FormatValue::Float(float) => float_repr(float),
+                    FormatValue::Str(text) => text.to_string(),
+                };
+                let text = match self.precision {
+                    Some(precision) => text.chars().take(precision).collect(),
+                    None => text,
+                };
+                self.pad("", &text, false)
+            }
+            Conversion::Decimal | Conversion::Octal | Conversion::Hex { .. } => {
+                let int = match value {
+                    FormatValue::Bool(bool) => BigInt::from(u8::from(bool)),
+                    FormatValue::Int(int) => int.clone(),
+                    FormatValue::Float(float) if self.conversion == Conversion::Decimal => {
+                        BigInt::from_f64(float.trunc())?
+                    }
+                    FormatValue::Float(_) | FormatValue::Str(_) => return None,
+                };
+                self.format_int(&int)
+            }
+            Conversion::Exponent { .. } | Conversion::Fixed { .. } | Conversion::General { .. } => {
+                let float = match value {
+                    FormatValue::Bool(bool) => f64::from(u8::from(bool)),
+                    // Python converts exactly representable integers only.
+                    FormatValue::Int(int) if int.abs().bits() <= 53 => int.to_f64()?,
+                    FormatValue::Float(float) => float,
+                    FormatValue::Int(_) | FormatValue::Str(_) => return None,
+                };
+                if !float.is_finite() {
+                    return None;
+                }
+                self.format_float(float)
+            }
+        };
+        formatted.push_str(&self.suffix);
+        Some(formatted)
+    }
+
+    fn sign(&self, negative: bool) -> &'static str {
+        match (negative, self.sign) {
+            (true, _) => "-",
+            (false, Some('+')) => "+",
+            (false, Some(_)) => " ",
+            (false, None) => "",
+        }
+    }
+
+    /// Pad `body` to the width, placing any zero padding after the `prefix`.
+    fn pad(&self, prefix: &str, body: &str, numeric: bool) -> String {
+        let len = prefix.chars().count() + body.chars().count();
+        let padding = self.width.saturating_sub(len);
+        let mut padded = String::with_capacity(len + padding);
+        if self.left_adjust {
+            padded.push_str(prefix);
+            padded.push_str(body);
+            padded.extend(std::iter::repeat_n(' ', padding));
+        } else if self.zero_pad && numeric {
+            padded.push_str(prefix);
+            padded.extend(std::iter::repeat_n('0', padding));
+            padded.push_str(body);
+        } else {
+            padded.extend(std::iter::repeat_n(' ', padding));
+            padded.push_str(prefix);
+            padded.push_str(body);
+        }
+        padded
+    }
+
+    fn format_int(&self, int: &BigInt) -> String {
+        let magnitude = int.magnitude();
+        let (radix_prefix, mut digits) = match self.conversion {
+            Conversion::Octal => ("0o", magnitude.to_str_radix(8)),
+            Conversion::Hex { upper: false } => ("0x", magnitude.to_str_radix(16)),
+            Conversion::Hex { upper: true } => ("0X", magnitude.to_str_radix(16).to_uppercase()),
+            _ => ("", magnitude.to_string()),
+        };
+        if let Some(precision) = self.precision
+            && digits.len() < precision
+        {
+            digits.insert_str(0, &"0".repeat(precision - digits.len()));
+        }
+        let mut prefix = self.sign(int.is_negative()).to_string();
+        if self.alternate {
+            prefix.push_str(radix_prefix);
+        }
+        self.pad(&prefix, &digits, true)
+    }
+
+    fn format_float(&self, float: f64) -> String {
+        let precision = self.precision.unwrap_or(6);
+        let (body, upper) = match self.conversion {
+            Conversion::Exponent { upper } => (self.exponent(float.abs(), precision), upper),
+            Conversion::Fixed { upper } => (self.fixed(float.abs(), precision), upper),
+            Conversion::General { upper } => (self.general(float.abs(), precision), upper),
+            _ => unreachable!("only called for float conversions"),
+        };
+        let body = match upper {
+            true => body.to_uppercase(),
+            false => body,
+        };
+        let prefix = self.sign(float.is_sign_negative());
+        self.pad(prefix, &body, true)
+    }
+
+    fn fixed(&self, float: f64, precision: usize) -> String {
+        let mut fixed = format!("{float:.precision$}");
+        if self.alternate && precision == 0 {
+            fixed.push('.');
+        }
+        fixed
+    }
+
+    fn exponent(&self, float: f64, precision: usize) -> String {
+        let (mut mantissa, exponent) = exponent_parts(float, precision);
+        if self.alternate && precision == 0 {
+            mantissa.push('.');
+        }
+        format_exponent(&mantissa, exponent)
+    }
+
+    fn general(&self, float: f64, precision: usize) -> String {
+        let precision = precision.max(1);
+        let (mantissa, exponent) = exponent_parts(float, precision - 1);
+        let precision = i64::try_from(precision).expect("precision is at most MAX_SIZE");
+        let (mut body, exponent) = if (-4..precision).contains(&exponent) {
+            let places = usize::try_from(precision - 1 - exponent).expect("checked above");
+            (format!("{float:.places$}"), None)
+        } else {
+            (mantissa, Some(exponent))
+        };
+        if self.alternate {
+            if !body.contains('.') {
+                body.push('.');
+            }
+        } else if body.contains('.') {
+            let trimmed = body.trim_end_matches('0').trim_end_matches('.').len();
+            body.truncate(trimmed);
+        }
+        match exponent {
+            Some(exponent) => format_exponent(&body, exponent),
+            None => body,
+        }
+    }
+}
+
+/// Split `float` into its rounded mantissa digits and decimal exponent.
+fn exponent_parts(float: f64, precision: usize) -> (String, i64) {
+    let formatted = format!("{float:.precision$e}");
+    let (mantissa, exponent) = formatted
+        .split_once('e')
+        .expect("Rust always includes an exponent");
+    (
+        mantissa.to_string(),
+        exponent.parse().expect("Rust exponents are integers"),
+    )
+}
+
+fn format_exponent(mantissa: &str, exponent: i64) -> String {
+    let sign = if exponent < 0 { '-' } else { '+' };
+    format!("{mantissa}e{sign}{:02}", exponent.unsigned_abs())
+}
+
+/// Format a float like Python's `repr`.
+pub fn float_repr(float: f64) -> String {
+    if float.is_nan() {
+        return "nan".to_string();
+    }
+    if float.is_infinite() {
+        return match float.is_sign_negative() {
+            true => "-inf".to_string(),
+            false => "inf".to_string(),
+        };
+    }
+    let scientific = format!("{float:e}");
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("Rust always includes an exponent");
+    let exponent: i64 = exponent.parse().expect("Rust exponents are integers");
+    if (-4..16).contains(&exponent) {
+        let mut fixed = float.to_string();
+        if !fixed.contains('.') {
+            fixed.push_str(".0");
+        }
+        fixed
+    } else {
+        format_exponent(mantissa, exponent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+    use pyo3::prelude::*;
+    use pyo3::types::PyString;
+
+    fn python_format(py: Python<'_>, spec: &str, value: FormatValue<'_>) -> Option<String> {
+        let value = match value {
+            FormatValue::Bool(bool) => bool.into_pyobject(py).unwrap().to_owned().into_any(),
+            FormatValue::Int(int) => int.into_pyobject(py).unwrap().into_any(),
+            FormatValue::Float(float) => float.into_pyobject(py).unwrap().into_any(),
+            FormatValue::Str(text) => text.into_pyobject(py).unwrap().into_any(),
+        };
+        PyString::new(py, &format!("%{spec}"))
+            .rem(value)
+            .ok()
+            .map(|formatted| formatted.extract().unwrap())
+    }
+
+    fn assert_matches_python(spec: &str, value: FormatValue<'_>) {
+        let Some(format) = StringFormat::parse(spec) else {
+            return;
+        };
+        let Some(formatted) = format.format(value) else {
+            return;
+        };
+        Python::attach(|py| {
+            assert_eq!(
+                Some(formatted),
+                python_format(py, spec, value),
+                "%{spec} % {value:?}"
+            );
+        });
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            StringFormat::parse("-+#08.3ld items"),
+            Some(StringFormat {
+                left_adjust: true,
+                sign: Some('+'),
+                alternate: true,
+                zero_pad: true,
+                width: 8,
+                precision: Some(3),
+                conversion: Conversion::Decimal,
+                suffix: " items".to_string(),
+            })
+        );
+        assert_eq!(StringFormat::parse(""), None);
+        assert_eq!(StringFormat::parse("5"), None);
+        assert_eq!(StringFormat::parse("r"), None);
+        assert_eq!(StringFormat::parse("*d"), None);
+        assert_eq!(StringFormat::parse("(name)s"), None);
+        assert_eq!(StringFormat::parse("d%%"), None);
+        assert_eq!(StringFormat::parse("99999d"), None);
+    }
+
+    #[test]
+    fn test_format() {
+        let format = |spec: &str, value| StringFormat::parse(spec).unwrap().format(value);
+        let one = BigInt::from(1);
+        assert_eq!(
+            format("03d", FormatValue::Int(&one)),
+            Some("001".to_string())
+        );
+        assert_eq!(
+            format(".2f", FormatValue::Float(1.005)),
+            Some("1.00".to_string())
+        );
+        assert_eq!(
+            format("E", FormatValue::Float(100.0)),
+            Some("1.000000E+02".to_string())
+        );
+        assert_eq!(
+            format("s", FormatValue::Float(1e16)),
+            Some("1e+16".to_string())
+        );
+        assert_eq!(format("d", FormatValue::Str("1")), None);
+        assert_eq!(format("x", FormatValue::Float(1.0)), None);
+        assert_eq!(format("f", FormatValue::Float(f64::INFINITY)), None);
+    }
+
+    #[test]
+    fn test_float_repr() {
+        assert_eq!(float_repr(1.0), "1.0");
+        assert_eq!(float_repr(-0.0), "-0.0");
+        assert_eq!(float_repr(0.0001), "0.0001");
+        assert_eq!(float_repr(0.00001), "1e-05");
+        assert_eq!(float_repr(1.5e300), "1.5e+300");
+        assert_eq!(float_repr(123_456_789_012_345.6), "123456789012345.6");
+    }
+
+    fn spec() -> impl Strategy<Value = String> {
+        "[-+ #0]{0,3}[0-9]{0,2}(\\.[0-9]{0,2})?[hlL]?[diouxXeEfFgGs]( x)?"
+    }
+
+    proptest! {
+        #[test]
+        fn test_int_matches_python(spec in spec(), value in any::<i128>()) {
+            Python::initialize();
+            assert_matches_python(&spec, FormatValue::Int(&value.into()));
+        }
+
+        #[test]
+        fn test_float_matches_python(spec in spec(), value in any::<f64>()) {
+            Python::initialize();
+            assert_matches_python(&spec, FormatValue::Float(value));
+        }
+
+        #[test]
+        fn test_small_float_matches_python(
+            spec in spec(),
+            value in -1e6f64..1e6,
+        ) {
+            Python::initialize();
+            assert_matches_python(&spec, FormatValue::Float(value));
+        }
+
+        #[test]
+        fn test_str_matches_python(spec in spec(), value in "\\PC{0,8}") {
+            Python::initialize();
+            assert_matches_python(&spec, FormatValue::Str(&value));
+        }
+
+        #[test]
+        fn test_float_repr_matches_python(value in any::<f64>()) {
+            Python::initialize();
+            Python::attach(|py| {
+                let repr: String = value.into_pyobject(py).unwrap().repr().unwrap().extract().unwrap();
+                assert_eq!(float_repr(value), repr);
+            });
+        }
+    }
+}
@@ -8,7 +8,7 @@ use pyo3::exceptions::{PyAttributeError, PyTypeError};
 use pyo3::intern;
 use pyo3::prelude::*;
 use pyo3::sync::{MutexExt, PyOnceLock};
-use pyo3::types::{PyBool, PyDict, PyList, PyNone, PyString, PyTuple};
+use pyo3::types::{PyBool, PyDict, PyIterator, PyList, PyNone, PyString, PyTuple};
 
 use crate::parse::Now;
 use crate::render::lorem::{COMMON_WORDS, paragraphs, words};
@@ -645,24 +645,22 @@ impl Evaluate for IfCondition {
 }
 
 impl Render for Tag {
-    fn render<'t>(
+    fn render(
         &self,
         py: Python<'_>,
-        template: TemplateString<'t>,
+        template: TemplateString<'_>,
         context: &mut Context,
-    ) -> RenderResult<'t> {
-        Ok(match self {
+        output: &mut String,
+    ) -> RenderResult {
+        match self {
             Self::Autoescape { enabled, nodes } => {
                 let autoescape = context.autoescape;
                 context.autoescape = enabled.into();
 
-                let mut rendered = vec![];
-                for node in nodes {
-                    rendered.push(node.render(py, template, context)?);
-                }
+                let rendered = nodes.render(py, template, context, output);
 
                 context.autoescape = autoescape;
-                Cow::Owned(rendered.join(""))
+                rendered
             }
             Self::If {
                 condition,
@@ -670,79 +668,134 @@ impl Render for Tag {
                 falsey,
             } => {
                 if condition.evaluate(py, template, context).unwrap_or(false) {
-                    truthy.render(py, template, context)?
+                    truthy.render(py, template, context, output)
                 } else {
-                    falsey.render(py, template, context)?
+                    falsey.render(py, template, context, output)
                 }
             }
-            Self::For(for_tag) => for_tag.render(py, template, context)?,
-            Self::Include(include_tag) => include_tag.render(py, template, context)?,
-            Self::Load => Cow::Borrowed(""),
-            Self::SimpleTag(simple_tag) => simple_tag.render(py, template, context)?,
-            Self::SimpleBlockTag(simple_tag) => simple_tag.render(py, template, context)?,
-            Self::Url(url) => url.render(py, template, context)?,
-            Self::CsrfToken(csrf_token) => csrf_token.render(py, template, context)?,
-            Self::Lorem(lorem) => lorem.render(py, template, context)?,
-            Self::Comment(_) => Cow::Borrowed(""),
-            Self::Now(now) => now.render(py, template, context)?,
-            Self::FirstOf(firstof) => firstof.render(py, template, context)?,
-            Self::TemplateTag(template_tag) => Cow::Borrowed(template_tag.output()),
-        })
+            Self::For(for_tag) => for_tag.render(py, template, context, output),
+            Self::Include(include_tag) => include_tag.render(py, template, context, output),
+            Self::Load => Ok(()),
+            Self::SimpleTag(simple_tag) => simple_tag.render(py, template, context, output),
+            Self::SimpleBlockTag(simple_tag) => simple_tag.render(py, template, context, output),
+            Self::Url(url) => url.render(py, template, context, output),
+            Self::CsrfToken(csrf_token) => csrf_token.render(py, template, context, output),
+            Self::Lorem(lorem) => lorem.render(py, template, context, output),
+            Self::Comment(_) => Ok(()),
+            Self::Now(now) => now.render(py, template, context, output),
+            Self::FirstOf(firstof) => firstof.render(py, template, context, output),
+            Self::TemplateTag(template_tag) => {
+                output.push_str(template_tag.output());
+                Ok(())
+            }
+        }
+    }
+}
+
+/// The values a `{% for %}` loop iterates over.
+enum ForValues {
+    /// A list, indexed in place, with its length when the loop started.
+    List {
+        list: Py<PyList>,
+        start_len: usize,
+        reversed: bool,
+    },
+    /// A tuple, indexed in place.
+    Tuple { tuple: Py<PyTuple>, reversed: bool },
+    /// Values collected before the first iteration, to reverse them or to know
+    /// the length of the loop.
+    Collected(std::vec::IntoIter<PyResult<Py<PyAny>>>),
+    /// Values fetched from a Python iterator as the loop runs.
+    Lazy(Py<PyIterator>),
+    /// The characters of a string.
+    Chars(std::vec::IntoIter<char>),
+}
+
+/// The position in a list or tuple for the iteration `index`, or `None` once
+/// the loop has run past either end.
+///
+/// Like Python's iterators, a forward loop sees items added while it runs,
+/// while a reversed loop starts from the end the sequence had at the start.
+fn sequence_position(index: usize, start_len: usize, len: usize, reversed: bool) -> Option<usize> {
+    let position = match reversed {
+        true => start_len.checked_sub(index + 1)?,
+        false => index,
+    };
+    (position < len).then_some(position)
+}
+
+/// The progress of a `{% for %}` loop through its values.
+pub struct ForState {
+    values: ForValues,
+    index: usize,
+}
+
+impl ForState {
+    /// Remove the loop's variables and `forloop` from the context.
+    pub fn leave(&self, context: &mut Context) {
+        // The variables are only set once an iteration has started.
+        if self.index > 0 {
+            context.pop_variables();
+        }
+        context.pop_for_loop();
     }
 }
 
 impl For {
-    fn render_python<'t>(
+    fn python_values(
         &self,
         iterable: &Bound<'_, PyAny>,
         py: Python<'_>,
-        template: TemplateString<'t>,
-        context: &mut Context,
-    ) -> RenderResult<'t> {
-        let mut parts = Vec::new();
-        let mut list: Vec<_> = match iterable.try_iter() {
-            Ok(iterator) => iterator.collect(),
+        template: TemplateString<'_>,
+    ) -> Result<(ForValues, Option<usize>), PyRenderError> {
+        let reversed = self.reversed;
+        if let Ok(list) = iterable.cast_exact::<PyList>() {
+            let len = list.len();
+            let values = ForValues::List {
+                list: list.clone().unbind(),
+                start_len: len,
+                reversed,
+            };
+            return Ok((values, Some(len)));
+        }
+        if let Ok(tuple) = iterable.cast_exact::<PyTuple>() {
+            let len = tuple.len();
+            let tuple = tuple.clone().unbind();
+            return Ok((ForValues::Tuple { tuple, reversed }, Some(len)));
+        }
+        let iterator = match iterable.try_iter() {
+            Ok(iterator) => iterator,
             Err(error) => {
                 let error = error.annotate(py, self.iterable.at, "here", template);
                 return Err(error.into());
             }
         };
-        if self.reversed {
-            list.reverse();
+        if !reversed {
+            if !self.uses_length {
+                return Ok((ForValues::Lazy(iterator.unbind()), None));
+            }
+            // Like Django, trust `__len__` when there is one. A `QuerySet`
+            // is only evaluated once, whether for its length or its values.
+            if iterable.hasattr(intern!(py, "__len__"))? {
+                let len = match iterable.len() {
+                    Ok(len) => len,
+                    Err(error) => {
+                        let error = error.annotate(py, self.iterable.at, "here", template);
+                        return Err(error.into());
+                    }
+                };
+                return Ok((ForValues::Lazy(iterator.unbind()), Some(len)));
+            }
         }
-        context.push_for_loop(list.len());
-        for (index, values) in list.into_iter().enumerate() {
-            let values = match values {
-                Ok(values) => values,
-                Err(error) => {
-                    let error =
-                        error.annotate(py, self.iterable.at, "while iterating this", template);
-                    return Err(error.into());
-                }
-            };
-            context.push_variables(
-                &self.variables.names,
-                self.variables.at,
-                values,
-                self.iterable.at,
-                index,
-                template,
-            )?;
-            parts.push(self.body.render(py, template, context)?);
-            context.increment_for_loop();
+        let mut list: Vec<_> = iterator.map(|values| values.map(Bound::unbind)).collect();
+        if reversed {
+            list.reverse();
         }
-        context.pop_variables();
-        context.pop_for_loop();
-        Ok(Cow::Owned(parts.join("")))
+        let len = list.len();
+        Ok((ForValues::Collected(list.into_iter()), Some(len)))
     }
 
-    fn render_string<'t>(
-        &self,
-        string: &str,
-        py: Python<'_>,
-        template: TemplateString<'t>,
-        context: &mut Context,
-    ) -> RenderResult<'t> {
+    fn string_values(&self, string: &str) -> Result<(ForValues, Option<usize>), PyRenderError> {
         if self.variables.names.len() > 1 {
             return Err(RenderError::TupleUnpackError {
                 expected_count: self.variables.names.len(),
@@ -752,71 +805,146 @@ impl For {
             }
             .into());
         }
-        let mut parts = Vec::new();
         let mut chars: Vec<_> = string.chars().collect();
         if self.reversed {
             chars.reverse();
         }
-
-        let variable = &self.variables.names[0];
-        context.push_for_loop(chars.len());
-        for (index, c) in chars.into_iter().enumerate() {
-            let c = PyString::new(py, &c.to_string());
-            context.push_variable(variable.clone(), c.into_any(), index);
-            parts.push(self.body.render(py, template, context)?);
-            context.increment_for_loop();
-        }
-        context.pop_variables();
-        context.pop_for_loop();
-        Ok(Cow::Owned(parts.join("")))
+        let len = chars.len();
+        Ok((ForValues::Chars(chars.into_iter()), Some(len)))
     }
-}
 
-impl Render for For {
-    fn render<'t>(
+    /// Start the loop, or return `None` if the `{% empty %}` branch should be
+    /// rendered instead.
+    pub fn start(
         &self,
         py: Python<'_>,
-        template: TemplateString<'t>,
+        template: TemplateString<'_>,
         context: &mut Context,
-    ) -> RenderResult<'t> {
+    ) -> Result<Option<ForState>, PyRenderError> {
         let Some(iterable) =
             self.iterable
                 .iterable
                 .resolve(py, template, context, ResolveFailures::Raise)?
         else {
-            return self.empty.render(py, template, context);
+            return Ok(None);
         };
-        match iterable {
-            Content::Py(iterable) => self.render_python(&iterable, py, template, context),
-            Content::String(s) => self.render_string(s.as_raw(), py, template, context),
+        let (values, len) = match iterable {
+            Content::Py(iterable) => self.python_values(&iterable, py, template)?,
+            Content::String(s) => self.string_values(s.as_raw())?,
             Content::Float(_) | Content::Int(_) | Content::Bool(_) => {
                 unreachable!("float, int and bool literals are not iterable")
             }
+        };
+        context.push_for_loop(len);
+        Ok(Some(ForState { values, index: 0 }))
+    }
+
+    /// Bind the loop variables for the next iteration, returning `false` and
+    /// leaving the loop once the values are exhausted.
+    pub fn next_iteration(
+        &self,
+        state: &mut ForState,
+        py: Python<'_>,
+        template: TemplateString<'_>,
+        context: &mut Context,
+    ) -> Result<bool, PyRenderError> {
+        let values = match &mut state.values {
+            ForValues::List {
+                list,
+                start_len,
+                reversed,
+            } => {
+                let list = list.bind(py);
+                // Index against the live length, as the list may change while looping.
+                sequence_position(state.index, *start_len, list.len(), *reversed)
+                    .map(|position| list.get_item(position))
+            }
+            ForValues::Tuple { tuple, reversed } => {
+                let tuple = tuple.bind(py);
+                let len = tuple.len();
+                sequence_position(state.index, len, len, *reversed)
+                    .map(|position| tuple.get_item(position))
+            }
+            ForValues::Collected(values) => values
+                .next()
+                .map(|values| values.map(|values| values.into_bound(py))),
+            ForValues::Lazy(iterator) => iterator.bind(py).clone().next(),
+            ForValues::Chars(chars) => chars
+                .next()
+                .map(|c| Ok(PyString::new(py, &c.to_string()).into_any())),
+        };
+        let values = match values {
+            Some(Ok(values)) => values,
+            Some(Err(error)) => {
+                state.leave(context);
+                let error = error.annotate(py, self.iterable.at, "while iterating this", template);
+                return Err(error.into());
+            }
+            None => {
+                state.leave(context);
+                return Ok(false);
+            }
+        };
+        if let Err(error) = context.push_variables(
+            &self.variables.names,
+            self.variables.at,
+            values,
+            self.iterable.at,
+            state.index,
+            template,
+        ) {
+            state.leave(context);
+            return Err(error);
+        }
+        state.index += 1;
+        Ok(true)
+    }
+}
+
+impl Render for For {
+    fn render(
+        &self,
+        py: Python<'_>,
+        template: TemplateString<'_>,
+        context: &mut Context,
+        output: &mut String,
+    ) -> RenderResult {
+        let Some(mut state) = self.start(py, template, context)? else {
+            return self.empty.render(py, template, context, output);
+        };
+        while self.next_iteration(&mut state, py, template, context)? {
+            self.body.render(py, template, context, output)?;
+            context.increment_for_loop();
         }
+        Ok(())
     }
 }
 
-enum IncludeTemplate<'py> {
-    Template(Arc<Template>),
+pub enum IncludeTemplate<'py> {
+    Template(Template),
     Callable(Bound<'py, PyAny>),
 }
 
-impl<'t, 'py> IncludeTemplate<'py> {
-    fn render(
-        &'t self,
+impl<'py> IncludeTemplate<'py> {
+    pub fn render(
+        &self,
         py: Python<'py>,
         context: &mut Context,
         at: At,
-        template: TemplateString<'t>,
-    ) -> RenderResult<'t> {
+        template: TemplateString<'_>,
+        output: &mut String,
+    ) -> RenderResult {
         match self {
-            Self::Template(template) => template.render(py, context),
+            Self::Template(template) => Ok(template.render(py, context, output)?),
             Self::Callable(callable) => {
                 let py_context = build_pycontext(py, context)?;
                 let result = callable.call1((py_context.clone(),));
                 retrieve_context(py, py_context, context);
                 match result {
-                    Ok(content) => Ok(Cow::Owned(content.to_string())),
+                    Ok(content) => {
+                        output.push_str(&content.to_string());
+                        Ok(())
+                    }
                     Err(error) => Err(error.annotate(py, at, "here", template).into()),
                 }
             }
@@ -825,7 +953,7 @@ impl<'t, 'py> IncludeTemplate<'py> {
 }
 
 impl Include {
-    fn template_at(&self) -> At {
+    pub fn template_at(&self) -> At {
         match &self.template_name {
             IncludeTemplateName::Text(text) => text.at,
             IncludeTemplateName::Variable(TagElement::Variable(variable)) => variable.at,
@@ -964,22 +1092,62 @@ impl Include {
     }
 }
 
-impl Render for Include {
-    fn render<'t>(
+/// How to restore the context after rendering an included template.
+enum IncludeScope {
+    /// Remove the keyword arguments added to the context.
+    Variables(Vec<String>),
+    /// Restore the outer context, replaced by one holding only the keyword arguments.
+    Only(Context),
+}
+
+impl IncludeScope {
+    fn exit(self, context: &mut Context) {
+        match self {
+            Self::Variables(names) => {
+                for key in names {
+                    context.pop_variable(&key);
+                }
+            }
+            Self::Only(outer) => *context = outer,
+        }
+    }
+}
+
+/// The context changes made to render an included template, undone by `exit`.
+pub struct IncludeState {
+    scope: IncludeScope,
+    /// Whether the included template got its own `RenderContext` scope.
+    render_state: bool,
+}
+
+impl IncludeState {
+    pub fn exit(self, py: Python<'_>, context: &mut Context) -> PyResult<()> {
+        let popped = match self.render_state {
+            true => context.pop_render_state(py),
+            false => Ok(()),
+        };
+        self.scope.exit(context);
+        popped
+    }
+}
+
+impl Include {
+    /// Find the included template and set up the context to render it with.
+    pub fn enter<'py>(
         &self,
-        py: Python<'_>,
-        template: TemplateString<'t>,
+        py: Python<'py>,
+        template: TemplateString<'_>,
         context: &mut Context,
-    ) -> RenderResult<'t> {
+    ) -> Result<(IncludeTemplate<'py>, IncludeState), PyRenderError> {
         let template_name = self.resolve_template_name(py, template, context)?;
         let include = self.get_template(template_name, py, template, context)?;
-        match self.only {
+        let scope = match self.only {
             false => {
                 let mut names = Vec::new();
                 let mut values = Vec::new();
                 for (at, element) in &self.kwargs {
                     let key = template.content(*at);
-                    names.push(key);
+                    names.push(key.to_string());
 
                     match element.resolve(
                         py,
@@ -992,15 +1160,9 @@ impl Render for Include {
                     }
                 }
                 for (key, value) in names.iter().zip(values) {
-                    context.append(key.to_string(), value);
+                    context.append(key.clone(), value);
                 }
-                let rendered = include
-                    .render(py, context, self.template_at(), template)
-                    .map(|content| Cow::Owned(content.into_owned()));
-                for key in names {
-                    context.pop_variable(key);
-                }
-                rendered
+                IncludeScope::Variables(names)
             }
             true => {
                 let mut inner_context = HashMap::new();
@@ -1015,33 +1177,54 @@ impl Render for Include {
                         inner_context.insert(key, value.to_py(py).unbind());
                     }
                 }
-                let request = context
-                    .request
-                    .as_ref()
-                    .map(|request| request.clone_ref(py));
-                let mut new_context = Context::new(inner_context, request, context.autoescape);
-                include
-                    .render(py, &mut new_context, self.template_at(), template)
-                    .map(|content| Cow::Owned(content.into_owned()))
+                let new_context = context.new_child(py, inner_context);
+                IncludeScope::Only(std::mem::replace(context, new_context))
             }
+        };
+        // Django templates push their own state when they render.
+        let render_state = matches!(include, IncludeTemplate::Template(_));
+        if render_state {
+            context.push_render_state(py)?;
         }
+        Ok((
+            include,
+            IncludeState {
+                scope,
+                render_state,
+            },
+        ))
     }
 }
 
-fn call_tag<'t>(
+impl Render for Include {
+    fn render(
+        &self,
+        py: Python<'_>,
+        template: TemplateString<'_>,
+        context: &mut Context,
+        output: &mut String,
+    ) -> RenderResult {
+        let (include, scope) = self.enter(py, template, context)?;
+        let rendered = include.render(py, context, self.template_at(), template, output);
+        scope.exit(py, context)?;
+        rendered
+    }
+}
+
+fn call_tag(
     py: Python<'_>,
     func: &Arc<Py<PyAny>>,
     at: At,
-    template: TemplateString<'t>,
+    template: TemplateString<'_>,
     args: VecDeque<Bound<'_, PyAny>>,
     kwargs: Bound<'_, PyDict>,
-) -> RenderResult<'t> {
+) -> Result<String, PyRenderError> {
     let func = func.bind(py);
     match func.call(
         PyTuple::new(py, args).expect("All arguments should be valid Python objects"),
         Some(&kwargs),
     ) {
-        Ok(content) => Ok(Cow::Owned(content.to_string())),
+        Ok(content) => Ok(content.to_string()),
         Err(error) => Err(error.annotate(py, at, "here", template).into()),
     }
 }
@@ -1121,29 +1304,30 @@ fn build_kwargs<'py>(
     Ok(py_kwargs)
 }
 
-fn store_target_var<'t>(
+fn store_target_var(
     py: Python<'_>,
     context: &mut Context,
-    content: Cow<'t, str>,
+    content: &str,
     target_var: Option<&String>,
-) -> Cow<'t, str> {
+    output: &mut String,
+) {
     match target_var {
-        None => content,
+        None => output.push_str(content),
         Some(target_var) => {
-            let content = PyString::new(py, &content).into_any();
+            let content = PyString::new(py, content).into_any();
             context.insert(target_var.clone(), content);
-            Cow::Borrowed("")
         }
     }
 }
 
 impl Render for SimpleTag {
-    fn render<'t>(
+    fn render(
         &self,
         py: Python<'_>,
-        template: TemplateString<'t>,
+        template: TemplateString<'_>,
         context: &mut Context,
-    ) -> RenderResult<'t> {
+        output: &mut String,
+    ) -> RenderResult {
         let mut args = build_args(py, template, context, &self.args)?;
         let kwargs = build_kwargs(py, template, context, &self.kwargs)?;
         let content = if self.takes_context {
@@ -1160,26 +1344,23 @@ impl Render for SimpleTag {
         } else {
             call_tag(py, &self.func, self.at, template, args, kwargs)?
         };
-        Ok(store_target_var(
-            py,
-            context,
-            content,
-            self.target_var.as_ref(),
-        ))
+        store_target_var(py, context, &content, self.target_var.as_ref(), output);
+        Ok(())
     }
 }
 
 impl Render for SimpleBlockTag {
-    fn render<'t>(
+    fn render(
         &self,
         py: Python<'_>,
-        template: TemplateString<'t>,
+        template: TemplateString<'_>,
         context: &mut Context,
-    ) -> RenderResult<'t> {
+        output: &mut String,
+    ) -> RenderResult {
         let mut args = build_args(py, template, context, &self.args)?;
         let kwargs = build_kwargs(py, template, context, &self.kwargs)?;
 
-        let content = self.nodes.render(py, template, context)?;
+        let content = self.nodes.render_to_string(py, template, context)?;
         let content = PyString::new(py, &content).into_any();
         args.push_front(content);
 
@@ -1197,22 +1378,19 @@ impl Render for SimpleBlockTag {
         } else {
             call_tag(py, &self.func, self.at, template, args, kwargs)?
         };
-        Ok(store_target_var(
-            py,
-            context,
-            content,
-            self.target_var.as_ref(),
-        ))
+        store_target_var(py, context, &content, self.target_var.as_ref(), output);
+        Ok(())
     }
 }
 
 impl Render for Now {
-    fn render<'t>(
+    fn render(
         &self,
         py: Python<'_>,
-        template: TemplateString<'t>,
+        template: TemplateString<'_>,
         context: &mut Context,
-    ) -> RenderResult<'t> {
+        output: &mut String,
+    ) -> RenderResult {
         let tz_mod = DJANGO_TIMEZONE
             .get_or_try_init(py, || -> Result<Py<PyAny>, PyRenderError> {
                 Ok(py.import("django.utils.timezone")?.into())
@@ -1252,11 +1430,11 @@ impl Render for Now {
         if let Some(asvar_at) = self.asvar {
             let var_name = template.content(asvar_at);
             context.insert(var_name.to_string(), result);
-            Ok(Cow::Borrowed(""))
         } else {
             let rendered = result.cast_into::<PyString>().map_err(PyErr::from)?;
-            Ok(Cow::Owned(rendered.to_str()?.to_owned()))
+            output.push_str(rendered.to_str()?);
         }
+        Ok(())
     }
 }
 
@@ -1272,28 +1450,26 @@ impl CsrfToken {
 }
 
 impl Render for CsrfToken {
-    fn render<'t>(
+    fn render(
         &self,
         py: Python<'_>,
-        _template: TemplateString<'t>,
+        _template: TemplateString<'_>,
         context: &mut Context,
-    ) -> RenderResult<'t> {
+        output: &mut String,
+    ) -> RenderResult {
         match context.get("csrf_token") {
             Some(token) => {
                 let bound_token = token.bind(py);
                 if let Ok(token_str) = bound_token.extract::<String>() {
-                    if token_str.is_empty() || token_str == "NOTPROVIDED" {
-                        Ok(Cow::Borrowed(""))
-                    } else {
-                        Ok(Cow::Owned(Self::input_html(&token_str)))
+                    if !token_str.is_empty() && token_str != "NOTPROVIDED" {
+                        output.push_str(&Self::input_html(&token_str));
                     }
                 } else if bound_token.is_truthy()? {
                     let token_py_str = bound_token.str()?;
                     let token_str = token_py_str.to_str()?;
-                    Ok(Cow::Owned(Self::input_html(token_str)))
-                } else {
-                    Ok(Cow::Borrowed(""))
+                    output.push_str(&Self::input_html(token_str));
                 }
+                Ok(())
             }
             None => {
                 let settings = DJANGO_SETTINGS.import(py, "django.conf", "settings")?;
@@ -1304,19 +1480,20 @@ impl Render for CsrfToken {
                     warn.call1((Self::MISSING_WARNING,))?;
                 }
 
-                Ok(Cow::Borrowed(""))
+                Ok(())
             }
         }
     }
 }
 
 impl Render for Lorem {
-    fn render<'t>(
+    fn render(
         &self,
         py: Python<'_>,
-        template: TemplateString<'t>,
+        template: TemplateString<'_>,
         context: &mut Context,
-    ) -> RenderResult<'t> {
+        output: &mut String,
+    ) -> RenderResult {
         let count_content = self.count.resolve(
             py,
             template,
@@ -1339,7 +1516,7 @@ impl Render for Lorem {
             }
             LoremMethod::Paragraphs | LoremMethod::Blocks => {
                 if val <= 0 {
-                    return Ok(Cow::Borrowed(""));
+                    return Ok(());
                 } else {
                     let count = val as usize;
                     let paras = paragraphs(count, self.common);
@@ -1356,17 +1533,19 @@ impl Render for Lorem {
             }
         };
 
-        Ok(Cow::Owned(text))
+        output.push_str(&text);
+        Ok(())
     }
 }
 
 impl Render for FirstOf {
-    fn render<'t>(
+    fn render(
         &self,
         py: Python<'_>,
-        template: TemplateString<'t>,
+        template: TemplateString<'_>,
         context: &mut Context,
-    ) -> RenderResult<'t> {
+        output: &mut String,
+    ) -> RenderResult {
         for var in &self.vars {
             if let Some(content) = var.resolve(
                 py,
@@ -1377,16 +1556,16 @@ impl Render for FirstOf {
             {
                 if let Some(asvar) = &self.asvar {
                     context.insert(asvar.to_string(), content.to_py(py));
-                    return Ok(Cow::Borrowed(""));
+                    return Ok(());
                 }
 
-                let format = content.render(context)?.to_string();
-                return Ok(Cow::Owned(format));
+                content.render_to(py, context, output)?;
+                return Ok(());
             }
         }
         if let Some(asvar) = &self.asvar {
             context.insert(asvar.to_string(), PyString::new(py, "").into_any());
         }
-        Ok(Cow::Borrowed(""))
+        Ok(())
     }
 }
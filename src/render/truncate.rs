@@ -0,0 +1,619 @@
+//! Text truncation matching `django.utils.text.Truncator`.
+//!
+//! The HTML aware variants tokenize the input like Python's `html.parser`
+//! does for Django's `TruncateHTMLParser`, so comments and declarations are
+//! dropped, character references are converted and text is re-escaped.
+
+use std::borrow::Cow;
+use std::collections::VecDeque;
+
+use html_escape::encode_quoted_attribute;
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::canonical_combining_class;
+
+use crate::render::html::unescape;
+
+const TRUNCATED_TEXT: &str = "%(truncated_text)s";
+
+/// `django.utils.html.VOID_ELEMENTS`
+const VOID_ELEMENTS: [&str; 16] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr", "frame", "spacer",
+];
+
+/// Elements whose content `html.parser` passes through as raw text.
+const CDATA_CONTENT_ELEMENTS: [&str; 2] = ["script", "style"];
+
+fn is_combining(c: char) -> bool {
+    canonical_combining_class(c) != 0
+}
+
+fn add_truncation_text(text: &str, truncate: &str) -> String {
+    if truncate.contains(TRUNCATED_TEXT) {
+        truncate.replace(TRUNCATED_TEXT, text)
+    } else if text.ends_with(truncate) {
+        text.to_string()
+    } else {
+        format!("{text}{truncate}")
+    }
+}
+
+/// The number of characters of text to keep so that, with the truncation
+/// text added, the result is at most `length` characters long.
+fn calculate_truncate_chars_length(length: usize, replacement: &str) -> usize {
+    let mut truncate_len = length;
+    for c in add_truncation_text("", replacement).chars() {
+        if !is_combining(c) {
+            truncate_len -= 1;
+            if truncate_len == 0 {
+                break;
+            }
+        }
+    }
+    truncate_len
+}
+
+/// Truncate a string after `length` characters, not counting combining
+/// characters.
+pub fn truncate_chars(text: &str, length: usize, replacement: &str) -> String {
+    if length == 0 {
+        return String::new();
+    }
+    let text: String = text.nfc().collect();
+    let truncate_len = calculate_truncate_chars_length(length, replacement);
+    let mut s_len = 0;
+    let mut end_index = None;
+    for (index, c) in text.char_indices() {
+        if is_combining(c) {
+            continue;
+        }
+        s_len += 1;
+        if end_index.is_none() && s_len > truncate_len {
+            end_index = Some(index);
+        }
+        if s_len > length {
+            return add_truncation_text(&text[..end_index.unwrap_or(0)], replacement);
+        }
+    }
+    text
+}
+
+/// Truncate a string after `length` words, normalising whitespace.
+pub fn truncate_words(text: &str, length: usize, replacement: &str) -> String {
+    if length == 0 {
+        return String::new();
+    }
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.len() > length {
+        return add_truncation_text(&words[..length].join(" "), replacement);
+    }
+    words.join(" ")
+}
+
+/// Truncate HTML after `length` characters of text, closing any open tags.
+pub fn truncate_chars_html(html: &str, length: usize, replacement: &str) -> String {
+    if length == 0 {
+        return String::new();
+    }
+    let html: String = html.nfc().collect();
+    let html_len = html.chars().count();
+    let mut processed_chars = 0;
+    truncate_html(
+        &html,
+        calculate_truncate_chars_length(length, replacement),
+        replacement,
+        |data, remaining, output| {
+            let data_len = data.chars().count();
+            processed_chars += data_len;
+            // The final piece of text exactly fills the length, so keep it as is.
+            if processed_chars == length && output.chars().count() + data_len == html_len {
+                return Processed::Complete;
+            }
+            let kept = match data.char_indices().nth(remaining) {
+                Some((index, _)) => &data[..index],
+                None => data,
+            };
+            Processed::Data {
+                len: data_len,
+                output: encode_quoted_attribute(kept).into_owned(),
+            }
+        },
+    )
+}
+
+/// Truncate HTML after `length` words of text, closing any open tags.
+pub fn truncate_words_html(html: &str, length: usize, replacement: &str) -> String {
+    if length == 0 {
+        return String::new();
+    }
+    truncate_html(html, length, replacement, |data, remaining, _output| {
+        let words = split_words(data);
+        let kept = words[..remaining.min(words.len())].join(" ");
+        Processed::Data {
+            len: words.len(),
+            output: encode_quoted_attribute(&kept).into_owned(),
+        }
+    })
+}
+
+/// Split on whitespace between words, like `re.split(r"(?<=\S)\s+(?=\S)", data)`.
+fn split_words(data: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = 0;
+    let mut chars = data.char_indices().peekable();
+    let mut previous_is_word = false;
+    while let Some((index, c)) = chars.next() {
+        if !c.is_whitespace() {
+            previous_is_word = true;
+            continue;
+        }
+        if !previous_is_word {
+            continue;
+        }
+        let mut end = index + c.len_utf8();
+        while let Some(&(next_index, next)) = chars.peek() {
+            if !next.is_whitespace() {
+                break;
+            }
+            end = next_index + next.len_utf8();
+            chars.next();
+        }
+        previous_is_word = false;
+        if chars.peek().is_some() {
+            words.push(&data[start..index]);
+            start = end;
+        }
+    }
+    words.push(&data[start..]);
+    words
+}
+
+enum Processed {
+    /// Output the data unchanged and stop.
+    Complete,
+    /// The data counted as `len` towards the length, with the escaped
+    /// `output` to keep if it doesn't all fit.
+    Data { len: usize, output: String },
+}
+
+fn truncate_html(
+    html: &str,
+    mut remaining: usize,
+    replacement: &str,
+    mut process: impl FnMut(&str, usize, &str) -> Processed,
+) -> String {
+    let mut output = String::with_capacity(html.len());
+    let mut tags = VecDeque::new();
+    for token in HtmlTokens::new(html) {
+        match token {
+            Token::StartTag {
+                name,
+                text,
+                self_closing,
+            } => {
+                output.push_str(text);
+                if !VOID_ELEMENTS.contains(&name.as_str()) {
+                    if self_closing {
+                        output.push_str(&format!("</{name}>"));
+                    } else {
+                        tags.push_front(name);
+                    }
+                }
+            }
+            Token::EndTag(name) => {
+                if !VOID_ELEMENTS.contains(&name.as_str()) {
+                    output.push_str(&format!("</{name}>"));
+                    if let Some(index) = tags.iter().position(|tag| *tag == name) {
+                        tags.remove(index);
+                    }
+                }
+            }
+            Token::Data(data) => {
+                match process(&data, remaining, &output) {
+                    Processed::Complete => output.push_str(&data),
+                    Processed::Data { len, output: text } if remaining < len => {
+                        output.push_str(&add_truncation_text(&text, replacement));
+                    }
+                    Processed::Data { len, output: text } => {
+                        remaining -= len;
+                        output.push_str(&text);
+                        continue;
+                    }
+                }
+                // Truncation is complete, so close any open tags.
+                for tag in tags {
+                    output.push_str(&format!("</{tag}>"));
+                }
+                break;
+            }
+        }
+    }
+    output
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token<'a> {
+    StartTag {
+        name: String,
+        text: &'a str,
+        self_closing: bool,
+    },
+    EndTag(String),
+    Data(Cow<'a, str>),
+}
+
+/// A tokenizer following the subset of `html.parser.HTMLParser` used by
+/// Django. Like Django, anything the parser would need more input to handle
+/// (unterminated tags or comments, or a possibly incomplete character
+/// reference at the end) is dropped.
+struct HtmlTokens<'a> {
+    html: &'a str,
+    position: usize,
+    cdata: Option<String>,
+}
+
+const fn is_tag_name_end(byte: u8) -> bool {
+    matches!(
+        byte,
+        b'\t' | b'\n' | b'\r' | b'\x0c' | b' ' | b'/' | b'>' | b'\0'
+    )
+}
+
+impl<'a> HtmlTokens<'a> {
+    fn new(html: &'a str) -> Self {
+        Self {
+            html,
+            position: 0,
+            cdata: None,
+        }
+    }
+
+    fn find_from(&self, pattern: &str, from: usize) -> Option<usize> {
+        self.html[from..].find(pattern).map(|index| from + index)
+    }
+
+    /// Whether the text from `start` may end in a character reference that
+    /// `html.parser` would wait for more input to complete.
+    fn has_incomplete_charref(&self, start: usize) -> bool {
+        let window = self
+            .html
+            .char_indices()
+            .rev()
+            .nth(33)
+            .map_or(0, |(index, _)| index)
+            .max(start);
+        match self.html[window..].rfind('&') {
+            Some(amp) => {
+                !self.html[window + amp..].contains(|c: char| c.is_whitespace() || c == ';')
+            }
+            None => false,
+        }
+    }
+
+    fn cdata_end(&self, name: &str) -> Option<usize> {
+        let lower = self.html[self.position..].to_ascii_lowercase();
+        let pattern = format!("</{name}");
+        let mut from = 0;
+        while let Some(index) = lower[from..].find(&pattern) {
+            let index = from + index;
+            match lower.as_bytes().get(index + pattern.len()) {
+                Some(b'\t' | b'\n' | b'\r' | b'\x0c' | b' ' | b'/' | b'>') => {
+                    return Some(self.position + index);
+                }
+                _ => from = index + 1,
+            }
+        }
+        None
+    }
+
+    fn parse_starttag(&mut self) -> Option<Token<'a>> {
+        let bytes = self.html.as_bytes();
+        let start = self.position;
+        let mut index = start + 1;
+        while index < bytes.len() && !is_tag_name_end(bytes[index]) {
+            index += 1;
+        }
+        let name = self.html[start + 1..index].to_lowercase();
+        let mut self_closing = false;
+        let end = loop {
+            match bytes.get(index)? {
+                b'>' => break index + 1,
+                b'/' if bytes.get(index + 1) == Some(&b'>') => {
+                    self_closing = true;
+                    break index + 2;
+                }
+                b'=' => {
+                    index += 1;
+                    while bytes.get(index).is_some_and(u8::is_ascii_whitespace) {
+                        index += 1;
+                    }
+                    match bytes.get(index)? {
+                        quote @ (b'"' | b'\'') => {
+                            let quote = char::from(*quote);
+                            index += self.html[index + 1..].find(quote)? + 2;
+                        }
+                        _ => {
+                            while bytes
+                                .get(index)
+                                .is_some_and(|b| *b != b'>' && !b.is_ascii_whitespace())
+                            {
+                                index += 1;
+                            }
+                        }
+                    }
+                }
+                _ => index += 1,
+            }
+        };
+        self.position = end;
+        if !self_closing && CDATA_CONTENT_ELEMENTS.contains(&name.as_str()) {
+            self.cdata = Some(name.clone());
+        }
+        Some(Token::StartTag {
+            name,
+            text: &self.html[start..end],
+            self_closing,
+        })
+    }
+
+    /// Parse an end tag, returning `None` if it is ignored.
+    fn parse_endtag(&mut self) -> Option<Option<Token<'a>>> {
+        let bytes = self.html.as_bytes();
+        let start = self.position;
+        let gt = self.find_from(">", start + 2)?;
+        let mut index = start + 2;
+        while bytes[index].is_ascii_whitespace() {
+            index += 1;
+        }
+        if !bytes[index].is_ascii_alphabetic() {
+            self.position = match bytes[start + 2] {
+                b'>' => start + 3,
+                // A bogus comment.
+                _ => gt + 1,
+            };
+            return Some(None);
+        }
+        if index > start + 2
+            && !self.html[index..gt]
+                .trim_end_matches(|c: char| c.is_ascii_whitespace())
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b':' | b'_'))
+        {
+            // Whitespace before the name is only allowed for simple end tags.
+            self.position = gt + 1;
+            return Some(None);
+        }
+        let name_start = index;
+        while index < bytes.len() && !is_tag_name_end(bytes[index]) {
+            index += 1;
+        }
+        let name = self.html[name_start..index].to_lowercase();
+        self.position = self.find_from(">", index)? + 1;
+        match &self.cdata {
+            Some(cdata) if *cdata != name => {
+                return Some(Some(Token::Data(Cow::Borrowed(
+                    &self.html[start..self.position],
+                ))));
+            }
+            Some(_) => self.cdata = None,
+            None => {}
+        }
+        Some(Some(Token::EndTag(name)))
+    }
+
+    /// Skip a construct starting at the current position and ending with `end`.
+    fn skip_until(&mut self, from: usize, end: &str) -> Option<()> {
+        self.position = self.find_from(end, from)? + end.len();
+        Some(())
+    }
+
+    fn next_token(&mut self) -> Option<Token<'a>> {
+        loop {
+            let start = self.position;
+            if start >= self.html.len() {
+                return None;
+            }
+            let end = match &self.cdata {
+                Some(name) => self.cdata_end(name)?,
+                None => match self.find_from("<", start) {
+                    Some(end) => end,
+                    None if self.has_incomplete_charref(start) => return None,
+                    None => self.html.len(),
+                },
+            };
+            if start < end {
+                self.position = end;
+                let data = &self.html[start..end];
+                return Some(Token::Data(match self.cdata {
+                    Some(_) => Cow::Borrowed(data),
+                    None => unescape(data),
+                }));
+            }
+
+            let rest = &self.html[start..];
+            if rest.as_bytes().get(1).is_some_and(u8::is_ascii_alphabetic) {
+                return self.parse_starttag();
+            } else if rest.starts_with("</") {
+                if let Some(token) = self.parse_endtag()? {
+                    return Some(token);
+                }
+            } else if rest.starts_with("<!--") {
+                self.skip_until(start + 4, "-->")?;
+            } else if rest.starts_with("<?") {
+                self.skip_until(start + 2, ">")?;
+            } else if rest.starts_with("<![") {
+                let end = if ["<![if", "<![else", "<![endif"].iter().any(|section| {
+                    rest.get(..section.len())
+                        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(section))
+                }) {
+                    "]>"
+                } else {
+                    "]]>"
+                };
+                self.skip_until(start + 3, end)?;
+            } else if rest.starts_with("<!") {
+                self.skip_until(start + 2, ">")?;
+            } else if rest.len() > 1 {
+                self.position = start + 1;
+                return Some(Token::Data(Cow::Borrowed("<")));
+            } else {
+                return None;
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for HtmlTokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_token()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HTML: &str =
+        "<p id=\"par\"><strong><em>The quick brown fox jumped over the lazy dog.</em></strong></p>";
+
+    #[test]
+    fn test_truncate_chars() {
+        let text = "The quick brown fox jumped over the lazy dog.";
+        assert_eq!(truncate_chars(text, 100, "…"), text);
+        assert_eq!(
+            truncate_chars(text, 21, "%(truncated_text)s…"),
+            "The quick brown fox …"
+        );
+        assert_eq!(truncate_chars(text, 23, "....."), "The quick brown fo.....");
+        assert_eq!(truncate_chars(text, 4, "....."), ".....");
+        assert_eq!(truncate_chars("asdf", 1, "..."), "...");
+        assert_eq!(truncate_chars("ou\u{308}ou\u{308}ou\u{308}", 3, "…"), "oü…");
+        assert_eq!(
+            truncate_chars("-B\u{30a}B\u{30a}----8", 3, "…"),
+            "-B\u{30a}…"
+        );
+        assert_eq!(
+            truncate_chars("-B\u{30a}B\u{30a}----8", 5, "…"),
+            "-B\u{30a}B\u{30a}-…"
+        );
+        assert_eq!(truncate_chars("-----", 4, "B\u{30a}"), "---B\u{30a}");
+        assert_eq!(truncate_chars("-----", 5, "B\u{30a}"), "-----");
+    }
+
+    #[test]
+    fn test_truncate_words() {
+        let text = "The quick brown fox jumped over the lazy dog.";
+        assert_eq!(truncate_words(text, 10, " …"), text);
+        assert_eq!(truncate_words(text, 4, " …"), "The quick brown fox …");
+        assert_eq!(
+            truncate_words(text, 4, "[snip]"),
+            "The quick brown fox[snip]"
+        );
+        assert_eq!(truncate_words("  a \t b  ", 5, " …"), "a b");
+    }
+
+    #[test]
+    fn test_truncate_chars_html() {
+        assert_eq!(truncate_chars_html(HTML, 80, "…"), HTML);
+        assert_eq!(truncate_chars_html(HTML, 46, "…"), HTML);
+        assert_eq!(
+            truncate_chars_html(HTML, 45, "…"),
+            "<p id=\"par\"><strong><em>The quick brown fox jumped over the lazy dog…</em></strong></p>"
+        );
+        assert_eq!(
+            truncate_chars_html(HTML, 10, "…"),
+            "<p id=\"par\"><strong><em>The quick…</em></strong></p>"
+        );
+        assert_eq!(
+            truncate_chars_html(HTML, 1, "…"),
+            "<p id=\"par\"><strong><em>…</em></strong></p>"
+        );
+        assert_eq!(
+            truncate_chars_html(HTML, 10, ""),
+            "<p id=\"par\"><strong><em>The quick </em></strong></p>"
+        );
+        assert_eq!(truncate_chars_html(HTML, 0, "…"), "");
+        assert_eq!(truncate_chars_html("foo</p>", 5, "…"), "foo</p>");
+        assert_eq!(
+            truncate_chars_html("<p>a &amp; b</p>", 100, "…"),
+            "<p>a &amp; b</p>"
+        );
+    }
+
+    #[test]
+    fn test_truncate_words_html() {
+        assert_eq!(truncate_words_html(HTML, 10, "…"), HTML);
+        assert_eq!(
+            truncate_words_html(HTML, 4, "…"),
+            "<p id=\"par\"><strong><em>The quick brown fox…</em></strong></p>"
+        );
+        assert_eq!(
+            truncate_words_html(
+                "<p>The  quick \t brown fox jumped over the lazy dog.</p>",
+                4,
+                "…"
+            ),
+            "<p>The quick brown fox…</p>"
+        );
+        assert_eq!(
+            truncate_words_html(
+                "<p>The quick <a href=\"xyz.html\"\n id=\"mylink\">brown fox</a> jumped over the lazy dog.</p>",
+                3,
+                "…"
+            ),
+            "<p>The quick <a href=\"xyz.html\"\n id=\"mylink\">brown…</a></p>"
+        );
+        assert_eq!(
+            truncate_words_html(
+                "<br/>The <hr />quick brown fox jumped over the lazy dog.",
+                3,
+                "…"
+            ),
+            "<br/>The <hr />quick brown…"
+        );
+        assert_eq!(
+            truncate_words_html(
+                "<br>The <hr/>quick <em>brown fox</em> jumped over the lazy dog.",
+                3,
+                "…"
+            ),
+            "<br>The <hr/>quick <em>brown…</em>"
+        );
+        assert_eq!(
+            truncate_words_html(
+                "<i>Buenos d&iacute;as! &#x00bf;C&oacute;mo est&aacute;?</i>",
+                3,
+                "…"
+            ),
+            "<i>Buenos días! ¿Cómo…</i>"
+        );
+        assert_eq!(
+            truncate_words_html("<p>I &lt;3 python, what about you?</p>", 3, "…"),
+            "<p>I &lt;3 python,…</p>"
+        );
+        assert_eq!(
+            truncate_words_html("<p/>a<!-- b -->c", 5, "…"),
+            "<p/></p>ac"
+        );
+    }
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(split_words(" a  b\tc "), vec![" a", "b", "c "]);
+        assert_eq!(split_words("   "), vec!["   "]);
+        assert_eq!(split_words("a"), vec!["a"]);
+    }
+
+    #[test]
+    fn test_html_tokens_incomplete() {
+        assert_eq!(truncate_words_html("Q&A", 5, "…"), "");
+        assert_eq!(truncate_words_html("a <b", 5, "…"), "a ");
+        assert_eq!(truncate_words_html("a < b", 5, "…"), "a &lt; b");
+        assert_eq!(
+            truncate_words_html("<script>a<b</script>c", 5, "…"),
+            "<script>a&lt;b</script>c"
+        );
+    }
+}
@@ -6,27 +6,38 @@ use std::collections::hash_map::Entry;
 use std::iter::zip;
 use std::sync::{Arc, Mutex};
 
-use html_escape::encode_quoted_attribute;
+use html_escape::{encode_quoted_attribute, encode_quoted_attribute_to_string};
 use miette::SourceSpan;
 use num_bigint::{BigInt, ToBigInt};
 use num_traits::{ToPrimitive, Zero};
-use pyo3::exceptions::{PyAttributeError, PyKeyError, PyTypeError};
+use pyo3::exceptions::{PyAttributeError, PyKeyError, PyRuntimeError, PyTypeError};
+use pyo3::import_exception;
 use pyo3::intern;
 use pyo3::prelude::*;
 use pyo3::sync::{MutexExt, PyOnceLock};
-use pyo3::types::{PyBool, PyDict, PyInt, PyString, PyType};
+use pyo3::types::{PyBool, PyDict, PyInt, PyIterator, PyList, PyString, PyTuple, PyType};
 
 use crate::error::{AnnotatePyErr, PyRenderError, RenderError};
-use crate::template::django_rusty_templates::{Engine, Template, get_template, select_template};
+use crate::template::django_rusty_templates::{
+    Engine, Template, builtins_context, get_template, select_template,
+};
 use crate::utils::PyResultMethods;
 use dtl_lexer::types::{At, TemplateString};
 
 static MARK_SAFE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+static RENDER_CONTEXT: PyOnceLock<Py<PyType>> = PyOnceLock::new();
+
+import_exception!(django.template.context, ContextPopException);
+
+/// Text at least this long is escaped with the interpreter detached when the
+/// engine's `detach` option is set.
+const DETACH_ESCAPE_LEN: usize = 16 * 1024;
 
 #[derive(Debug, Clone)]
 pub struct ForLoop {
     count: usize,
-    len: usize,
+    /// The length of the loop, if it was needed up front.
+    len: Option<usize>,
 }
 
 impl ForLoop {
@@ -38,23 +49,43 @@ impl ForLoop {
         self.count + 1
     }
 
-    pub fn rev_counter(&self) -> usize {
-        self.len - self.count
+    /// The number of iterations left, or `None` if the loop's length is unknown.
+    ///
+    /// Length marking only covers the template a loop is in, so a template
+    /// rendered by a tag inside the loop can still ask for it.
+    ///
+    /// Like Django, this counts down from the length the values reported, so
+    /// it goes below one for values which yield more items than that.
+    pub fn rev_counter(&self) -> Option<i64> {
+        let len = i64::try_from(self.len?).ok()?;
+        let count = i64::try_from(self.count).ok()?;
+        Some(len - count)
     }
 
-    pub fn rev_counter0(&self) -> usize {
-        self.len - self.count - 1
+    pub fn rev_counter0(&self) -> Option<i64> {
+        self.rev_counter().map(|rev_counter| rev_counter - 1)
     }
 
     pub fn first(&self) -> bool {
         self.count == 0
     }
 
-    pub fn last(&self) -> bool {
-        self.count + 1 == self.len
+    pub fn last(&self) -> Option<bool> {
+        self.len.map(|len| self.count + 1 == len)
     }
 }
 
+/// Django's `RenderContext`, created when a tag first asks for it.
+///
+/// It is shared by a context and its children, and gets a new scope for each
+/// nested template render, like Django's `RenderContext.push_state`.
+#[derive(Debug, Default)]
+struct RenderContext {
+    context: Option<Py<PyAny>>,
+    /// How many template renders are nested inside the outermost one.
+    depth: usize,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum IncludeTemplateKey {
     String(String),
@@ -67,8 +98,15 @@ pub struct Context {
     loops: Vec<ForLoop>,
     pub request: Option<Py<PyAny>>,
     pub autoescape: bool,
+    pub use_l10n: Option<bool>,
+    pub use_tz: Option<bool>,
+    pub template_name: Option<String>,
+    pub template: Option<Py<Template>>,
+    /// Whether to detach from the interpreter while escaping large text.
+    pub detach: bool,
+    render_context: Arc<Mutex<RenderContext>>,
     names: Vec<HashSet<String>>,
-    include_cache: HashMap<IncludeTemplateKey, Arc<Template>>,
+    include_cache: HashMap<IncludeTemplateKey, Template>,
 }
 
 impl Context {
@@ -82,6 +120,12 @@ impl Context {
             request,
             context,
             autoescape,
+            use_l10n: None,
+            use_tz: None,
+            template_name: None,
+            template: None,
+            detach: false,
+            render_context: Arc::default(),
             loops: Vec::new(),
             names: Vec::new(),
             include_cache: HashMap::new(),
@@ -97,6 +141,12 @@ impl Context {
                 .map(|(k, v)| (k.clone(), v.iter().map(|v| v.clone_ref(py)).collect()))
                 .collect(),
             autoescape: self.autoescape,
+            use_l10n: self.use_l10n,
+            use_tz: self.use_tz,
+            template_name: self.template_name.clone(),
+            template: self.template.as_ref().map(|v| v.clone_ref(py)),
+            detach: self.detach,
+            render_context: Arc::clone(&self.render_context),
             loops: self.loops.clone(),
             names: self.names.clone(),
             include_cache: self.include_cache.clone(),
@@ -107,6 +157,13 @@ impl Context {
         self.context.get(key)?.last()
     }
 
+    /// Iterate over the current value of every variable in the context.
+    pub fn items(&self) -> impl Iterator<Item = (&str, &Py<PyAny>)> {
+        self.context
+            .iter()
+            .filter_map(|(k, v)| Some((k.as_str(), v.last()?)))
+    }
+
     pub fn display(&self, py: Python<'_>) -> String {
         let context: BTreeMap<_, _> = self
             .context
@@ -160,11 +217,10 @@ impl Context {
         template: TemplateString<'_>,
     ) -> Result<(), PyRenderError> {
         let replace = index != 0;
-        if !replace {
-            let names_set = names.iter().cloned().collect();
-            self.names.push(names_set);
-        }
         if names.len() == 1 {
+            if !replace {
+                self.names.push(names.iter().cloned().collect());
+            }
             self._insert(names[0].clone(), values, replace);
         } else {
             let py = values.py();
@@ -191,6 +247,9 @@ impl Context {
                 }
             };
             if names.len() == values.len() {
+                if !replace {
+                    self.names.push(names.iter().cloned().collect());
+                }
                 for (name, value) in zip(names, values) {
                     self._insert(name.clone(), value, replace);
                 }
@@ -223,7 +282,147 @@ impl Context {
         }
     }
 
-    pub fn push_for_loop(&mut self, len: usize) {
+    /// Push a new scope of variables, like Django's `Context.push`.
+    pub fn push(&mut self, values: HashMap<String, Bound<'_, PyAny>>) {
+        self.names.push(values.keys().cloned().collect());
+        for (name, value) in values {
+            self.append(name, value);
+        }
+    }
+
+    /// The number of scopes pushed on top of the initial variables.
+    pub fn scope_count(&self) -> usize {
+        self.names.len()
+    }
+
+    /// The names of the variables set in `scope`.
+    pub fn scope_names(&self, scope: usize) -> Option<&HashSet<String>> {
+        self.names.get(scope)
+    }
+
+    /// The index into `key`'s values of its value in `scope`, or where that
+    /// value would go, below the values of the scopes pushed since.
+    fn scope_index(&self, scope: usize, key: &str) -> usize {
+        let len = self.context.get(key).map_or(0, Vec::len);
+        let above = self.names[scope + 1..]
+            .iter()
+            .filter(|names| names.contains(key))
+            .count();
+        len - above
+    }
+
+    /// The value of `key` in `scope`, even if a later scope shadows it.
+    pub fn scope_get(&self, scope: usize, key: &str) -> Option<&Py<PyAny>> {
+        if !self.names.get(scope)?.contains(key) {
+            return None;
+        }
+        let index = self.scope_index(scope, key) - 1;
+        self.context.get(key)?.get(index)
+    }
+
+    /// Set `key` in `scope`, like writing to one of Django's `Context.dicts`.
+    pub fn scope_set(&mut self, scope: usize, key: String, value: Py<PyAny>) {
+        let index = self.scope_index(scope, &key);
+        let new_in_scope = self.names[scope].insert(key.clone());
+        let values = self.context.entry(key).or_default();
+        match new_in_scope {
+            true => values.insert(index, value),
+            false => values[index - 1] = value,
+        }
+    }
+
+    /// Remove `key` from `scope`, returning its value.
+    pub fn scope_remove(&mut self, scope: usize, key: &str) -> Option<Py<PyAny>> {
+        let index = self
+            .scope_get(scope, key)
+            .map(|_| self.scope_index(scope, key))?;
+        self.names[scope].remove(key);
+        Some(self.context.get_mut(key)?.remove(index - 1))
+    }
+
+    /// Pop the innermost scope of variables, returning the values it held.
+    pub fn pop(&mut self) -> Option<HashMap<String, Py<PyAny>>> {
+        let names = self.names.pop()?;
+        Some(
+            names
+                .into_iter()
+                .filter_map(|name| {
+                    let value = self.context.get_mut(&name)?.pop()?;
+                    Some((name, value))
+                })
+                .collect(),
+        )
+    }
+
+    /// Create a context with the same settings but only the given variables,
+    /// like Django's `Context.new`.
+    pub fn new_child(&self, py: Python<'_>, values: HashMap<String, Py<PyAny>>) -> Self {
+        let mut context = builtins_context(py);
+        context.extend(values);
+        let mut context = Self::new(
+            context,
+            self.request.as_ref().map(|v| v.clone_ref(py)),
+            self.autoescape,
+        );
+        context.use_l10n = self.use_l10n;
+        context.use_tz = self.use_tz;
+        context.template_name.clone_from(&self.template_name);
+        context.template = self.template.as_ref().map(|v| v.clone_ref(py));
+        context.render_context = Arc::clone(&self.render_context);
+        context
+    }
+
+    /// Django's `RenderContext` for tags which keep state while rendering a template.
+    pub fn render_context<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let mut guard = self
+            .render_context
+            .lock_py_attached(py)
+            .expect("Mutex should not be poisoned");
+        if let Some(render_context) = &guard.context {
+            return Ok(render_context.bind(py).clone());
+        }
+        let render_context = RENDER_CONTEXT
+            .import(py, "django.template.context", "RenderContext")?
+            .call0()?;
+        for _ in 0..guard.depth {
+            render_context.call_method0(intern!(py, "push"))?;
+        }
+        guard.context = Some(render_context.clone().unbind());
+        Ok(render_context)
+    }
+
+    /// Start a new `RenderContext` scope for a nested template render.
+    pub fn push_render_state(&self, py: Python<'_>) -> PyResult<()> {
+        let mut guard = self
+            .render_context
+            .lock_py_attached(py)
+            .expect("Mutex should not be poisoned");
+        guard.depth += 1;
+        if let Some(render_context) = &guard.context {
+            render_context.bind(py).call_method0(intern!(py, "push"))?;
+        }
+        Ok(())
+    }
+
+    /// End the `RenderContext` scope of a nested template render.
+    pub fn pop_render_state(&self, py: Python<'_>) -> PyResult<()> {
+        let mut guard = self
+            .render_context
+            .lock_py_attached(py)
+            .expect("Mutex should not be poisoned");
+        let Some(depth) = guard.depth.checked_sub(1) else {
+            return Err(PyRuntimeError::new_err(
+                "Cannot pop a render state that was not pushed.",
+            ));
+        };
+        guard.depth = depth;
+        if let Some(render_context) = &guard.context {
+            render_context.bind(py).call_method0(intern!(py, "pop"))?;
+        }
+        Ok(())
+    }
+
+    pub fn push_for_loop(&mut self, len: Option<usize>) {
         self.loops.push(ForLoop { count: 0, len });
     }
 
@@ -278,7 +477,7 @@ impl Context {
         py: Python,
         engine: &Arc<Engine>,
         key: &IncludeTemplateKey,
-    ) -> Result<Arc<Template>, PyErr> {
+    ) -> Result<Template, PyErr> {
         match self.include_cache.entry(key.clone()) {
             Entry::Occupied(entry) => Ok(entry.get().clone()),
             Entry::Vacant(entry) => {
@@ -290,7 +489,7 @@ impl Context {
                         select_template(engine.clone(), py, templates.clone())?
                     }
                 };
-                Ok(entry.insert(Arc::new(include)).clone())
+                Ok(entry.insert(include).clone())
             }
         }
     }
@@ -364,10 +563,337 @@ impl PyContext {
             .context
             .lock_py_attached(py)
             .expect("Mutex should not be poisoned");
-        if let Some(last) = guard.names.last_mut() {
-            last.insert(key.clone());
+        // Shadow rather than overwrite a variable from an outer scope, so it is
+        // restored when the current scope is popped.
+        let new_in_scope = guard
+            .names
+            .last_mut()
+            .is_some_and(|last| last.insert(key.clone()));
+        if new_in_scope {
+            guard.append(key, value);
+        } else {
+            guard.insert(key, value);
         }
-        guard.insert(key, value);
+    }
+
+    #[getter]
+    fn autoescape(&self, py: Python<'_>) -> bool {
+        let guard = self
+            .context
+            .lock_py_attached(py)
+            .expect("Mutex should not be poisoned");
+        guard.autoescape
+    }
+
+    #[setter]
+    fn set_autoescape(&self, py: Python<'_>, autoescape: bool) {
+        let mut guard = self
+            .context
+            .lock_py_attached(py)
+            .expect("Mutex should not be poisoned");
+        guard.autoescape = autoescape;
+    }
+
+    #[getter]
+    fn use_l10n(&self, py: Python<'_>) -> Option<bool> {
+        let guard = self
+            .context
+            .lock_py_attached(py)
+            .expect("Mutex should not be poisoned");
+        guard.use_l10n
+    }
+
+    #[getter]
+    fn use_tz(&self, py: Python<'_>) -> Option<bool> {
+        let guard = self
+            .context
+            .lock_py_attached(py)
+            .expect("Mutex should not be poisoned");
+        guard.use_tz
+    }
+
+    #[getter]
+    fn template_name(&self, py: Python<'_>) -> Option<String> {
+        let guard = self
+            .context
+            .lock_py_attached(py)
+            .expect("Mutex should not be poisoned");
+        guard.template_name.clone()
+    }
+
+    #[getter]
+    fn template<'py>(&self, py: Python<'py>) -> Option<Bound<'py, Template>> {
+        let guard = self
+            .context
+            .lock_py_attached(py)
+            .expect("Mutex should not be poisoned");
+        guard
+            .template
+            .as_ref()
+            .map(|template| template.bind(py).clone())
+    }
+
+    #[getter]
+    fn render_context<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let guard = self
+            .context
+            .lock_py_attached(py)
+            .expect("Mutex should not be poisoned");
+        guard.render_context(py)
+    }
+
+    #[pyo3(signature = (*args, **kwargs))]
+    fn push<'py>(
+        &self,
+        py: Python<'py>,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<ContextDict> {
+        let values = py
+            .get_type::<PyDict>()
+            .call(args, kwargs)?
+            .cast_into::<PyDict>()?;
+        self.push_dict(py, values)
+    }
+
+    fn pop<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let mut guard = self
+            .context
+            .lock_py_attached(py)
+            .expect("Mutex should not be poisoned");
+        let Some(values) = guard.pop() else {
+            return Err(ContextPopException::new_err(()));
+        };
+        let dict = PyDict::new(py);
+        for (key, value) in values {
+            dict.set_item(key, value)?;
+        }
+        Ok(dict)
+    }
+
+    fn update<'py>(
+        &self,
+        py: Python<'py>,
+        other_dict: &Bound<'py, PyAny>,
+    ) -> PyResult<ContextDict> {
+        if !other_dict.hasattr(intern!(py, "__getitem__"))? {
+            return Err(PyTypeError::new_err(
+                "other_dict must be a mapping (dictionary-like) object.",
+            ));
+        }
+        let values = py
+            .get_type::<PyDict>()
+            .call1((other_dict,))?
+            .cast_into::<PyDict>()?;
+        self.push_dict(py, values)
+    }
+
+    fn flatten<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let guard = self
+            .context
+            .lock_py_attached(py)
+            .expect("Mutex should not be poisoned");
+        let dict = PyDict::new(py);
+        for (key, value) in guard.items() {
+            dict.set_item(key, value)?;
+        }
+        Ok(dict)
+    }
+
+    fn keys(&self, py: Python<'_>) -> Vec<String> {
+        let guard = self
+            .context
+            .lock_py_attached(py)
+            .expect("Mutex should not be poisoned");
+        guard.items().map(|(key, _)| key.to_string()).collect()
+    }
+
+    #[pyo3(name = "new", signature = (values=None))]
+    fn new_context(&self, py: Python<'_>, values: Option<&Bound<'_, PyAny>>) -> PyResult<Self> {
+        let values = match values {
+            Some(values) => values.extract()?,
+            None => HashMap::new(),
+        };
+        let guard = self
+            .context
+            .lock_py_attached(py)
+            .expect("Mutex should not be poisoned");
+        Ok(Self::new(guard.new_child(py, values)))
+    }
+}
+
+impl PyContext {
+    fn push_dict(&self, py: Python<'_>, values: Bound<'_, PyDict>) -> PyResult<ContextDict> {
+        let mut guard = self
+            .context
+            .lock_py_attached(py)
+            .expect("Mutex should not be poisoned");
+        guard.push(values.extract()?);
+        Ok(ContextDict {
+            context: self.context.clone(),
+            scope: guard.scope_count() - 1,
+            values: values.unbind(),
+        })
+    }
+}
+
+/// The scope returned by `PyContext.push` and `PyContext.update`.
+///
+/// Like Django's `ContextDict`, this is a mapping whose changes are seen by the
+/// context, and it can be used as a context manager to pop the scope again on
+/// exit. Once popped, it keeps the values it held.
+#[pyclass(mapping)]
+pub struct ContextDict {
+    context: Arc<Mutex<Context>>,
+    scope: usize,
+    /// The values held once the scope has been popped.
+    values: Py<PyDict>,
+}
+
+impl ContextDict {
+    /// Run `f` with the context if this scope is still in it, or else with
+    /// the values it held when it was popped.
+    fn with_scope<'py, T>(
+        &self,
+        py: Python<'py>,
+        in_context: impl FnOnce(&mut Context) -> T,
+        popped: impl FnOnce(&Bound<'py, PyDict>) -> T,
+    ) -> T {
+        let mut guard = self
+            .context
+            .lock_py_attached(py)
+            .expect("Mutex should not be poisoned");
+        if self.scope < guard.scope_count() {
+            in_context(&mut guard)
+        } else {
+            drop(guard);
+            popped(self.values.bind(py))
+        }
+    }
+
+    fn get_item<'py>(&self, py: Python<'py>, key: &str) -> PyResult<Option<Bound<'py, PyAny>>> {
+        self.with_scope(
+            py,
+            |context| {
+                Ok(context
+                    .scope_get(self.scope, key)
+                    .map(|value| value.bind(py).clone()))
+            },
+            |values| values.get_item(key),
+        )
+    }
+}
+
+#[pymethods]
+impl ContextDict {
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        py: Python<'_>,
+        _exc_type: &Bound<'_, PyAny>,
+        _exc_value: &Bound<'_, PyAny>,
+        _traceback: &Bound<'_, PyAny>,
+    ) -> PyResult<()> {
+        let mut guard = self
+            .context
+            .lock_py_attached(py)
+            .expect("Mutex should not be poisoned");
+        let is_top = self.scope + 1 == guard.scope_count();
+        let popped = guard.pop();
+        if let Some(popped) = popped.filter(|_| is_top) {
+            let values = self.values.bind(py);
+            values.clear();
+            for (key, value) in popped {
+                values.set_item(key, value)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn __getitem__<'py>(&self, py: Python<'py>, key: String) -> PyResult<Bound<'py, PyAny>> {
+        match self.get_item(py, &key)? {
+            Some(value) => Ok(value),
+            None => Err(PyKeyError::new_err(key)),
+        }
+    }
+
+    #[pyo3(signature = (key, otherwise=None))]
+    fn get<'py>(
+        &self,
+        py: Python<'py>,
+        key: String,
+        otherwise: Option<Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        Ok(match self.get_item(py, &key)? {
+            Some(value) => value,
+            None => otherwise.unwrap_or_else(|| py.None().into_bound(py)),
+        })
+    }
+
+    fn __setitem__<'py>(
+        &self,
+        py: Python<'py>,
+        key: String,
+        value: Bound<'py, PyAny>,
+    ) -> PyResult<()> {
+        self.with_scope(
+            py,
+            |context| {
+                context.scope_set(self.scope, key.clone(), value.clone().unbind());
+                Ok(())
+            },
+            |values| values.set_item(&key, &value),
+        )
+    }
+
+    fn __delitem__(&self, py: Python<'_>, key: String) -> PyResult<()> {
+        let removed = self.with_scope(
+            py,
+            |context| Ok(context.scope_remove(self.scope, &key).is_some()),
+            |values| {
+                let contained = values.contains(&key)?;
+                if contained {
+                    values.del_item(&key)?;
+                }
+                Ok::<_, PyErr>(contained)
+            },
+        )?;
+        match removed {
+            true => Ok(()),
+            false => Err(PyKeyError::new_err(key)),
+        }
+    }
+
+    fn __contains__(&self, py: Python<'_>, key: String) -> PyResult<bool> {
+        Ok(self.get_item(py, &key)?.is_some())
+    }
+
+    fn __len__(&self, py: Python<'_>) -> usize {
+        self.with_scope(
+            py,
+            |context| context.scope_names(self.scope).map_or(0, HashSet::len),
+            |values| values.len(),
+        )
+    }
+
+    fn keys(&self, py: Python<'_>) -> PyResult<Vec<String>> {
+        self.with_scope(
+            py,
+            |context| {
+                Ok(context
+                    .scope_names(self.scope)
+                    .map(|names| names.iter().cloned().collect())
+                    .unwrap_or_default())
+            },
+            |values| values.keys().extract(),
+        )
+    }
+
+    fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyIterator>> {
+        PyList::new(py, self.keys(py)?)?.try_iter()
     }
 }
 
@@ -479,6 +1005,28 @@ impl<'t, 'py> Content<'t, 'py> {
         Ok(render_value_in_context(output, context))
     }
 
+    /// Render into `output`, escaping large text with the interpreter
+    /// detached if `context.detach` is set.
+    pub fn render_to(self, py: Python<'_>, context: &Context, output: &mut String) -> PyResult<()> {
+        let content = match self {
+            Self::Py(content) => resolve_python(content, context)?,
+            Self::String(content) => content,
+            content => {
+                output.push_str(&content.render(context)?);
+                return Ok(());
+            }
+        };
+        match content {
+            ContentString::HtmlUnsafe(content)
+                if context.autoescape && context.detach && content.len() >= DETACH_ESCAPE_LEN =>
+            {
+                py.detach(|| encode_quoted_attribute_to_string(&content, output));
+            }
+            content => output.push_str(&render_value_in_context(content, context)),
+        }
+        Ok(())
+    }
+
     pub fn resolve_string(self, context: &Context) -> PyResult<ContentString<'t>> {
         Ok(match self {
             Self::String(content) => content,
@@ -529,7 +1077,7 @@ impl<'t, 'py> Content<'t, 'py> {
     pub fn resolve_usize(self, argument_at: At) -> Result<usize, PyRenderError> {
         match self {
             Self::Int(n) => resolve_bigint(n, argument_at),
-            Self::String(s) => match s.as_raw().parse::<BigInt>() {
+            Self::String(s) => match s.as_raw().trim().parse::<BigInt>() {
                 Ok(n) => resolve_bigint(n, argument_at),
                 Err(_) => Err(RenderError::InvalidArgumentInteger {
                     argument: format!("'{}'", s.as_raw()),
@@ -547,22 +1095,24 @@ impl<'t, 'py> Content<'t, 'py> {
             },
             Self::Py(obj) => match obj.extract::<BigInt>() {
                 Ok(n) => resolve_bigint(n, argument_at),
-                Err(_) => {
-                    let argument = obj.to_string();
-                    let argument_at = argument_at.into();
-                    match obj.extract::<f64>() {
-                        Ok(_) => Err(RenderError::InvalidArgumentFloat {
-                            argument,
-                            argument_at,
-                        }
-                        .into()),
+                Err(_) if obj.is_instance_of::<PyString>() => {
+                    match obj.str()?.to_cow()?.trim().parse::<BigInt>() {
+                        Ok(n) => resolve_bigint(n, argument_at),
                         Err(_) => Err(RenderError::InvalidArgumentInteger {
-                            argument,
-                            argument_at,
+                            argument: obj.to_string(),
+                            argument_at: argument_at.into(),
                         }
                         .into()),
                     }
                 }
+                Err(_) => match obj.extract::<f64>() {
+                    Ok(f) => Self::Float(f).resolve_usize(argument_at),
+                    Err(_) => Err(RenderError::InvalidArgumentInteger {
+                        argument: obj.to_string(),
+                        argument_at: argument_at.into(),
+                    }
+                    .into()),
+                },
             },
             Self::Bool(true) => Ok(1),
             Self::Bool(false) => Ok(0),
@@ -0,0 +1,262 @@
+//! URL quoting matching Python's `urllib.parse` and Django's `smart_urlquote`.
+
+use std::borrow::Cow;
+use std::net::Ipv6Addr;
+
+/// Characters `urllib.parse.quote` never quotes.
+const ALWAYS_SAFE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789_.-~";
+
+/// `RFC3986_SUBDELIMS + RFC3986_GENDELIMS + "~"` from `django.utils.html`.
+const SMART_URLQUOTE_SAFE: &str = "!$&'()*+,;=:/?#[]@~";
+
+/// Percent-encode `text` like `urllib.parse.quote(text, safe)`.
+///
+/// As in Python, non-ASCII characters in `safe` are ignored.
+pub fn quote(text: &str, safe: &str) -> String {
+    let mut quoted = String::with_capacity(text.len());
+    for &byte in text.as_bytes() {
+        if ALWAYS_SAFE.contains(&byte) || (byte.is_ascii() && safe.as_bytes().contains(&byte)) {
+            quoted.push(char::from(byte));
+        } else {
+            quoted.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    quoted
+}
+
+/// Like `urllib.parse.quote_plus(text, safe="")`.
+fn quote_plus(text: &str) -> String {
+    match text.contains(' ') {
+        true => quote(text, " ").replace(' ', "+"),
+        false => quote(text, ""),
+    }
+}
+
+/// Percent-decode one run of ASCII characters.
+fn unquote_ascii(text: &str, unquoted: &mut String) {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%'
+            && let Some(hex) = text.get(index + 1..index + 3)
+            && let Ok(byte) = u8::from_str_radix(hex, 16)
+            && hex.bytes().all(|b| b.is_ascii_hexdigit())
+        {
+            decoded.push(byte);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    unquoted.push_str(&String::from_utf8_lossy(&decoded));
+}
+
+/// Replace `%xx` escapes like `urllib.parse.unquote`, decoding as UTF-8 and
+/// replacing invalid sequences.
+pub fn unquote(text: &str) -> Cow<'_, str> {
+    if !text.contains('%') {
+        return Cow::Borrowed(text);
+    }
+    let mut unquoted = String::with_capacity(text.len());
+    let mut rest = text;
+    while !rest.is_empty() {
+        let ascii_len = rest.find(|c: char| !c.is_ascii()).unwrap_or(rest.len());
+        unquote_ascii(&rest[..ascii_len], &mut unquoted);
+        rest = &rest[ascii_len..];
+        let other_len = rest.find(|c: char| c.is_ascii()).unwrap_or(rest.len());
+        unquoted.push_str(&rest[..other_len]);
+        rest = &rest[other_len..];
+    }
+    Cow::Owned(unquoted)
+}
+
+/// Like `urllib.parse.parse_qsl(query, keep_blank_values=True)`.
+fn parse_qsl(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (
+                unquote(&name.replace('+', " ")).into_owned(),
+                unquote(&value.replace('+', " ")).into_owned(),
+            )
+        })
+        .collect()
+}
+
+fn unquote_quote(segment: &str) -> String {
+    quote(&unquote(segment), SMART_URLQUOTE_SAFE)
+}
+
+/// The components of a URL, as split by `urllib.parse.urlsplit`.
+struct SplitUrl<'a> {
+    scheme: Cow<'a, str>,
+    netloc: &'a str,
+    path: &'a str,
+    query: &'a str,
+    fragment: &'a str,
+}
+
+/// Validate a bracketed host like `urllib.parse._check_bracketed_netloc`.
+fn is_valid_bracketed_netloc(netloc: &str) -> bool {
+    let host_and_port = netloc.rsplit_once('@').map_or(netloc, |(_, host)| host);
+    let (before, bracketed) = host_and_port.split_once('[').unwrap_or((host_and_port, ""));
+    let host = match host_and_port.contains('[') {
+        true => {
+            if !before.is_empty() {
+                return false;
+            }
+            let (host, port) = bracketed.split_once(']').unwrap_or((bracketed, ""));
+            if !port.is_empty() && !port.starts_with(':') {
+                return false;
+            }
+            host
+        }
+        false => host_and_port
+            .split_once(':')
+            .map_or(host_and_port, |(host, _)| host),
+    };
+    match host.strip_prefix('v') {
+        Some(future) => future.split_once('.').is_some_and(|(version, address)| {
+            !version.is_empty()
+                && version.bytes().all(|b| b.is_ascii_hexdigit())
+                && !address.is_empty()
+        }),
+        None => match host.split_once('%') {
+            Some((address, scope)) => {
+                !scope.is_empty() && !scope.contains('%') && address.parse::<Ipv6Addr>().is_ok()
+            }
+            None => host.parse::<Ipv6Addr>().is_ok(),
+        },
+    }
+}
+
+/// Split a URL like `urllib.parse.urlsplit`, for URLs starting with a scheme
+/// and `//`. Returns `None` where Python raises a `ValueError`.
+fn urlsplit(url: &str) -> Option<SplitUrl<'_>> {
+    let (scheme, rest) = url.split_once(':')?;
+    let rest = rest.strip_prefix("//")?;
+    let netloc_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let (netloc, rest) = rest.split_at(netloc_end);
+    if netloc.contains('[') != netloc.contains(']') {
+        return None;
+    }
+    if netloc.contains('[') && !is_valid_bracketed_netloc(netloc) {
+        return None;
+    }
+    let (rest, fragment) = rest.split_once('#').unwrap_or((rest, ""));
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    Some(SplitUrl {
+        scheme: match scheme.bytes().any(|b| b.is_ascii_uppercase()) {
+            true => Cow::Owned(scheme.to_ascii_lowercase()),
+            false => Cow::Borrowed(scheme),
+        },
+        netloc,
+        path,
+        query,
+        fragment,
+    })
+}
+
+/// Whether Python's `idna` codec accepts an ASCII domain.
+fn is_valid_ascii_idna(netloc: &str) -> bool {
+    let labels: Vec<&str> = netloc.split('.').collect();
+    let (last, labels) = labels.split_last().expect("split always yields a label");
+    netloc.is_empty()
+        || (labels.iter().all(|label| (1..64).contains(&label.len())) && last.len() < 64)
+}
+
+/// Quote a URL if it isn't already quoted, like Django's `smart_urlquote`.
+///
+/// The URL must start with `http://` or `https://` (in any case). Returns
+/// `None` if the network location is not ASCII and so needs IDNA encoding.
+pub fn smart_urlquote(url: &str) -> Option<String> {
+    let Some(split) = urlsplit(url) else {
+        return Some(unquote_quote(url));
+    };
+    if !split.netloc.is_ascii() {
+        return None;
+    }
+    if !is_valid_ascii_idna(split.netloc) {
+        return Some(unquote_quote(url));
+    }
+
+    let mut quoted = format!("{}://{}", split.scheme, split.netloc);
+    quoted.push_str(&unquote_quote(split.path));
+    if !split.query.is_empty() {
+        let query = parse_qsl(split.query)
+            .iter()
+            .map(|(name, value)| {
+                format!(
+                    "{}={}",
+                    quote_plus(&unquote(name)),
+                    quote_plus(&unquote(value))
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("&");
+        if !query.is_empty() {
+            quoted.push('?');
+            quoted.push_str(&query);
+        }
+    }
+    let fragment = unquote_quote(split.fragment);
+    if !fragment.is_empty() {
+        quoted.push('#');
+        quoted.push_str(&fragment);
+    }
+    Some(quoted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("a b/c?d=é", "/"), "a%20b/c%3Fd%3D%C3%A9");
+        assert_eq!(quote("a/b~c", ""), "a%2Fb~c");
+        assert_eq!(quote("a&b", "&é"), "a&b");
+    }
+
+    #[test]
+    fn test_unquote() {
+        assert_eq!(unquote("a%20b%zz%"), "a b%zz%");
+        assert_eq!(unquote("%C3%A9%C3"), "é\u{fffd}");
+        assert_eq!(unquote("é%20"), "é ");
+        assert_eq!(unquote("plain"), "plain");
+    }
+
+    #[test]
+    fn test_smart_urlquote() {
+        let cases = [
+            ("http://example.com", "http://example.com"),
+            ("HTTP://Example.com/a b", "http://Example.com/a%20b"),
+            ("http://example.com/%20", "http://example.com/%20"),
+            (
+                "http://example.com/?q=a+b&x=%20y&z",
+                "http://example.com/?q=a+b&x=+y&z=",
+            ),
+            ("http://example.com/?", "http://example.com/"),
+            (
+                "http://example.com/#frag ment",
+                "http://example.com/#frag%20ment",
+            ),
+            ("http://[::1]:8000/", "http://[::1]:8000/"),
+            ("http://[::1/", "http://[::1/"),
+            ("http://[1.2.3.4]/", "http://[1.2.3.4]/"),
+            (
+                "http://[::1%25eth0]/a%20b c",
+                "http://[::1%25eth0]/a%20b%20c",
+            ),
+            ("http://example..com/", "http://example..com/"),
+        ];
+        for (url, expected) in cases {
+            assert_eq!(smart_urlquote(url).as_deref(), Some(expected), "{url}");
+        }
+        assert_eq!(smart_urlquote("http://exämple.com"), None);
+    }
+}
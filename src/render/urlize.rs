@@ -0,0 +1,391 @@
+//! Convert URLs and email addresses in text into links, matching
+//! `django.utils.html.Urlizer`.
+//!
+//! See <https://github.com/django/django/blob/stable/5.2.x/django/utils/html.py>
+
+use std::borrow::Cow;
+
+use html_escape::encode_quoted_attribute;
+use pyo3::prelude::*;
+use pyo3::sync::PyOnceLock;
+
+use crate::render::html::unescape;
+use crate::render::url::{quote, smart_urlquote};
+
+static SMART_URLQUOTE: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+
+const MAX_URL_LENGTH: usize = 2048;
+const TRAILING_PUNCTUATION: [char; 5] = ['.', ',', ':', ';', '!'];
+const TRAILING_PUNCTUATION_NO_SEMICOLON: [char; 4] = ['.', ',', ':', '!'];
+const WRAPPING_PUNCTUATION: [(char, char); 2] = [('(', ')'), ('[', ']')];
+const TLDS: [&str; 7] = ["com", "edu", "gov", "int", "mil", "net", "org"];
+
+/// Options for converting text to links.
+pub struct Urlizer {
+    /// Truncate link text longer than this to `limit - 1` characters and an ellipsis.
+    pub trim_url_limit: Option<isize>,
+    /// Give links a `rel="nofollow"` attribute.
+    pub nofollow: bool,
+    /// Escape the link text and URLs.
+    pub autoescape: bool,
+    /// Whether the text was marked as safe.
+    pub safe_input: bool,
+}
+
+const fn is_word_separator(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '<' | '>' | '"' | '\'')
+}
+
+/// Split on runs of separators, keeping the separators, like
+/// `re.split(r"""([\s<>"']+)""", text)`.
+fn split_words(text: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = 0;
+    let mut in_separator = false;
+    for (index, c) in text.char_indices() {
+        if is_word_separator(c) != in_separator {
+            if in_separator || index > 0 {
+                words.push(&text[start..index]);
+            } else {
+                words.push("");
+            }
+            start = index;
+            in_separator = !in_separator;
+        }
+    }
+    words.push(&text[start..]);
+    if in_separator {
+        words.push("");
+    }
+    words
+}
+
+/// Characters matched by `[a-z¡-￿0-9]` with `re.IGNORECASE`.
+fn is_domain_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || ('\u{a1}'..='\u{ffff}').contains(&c)
+}
+
+fn is_domain_label(label: &str) -> bool {
+    (1..=63).contains(&label.chars().count())
+        && !label.starts_with('-')
+        && !label.ends_with('-')
+        && label.chars().all(|c| c == '-' || is_domain_char(c))
+}
+
+/// Like `Urlizer.simple_url_re`: `^https?://\[?\w`, ignoring case.
+fn is_simple_url(middle: &str) -> bool {
+    let Some(rest) = ["http://", "https://"].iter().find_map(|scheme| {
+        middle
+            .get(..scheme.len())
+            .filter(|prefix| prefix.eq_ignore_ascii_case(scheme))
+            .map(|_| &middle[scheme.len()..])
+    }) else {
+        return false;
+    };
+    let rest = rest.strip_prefix('[').unwrap_or(rest);
+    rest.chars()
+        .next()
+        .is_some_and(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Like `Urlizer.simple_url_2_re`, matching `www.` links and domains ending
+/// in one of the original seven gTLDs.
+///
+/// Every label must match `DomainNameValidator.hostname_re` or `domain_re`.
+fn is_simple_url_2(middle: &str) -> bool {
+    let starts_with = |prefix: &str| {
+        middle
+            .get(..prefix.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+    };
+    if starts_with("www.") {
+        return true;
+    }
+    if starts_with("http") {
+        return false;
+    }
+    let host = middle.split_once('/').map_or(middle, |(host, _)| host);
+    let labels: Vec<&str> = host.split('.').collect();
+    let Some((tld, labels)) = labels.split_last() else {
+        return false;
+    };
+    TLDS.iter().any(|t| t.eq_ignore_ascii_case(tld))
+        && !labels.is_empty()
+        && labels.iter().all(|label| is_domain_label(label))
+}
+
+/// Like `Urlizer.is_email_simple`.
+fn is_email_simple(value: &str) -> bool {
+    if value.starts_with('@') || value.ends_with('@') {
+        return false;
+    }
+    let mut parts = value.split('@');
+    let (Some(_), Some(domain), None) = (parts.next(), parts.next(), parts.next()) else {
+        return false;
+    };
+    domain.chars().count() <= 63 && domain.contains('.') && !domain.starts_with('.')
+}
+
+/// The byte offset of the last `count` characters of `text`.
+fn split_last_chars(text: &str, count: usize) -> usize {
+    match count {
+        0 => text.len(),
+        count => text
+            .char_indices()
+            .rev()
+            .nth(count - 1)
+            .map_or(0, |(index, _)| index),
+    }
+}
+
+/// Trim trailing and wrapping punctuation from `word`, like
+/// `Urlizer.trim_punctuation`. Returns the lead, middle and trail.
+fn trim_punctuation(word: &str) -> (&str, &str, String) {
+    let mut middle = word.trim_start_matches(|c| WRAPPING_PUNCTUATION.iter().any(|(o, _)| *o == c));
+    let lead = &word[..word.len() - middle.len()];
+    let mut trail = Vec::new();
+    let mut counts = WRAPPING_PUNCTUATION.map(|(opening, closing)| {
+        (
+            middle.matches(opening).count(),
+            middle.matches(closing).count(),
+        )
+    });
+    let mut trimmed_something = true;
+    while trimmed_something && !middle.is_empty() {
+        trimmed_something = false;
+        for ((_, closing), (opening_count, closing_count)) in
+            WRAPPING_PUNCTUATION.iter().zip(counts.iter_mut())
+        {
+            if *opening_count < *closing_count && middle.ends_with(*closing) {
+                let strip = *closing_count - *opening_count;
+                let split = split_last_chars(middle, strip);
+                trail.push(&middle[split..]);
+                middle = &middle[..split];
+                trimmed_something = true;
+                *closing_count -= strip;
+            }
+        }
+
+        let amp = middle.rfind('&');
+        let rstripped = match amp {
+            None => middle.trim_end_matches(TRAILING_PUNCTUATION),
+            Some(_) => middle.trim_end_matches(TRAILING_PUNCTUATION_NO_SEMICOLON),
+        };
+        if rstripped.len() != middle.len() {
+            trail.push(&middle[rstripped.len()..]);
+            middle = rstripped;
+            trimmed_something = true;
+        }
+
+        if middle.ends_with(';') {
+            // Only strip if not part of an HTML entity.
+            let potential_entity = &middle[amp.unwrap_or(middle.len() - 1)..];
+            let escaped = unescape(potential_entity);
+            if escaped == potential_entity || escaped.ends_with(';') {
+                let rstripped = middle.trim_end_matches(TRAILING_PUNCTUATION);
+                let trail_start = rstripped.len();
+                let trailing_semicolons = middle.len() - middle.trim_end_matches(';').len();
+                if amp.is_some() && trailing_semicolons > 1 {
+                    // Leave up to most recent semicolon as might be an entity.
+                    let recent_semicolon = middle[trail_start..]
+                        .find(';')
+                        .expect("middle ends with a semicolon");
+                    let middle_semicolon_index = recent_semicolon + trail_start + 1;
+                    trail.push(&middle[middle_semicolon_index..]);
+                    middle = &middle[..middle_semicolon_index];
+                } else {
+                    trail.push(&middle[trail_start..]);
+                    middle = rstripped;
+                }
+                trimmed_something = true;
+            }
+        }
+    }
+    trail.reverse();
+    (lead, middle, trail.concat())
+}
+
+fn escape(text: &str) -> Cow<'_, str> {
+    encode_quoted_attribute(text)
+}
+
+impl Urlizer {
+    /// Convert any URLs in `text` into clickable links.
+    pub fn urlize(&self, py: Python<'_>, text: &str) -> PyResult<String> {
+        let mut urlized = String::with_capacity(text.len());
+        for word in split_words(text) {
+            urlized.push_str(&self.handle_word(py, word)?);
+        }
+        Ok(urlized)
+    }
+
+    fn handle_word<'a>(&self, py: Python<'_>, word: &'a str) -> PyResult<Cow<'a, str>> {
+        if word.contains(['.', '@', ':']) {
+            let (lead, middle, trail) = trim_punctuation(word);
+            let mut nofollow = self.nofollow;
+            let short = middle.chars().count() <= MAX_URL_LENGTH;
+            let url = if short && is_simple_url(middle) {
+                Some(self.smart_urlquote(py, &unescape(middle))?)
+            } else if short && is_simple_url_2(middle) {
+                Some(self.smart_urlquote(py, &format!("http://{}", unescape(middle)))?)
+            } else if !middle.contains(':') && is_email_simple(middle) {
+                let (local, domain) = middle.rsplit_once('@').expect("emails contain an @");
+                // Encode per RFC 6068 Section 2 (items 1, 4, 5). Defer any IDN
+                // to the email client.
+                nofollow = false;
+                Some(format!("mailto:{}@{}", quote(local, ""), quote(domain, "")))
+            } else {
+                None
+            };
+            if let Some(url) = url {
+                let trimmed = self.trim_url(middle);
+                let (lead, trimmed, trail) = match self.autoescape && !self.safe_input {
+                    true => (escape(lead), escape(&trimmed).into_owned(), escape(&trail)),
+                    false => (
+                        Cow::Borrowed(lead),
+                        trimmed.into_owned(),
+                        Cow::Borrowed(&*trail),
+                    ),
+                };
+                let attrs = if nofollow { " rel=\"nofollow\"" } else { "" };
+                return Ok(Cow::Owned(format!(
+                    "{lead}<a href=\"{}\"{attrs}>{trimmed}</a>{trail}",
+                    escape(&url)
+                )));
+            }
+        }
+        Ok(match !self.safe_input && self.autoescape {
+            true => escape(word),
+            false => Cow::Borrowed(word),
+        })
+    }
+
+    fn smart_urlquote(&self, py: Python<'_>, url: &str) -> PyResult<String> {
+        match smart_urlquote(url) {
+            Some(url) => Ok(url),
+            None => SMART_URLQUOTE
+                .import(py, "django.utils.html", "smart_urlquote")?
+                .call1((url,))?
+                .extract(),
+        }
+    }
+
+    fn trim_url<'a>(&self, url: &'a str) -> Cow<'a, str> {
+        match self.trim_url_limit {
+            Some(limit) if url.chars().count() > usize::try_from(limit).unwrap_or(0) => {
+                let keep = usize::try_from(limit.saturating_sub(1)).unwrap_or(0);
+                let end = url.char_indices().nth(keep).map_or(url.len(), |(i, _)| i);
+                Cow::Owned(format!("{}…", &url[..end]))
+            }
+            _ => Cow::Borrowed(url),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn urlize(text: &str) -> String {
+        Python::initialize();
+        Python::attach(|py| {
+            Urlizer {
+                trim_url_limit: None,
+                nofollow: true,
+                autoescape: true,
+                safe_input: false,
+            }
+            .urlize(py, text)
+            .unwrap()
+        })
+    }
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(split_words("a b"), vec!["a", " ", "b"]);
+        assert_eq!(
+            split_words(" a<>b "),
+            vec!["", " ", "a", "<>", "b", " ", ""]
+        );
+        assert_eq!(split_words(""), vec![""]);
+    }
+
+    #[test]
+    fn test_trim_punctuation() {
+        let cases = [
+            ("(example.com)", ("(", "example.com", ")")),
+            ("example.com).", ("", "example.com", ").")),
+            ("[(example.com)]", ("[(", "example.com", ")]")),
+            ("example.com/a_(b)", ("", "example.com/a_(b)", "")),
+            ("example.com&amp;;", ("", "example.com&amp;", ";")),
+            ("example.com&lt;", ("", "example.com&lt;", "")),
+            ("example.com;;", ("", "example.com", ";;")),
+            ("a.b!!", ("", "a.b", "!!")),
+        ];
+        for (word, (lead, middle, trail)) in cases {
+            assert_eq!(
+                trim_punctuation(word),
+                (lead, middle, trail.to_string()),
+                "{word}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_urlize() {
+        let cases = [
+            (
+                "Check out www.djangoproject.com",
+                "Check out <a href=\"http://www.djangoproject.com\" rel=\"nofollow\">www.djangoproject.com</a>",
+            ),
+            (
+                "(https://example.com/path?q=a b).",
+                "(<a href=\"https://example.com/path?q=a\" rel=\"nofollow\">https://example.com/path?q=a</a> b).",
+            ),
+            (
+                "djangoproject.org",
+                "<a href=\"http://djangoproject.org\" rel=\"nofollow\">djangoproject.org</a>",
+            ),
+            (
+                "info@djangoproject.org",
+                "<a href=\"mailto:info@djangoproject.org\">info@djangoproject.org</a>",
+            ),
+            ("a<b>c", "a&lt;b&gt;c"),
+            ("example.xyz", "example.xyz"),
+            ("http://", "http://"),
+            (
+                "http://example.com?a=1&b=2",
+                "<a href=\"http://example.com?a=1&amp;b=2\" rel=\"nofollow\">http://example.com?a=1&amp;b=2</a>",
+            ),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(urlize(text), expected, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_simple_url_2() {
+        assert!(is_simple_url_2("example.com"));
+        assert!(is_simple_url_2("sub.example.COM/path"));
+        assert!(is_simple_url_2("WWW.anything"));
+        assert!(!is_simple_url_2("-example.com"));
+        assert!(!is_simple_url_2("http.com.example.com"));
+        assert!(!is_simple_url_2("example..com"));
+        assert!(!is_simple_url_2("a_b.com"));
+        assert!(!is_simple_url_2(".com"));
+    }
+
+    #[test]
+    fn test_trim_url() {
+        let urlizer = |limit| Urlizer {
+            trim_url_limit: Some(limit),
+            nofollow: false,
+            autoescape: false,
+            safe_input: false,
+        };
+        assert_eq!(urlizer(5).trim_url("abcdefgh"), "abcd…");
+        assert_eq!(urlizer(8).trim_url("abcdefgh"), "abcdefgh");
+        assert_eq!(urlizer(0).trim_url("abc"), "…");
+        assert_eq!(urlizer(-3).trim_url("abc"), "…");
+    }
+}
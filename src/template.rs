@@ -1,12 +1,15 @@
 use pyo3::prelude::*;
 
-#[pymodule]
+#[pymodule(gil_used = false)]
 pub mod django_rusty_templates {
     use std::borrow::Cow;
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
+    use std::hash::{DefaultHasher, Hash, Hasher};
     use std::path::PathBuf;
     use std::sync::{Arc, Mutex};
 
+    use cached::Cached;
+    use cached::stores::LruCache;
     use encoding_rs::Encoding;
     use pyo3::exceptions::{
         PyAttributeError, PyImportError, PyOverflowError, PyTypeError, PyValueError,
@@ -15,15 +18,18 @@ pub mod django_rusty_templates {
     use pyo3::intern;
     use pyo3::prelude::*;
     use pyo3::sync::{MutexExt, PyOnceLock};
-    use pyo3::types::{PyBool, PyDict, PyIterator, PyList, PyString, PyTuple};
+    use pyo3::types::{PyBool, PyDict, PyIterator, PyList, PyString, PyTuple, PyType};
 
-    use crate::error::RenderError;
+    use crate::compiled;
+    use crate::error::{PyRenderError, RenderError, culprit_at, diagnostic_at, get_exception_info};
     use crate::loaders::{AppDirsLoader, CachedLoader, FileSystemLoader, Loader, LocMemLoader};
+    use crate::optimise::optimise;
     use crate::parse::{Parser, TokenTree};
+    use crate::render::Render;
+    use crate::render::stream::Stream;
     use crate::render::types::{Context, PyContext};
-    use crate::render::{Render, RenderResult};
     use crate::utils::PyResultMethods;
-    use dtl_lexer::types::TemplateString;
+    use dtl_lexer::types::{At, TemplateString};
 
     import_exception!(django.core.exceptions, ImproperlyConfigured);
     import_exception!(django.template.base, VariableDoesNotExist);
@@ -33,6 +39,7 @@ pub mod django_rusty_templates {
     import_exception!(django.urls, NoReverseMatch);
 
     static IMPORT_STRING: PyOnceLock<Py<PyAny>> = PyOnceLock::new();
+    static DJANGO_CONTEXT: PyOnceLock<Py<PyType>> = PyOnceLock::new();
 
     trait WithSourceCode {
         fn with_source_code(
@@ -90,10 +97,18 @@ pub mod django_rusty_templates {
         template_name: String,
     }
 
-    fn import_libraries(libraries: Bound<'_, PyAny>) -> PyResult<HashMap<String, Py<PyAny>>> {
+    /// The template library of `django.contrib.humanize`, which is implemented natively.
+    const HUMANIZE_LIBRARY: &str = "django.contrib.humanize.templatetags.humanize";
+
+    type Libraries = HashMap<String, Py<PyAny>>;
+
+    /// Import the template libraries, also returning the names which load
+    /// `django.contrib.humanize`.
+    fn import_libraries(libraries: Bound<'_, PyAny>) -> PyResult<(Libraries, HashSet<String>)> {
         let py = libraries.py();
         let libraries: HashMap<String, String> = libraries.extract()?;
         let mut libs = HashMap::with_capacity(libraries.len());
+        let mut humanize = HashSet::new();
         for (name, path) in libraries {
             let library = match py.import(&path).ok_or_isinstance_of::<PyImportError>(py)? {
                 Ok(library) => library,
@@ -113,9 +128,12 @@ pub mod django_rusty_templates {
                 let error = format!("Module '{path}' does not have a variable named 'register'");
                 return Err(InvalidTemplateLibrary::new_err(error));
             };
+            if path == HUMANIZE_LIBRARY {
+                humanize.insert(name.clone());
+            }
             libs.insert(name, library.unbind());
         }
-        Ok(libs)
+        Ok((libs, humanize))
     }
 
     /// Helper function to unpack a loader tuple configuration.
@@ -231,7 +249,7 @@ pub mod django_rusty_templates {
         app_dirs: bool,
         context_processors: Vec<String>,
         debug: bool,
-        template_loaders: Mutex<Vec<Loader>>,
+        template_loaders: Vec<Loader>,
         string_if_invalid: String,
         #[allow(dead_code)]
         encoding: &'static Encoding,
@@ -239,6 +257,13 @@ pub mod django_rusty_templates {
         pub autoescape: bool,
         loaded_context_processors: Vec<Py<PyAny>>,
         pub libraries: HashMap<String, Py<PyAny>>,
+        pub humanize_libraries: HashSet<String>,
+        /// Detach from the interpreter during long stretches of pure Rust
+        /// work, so other Python threads can run.
+        pub detach: bool,
+        /// Where compiled templates are loaded from, and written to by
+        /// `Engine.precompile`.
+        pub template_cache_dir: Option<PathBuf>,
     }
 
     impl Engine {
@@ -249,13 +274,16 @@ pub mod django_rusty_templates {
                 app_dirs: false,
                 context_processors: Vec::new(),
                 debug: false,
-                template_loaders: Mutex::new(Vec::new()),
+                template_loaders: Vec::new(),
                 string_if_invalid: String::new(),
                 encoding: encoding_rs::UTF_8,
                 builtins: Vec::new(),
                 autoescape: false,
                 loaded_context_processors: Vec::new(),
                 libraries: HashMap::new(),
+                humanize_libraries: HashSet::new(),
+                detach: false,
+                template_cache_dir: None,
             }
         }
     }
@@ -266,18 +294,13 @@ pub mod django_rusty_templates {
         template_name: Cow<str>,
     ) -> PyResult<Template> {
         let mut tried = Vec::new();
-        let mut loaders = engine
-            .template_loaders
-            .lock_py_attached(py)
-            .expect("Mutex should not be poisoned");
-        for loader in loaders.iter_mut() {
+        for loader in &engine.template_loaders {
             match loader.get_template(py, &template_name, engine.clone()) {
                 Ok(template) => return template,
                 Err(mut e) if !e.tried.is_empty() => tried.append(&mut e.tried),
                 Err(_) => {}
             }
         }
-        drop(loaders);
         Err(TemplateDoesNotExist::new_err((
             template_name.into_owned(),
             tried
@@ -308,16 +331,110 @@ pub mod django_rusty_templates {
         Err(TemplateDoesNotExist::new_err(not_found.join(", ")))
     }
 
+    /// Templates parsed by `Engine.from_string`, keyed by a hash of their
+    /// source. The entries are dropped when the loaded libraries change.
+    #[derive(Debug)]
+    struct ParseCache {
+        libraries: u64,
+        templates: LruCache<u64, Template>,
+        hits: u64,
+        misses: u64,
+    }
+
+    impl ParseCache {
+        fn new(size: usize) -> Option<Self> {
+            let templates = LruCache::builder().max_size(size).build().ok()?;
+            Some(Self {
+                libraries: 0,
+                templates,
+                hits: 0,
+                misses: 0,
+            })
+        }
+
+        fn get(&mut self, libraries: u64, key: u64, template: &str) -> Option<Template> {
+            if libraries != self.libraries {
+                self.templates.cache_clear();
+                self.libraries = libraries;
+            }
+            // Check the source too, in case two sources share a hash.
+            match self.templates.cache_get(&key) {
+                Some(cached) if cached.template == template => {
+                    self.hits += 1;
+                    Some(cached.clone())
+                }
+                _ => {
+                    self.misses += 1;
+                    None
+                }
+            }
+        }
+
+        fn insert(&mut self, libraries: u64, key: u64, template: Template) {
+            // Another thread may have seen the libraries change while this parsed.
+            if libraries == self.libraries {
+                self.templates.cache_set(key, template);
+            }
+        }
+
+        /// Drop every template and reset the statistics, like `functools.lru_cache`'s
+        /// `cache_clear`.
+        fn clear(&mut self) {
+            self.templates.cache_clear();
+            self.hits = 0;
+            self.misses = 0;
+        }
+    }
+
+    /// A fingerprint of the template libraries and the tags and filters
+    /// they register, which decide how a template is parsed.
+    ///
+    /// Every registered name and the identity of its function are included, so
+    /// replacing a tag or filter is noticed as well as adding or removing one.
+    fn libraries_fingerprint(py: Python<'_>, libraries: &Libraries) -> PyResult<u64> {
+        let mut fingerprint = 0;
+        for (name, library) in libraries {
+            let library = library.bind(py);
+            let mut hasher = DefaultHasher::new();
+            name.hash(&mut hasher);
+            library.as_ptr().hash(&mut hasher);
+            for attr in [intern!(py, "tags"), intern!(py, "filters")] {
+                let registered = match library.getattr(attr) {
+                    Ok(registered) => registered,
+                    Err(e) if e.is_instance_of::<PyAttributeError>(py) => continue,
+                    Err(e) => return Err(e),
+                };
+                registered.as_ptr().hash(&mut hasher);
+                if let Ok(registered) = registered.cast::<PyDict>() {
+                    let mut entries: u64 = 0;
+                    for (key, value) in registered {
+                        let mut entry = DefaultHasher::new();
+                        key.hash()?.hash(&mut entry);
+                        value.as_ptr().hash(&mut entry);
+                        entries = entries.wrapping_add(entry.finish());
+                    }
+                    entries.hash(&mut hasher);
+                }
+            }
+            // Combine without depending on the iteration order.
+            fingerprint ^= hasher.finish();
+        }
+        Ok(fingerprint)
+    }
+
     #[derive(Debug)]
     #[pyclass(name = "Engine")]
     pub struct PyEngine {
         engine: Arc<Engine>,
+        /// Kept here rather than on `Engine`, which each cached template
+        /// holds on to.
+        parse_cache: Option<Mutex<ParseCache>>,
     }
 
     #[pymethods]
     impl PyEngine {
         #[new]
-        #[pyo3(signature = (dirs=None, app_dirs=false, context_processors=None, debug=false, loaders=None, string_if_invalid=String::new(), file_charset="utf-8".to_string(), libraries=None, builtins=None, autoescape=true))]
+        #[pyo3(signature = (dirs=None, app_dirs=false, context_processors=None, debug=false, loaders=None, string_if_invalid=String::new(), file_charset="utf-8".to_string(), libraries=None, builtins=None, autoescape=true, detach=false, parse_cache_size=128, template_cache_dir=None))]
         #[allow(clippy::too_many_arguments)] // We're matching Django's Engine __init__ signature
         pub fn new(
             py: Python<'_>,
@@ -331,6 +448,9 @@ pub mod django_rusty_templates {
             libraries: Option<Bound<'_, PyAny>>,
             #[allow(unused_variables)] builtins: Option<Bound<'_, PyAny>>,
             autoescape: bool,
+            detach: bool,
+            parse_cache_size: usize,
+            template_cache_dir: Option<PathBuf>,
         ) -> PyResult<Self> {
             let dirs = match dirs {
                 Some(dirs) => dirs.extract()?,
@@ -375,8 +495,8 @@ pub mod django_rusty_templates {
                     vec![cached_loader]
                 }
             };
-            let libraries = match libraries {
-                None => HashMap::new(),
+            let (libraries, humanize_libraries) = match libraries {
+                None => (HashMap::new(), HashSet::new()),
                 Some(libraries) => import_libraries(libraries)?,
             };
             let builtins = vec![];
@@ -385,16 +505,20 @@ pub mod django_rusty_templates {
                 app_dirs,
                 context_processors: processors,
                 debug,
-                template_loaders: template_loaders.into(),
+                template_loaders,
                 string_if_invalid,
                 encoding,
                 builtins,
                 autoescape,
                 loaded_context_processors,
                 libraries,
+                humanize_libraries,
+                detach,
+                template_cache_dir,
             };
             Ok(Self {
                 engine: Arc::new(engine),
+                parse_cache: ParseCache::new(parse_cache_size).map(Mutex::new),
             })
         }
 
@@ -406,6 +530,31 @@ pub mod django_rusty_templates {
             get_template(self.engine.clone(), py, Cow::Owned(template_name))
         }
 
+        /// The names of every template the loaders can find.
+        pub fn template_names(&self, py: Python<'_>) -> PyResult<Vec<String>> {
+            let mut names = Vec::new();
+            for loader in &self.engine.template_loaders {
+                names.append(&mut loader.template_names(py)?);
+            }
+            names.sort_unstable();
+            names.dedup();
+            Ok(names)
+        }
+
+        /// Load the template specified by `template_name` and write its
+        /// compiled form to `template_cache_dir`, so it can be loaded
+        /// without parsing it again.
+        pub fn precompile(&self, py: Python<'_>, template_name: String) -> PyResult<()> {
+            let Some(dir) = &self.engine.template_cache_dir else {
+                return Err(ImproperlyConfigured::new_err(
+                    "Templates can only be precompiled when template_cache_dir is set.",
+                ));
+            };
+            let template = get_template(self.engine.clone(), py, Cow::Borrowed(&template_name))?;
+            compiled::store(dir, &template, &template_name)?;
+            Ok(())
+        }
+
         /// Given a list of template names, return the first that can be loaded.
         ///
         /// See <https://docs.djangoproject.com/en/stable/ref/templates/api/#django.template.Engine.select_template>
@@ -419,11 +568,64 @@ pub mod django_rusty_templates {
 
         #[allow(clippy::wrong_self_convention)] // We're implementing a Django interface
         pub fn from_string(&self, template_code: Bound<'_, PyString>) -> PyResult<Template> {
-            Template::new_from_string(
-                template_code.py(),
-                template_code.extract()?,
-                self.engine.clone(),
-            )
+            let py = template_code.py();
+            let template: String = template_code.extract()?;
+            let Some(cache) = &self.parse_cache else {
+                return Template::new_from_string(py, template, self.engine.clone());
+            };
+            let libraries = libraries_fingerprint(py, &self.engine.libraries)?;
+            let mut hasher = DefaultHasher::new();
+            template.hash(&mut hasher);
+            let key = hasher.finish();
+            if let Some(cached) = cache
+                .lock_py_attached(py)
+                .expect("Mutex should not be poisoned")
+                .get(libraries, key, &template)
+            {
+                return Ok(cached);
+            }
+            // Parse without holding the lock, so other threads can use the cache.
+            let parsed = Template::new_from_string(py, template, self.engine.clone())?;
+            cache
+                .lock_py_attached(py)
+                .expect("Mutex should not be poisoned")
+                .insert(libraries, key, parsed.clone());
+            Ok(parsed)
+        }
+
+        /// Drop the templates cached by `from_string`, like `functools.lru_cache`'s
+        /// `cache_clear`.
+        pub fn clear_parse_cache(&self, py: Python<'_>) {
+            if let Some(cache) = &self.parse_cache {
+                cache
+                    .lock_py_attached(py)
+                    .expect("Mutex should not be poisoned")
+                    .clear();
+            }
+        }
+
+        /// Report how well the parse cache used by `from_string` is working, like
+        /// `functools.lru_cache`'s `cache_info`.
+        pub fn parse_cache_info<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+            let info = PyDict::new(py);
+            match &self.parse_cache {
+                Some(cache) => {
+                    let cache = cache
+                        .lock_py_attached(py)
+                        .expect("Mutex should not be poisoned");
+                    info.set_item("hits", cache.hits)?;
+                    info.set_item("misses", cache.misses)?;
+                    info.set_item("maxsize", cache.templates.capacity())?;
+                    info.set_item("currsize", cache.templates.cache_size())?;
+                }
+                None => {
+                    info.set_item("hits", 0)?;
+                    info.set_item("misses", 0)?;
+                    info.set_item("maxsize", 0)?;
+                    info.set_item("currsize", 0)?;
+                }
+            }
+            Ok(info)
         }
 
         /// Render the template specified by `template_name` with the given context.
@@ -443,7 +645,7 @@ pub mod django_rusty_templates {
                 self.get_template(py, template_name.extract()?)?
             };
 
-            template.py_render(py, context, None)
+            Template::py_render_bound(&Bound::new(py, template)?, context, None)
         }
 
         #[getter]
@@ -500,15 +702,39 @@ pub mod django_rusty_templates {
         }
     }
 
-    #[derive(Debug, Clone)]
-    #[pyclass(skip_from_py_object)]
-    pub struct Template {
+    /// The source and parsed nodes of a template.
+    #[derive(Debug)]
+    pub struct TemplateData {
         pub filename: Option<PathBuf>,
         pub template: String,
         pub nodes: Vec<TokenTree>,
         pub engine: Arc<Engine>,
     }
 
+    /// A handle to a parsed template. Cloning it shares the parsed data
+    /// instead of copying it, so cached templates are cheap to hand out.
+    #[derive(Debug, Clone)]
+    #[pyclass(skip_from_py_object)]
+    pub struct Template {
+        data: Arc<TemplateData>,
+    }
+
+    impl std::ops::Deref for Template {
+        type Target = TemplateData;
+
+        fn deref(&self) -> &TemplateData {
+            &self.data
+        }
+    }
+
+    impl From<TemplateData> for Template {
+        fn from(data: TemplateData) -> Self {
+            Self {
+                data: Arc::new(data),
+            }
+        }
+    }
+
     impl Template {
         pub fn new(
             py: Python<'_>,
@@ -517,6 +743,12 @@ pub mod django_rusty_templates {
             template_name: &str,
             engine: Arc<Engine>,
         ) -> PyResult<Self> {
+            if let Some(dir) = &engine.template_cache_dir
+                && let Some(compiled) =
+                    compiled::load(dir, template, &filename, template_name, &engine)
+            {
+                return Ok(compiled);
+            }
             let mut parser = Parser::new(
                 py,
                 TemplateString(template),
@@ -527,17 +759,21 @@ pub mod django_rusty_templates {
                 Ok(nodes) => nodes,
                 Err(err) => {
                     let err = err.try_into_parse_error()?;
-                    let source =
-                        miette::NamedSource::new(filename.to_string_lossy(), template.to_string());
-                    return Err(TemplateSyntaxError::with_source_code(err.into(), source));
+                    let at = diagnostic_at(&err);
+                    let name = filename.to_string_lossy();
+                    let source = miette::NamedSource::new(&name, template.to_string());
+                    let err = TemplateSyntaxError::with_source_code(err.into(), source);
+                    return Err(with_template_debug(py, err, &engine, template, &name, at));
                 }
             };
-            Ok(Self {
+            let nodes = optimise(py, TemplateString(template), nodes, engine.detach);
+            Ok(TemplateData {
                 template: template.to_string(),
                 filename: Some(filename),
                 nodes,
                 engine,
-            })
+            }
+            .into())
         }
 
         pub fn new_from_string(
@@ -550,109 +786,147 @@ pub mod django_rusty_templates {
                 Ok(nodes) => nodes,
                 Err(err) => {
                     let err = err.try_into_parse_error()?;
-                    return Err(TemplateSyntaxError::with_source_code(err.into(), template));
+                    let at = diagnostic_at(&err);
+                    let py_err =
+                        TemplateSyntaxError::with_source_code(err.into(), template.clone());
+                    return Err(with_template_debug(
+                        py,
+                        py_err,
+                        &engine,
+                        &template,
+                        UNKNOWN_SOURCE,
+                        at,
+                    ));
                 }
             };
-            Ok(Self {
+            let nodes = optimise(py, TemplateString(&template), nodes, engine.detach);
+            Ok(TemplateData {
                 template,
                 filename: None,
                 nodes,
                 engine,
-            })
+            }
+            .into())
         }
 
-        pub fn render(&self, py: Python<'_>, context: &mut Context) -> RenderResult<'_> {
-            let mut rendered = String::with_capacity(self.template.len());
+        /// Render the template, appending it to `output`.
+        pub fn render(
+            &self,
+            py: Python<'_>,
+            context: &mut Context,
+            output: &mut String,
+        ) -> PyResult<()> {
+            output.reserve(self.template.len());
             let template = TemplateString(&self.template);
-            for node in &self.nodes {
-                let content = node.render(py, template, context)?;
-                rendered.push_str(&content);
-            }
-            Ok(Cow::Owned(rendered))
+            self.nodes
+                .render(py, template, context, output)
+                .map_err(|err| self.render_error(py, err))
         }
 
-        fn _render(&self, py: Python<'_>, context: &mut Context) -> PyResult<String> {
-            match self.render(py, context) {
-                Ok(content) => Ok(content.to_string()),
-                Err(err) => {
-                    let err = err.try_into_render_error()?;
-                    match err {
+        pub fn render_error(&self, py: Python<'_>, err: PyRenderError) -> PyErr {
+            let (err, at) = match err {
+                PyRenderError::PyErr(err) => {
+                    let at = culprit_at(py, &err);
+                    (err, at)
+                }
+                PyRenderError::RenderError(err) => {
+                    let at = diagnostic_at(&err);
+                    let source = self.template.clone();
+                    let err = match err {
                         RenderError::RelativePathError(_) => {
-                            Err(TemplateSyntaxError::with_source_code(
-                                err.into(),
-                                self.template.clone(),
-                            ))
+                            TemplateSyntaxError::with_source_code(err.into(), source)
                         }
                         RenderError::VariableDoesNotExist { .. }
                         | RenderError::ArgumentDoesNotExist { .. } => {
-                            Err(VariableDoesNotExist::with_source_code(
-                                err.into(),
-                                self.template.clone(),
-                            ))
+                            VariableDoesNotExist::with_source_code(err.into(), source)
                         }
                         RenderError::InvalidArgumentInteger { .. }
                         | RenderError::InvalidArgumentString { .. }
-                        | RenderError::TupleUnpackError { .. } => Err(
-                            PyValueError::with_source_code(err.into(), self.template.clone()),
-                        ),
+                        | RenderError::TupleUnpackError { .. } => {
+                            PyValueError::with_source_code(err.into(), source)
+                        }
                         RenderError::OverflowError { .. }
-                        | RenderError::InvalidArgumentFloat { .. } => Err(
-                            PyOverflowError::with_source_code(err.into(), self.template.clone()),
-                        ),
-                    }
+                        | RenderError::InvalidArgumentFloat { .. } => {
+                            PyOverflowError::with_source_code(err.into(), source)
+                        }
+                    };
+                    (err, at)
                 }
-            }
+            };
+            let name = self
+                .filename
+                .as_ref()
+                .map_or(Cow::Borrowed(UNKNOWN_SOURCE), |filename| {
+                    filename.to_string_lossy()
+                });
+            with_template_debug(py, err, &self.engine, &self.template, &name, at)
+        }
+
+        fn _render(&self, py: Python<'_>, context: &mut Context) -> PyResult<String> {
+            let mut output = String::new();
+            self.render(py, context, &mut output)?;
+            Ok(output)
         }
-    }
 
-    #[pymethods]
-    impl Template {
-        #[pyo3(name = "render", signature = (context=None, request=None))]
         pub fn py_render(
             &self,
             py: Python<'_>,
             context: Option<Bound<'_, PyAny>>,
             request: Option<Bound<'_, PyAny>>,
         ) -> PyResult<String> {
-            let mut base_context = HashMap::from([
-                ("None".to_string(), py.None()),
-                ("True".to_string(), PyBool::new(py, true).to_owned().into()),
-                (
-                    "False".to_string(),
-                    PyBool::new(py, false).to_owned().into(),
-                ),
-            ]);
-            let request = request.map(pyo3::Bound::unbind);
-            if let Some(ref request) = request {
-                for processor in &self.engine.loaded_context_processors {
-                    let processor = processor.bind(py);
-                    let processor_context = processor.call1((request,))?;
-                    let processor_context: HashMap<_, _> = match processor_context.extract() {
-                        Ok(processor_context) => processor_context,
-                        Err(_) => {
-                            let processor_module = processor.getattr("__module__")?;
-                            let processor_name = processor.getattr("__qualname__")?;
-                            let message = format!(
-                                "Context processor '{processor_module}.{processor_name}' didn't return a dictionary."
-                            );
-                            let error = PyTypeError::new_err(message);
-                            return Err(error);
-                        }
-                    };
-                    base_context.extend(processor_context);
-                }
-            }
-            let mut context = match context {
+            self.render_python(py, context, request, None)
+        }
+
+        /// Render with a context passed from Python, exposing `bound` as `context.template`.
+        fn render_python(
+            &self,
+            py: Python<'_>,
+            context: Option<Bound<'_, PyAny>>,
+            request: Option<Bound<'_, PyAny>>,
+            bound: Option<Py<Self>>,
+        ) -> PyResult<String> {
+            let mut context = self.python_context(py, context, request, bound)?;
+            context.start(py)?;
+            let rendered = context.with_context(py, |context| self._render(py, context));
+            context.finish(py)?;
+            rendered
+        }
+
+        /// Build the context to render with from the arguments passed from Python.
+        fn python_context(
+            &self,
+            py: Python<'_>,
+            context: Option<Bound<'_, PyAny>>,
+            request: Option<Bound<'_, PyAny>>,
+            bound: Option<Py<Self>>,
+        ) -> PyResult<PythonContext> {
+            let context = match context {
                 Some(py_context) if py_context.is_instance_of::<PyContext>() => {
                     let extracted: PyContext = py_context
                         .extract()
                         .expect("The type of py_context should be PyContext");
-                    let mut context = extracted
-                        .context
-                        .lock_py_attached(py)
-                        .expect("Mutex should not be poisoned");
-                    return self._render(py, &mut context);
+                    return Ok(PythonContext::Shared {
+                        context: extracted.context,
+                        bound,
+                        bound_template: false,
+                    });
                 }
+                Some(django_context) if is_django_context(&django_context)? => {
+                    return self.django_context(py, django_context, request, bound);
+                }
+                context => context,
+            };
+            let mut base_context = builtins_context(py);
+            if let Some(ref request) = request {
+                let processors = self
+                    .engine
+                    .loaded_context_processors
+                    .iter()
+                    .map(|processor| processor.bind(py).clone());
+                base_context.extend(run_context_processors(request, processors)?);
+            }
+            let request = request.map(pyo3::Bound::unbind);
+            let mut context = match context {
                 Some(context) => {
                     let new_context: HashMap<_, _> = context.extract()?;
                     base_context.extend(new_context);
@@ -660,10 +934,352 @@ pub mod django_rusty_templates {
                 }
                 None => Context::new(base_context, request, self.engine.autoescape),
             };
+            context.template = bound;
+            context.detach = self.engine.detach;
+            Ok(PythonContext::New(context))
+        }
+
+        /// Build the context to render a `django.template.Context` or `RequestContext` with.
+        fn django_context(
+            &self,
+            py: Python<'_>,
+            django_context: Bound<'_, PyAny>,
+            request: Option<Bound<'_, PyAny>>,
+            bound: Option<Py<Self>>,
+        ) -> PyResult<PythonContext> {
+            let request = match django_context.getattr_opt(intern!(py, "request"))? {
+                Some(request) => Some(request),
+                None => request,
+            };
+            let mut processed = HashMap::new();
+            if let Some(ref request) = request {
+                let mut processors: Vec<_> = self
+                    .engine
+                    .loaded_context_processors
+                    .iter()
+                    .map(|processor| processor.bind(py).clone())
+                    .collect();
+                if let Some(extra) = django_context.getattr_opt(intern!(py, "_processors"))? {
+                    for processor in extra.try_iter()? {
+                        processors.push(processor?);
+                    }
+                }
+                processed = run_context_processors(request, processors)?;
+            }
+            // Like `RequestContext.bind_template`, the processors fill the slot at
+            // `_processors_index`: above the constructor's dict and below later pushes.
+            let processors_index: Option<usize> = django_context
+                .getattr_opt(intern!(py, "_processors_index"))?
+                .map(|index| index.extract())
+                .transpose()?;
+            let mut base_context = builtins_context(py);
+            if processors_index.is_none() {
+                base_context.extend(std::mem::take(&mut processed));
+            }
+            for (index, dict) in django_context
+                .getattr(intern!(py, "dicts"))?
+                .try_iter()?
+                .enumerate()
+            {
+                if Some(index) == processors_index {
+                    base_context.extend(std::mem::take(&mut processed));
+                } else {
+                    let dict: HashMap<String, Py<PyAny>> = dict?.extract()?;
+                    base_context.extend(dict);
+                }
+            }
+            let initial: HashMap<String, Py<PyAny>> = base_context
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone_ref(py)))
+                .collect();
 
-            self._render(py, &mut context)
+            let autoescape = django_context
+                .getattr(intern!(py, "autoescape"))?
+                .is_truthy()?;
+            let request = request.map(pyo3::Bound::unbind);
+            let mut context = Context::new(base_context, request, autoescape);
+            context.use_l10n = django_context.getattr(intern!(py, "use_l10n"))?.extract()?;
+            context.use_tz = django_context.getattr(intern!(py, "use_tz"))?.extract()?;
+            context.template_name = django_context
+                .getattr(intern!(py, "template_name"))?
+                .extract()?;
+            context.template = bound;
+            context.detach = self.engine.detach;
+            Ok(PythonContext::Django {
+                context,
+                django_context: django_context.unbind(),
+                initial,
+            })
         }
     }
+
+    /// The context a template renders with, built from the arguments passed from Python.
+    enum PythonContext {
+        New(Context),
+        /// The context of a template tag's `context` argument, shared with Python.
+        Shared {
+            context: Arc<Mutex<Context>>,
+            bound: Option<Py<Template>>,
+            /// Whether `bound` was bound as `context.template` by `start`.
+            bound_template: bool,
+        },
+        /// A `django.template.Context` or `RequestContext`.
+        ///
+        /// Like Django, variables set at the top level of the template (for example by
+        /// `{% url ... as var %}`) are written back to the caller's context.
+        Django {
+            context: Context,
+            django_context: Py<PyAny>,
+            initial: HashMap<String, Py<PyAny>>,
+        },
+    }
+
+    impl PythonContext {
+        /// Prepare to render, once however many calls to `with_context` the
+        /// render takes.
+        fn start(&mut self, py: Python<'_>) -> PyResult<()> {
+            if let Self::Shared {
+                context,
+                bound,
+                bound_template,
+            } = self
+            {
+                let mut context = context
+                    .lock_py_attached(py)
+                    .expect("Mutex should not be poisoned");
+                // Like Django's `Context.bind_template`, only bind the outermost template.
+                *bound_template = context.template.is_none();
+                if *bound_template {
+                    context.template = bound.as_ref().map(|bound| bound.clone_ref(py));
+                }
+                // Like Django's `RenderContext.push_state`, each template render
+                // keeps its own render context state.
+                context.push_render_state(py)?;
+            }
+            Ok(())
+        }
+
+        fn with_context<T>(
+            &mut self,
+            py: Python<'_>,
+            f: impl FnOnce(&mut Context) -> PyResult<T>,
+        ) -> PyResult<T> {
+            match self {
+                Self::New(context) | Self::Django { context, .. } => f(context),
+                Self::Shared { context, .. } => f(&mut context
+                    .lock_py_attached(py)
+                    .expect("Mutex should not be poisoned")),
+            }
+        }
+
+        /// Undo `start` and write the variables set while rendering back to a
+        /// Django context.
+        fn finish(&mut self, py: Python<'_>) -> PyResult<()> {
+            match self {
+                Self::New(_) => Ok(()),
+                Self::Shared {
+                    context,
+                    bound_template,
+                    ..
+                } => {
+                    let mut context = context
+                        .lock_py_attached(py)
+                        .expect("Mutex should not be poisoned");
+                    if std::mem::take(bound_template) {
+                        context.template = None;
+                    }
+                    context.pop_render_state(py)
+                }
+                Self::Django {
+                    context,
+                    django_context,
+                    initial,
+                } => {
+                    let django_context = django_context.bind(py);
+                    for (key, value) in context.items() {
+                        if initial.get(key).is_none_or(|initial| !initial.is(value)) {
+                            django_context.set_item(key, value)?;
+                        }
+                    }
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    /// An iterator over the rendered chunks of a template, returned by `Template.stream`.
+    #[pyclass]
+    pub struct TemplateStream {
+        template: Py<Template>,
+        /// The context to render with, or `None` once the stream has finished.
+        context: Option<PythonContext>,
+        stream: Stream,
+        chunk_size: usize,
+    }
+
+    #[pymethods]
+    impl TemplateStream {
+        fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+            slf
+        }
+
+        fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<String>> {
+            let Some(context) = &mut self.context else {
+                return Ok(None);
+            };
+            let template = self.template.bind(py).borrow();
+            let chunk = context.with_context(py, |context| {
+                self.stream
+                    .next_chunk(py, &template, context, self.chunk_size)
+            });
+            if !matches!(chunk, Ok(Some(_))) {
+                self.close(py)?;
+            }
+            chunk
+        }
+
+        /// Stop rendering and restore the context, like a generator's `close`.
+        ///
+        /// `StreamingHttpResponse` calls this when the response is closed.
+        fn close(&mut self, py: Python<'_>) -> PyResult<()> {
+            let Some(mut context) = self.context.take() else {
+                return Ok(());
+            };
+            let unwound = context.with_context(py, |context| self.stream.unwind(py, context));
+            context.finish(py)?;
+            unwound
+        }
+    }
+
+    impl Drop for TemplateStream {
+        fn drop(&mut self) {
+            if self.context.is_some() {
+                Python::attach(|py| {
+                    if let Err(error) = self.close(py) {
+                        error.write_unraisable(py, None);
+                    }
+                });
+            }
+        }
+    }
+
+    /// The template name Django reports for templates without an origin.
+    const UNKNOWN_SOURCE: &str = "<unknown source>";
+
+    /// Attach Django's `template_debug` information to an exception when the engine is in
+    /// debug mode, so the technical 500 page can show the failing template source.
+    fn with_template_debug(
+        py: Python<'_>,
+        err: PyErr,
+        engine: &Engine,
+        source: &str,
+        name: &str,
+        at: Option<At>,
+    ) -> PyErr {
+        let Some(at) = at else { return err };
+        if !engine.debug {
+            return err;
+        }
+        let value = err.value(py);
+        // An included template has already attached the information for its own source.
+        if value.hasattr(intern!(py, "template_debug")).unwrap_or(true) {
+            return err;
+        }
+        let message = value
+            .getattr(intern!(py, "args"))
+            .and_then(|args| args.get_item(0))
+            .and_then(|message| message.str())
+            .map_or_else(
+                |_| "(Could not get exception message)".to_string(),
+                |message| message.to_string(),
+            );
+        if let Ok(template_debug) = get_exception_info(py, source, name, at, &message) {
+            let _ = value.setattr(intern!(py, "template_debug"), template_debug);
+        }
+        err
+    }
+
+    #[pymethods]
+    impl Template {
+        #[pyo3(name = "render", signature = (context=None, request=None))]
+        fn py_render_bound(
+            slf: &Bound<'_, Self>,
+            context: Option<Bound<'_, PyAny>>,
+            request: Option<Bound<'_, PyAny>>,
+        ) -> PyResult<String> {
+            slf.borrow()
+                .render_python(slf.py(), context, request, Some(slf.clone().unbind()))
+        }
+
+        /// Render the template as an iterator of chunks of at least `chunk_size` bytes,
+        /// except for the last, for use with `StreamingHttpResponse`.
+        #[pyo3(signature = (context=None, request=None, chunk_size=8192))]
+        fn stream(
+            slf: &Bound<'_, Self>,
+            context: Option<Bound<'_, PyAny>>,
+            request: Option<Bound<'_, PyAny>>,
+            chunk_size: usize,
+        ) -> PyResult<TemplateStream> {
+            let template = slf.clone().unbind();
+            let mut context = slf.borrow().python_context(
+                slf.py(),
+                context,
+                request,
+                Some(template.clone_ref(slf.py())),
+            )?;
+            context.start(slf.py())?;
+            Ok(TemplateStream {
+                template,
+                context: Some(context),
+                stream: Stream::default(),
+                chunk_size,
+            })
+        }
+    }
+
+    fn is_django_context(context: &Bound<'_, PyAny>) -> PyResult<bool> {
+        // Avoid importing Django in the common case of rendering with a dict.
+        if context.is_instance_of::<PyDict>() {
+            return Ok(false);
+        }
+        let context_class = DJANGO_CONTEXT.import(context.py(), "django.template", "Context")?;
+        context.is_instance(context_class)
+    }
+
+    pub fn builtins_context(py: Python<'_>) -> HashMap<String, Py<PyAny>> {
+        HashMap::from([
+            ("None".to_string(), py.None()),
+            ("True".to_string(), PyBool::new(py, true).to_owned().into()),
+            (
+                "False".to_string(),
+                PyBool::new(py, false).to_owned().into(),
+            ),
+        ])
+    }
+
+    fn run_context_processors<'py>(
+        request: &Bound<'py, PyAny>,
+        processors: impl IntoIterator<Item = Bound<'py, PyAny>>,
+    ) -> PyResult<HashMap<String, Py<PyAny>>> {
+        let mut context = HashMap::new();
+        for processor in processors {
+            let processor_context = processor.call1((request,))?;
+            let processor_context: HashMap<_, _> = match processor_context.extract() {
+                Ok(processor_context) => processor_context,
+                Err(_) => {
+                    let processor_module = processor.getattr("__module__")?;
+                    let processor_name = processor.getattr("__qualname__")?;
+                    let message = format!(
+                        "Context processor '{processor_module}.{processor_name}' didn't return a dictionary."
+                    );
+                    let error = PyTypeError::new_err(message);
+                    return Err(error);
+                }
+            };
+            context.extend(processor_context);
+        }
+        Ok(context)
+    }
 }
 
 #[cfg(test)]
@@ -671,9 +1287,10 @@ mod tests {
     use std::sync::Arc;
 
     use super::django_rusty_templates::*;
+    use crate::error::get_exception_info;
 
     use pyo3::Python;
-    use pyo3::types::{PyDict, PyDictMethods, PyList, PyString};
+    use pyo3::types::{PyAnyMethods, PyDict, PyDictMethods, PyList, PyString};
 
     #[test]
     fn test_syntax_error() {
@@ -732,6 +1349,39 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_exception_info() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let source = "<p>\n{{ café|bad }}\n</p>";
+            let info = get_exception_info(py, source, "<unknown source>", (13, 3), "Oops").unwrap();
+            let get = |key: &str| info.get_item(key).unwrap().unwrap();
+
+            assert_eq!(get("message").extract::<String>().unwrap(), "Oops");
+            assert_eq!(get("name").extract::<String>().unwrap(), "<unknown source>");
+            assert_eq!(get("line").extract::<usize>().unwrap(), 2);
+            assert_eq!(get("start").extract::<usize>().unwrap(), 12);
+            assert_eq!(get("end").extract::<usize>().unwrap(), 15);
+            assert_eq!(get("before").extract::<String>().unwrap(), "{{ café|");
+            assert_eq!(get("during").extract::<String>().unwrap(), "bad");
+            assert_eq!(get("after").extract::<String>().unwrap(), " }}\n");
+            assert_eq!(get("top").extract::<usize>().unwrap(), 1);
+            assert_eq!(get("bottom").extract::<usize>().unwrap(), 4);
+            assert_eq!(get("total").extract::<usize>().unwrap(), 4);
+            assert_eq!(
+                get("source_lines")
+                    .extract::<Vec<(usize, String)>>()
+                    .unwrap(),
+                vec![
+                    (1, "&lt;p&gt;\n".to_string()),
+                    (2, "{{ café|bad }}\n".to_string()),
+                    (3, "&lt;/p&gt;".to_string()),
+                ]
+            );
+        });
+    }
+
     #[test]
     fn test_render_empty_template() {
         Python::initialize();
@@ -817,6 +1467,29 @@ user = User(["Lily"])
         });
     }
 
+    #[test]
+    fn test_render_template_detached() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let mut engine = Engine::empty();
+            engine.autoescape = true;
+            engine.detach = true;
+            let template_string = "{# comment #}<p>{{ html }}</p>".to_string();
+            let template =
+                Template::new_from_string(py, template_string, Arc::new(engine)).unwrap();
+            let context = PyDict::new(py);
+            context.set_item("html", "<b>".repeat(10_000)).unwrap();
+
+            assert_eq!(
+                template
+                    .py_render(py, Some(context.into_any()), None)
+                    .unwrap(),
+                format!("<p>{}</p>", "&lt;b&gt;".repeat(10_000))
+            );
+        });
+    }
+
     #[test]
     fn test_engine_from_string() {
         Python::initialize();
@@ -834,6 +1507,9 @@ user = User(["Lily"])
                 None,
                 None,
                 false,
+                false,
+                128,
+                None,
             )
             .unwrap();
             let template_string = PyString::new(py, "Hello {{ user }}!");
@@ -847,6 +1523,116 @@ user = User(["Lily"])
         });
     }
 
+    #[test]
+    fn test_engine_parse_cache() {
+        use std::collections::HashMap;
+
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = PyEngine::new(
+                py,
+                None,
+                false,
+                None,
+                false,
+                None,
+                String::new(),
+                "utf-8".to_string(),
+                None,
+                None,
+                false,
+                false,
+                1,
+                None,
+            )
+            .unwrap();
+            let hello = engine
+                .from_string(PyString::new(py, "Hello {{ user }}!"))
+                .unwrap();
+            let cached = engine
+                .from_string(PyString::new(py, "Hello {{ user }}!"))
+                .unwrap();
+            assert!(std::ptr::eq(&*hello, &*cached));
+
+            // The cache holds a single template, so this evicts the first.
+            engine
+                .from_string(PyString::new(py, "Goodbye {{ user }}!"))
+                .unwrap();
+            let reparsed = engine
+                .from_string(PyString::new(py, "Hello {{ user }}!"))
+                .unwrap();
+            assert!(!std::ptr::eq(&*hello, &*reparsed));
+
+            let info = engine.parse_cache_info(py).unwrap();
+            let info: HashMap<String, usize> = info.extract().unwrap();
+            assert_eq!(
+                info,
+                HashMap::from([
+                    ("hits".to_string(), 1),
+                    ("misses".to_string(), 3),
+                    ("maxsize".to_string(), 1),
+                    ("currsize".to_string(), 1),
+                ])
+            );
+
+            engine.clear_parse_cache(py);
+            let cleared = engine
+                .from_string(PyString::new(py, "Hello {{ user }}!"))
+                .unwrap();
+            assert!(!std::ptr::eq(&*reparsed, &*cleared));
+
+            let info = engine.parse_cache_info(py).unwrap();
+            let info: HashMap<String, usize> = info.extract().unwrap();
+            assert_eq!(
+                info,
+                HashMap::from([
+                    ("hits".to_string(), 0),
+                    ("misses".to_string(), 1),
+                    ("maxsize".to_string(), 1),
+                    ("currsize".to_string(), 1),
+                ])
+            );
+        });
+    }
+
+    #[test]
+    fn test_engine_parse_cache_disabled() {
+        Python::initialize();
+
+        Python::attach(|py| {
+            let engine = PyEngine::new(
+                py,
+                None,
+                false,
+                None,
+                false,
+                None,
+                String::new(),
+                "utf-8".to_string(),
+                None,
+                None,
+                false,
+                false,
+                0,
+                None,
+            )
+            .unwrap();
+            let hello = engine.from_string(PyString::new(py, "Hello!")).unwrap();
+            let again = engine.from_string(PyString::new(py, "Hello!")).unwrap();
+            assert!(!std::ptr::eq(&*hello, &*again));
+
+            let info = engine.parse_cache_info(py).unwrap();
+            let maxsize: usize = info
+                .get_item("maxsize")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(maxsize, 0);
+        });
+    }
+
     #[test]
     fn test_clone_template() {
         use std::collections::HashMap;
@@ -878,6 +1664,9 @@ user = User(["Lily"])
                 ),
                 None,
                 false,
+                false,
+                128,
+                None,
             )
             .unwrap();
             let template = engine
@@ -931,6 +1720,9 @@ user = User(["Lily"])
                 ),
                 None,
                 false,
+                false,
+                128,
+                None,
             )
             .unwrap();
 
@@ -984,6 +1776,9 @@ user = User(["Lily"])
                 None,
                 None,
                 false,
+                false,
+                128,
+                None,
             )
             .unwrap_err();
 
@@ -1,8 +1,14 @@
+use std::sync::Arc;
+
 use num_bigint::BigInt;
+use pyo3::prelude::*;
+use pyo3::types::PyString;
+use serde::{Deserialize, Serialize};
 
-use dtl_lexer::types::{At, Variable};
+use crate::compiled::VariableRef;
+use dtl_lexer::types::{At, TemplateString};
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Text {
     pub at: At,
 }
@@ -13,7 +19,43 @@ impl Text {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Output known when the template is parsed, such as adjacent text merged
+/// together or a filter applied to a literal.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Constant {
+    /// The output when autoescaping is enabled.
+    pub text: String,
+    /// The output when autoescaping is disabled, if it differs from `text`.
+    pub unescaped: Option<String>,
+}
+
+impl Constant {
+    pub fn new(text: String) -> Self {
+        Self {
+            text,
+            unescaped: None,
+        }
+    }
+
+    pub fn output(&self, autoescape: bool) -> &str {
+        match (autoescape, &self.unescaped) {
+            (false, Some(unescaped)) => unescaped,
+            _ => &self.text,
+        }
+    }
+
+    /// Append `other`, which is rendered after this.
+    pub fn push(&mut self, other: &Self) {
+        if self.unescaped.is_some() || other.unescaped.is_some() {
+            let mut unescaped = self.unescaped.take().unwrap_or_else(|| self.text.clone());
+            unescaped.push_str(other.output(false));
+            self.unescaped = Some(unescaped);
+        }
+        self.text.push_str(&other.text);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TranslatedText {
     pub at: At,
 }
@@ -24,7 +66,64 @@ impl TranslatedText {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+/// A variable such as `user.name`, with the lookups for each part after the
+/// first prepared when the template is parsed.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(into = "VariableRef", try_from = "VariableRef")]
+pub struct Variable {
+    pub at: At,
+    pub lookups: Arc<[Lookup]>,
+}
+
+/// A key to look up on the value of the previous part of a variable.
+#[derive(Debug)]
+pub struct Lookup {
+    /// The interned key, for item and attribute lookups.
+    pub key: Py<PyString>,
+    /// The key as a list index, if it is one.
+    pub index: Option<usize>,
+    pub at: At,
+}
+
+impl Variable {
+    pub fn new(py: Python<'_>, template: TemplateString<'_>, at: At) -> Self {
+        let lookups = dtl_lexer::types::Variable::new(at)
+            .parts(template)
+            .skip(1)
+            .map(|(part, at)| Lookup {
+                key: PyString::intern(py, part).unbind(),
+                index: part.parse().ok(),
+                at,
+            })
+            .collect();
+        Self { at, lookups }
+    }
+
+    pub fn parts<'t>(
+        &self,
+        template: TemplateString<'t>,
+    ) -> impl Iterator<Item = (&'t str, At)> + use<'t> {
+        dtl_lexer::types::Variable::new(self.at).parts(template)
+    }
+
+    /// The name looked up in the context.
+    pub fn first<'t>(&self, template: TemplateString<'t>) -> (&'t str, At) {
+        self.parts(template)
+            .next()
+            .expect("Variable names cannot be empty")
+    }
+}
+
+// The lookups are derived from `at`, so comparing it is enough.
+impl PartialEq for Variable {
+    fn eq(&self, other: &Self) -> bool {
+        self.at == other.at
+    }
+}
+
+impl Eq for Variable {}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ArgumentType {
     Variable(Variable),
     ForVariable(ForVariable),
@@ -34,13 +133,13 @@ pub enum ArgumentType {
     Float(f64),
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Argument {
     pub at: At,
     pub argument_type: ArgumentType,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ForVariableName {
     Counter,
     Counter0,
@@ -51,7 +150,7 @@ pub enum ForVariableName {
     Object,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ForVariable {
     pub variant: ForVariableName,
     pub parent_count: usize,
@@ -395,3 +395,16 @@ def test_center_argument_float_negative_inf_python(assert_render_error):
         django_message=django_message,
         rusty_message=rusty_message,
     )
+
+
+def test_center_unicode(assert_render):
+    assert_render("{{ foo|center:6 }}", {"foo": "café"}, " café ")
+
+
+def test_center_escapes_after_padding(assert_render):
+    assert_render(".{{ foo|center:5 }}.", {"foo": "a&b"}, ". a&amp;b .")
+
+
+def test_center_width_from_context(assert_render):
+    context = {"foo": "test", "width": "6"}
+    assert_render("{{ foo|center:width }}", context, " test ")
@@ -204,3 +204,113 @@ def test_date_error_propagation(assert_render_error):
    ╰────
 """),
     )
+
+
+@pytest.mark.parametrize(
+    "template,context,expected",
+    [
+        pytest.param(
+            '{{ d|date:"SHORT_DATE_FORMAT" }}',
+            {"d": datetime(2008, 1, 1)},
+            "01/01/2008",
+            id="named_format",
+        ),
+        pytest.param(
+            '{{ d|date:"DATETIME_FORMAT" }}',
+            {"d": datetime(2008, 1, 1)},
+            "Jan. 1, 2008, midnight",
+            id="named_datetime_format",
+        ),
+        pytest.param(
+            '{{ d|date:"D, l, N, w, z, W, t, L, b, E, S" }}',
+            {"d": datetime(2008, 1, 6)},
+            "Sun, Sunday, Jan., 0, 6, 1, 31, True, jan, January, th",
+            id="date_characters",
+        ),
+        pytest.param(
+            '{{ d|date:"c" }}',
+            {"d": datetime(2008, 1, 6, 15, 5, 9, 120)},
+            "2008-01-06T15:05:09.000120",
+            id="isoformat",
+        ),
+        pytest.param(
+            '{{ d|date:"c" }}',
+            {"d": datetime(2008, 1, 6, 15, tzinfo=timezone.get_fixed_timezone(60))},
+            "2008-01-06T15:00:00+01:00",
+            id="isoformat_aware",
+        ),
+        pytest.param(
+            '{{ d|date:"r" }}',
+            {
+                "d": datetime(
+                    2008, 1, 6, 15, 5, 9, tzinfo=timezone.get_fixed_timezone(60)
+                )
+            },
+            "Sun, 06 Jan 2008 15:05:09 +0100",
+            id="rfc5322",
+        ),
+        pytest.param(
+            '{{ d|date:"O Z T I" }}',
+            {"d": datetime(2008, 1, 6, tzinfo=timezone.get_fixed_timezone(-90))},
+            "-0130 -5400 -0130 0",
+            id="timezone",
+        ),
+        pytest.param(
+            '{{ d|date:"U" }}',
+            {"d": datetime(2008, 1, 6, tzinfo=timezone.get_fixed_timezone(0))},
+            "1199577600",
+            id="timestamp",
+        ),
+        pytest.param(
+            '{{ d|date:"y Y" }}',
+            {"d": datetime(5, 1, 1)},
+            "05 0005",
+            id="small_year",
+        ),
+        pytest.param(
+            '{{ d|date:"<Y>" }}',
+            {"d": datetime(2008, 1, 1)},
+            "&lt;2008&gt;",
+            id="escaped",
+        ),
+        pytest.param(
+            '{{ d|date:"" }}',
+            {"d": datetime(2008, 1, 1)},
+            "Jan. 1, 2008",
+            id="empty_format",
+        ),
+    ],
+)
+def test_date_format_characters(assert_render, template, context, expected):
+    assert_render(template, context, expected)
+
+
+def test_date_localized_names(assert_render):
+    with translation.override("fr"):
+        assert_render(
+            template='{{ d|date:"l j F Y" }}',
+            context={"d": datetime(2008, 1, 1)},
+            expected="mardi 1 janvier 2008",
+        )
+
+
+def test_date_time_format_for_date(assert_render_error):
+    from datetime import date
+
+    assert_render_error(
+        template='{{ d|date:"H" }}',
+        context={"d": date(2008, 1, 1)},
+        exception=TypeError,
+        django_message=snapshot(
+            "The format for date objects may not contain time-related format specifiers (found 'H')."
+        ),
+        rusty_message=snapshot("""\
+  × The format for date objects may not contain time-related format specifiers
+  │ (found 'H').
+   ╭────
+ 1 │ {{ d|date:"H" }}
+   ·      ──┬─
+   ·        ╰── here
+   ╰────
+"""),
+    )
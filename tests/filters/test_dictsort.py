@@ -0,0 +1,151 @@
+from inline_snapshot import snapshot
+import pytest
+
+
+PEOPLE = [
+    {"age": 23, "name": "Barbara-Ann"},
+    {"age": 63, "name": "Ra Ra Rasputin"},
+    {"name": "Jonny B Goode", "age": 18},
+]
+
+NESTED = [
+    {"foo": {"bar": 1, "baz": "c"}},
+    {"foo": {"bar": 2, "baz": "b"}},
+    {"foo": {"bar": 3, "baz": "a"}},
+]
+
+
+class Author:
+    def __init__(self, name):
+        self.name = name
+
+
+class Book:
+    def __init__(self, title, author):
+        self.title = title
+        self.author = Author(author)
+
+
+BOOKS = [Book("b", "Zed"), Book("a", "Amy"), Book("c", "Max")]
+
+
+@pytest.mark.parametrize(
+    "filter,value,expected",
+    [
+        pytest.param(
+            'dictsort:"age"',
+            PEOPLE,
+            "Jonny B Goode,Barbara-Ann,Ra Ra Rasputin,",
+            id="key",
+        ),
+        pytest.param(
+            'dictsortreversed:"age"',
+            PEOPLE,
+            "Ra Ra Rasputin,Barbara-Ann,Jonny B Goode,",
+            id="key_reversed",
+        ),
+        pytest.param(
+            'dictsort:"author.name"',
+            BOOKS,
+            "a,c,b,",
+            id="attributes",
+        ),
+        pytest.param(
+            'dictsortreversed:"author.name"',
+            BOOKS,
+            "b,c,a,",
+            id="attributes_reversed",
+        ),
+    ],
+)
+def test_dictsort(assert_render, filter, value, expected):
+    attribute = "title" if value is BOOKS else "name"
+    template = (
+        f"{{% for item in value|{filter} %}}{{{{ item.{attribute} }}}},{{% endfor %}}"
+    )
+    assert_render(template, {"value": value}, expected)
+
+
+def test_dictsort_nested_keys(assert_render):
+    template = '{% for item in value|dictsort:"foo.baz" %}{{ item.foo.bar }}{% endfor %}'
+    assert_render(template, {"value": NESTED}, "321")
+
+
+@pytest.mark.parametrize(
+    "argument,value",
+    [
+        pytest.param("0", [("a", "42"), ("c", "string"), ("b", "foo")], id="tuples"),
+        pytest.param(
+            '"0"',
+            [{"0": "a", "1": "42"}, {"0": "c", "1": "string"}, {"0": "b", "1": "foo"}],
+            id="tuple_like_dicts",
+        ),
+    ],
+)
+def test_dictsort_index(assert_render, argument, value):
+    template = f"{{% for item in value|dictsort:{argument} %}}{{{{ item.0 }}}}{{% endfor %}}"
+    assert_render(template, {"value": value}, "abc")
+
+
+def test_dictsort_stable(assert_render):
+    value = [{"a": 1, "b": "x"}, {"a": 0, "b": "y"}, {"a": 1, "b": "z"}]
+    template = '{% for item in value|dictsortreversed:"a" %}{{ item.b }}{% endfor %}'
+    assert_render(template, {"value": value}, "xzy")
+
+
+@pytest.mark.parametrize(
+    "value",
+    [
+        pytest.param([1, 2, 3], id="list_of_ints"),
+        pytest.param("Hello!", id="string"),
+        pytest.param({"a": 1}, id="dict"),
+        pytest.param(1, id="int"),
+        pytest.param([{"age": 1}, {"name": "x"}], id="missing_attribute"),
+        pytest.param([{"age": 1}, {"age": "x"}], id="incomparable"),
+    ],
+)
+def test_dictsort_invalid_values(assert_render, value):
+    assert_render('{{ value|dictsort:"age" }}', {"value": value}, "")
+    assert_render('{{ value|dictsortreversed:"age" }}', {"value": value}, "")
+
+
+@pytest.mark.parametrize("argument", ["_private", "foo._private", "foo.__class__"])
+def test_dictsort_private(assert_render, argument):
+    template = f'{{{{ value|dictsort:"{argument}" }}}}'
+    assert_render(template, {"value": NESTED}, "")
+
+
+def test_dictsort_undefined(assert_render):
+    assert_render('{{ value|dictsort:"age" }}', {}, "[]")
+
+
+def test_dictsort_missing_key(assert_render_error):
+    assert_render_error(
+        template="{{ value|dictsort:0 }}",
+        context={"value": [{"a": 1}]},
+        exception=KeyError,
+        django_message=snapshot("0"),
+        rusty_message=snapshot("""\
+  × 0
+   ╭────
+ 1 │ {{ value|dictsort:0 }}
+   ·                   ┬
+   ·                   ╰── here
+   ╰────
+"""),
+    )
+
+
+def test_dictsort_no_argument(assert_parse_error):
+    assert_parse_error(
+        template="{{ value|dictsort }}",
+        django_message=snapshot("dictsort requires 2 arguments, 1 provided"),
+        rusty_message=snapshot("""\
+  × Expected an argument
+   ╭────
+ 1 │ {{ value|dictsort }}
+   ·          ────┬───
+   ·              ╰── here
+   ╰────
+"""),
+    )
@@ -0,0 +1,60 @@
+from inline_snapshot import snapshot
+import pytest
+from django.utils.safestring import mark_safe
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        pytest.param(["<a>", "&"], "&lt;a&gt;, &amp;", id="list"),
+        pytest.param([mark_safe("<a>"), "<b>"], "<a>, &lt;b&gt;", id="safe_item"),
+        pytest.param(("x", 1), "x, 1", id="tuple"),
+        pytest.param("<>", "&lt;, &gt;", id="string"),
+        pytest.param([], "", id="empty"),
+    ],
+)
+def test_escapeseq(assert_render, value, expected):
+    assert_render('{{ value|escapeseq|join:", " }}', {"value": value}, expected)
+
+
+def test_escapeseq_autoescape_off(assert_render):
+    template = (
+        '{% autoescape off %}{{ value|escapeseq|join:", " }}{% endautoescape %}'
+    )
+    assert_render(template, {"value": ["<a>", "&"]}, "&lt;a&gt;, &amp;")
+
+
+def test_escapeseq_undefined(assert_render):
+    assert_render('{{ value|escapeseq|join:", " }}', {}, "")
+
+
+def test_escapeseq_not_iterable(assert_render_error):
+    assert_render_error(
+        template="{{ value|escapeseq }}",
+        context={"value": 1},
+        exception=TypeError,
+        django_message=snapshot("'int' object is not iterable"),
+        rusty_message=snapshot("""\
+  × 'int' object is not iterable
+   ╭────
+ 1 │ {{ value|escapeseq }}
+   ·          ────┬────
+   ·              ╰── here
+   ╰────
+"""),
+    )
+
+
+def test_escapeseq_unexpected_argument(assert_parse_error):
+    assert_parse_error(
+        template="{{ value|escapeseq:1 }}",
+        django_message=snapshot("escapeseq requires 1 arguments, 2 provided"),
+        rusty_message=snapshot("""\
+  × escapeseq filter does not take an argument
+   ╭────
+ 1 │ {{ value|escapeseq:1 }}
+   ·                    ┬
+   ·                    ╰── unexpected argument
+   ╰────
+"""),
+    )
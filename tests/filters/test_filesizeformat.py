@@ -0,0 +1,85 @@
+from inline_snapshot import snapshot
+import pytest
+from django.utils.translation import override
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        pytest.param(0, "0\xa0bytes", id="zero"),
+        pytest.param(1, "1\xa0byte", id="one"),
+        pytest.param(1023, "1023\xa0bytes", id="bytes"),
+        pytest.param(1024, "1.0\xa0KB", id="kilobyte"),
+        pytest.param(1280, "1.2\xa0KB", id="round_half_even"),
+        pytest.param(10 * 1024, "10.0\xa0KB", id="kilobytes"),
+        pytest.param(1024 * 1024 - 1, "1024.0\xa0KB", id="almost_megabyte"),
+        pytest.param(1024**2, "1.0\xa0MB", id="megabyte"),
+        pytest.param(1024**3 * 50, "50.0\xa0GB", id="gigabytes"),
+        pytest.param(1024**4, "1.0\xa0TB", id="terabyte"),
+        pytest.param(1024**5, "1.0\xa0PB", id="petabyte"),
+        pytest.param(1024**5 * 2000, "2000.0\xa0PB", id="petabytes"),
+        pytest.param(2**60 + 1, "1024.0\xa0PB", id="big"),
+        pytest.param(10**40, "8881784197001253000000000.0\xa0PB", id="huge"),
+        pytest.param(-100, "-100\xa0bytes", id="negative_bytes"),
+        pytest.param(-(1024**3), "-1.0\xa0GB", id="negative"),
+        pytest.param(1.9, "1\xa0byte", id="float"),
+        pytest.param(True, "1\xa0byte", id="bool"),
+        pytest.param("2048", "2.0\xa0KB", id="string"),
+        pytest.param("abc", "0\xa0bytes", id="not_a_number"),
+        pytest.param("1.5", "0\xa0bytes", id="string_float"),
+        pytest.param(None, "0\xa0bytes", id="none"),
+        pytest.param([], "0\xa0bytes", id="list"),
+        pytest.param(float("nan"), "0\xa0bytes", id="nan"),
+    ],
+)
+def test_filesizeformat(assert_render, value, expected):
+    assert_render("{{ value|filesizeformat }}", {"value": value}, expected)
+
+
+def test_filesizeformat_undefined(assert_render):
+    assert_render("{{ value|filesizeformat }}", {}, "0\xa0bytes")
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        (1, "1\xa0Byte"),
+        (10, "10\xa0Bytes"),
+        (1024 * 1024 * 1.5, "1,5\xa0MB"),
+    ],
+)
+def test_filesizeformat_translation(assert_render, value, expected):
+    with override("de"):
+        assert_render("{{ value|filesizeformat }}", {"value": value}, expected)
+
+
+def test_filesizeformat_overflow(assert_render_error):
+    assert_render_error(
+        template="{{ value|filesizeformat }}",
+        context={"value": float("inf")},
+        exception=OverflowError,
+        django_message=snapshot("cannot convert float infinity to integer"),
+        rusty_message=snapshot("""\
+  × cannot convert float infinity to integer
+   ╭────
+ 1 │ {{ value|filesizeformat }}
+   ·          ───────┬──────
+   ·                 ╰── here
+   ╰────
+"""),
+    )
+
+
+def test_filesizeformat_unexpected_argument(assert_parse_error):
+    assert_parse_error(
+        template="{{ value|filesizeformat:1 }}",
+        django_message=snapshot("filesizeformat requires 1 arguments, 2 provided"),
+        rusty_message=snapshot("""\
+  × filesizeformat filter does not take an argument
+   ╭────
+ 1 │ {{ value|filesizeformat:1 }}
+   ·                         ┬
+   ·                         ╰── unexpected argument
+   ╰────
+"""),
+    )
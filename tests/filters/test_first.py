@@ -0,0 +1,45 @@
+from inline_snapshot import snapshot
+import pytest
+from django.utils.safestring import mark_safe
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        pytest.param(["a", "b"], "a", id="list"),
+        pytest.param(("<a>", "b"), "&lt;a&gt;", id="escaped"),
+        pytest.param([mark_safe("<a>")], "<a>", id="safe_item"),
+        pytest.param("abc", "a", id="string"),
+        pytest.param(mark_safe("<b>"), "&lt;", id="safe_string"),
+        pytest.param([], "", id="empty_list"),
+        pytest.param("", "", id="empty_string"),
+    ],
+)
+def test_first(assert_render, value, expected):
+    assert_render("{{ value|first }}", {"value": value}, expected)
+
+
+def test_first_undefined(assert_render):
+    assert_render("{{ value|first }}", {}, "")
+
+
+def test_first_autoescape_off(assert_render):
+    template = "{% autoescape off %}{{ value|first }}{% endautoescape %}"
+    assert_render(template, {"value": ["<a>"]}, "<a>")
+
+
+def test_first_not_subscriptable(assert_render_error):
+    assert_render_error(
+        template="{{ value|first }}",
+        context={"value": 1},
+        exception=TypeError,
+        django_message=snapshot("'int' object is not subscriptable"),
+        rusty_message=snapshot("""\
+  × 'int' object is not subscriptable
+   ╭────
+ 1 │ {{ value|first }}
+   ·          ──┬──
+   ·            ╰── here
+   ╰────
+"""),
+    )
@@ -0,0 +1,96 @@
+from decimal import Decimal
+
+import pytest
+from django.template import engines
+from django.test import override_settings
+from django.utils import translation
+from django.utils.safestring import mark_safe
+
+
+@pytest.mark.parametrize(
+    "value,argument,expected",
+    [
+        (7.7, "", "7.7"),
+        (7.0, "", "7"),
+        (0.7, "", "0.7"),
+        (-0.7, "", "-0.7"),
+        (0.07, "", "0.1"),
+        (0.007, "", "0.0"),
+        (0.0, "", "0"),
+        (7.7, ":3", "7.700"),
+        (6.000000, ":3", "6.000"),
+        (6.200000, ":3", "6.200"),
+        (6.200000, ":-3", "6.200"),
+        (13.1031, ":-3", "13.103"),
+        (11.1197, ":-2", "11.12"),
+        (11.0000, ":-2", "11"),
+        (11.000001, ":-2", "11.00"),
+        (8.2798, ":3", "8.280"),
+        (5555.555, ":2", "5555.56"),
+        (001.3000, ":2", "1.30"),
+        (0.12345, ":2", "0.12"),
+        (Decimal("555.555"), ":2", "555.56"),
+        (Decimal("09.000"), "", "9"),
+        (Decimal("1.0000000000000000000000001"), ":25", "1.0000000000000000000000001"),
+        (Decimal("-0.0001"), ":2", "0.00"),
+        (Decimal("1E+20"), ":2", "100000000000000000000.00"),
+        ("1e-999999999", ":2", "0.00"),
+        ("-1e-999999999", "", "0.0"),
+        ("foo", "", ""),
+        (13.1031, ":'bar'", "13.1031"),
+        (18.125, ":2", "18.13"),
+        ("¿Cómo esta usted?", "", ""),
+        (None, "", ""),
+        (-1.323297138040798e35, ":2", "-132329713804079800000000000000000000.00"),
+        (1.5e-15, ":20", "0.00000000000000150000"),
+        (1.0e16, "", "10000000000000000"),
+        (float("inf"), "", "inf"),
+        (float("nan"), "", "nan"),
+        (True, "", "1"),
+        (12, ":2", "12.00"),
+        (66666.666, ":'2g'", "66,666.67"),
+        (10000, ":'g'", "10,000"),
+        (10000, ":'-2g'", "10,000"),
+        (1234.5, ":'u'", "1234.5"),
+        (1234.5, ":'2gu'", "1234.50"),
+    ],
+)
+def test_floatformat(assert_render, value, argument, expected):
+    template = f"{{{{ value|floatformat{argument} }}}}"
+    assert_render(template, {"value": value}, expected)
+
+
+def test_floatformat_missing_variable(assert_render):
+    assert_render("{{ missing|floatformat }}", {}, "")
+
+
+def test_floatformat_safe_input(assert_render):
+    assert_render("{{ value|floatformat }}", {"value": mark_safe("<b>")}, "")
+    assert_render("{{ value|floatformat:'x' }}", {"value": mark_safe("1<b>")}, "")
+    assert_render("{{ value|floatformat:'x' }}", {"value": "1.5"}, "1.5")
+
+
+def test_floatformat_localized(assert_render):
+    with translation.override("de"):
+        assert_render("{{ value|floatformat:2 }}", {"value": 66666.666}, "66666,67")
+        assert_render(
+            "{{ value|floatformat:'2g' }}", {"value": 66666.666}, "66.666,67"
+        )
+        assert_render(
+            "{{ value|floatformat:'2u' }}", {"value": 66666.666}, "66666.67"
+        )
+
+
+@override_settings(USE_THOUSAND_SEPARATOR=True)
+def test_floatformat_thousand_separator(assert_render):
+    assert_render("{{ value|floatformat:2 }}", {"value": 66666.666}, "66,666.67")
+    assert_render("{{ value|floatformat:'2u' }}", {"value": 66666.666}, "66666.67")
+
+
+def test_floatformat_huge_integer():
+    # Django takes too long converting this to an `int` to test it here.
+    template = engines["rusty"].from_string("{{ value|floatformat }}")
+    with pytest.raises(ValueError) as exc_info:
+        template.render({"value": "1e999999999"})
+
+    assert "Exceeds the limit (4300 digits)" in str(exc_info.value)
@@ -0,0 +1,87 @@
+from inline_snapshot import snapshot
+import pytest
+
+
+@pytest.mark.parametrize(
+    "value,digit,expected",
+    [
+        pytest.param(123, 1, "3", id="first"),
+        pytest.param(123, 2, "2", id="second"),
+        pytest.param(123, 3, "1", id="third"),
+        pytest.param(123, 4, "0", id="too_far"),
+        pytest.param(123, 10**30, "0", id="huge"),
+        pytest.param(123, 0, "123", id="zero"),
+        pytest.param(123, -1, "123", id="negative"),
+        pytest.param("123", "2", "2", id="strings"),
+        pytest.param(12.7, 1, "2", id="float"),
+        pytest.param(True, 1, "1", id="bool"),
+        pytest.param("xyz", 1, "xyz", id="invalid_value"),
+        pytest.param(123, "abc", "123", id="invalid_argument"),
+        pytest.param("<b>", "abc", "&lt;b&gt;", id="escaped"),
+        pytest.param(-123, 3, "1", id="negative_value"),
+    ],
+)
+def test_get_digit(assert_render, value, digit, expected):
+    assert_render(
+        "{{ value|get_digit:digit }}", {"value": value, "digit": digit}, expected
+    )
+
+
+def test_get_digit_literal(assert_render):
+    assert_render("{{ 9876|get_digit:3 }}", {}, "8")
+
+
+def test_get_digit_missing(assert_render):
+    assert_render(".{{ missing|get_digit:1 }}.", {}, "..")
+
+
+def test_get_digit_no_argument(assert_parse_error):
+    assert_parse_error(
+        template="{{ n|get_digit }}",
+        django_message=snapshot("get_digit requires 2 arguments, 1 provided"),
+        rusty_message=snapshot("""\
+  × Expected an argument
+   ╭────
+ 1 │ {{ n|get_digit }}
+   ·      ────┬────
+   ·          ╰── here
+   ╰────
+"""),
+    )
+
+
+def test_get_digit_none(assert_render_error):
+    assert_render_error(
+        template="{{ n|get_digit:1 }}",
+        context={"n": None},
+        exception=TypeError,
+        django_message=snapshot(
+            "int() argument must be a string, a bytes-like object or a real number, not 'NoneType'"
+        ),
+        rusty_message=snapshot("""\
+  × int() argument must be a string, a bytes-like object or a real number, not
+  │ 'NoneType'
+   ╭────
+ 1 │ {{ n|get_digit:1 }}
+   ·      ────┬────
+   ·          ╰── here
+   ╰────
+"""),
+    )
+
+
+def test_get_digit_minus_sign(assert_render_error):
+    assert_render_error(
+        template="{{ n|get_digit:4 }}",
+        context={"n": -123},
+        exception=ValueError,
+        django_message=snapshot("invalid literal for int() with base 10: '-'"),
+        rusty_message=snapshot("""\
+  × invalid literal for int() with base 10: '-'
+   ╭────
+ 1 │ {{ n|get_digit:4 }}
+   ·      ────┬────
+   ·          ╰── here
+   ╰────
+"""),
+    )
@@ -0,0 +1,43 @@
+from inline_snapshot import snapshot
+import pytest
+from django.utils.safestring import mark_safe
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        pytest.param("S\xf8r-Tr\xf8ndelag", "S%C3%B8r-Tr%C3%B8ndelag", id="unicode"),
+        pytest.param(
+            "fran%C3%A7ois%20%26%20jill", "fran%C3%A7ois%20%26%20jill", id="quoted"
+        ),
+        pytest.param("a b", "a%20b", id="space"),
+        pytest.param("?test=1&me=2", "?test=1&amp;me=2", id="escaped"),
+        pytest.param(mark_safe("?test=1&me=2"), "?test=1&me=2", id="safe"),
+    ],
+)
+def test_iriencode(assert_render, value, expected):
+    assert_render("{{ value|iriencode }}", {"value": value}, expected)
+
+
+def test_iriencode_undefined(assert_render):
+    assert_render("{{ value|iriencode }}", {}, "")
+
+
+def test_iriencode_autoescape_off(assert_render):
+    template = "{% autoescape off %}{{ value|iriencode }}{% endautoescape %}"
+    assert_render(template, {"value": "?test=1&me=2"}, "?test=1&me=2")
+
+
+def test_iriencode_with_argument(assert_parse_error):
+    assert_parse_error(
+        template="{{ var|iriencode:arg }}",
+        django_message=snapshot("iriencode requires 1 arguments, 2 provided"),
+        rusty_message=snapshot("""\
+  × iriencode filter does not take an argument
+   ╭────
+ 1 │ {{ var|iriencode:arg }}
+   ·                  ─┬─
+   ·                   ╰── unexpected argument
+   ╰────
+"""),
+    )
@@ -0,0 +1,109 @@
+from inline_snapshot import snapshot
+import pytest
+from django.utils.safestring import mark_safe
+
+
+@pytest.mark.parametrize(
+    "template,context,expected",
+    [
+        pytest.param(
+            '{{ a|join:", " }}',
+            {"a": ["alpha", "beta & me"]},
+            "alpha, beta &amp; me",
+            id="escaped_items",
+        ),
+        pytest.param(
+            '{{ a|join:" &amp; " }}',
+            {"a": ["alpha", "beta & me"]},
+            "alpha &amp; beta &amp; me",
+            id="safe_literal_separator",
+        ),
+        pytest.param(
+            "{{ a|join:var }}",
+            {"a": ["alpha", "beta & me"], "var": " & "},
+            "alpha &amp; beta &amp; me",
+            id="escaped_separator",
+        ),
+        pytest.param(
+            "{{ a|join:var }}",
+            {"a": ["alpha", "beta & me"], "var": mark_safe(" & ")},
+            "alpha & beta &amp; me",
+            id="safe_separator",
+        ),
+        pytest.param(
+            '{{ a|join:"," }}',
+            {"a": ["<a>", mark_safe("<b>")]},
+            "&lt;a&gt;,<b>",
+            id="safe_item",
+        ),
+        pytest.param('{{ a|join:"," }}', {"a": [1, 2]}, "1,2", id="ints"),
+        pytest.param('{{ a|join:"-" }}', {"a": "abc"}, "a-b-c", id="string"),
+        pytest.param('{{ a|join:"-" }}', {"a": mark_safe("<>")}, "&lt;-&gt;", id="safe_string"),
+        pytest.param("{{ a|join:5 }}", {"a": ["a", "b"]}, "a5b", id="int_separator"),
+        pytest.param('{{ a|join:"," }}', {"a": 123}, "123", id="not_iterable"),
+        pytest.param('{{ a|join:"," }}', {}, "", id="undefined"),
+        pytest.param(
+            '{{ a|join:"," }}',
+            {"a": (x for x in ["a", "b"])},
+            "a,b",
+            id="generator",
+        ),
+    ],
+)
+def test_join(assert_render, template, context, expected):
+    assert_render(template, context, expected)
+
+
+@pytest.mark.parametrize(
+    "template,context,expected",
+    [
+        pytest.param(
+            '{{ a|join:", " }}',
+            {"a": ["alpha", "beta & me"]},
+            "alpha, beta & me",
+            id="items",
+        ),
+        pytest.param(
+            "{{ a|join:var }}",
+            {"a": ["alpha", "beta & me"], "var": " & "},
+            "alpha & beta & me",
+            id="separator",
+        ),
+        pytest.param('{{ a|join:"," }}', {"a": [1, 2]}, "[1, 2]", id="not_strings"),
+    ],
+)
+def test_join_autoescape_off(assert_render, template, context, expected):
+    template = f"{{% autoescape off %}}{template}{{% endautoescape %}}"
+    assert_render(template, context, expected)
+
+
+def test_join_autoescape_off_int_separator(assert_render_error):
+    assert_render_error(
+        template="{% autoescape off %}{{ a|join:5 }}{% endautoescape %}",
+        context={"a": ["a", "b"]},
+        exception=AttributeError,
+        django_message=snapshot("'int' object has no attribute 'join'"),
+        rusty_message=snapshot("""\
+  × 'int' object has no attribute 'join'
+   ╭────
+ 1 │ {% autoescape off %}{{ a|join:5 }}{% endautoescape %}
+   ·                               ┬
+   ·                               ╰── here
+   ╰────
+"""),
+    )
+
+
+def test_join_no_argument(assert_parse_error):
+    assert_parse_error(
+        template="{{ a|join }}",
+        django_message=snapshot("join requires 2 arguments, 1 provided"),
+        rusty_message=snapshot("""\
+  × Expected an argument
+   ╭────
+ 1 │ {{ a|join }}
+   ·      ──┬─
+   ·        ╰── here
+   ╰────
+"""),
+    )
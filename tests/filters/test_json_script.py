@@ -0,0 +1,90 @@
+import datetime
+from decimal import Decimal
+
+from inline_snapshot import snapshot
+import pytest
+from django.utils.safestring import mark_safe
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        pytest.param(
+            {"a": "<b>&'\"", "c": [1, 2.5, None, True]},
+            '{"a": "\\u003Cb\\u003E\\u0026\'\\"", "c": [1, 2.5, null, true]}',
+            id="dict",
+        ),
+        pytest.param("é\n", '"\\u00e9\\n"', id="string"),
+        pytest.param(mark_safe("<b>"), '"\\u003Cb\\u003E"', id="safe_string"),
+        pytest.param((1, 2), "[1, 2]", id="tuple"),
+        pytest.param({1: 2, None: 3}, '{"1": 2, "null": 3}', id="non_string_keys"),
+        pytest.param(float("inf"), "Infinity", id="infinity"),
+        pytest.param(1e16, "1e+16", id="float"),
+        pytest.param(
+            {"when": datetime.datetime(2024, 1, 2, 3, 4, 5)},
+            '{"when": "2024-01-02T03:04:05"}',
+            id="datetime",
+        ),
+        pytest.param([Decimal("1.50")], '["1.50"]', id="decimal"),
+    ],
+)
+def test_json_script(assert_render, value, expected):
+    template = "{{ value|json_script }}"
+    expected = f'<script type="application/json">{expected}</script>'
+    assert_render(template, {"value": value}, expected)
+
+
+def test_json_script_element_id(assert_render):
+    template = '{{ value|json_script:"test_id" }}'
+    expected = '<script id="test_id" type="application/json">{"a": 1}</script>'
+    assert_render(template, {"value": {"a": 1}}, expected)
+
+
+def test_json_script_escaped_element_id(assert_render):
+    template = "{{ value|json_script:element_id }}"
+    context = {"value": 1, "element_id": "<x>"}
+    expected = '<script id="&lt;x&gt;" type="application/json">1</script>'
+    assert_render(template, context, expected)
+
+
+def test_json_script_safe_element_id(assert_render):
+    template = "{{ value|json_script:element_id }}"
+    context = {"value": 1, "element_id": mark_safe("<x>")}
+    expected = '<script id="<x>" type="application/json">1</script>'
+    assert_render(template, context, expected)
+
+
+def test_json_script_empty_element_id(assert_render):
+    template = '{{ value|json_script:"" }}'
+    expected = '<script type="application/json">1</script>'
+    assert_render(template, {"value": 1}, expected)
+
+
+def test_json_script_autoescape_off(assert_render):
+    template = (
+        "{% autoescape off %}{{ value|json_script }}{% endautoescape %}"
+    )
+    expected = '<script type="application/json">"\\u003C/script\\u003E"</script>'
+    assert_render(template, {"value": "</script>"}, expected)
+
+
+def test_json_script_undefined(assert_render):
+    expected = '<script type="application/json">""</script>'
+    assert_render("{{ value|json_script }}", {}, expected)
+
+
+def test_json_script_not_serializable(assert_render_error):
+    assert_render_error(
+        template="{{ value|json_script }}",
+        context={"value": {"a": object()}},
+        exception=TypeError,
+        django_message=snapshot("Object of type object is not JSON serializable"),
+        rusty_message=snapshot("""\
+  × Object of type object is not JSON serializable
+   ╭────
+ 1 │ {{ value|json_script }}
+   ·          ─────┬─────
+   ·               ╰── here
+   ╰────
+"""),
+    )
@@ -0,0 +1,46 @@
+from inline_snapshot import snapshot
+import pytest
+from django.utils.safestring import mark_safe
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        pytest.param("line 1", "<p>line 1</p>", id="single_line"),
+        pytest.param("line 1\nline 2", "<p>line 1<br>line 2</p>", id="newline"),
+        pytest.param("line 1\rline 2", "<p>line 1<br>line 2</p>", id="carriage_return"),
+        pytest.param("line 1\r\nline 2", "<p>line 1<br>line 2</p>", id="crlf"),
+        pytest.param("a\n\nb", "<p>a</p>\n\n<p>b</p>", id="paragraphs"),
+        pytest.param("a\r\n\r\n\r\nb", "<p>a</p>\n\n<p>b</p>", id="many_newlines"),
+        pytest.param("a\n\n", "<p>a</p>\n\n<p></p>", id="trailing_newlines"),
+        pytest.param("x&\ny", "<p>x&amp;<br>y</p>", id="escaped"),
+        pytest.param(mark_safe("x&\ny"), "<p>x&<br>y</p>", id="safe"),
+        pytest.param(123, "<p>123</p>", id="int"),
+    ],
+)
+def test_linebreaks(assert_render, value, expected):
+    assert_render("{{ value|linebreaks }}", {"value": value}, expected)
+
+
+def test_linebreaks_undefined(assert_render):
+    assert_render("{{ value|linebreaks }}", {}, "<p></p>")
+
+
+def test_linebreaks_autoescape_off(assert_render):
+    template = "{% autoescape off %}{{ value|linebreaks }}{% endautoescape %}"
+    assert_render(template, {"value": "x&\ny"}, "<p>x&<br>y</p>")
+
+
+def test_linebreaks_with_argument(assert_parse_error):
+    assert_parse_error(
+        template="{{ var|linebreaks:arg }}",
+        django_message=snapshot("linebreaks requires 1 arguments, 2 provided"),
+        rusty_message=snapshot("""\
+  × linebreaks filter does not take an argument
+   ╭────
+ 1 │ {{ var|linebreaks:arg }}
+   ·                   ─┬─
+   ·                    ╰── unexpected argument
+   ╰────
+"""),
+    )
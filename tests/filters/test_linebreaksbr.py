@@ -0,0 +1,30 @@
+import pytest
+from django.utils.safestring import mark_safe
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        pytest.param("line 1\nline 2", "line 1<br>line 2", id="newline"),
+        pytest.param("line 1\rline 2", "line 1<br>line 2", id="carriage_return"),
+        pytest.param("line 1\r\nline 2", "line 1<br>line 2", id="crlf"),
+        pytest.param(
+            "foo\n<a>bar</a>\n",
+            "foo<br>&lt;a&gt;bar&lt;/a&gt;<br>",
+            id="escaped",
+        ),
+        pytest.param(mark_safe("foo\n<a>bar</a>\n"), "foo<br><a>bar</a><br>", id="safe"),
+        pytest.param(123, "123", id="int"),
+    ],
+)
+def test_linebreaksbr(assert_render, value, expected):
+    assert_render("{{ value|linebreaksbr }}", {"value": value}, expected)
+
+
+def test_linebreaksbr_undefined(assert_render):
+    assert_render("{{ value|linebreaksbr }}", {}, "")
+
+
+def test_linebreaksbr_autoescape_off(assert_render):
+    template = "{% autoescape off %}{{ value|linebreaksbr }}{% endautoescape %}"
+    assert_render(template, {"value": "foo\n<a>bar</a>"}, "foo<br><a>bar</a>")
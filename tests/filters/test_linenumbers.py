@@ -0,0 +1,29 @@
+import pytest
+from django.utils.safestring import mark_safe
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        pytest.param("line 1\nline 2", "1. line 1\n2. line 2", id="lines"),
+        pytest.param(
+            "\n".join(["x"] * 10),
+            "\n".join(f"{i:02}. x" for i in range(1, 11)),
+            id="padded",
+        ),
+        pytest.param("x&y\n<b>", "1. x&amp;y\n2. &lt;b&gt;", id="escaped"),
+        pytest.param(mark_safe("x&y\n<b>"), "1. x&y\n2. <b>", id="safe"),
+        pytest.param("a\r\nb", "1. a\r\n2. b", id="crlf"),
+    ],
+)
+def test_linenumbers(assert_render, value, expected):
+    assert_render("{{ value|linenumbers }}", {"value": value}, expected)
+
+
+def test_linenumbers_undefined(assert_render):
+    assert_render("{{ value|linenumbers }}", {}, "1. ")
+
+
+def test_linenumbers_autoescape_off(assert_render):
+    template = "{% autoescape off %}{{ value|linenumbers }}{% endautoescape %}"
+    assert_render(template, {"value": "x&y\n<b>"}, "1. x&y\n2. <b>")
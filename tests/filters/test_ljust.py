@@ -0,0 +1,65 @@
+from django.utils.safestring import mark_safe
+from inline_snapshot import snapshot
+import pytest
+
+
+def test_ljust_autoescape_off(assert_render):
+    template = (
+        '{% autoescape off %}.{{ a|ljust:"5" }}. .{{ b|ljust:"5" }}.{% endautoescape %}'
+    )
+    context = {"a": "a&b", "b": mark_safe("a&b")}
+    assert_render(template, context, ".a&b  . .a&b  .")
+
+
+def test_ljust(assert_render):
+    template = '.{{ a|ljust:"5" }}. .{{ b|ljust:"5" }}.'
+    context = {"a": "a&b", "b": mark_safe("a&b")}
+    assert_render(template, context, ".a&amp;b  . .a&b  .")
+
+
+@pytest.mark.parametrize(
+    "value,width,expected",
+    [
+        pytest.param("test", 2, "test", id="shorter"),
+        pytest.param("test", -5, "test", id="negative"),
+        pytest.param("test", 6.5, "test  ", id="float"),
+        pytest.param("test", "6", "test  ", id="string"),
+        pytest.param("test", " 6 ", "test  ", id="string_with_spaces"),
+        pytest.param("café", 6, "café  ", id="unicode"),
+        pytest.param(123, 5, "123  ", id="int"),
+    ],
+)
+def test_ljust_width(assert_render, value, width, expected):
+    assert_render("{{ value|ljust:width }}", {"value": value, "width": width}, expected)
+
+
+def test_ljust_no_argument(assert_parse_error):
+    assert_parse_error(
+        template="{{ foo|ljust }}",
+        django_message=snapshot("ljust requires 2 arguments, 1 provided"),
+        rusty_message=snapshot("""\
+  × Expected an argument
+   ╭────
+ 1 │ {{ foo|ljust }}
+   ·        ──┬──
+   ·          ╰── here
+   ╰────
+"""),
+    )
+
+
+def test_ljust_argument_string(assert_render_error):
+    assert_render_error(
+        template="{{ foo|ljust:'foo' }}",
+        context={"foo": "test"},
+        exception=ValueError,
+        django_message=snapshot("invalid literal for int() with base 10: 'foo'"),
+        rusty_message=snapshot("""\
+  × Couldn't convert argument ('foo') to integer
+   ╭────
+ 1 │ {{ foo|ljust:'foo' }}
+   ·              ──┬──
+   ·                ╰── argument
+   ╰────
+"""),
+    )
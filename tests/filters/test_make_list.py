@@ -0,0 +1,29 @@
+import pytest
+from django.utils.safestring import mark_safe
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        pytest.param("abc", "[&#x27;a&#x27;, &#x27;b&#x27;, &#x27;c&#x27;]", id="string"),
+        pytest.param(123, "[&#x27;1&#x27;, &#x27;2&#x27;, &#x27;3&#x27;]", id="int"),
+        pytest.param(mark_safe("<"), "[&#x27;&lt;&#x27;]", id="safe"),
+        pytest.param("", "[]", id="empty"),
+    ],
+)
+def test_make_list(assert_render, value, expected):
+    assert_render("{{ value|make_list }}", {"value": value}, expected)
+
+
+def test_make_list_undefined(assert_render):
+    assert_render("{{ value|make_list }}", {}, "[]")
+
+
+def test_make_list_autoescape_off(assert_render):
+    template = "{% autoescape off %}{{ value|make_list }}{% endautoescape %}"
+    assert_render(template, {"value": "a<"}, "['a', '<']")
+
+
+def test_make_list_chained(assert_render):
+    template = '{{ value|make_list|join:"-" }} {{ value|make_list|first }}'
+    assert_render(template, {"value": "a&b"}, "a-&amp;-b a")
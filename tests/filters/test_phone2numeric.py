@@ -0,0 +1,48 @@
+from django.utils.safestring import mark_safe
+from inline_snapshot import snapshot
+
+
+def test_phone2numeric(assert_render):
+    template = "{{ a|phone2numeric }} {{ b|phone2numeric }}"
+    context = {"a": "<1-800-call-me>", "b": mark_safe("<1-800-call-me>")}
+    assert_render(template, context, "&lt;1-800-2255-63&gt; <1-800-2255-63>")
+
+
+def test_phone2numeric_autoescape_off(assert_render):
+    template = (
+        "{% autoescape off %}"
+        "{{ a|phone2numeric }} {{ b|phone2numeric }}"
+        "{% endautoescape %}"
+    )
+    context = {"a": "<1-800-call-me>", "b": mark_safe("<1-800-call-me>")}
+    assert_render(template, context, "<1-800-2255-63> <1-800-2255-63>")
+
+
+def test_phone2numeric_all_letters(assert_render):
+    template = "{{ a|phone2numeric }}"
+    context = {"a": "How razorback-jumping frogs can level six piqued gymnasts!"}
+    expected = "469 729672225-5867464 37647 226 53835 749 747833 49662787!"
+    assert_render(template, context, expected)
+
+
+def test_phone2numeric_non_string(assert_render):
+    assert_render("{{ a|phone2numeric }}", {"a": 123}, "123")
+
+
+def test_phone2numeric_missing(assert_render):
+    assert_render("{{ a|phone2numeric }}", {}, "")
+
+
+def test_phone2numeric_unexpected_argument(assert_parse_error):
+    assert_parse_error(
+        template="{{ value|phone2numeric:1 }}",
+        django_message=snapshot("phone2numeric requires 1 arguments, 2 provided"),
+        rusty_message=snapshot("""\
+  × phone2numeric filter does not take an argument
+   ╭────
+ 1 │ {{ value|phone2numeric:1 }}
+   ·                        ┬
+   ·                        ╰── unexpected argument
+   ╰────
+"""),
+    )
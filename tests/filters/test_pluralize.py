@@ -0,0 +1,87 @@
+from decimal import Decimal
+
+from inline_snapshot import snapshot
+import pytest
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        pytest.param(0, "s", id="zero"),
+        pytest.param(1, "", id="one"),
+        pytest.param(2, "s", id="two"),
+        pytest.param(1.0, "", id="float_one"),
+        pytest.param(1.5, "s", id="float"),
+        pytest.param(Decimal("1"), "", id="decimal"),
+        pytest.param(True, "", id="true"),
+        pytest.param("1", "", id="string_one"),
+        pytest.param(" 1.0 ", "", id="string_float"),
+        pytest.param("2", "s", id="string_two"),
+        pytest.param("1_0", "s", id="string_underscore"),
+        pytest.param("abc", "", id="not_a_number"),
+        pytest.param([1], "", id="list_one"),
+        pytest.param([1, 2], "s", id="list_two"),
+        pytest.param([], "s", id="empty_list"),
+        pytest.param(object(), "", id="unsized"),
+    ],
+)
+def test_pluralize(assert_render, value, expected):
+    assert_render("{{ value|pluralize }}", {"value": value}, expected)
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [(0, "ies"), (1, "y"), (2, "ies")],
+)
+def test_pluralize_singular_and_plural(assert_render, value, expected):
+    assert_render('{{ value|pluralize:"y,ies" }}', {"value": value}, expected)
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [(1, ""), (2, "es")],
+)
+def test_pluralize_suffix(assert_render, value, expected):
+    assert_render('{{ value|pluralize:"es" }}', {"value": value}, expected)
+
+
+def test_pluralize_too_many_bits(assert_render):
+    assert_render('{{ value|pluralize:"y,ie,s" }}', {"value": 2}, "")
+
+
+def test_pluralize_variable_argument(assert_render):
+    template = "{{ value|pluralize:suffix }}"
+    assert_render(template, {"value": 1, "suffix": "um,a"}, "um")
+
+
+def test_pluralize_undefined(assert_render):
+    assert_render("{{ value|pluralize }}", {}, "")
+
+
+def test_pluralize_escaped(assert_render):
+    assert_render('{{ value|pluralize:"<,>" }}', {"value": 2}, "&gt;")
+
+
+def test_pluralize_autoescape_off(assert_render):
+    template = '{% autoescape off %}{{ value|pluralize:"<,>" }}{% endautoescape %}'
+    assert_render(template, {"value": 1}, "<")
+
+
+def test_pluralize_argument_not_string(assert_render_error):
+    assert_render_error(
+        template="{{ value|pluralize:1 }}",
+        context={"value": 1},
+        exception=TypeError,
+        rusty_exception=ValueError,
+        django_message=snapshot(
+            "argument of type 'int' is not a container or iterable"
+        ),
+        rusty_message=snapshot("""\
+  × String argument expected
+   ╭────
+ 1 │ {{ value|pluralize:1 }}
+   ·                    ┬
+   ·                    ╰── here
+   ╰────
+"""),
+    )
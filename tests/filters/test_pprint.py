@@ -0,0 +1,61 @@
+from django.utils.safestring import mark_safe
+from inline_snapshot import snapshot
+
+
+def test_pprint(assert_render):
+    template = "{{ value|pprint }}"
+    context = {"value": {"b": [1, 2], "a": ("x", None)}}
+    expected = "{&#x27;a&#x27;: (&#x27;x&#x27;, None), &#x27;b&#x27;: [1, 2]}"
+    assert_render(template, context, expected)
+
+
+def test_pprint_autoescape_off(assert_render):
+    template = "{% autoescape off %}{{ value|pprint }}{% endautoescape %}"
+    context = {"value": ["<a>", 1.5, True]}
+    assert_render(template, context, "['<a>', 1.5, True]")
+
+
+def test_pprint_safe(assert_render):
+    template = "{{ value|pprint }}"
+    assert_render(template, {"value": mark_safe("<a>")}, "'<a>'")
+
+
+def test_pprint_literal(assert_render):
+    template = "{{ 1|pprint }} {{ 'a'|pprint }} {{ 2.5|pprint }}"
+    assert_render(template, {}, "1 &#x27;a&#x27; 2.5")
+
+
+def test_pprint_long(assert_render):
+    template = "{% autoescape off %}{{ value|pprint }}{% endautoescape %}"
+    value = list(range(30))
+    expected = "[" + ",\n ".join(str(n) for n in value) + "]"
+    assert_render(template, {"value": value}, expected)
+
+
+def test_pprint_missing(assert_render):
+    assert_render("{{ value|pprint }}", {}, "&#x27;&#x27;")
+
+
+def test_pprint_broken_repr(assert_render):
+    class Broken:
+        def __repr__(self):
+            raise ValueError("bad repr")
+
+    template = "{{ value|pprint }}"
+    expected = "Error in formatting: ValueError: bad repr"
+    assert_render(template, {"value": Broken()}, expected)
+
+
+def test_pprint_unexpected_argument(assert_parse_error):
+    assert_parse_error(
+        template="{{ value|pprint:1 }}",
+        django_message=snapshot("pprint requires 1 arguments, 2 provided"),
+        rusty_message=snapshot("""\
+  × pprint filter does not take an argument
+   ╭────
+ 1 │ {{ value|pprint:1 }}
+   ·                 ┬
+   ·                 ╰── unexpected argument
+   ╰────
+"""),
+    )
@@ -0,0 +1,44 @@
+from inline_snapshot import snapshot
+import pytest
+from django.utils.safestring import mark_safe
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        pytest.param(["a&b"], "a&amp;b", id="list"),
+        pytest.param([mark_safe("a&b")], "a&b", id="safe_item"),
+        pytest.param("&", "&amp;", id="string"),
+        pytest.param(mark_safe("&"), "&", id="safe_string"),
+        pytest.param([], "", id="empty_list"),
+        pytest.param("", "", id="empty_string"),
+    ],
+)
+def test_random(assert_render, value, expected):
+    assert_render("{{ value|random }}", {"value": value}, expected)
+
+
+def test_random_choice(render_output):
+    for _ in range(10):
+        assert render_output("{{ value|random }}", {"value": ["a", "b", "c"]}) in "abc"
+
+
+def test_random_undefined(assert_render):
+    assert_render("{{ value|random }}", {}, "")
+
+
+def test_random_no_len(assert_render_error):
+    assert_render_error(
+        template="{{ value|random }}",
+        context={"value": 1},
+        exception=TypeError,
+        django_message=snapshot("object of type 'int' has no len()"),
+        rusty_message=snapshot("""\
+  × object of type 'int' has no len()
+   ╭────
+ 1 │ {{ value|random }}
+   ·          ───┬──
+   ·             ╰── here
+   ╰────
+"""),
+    )
@@ -0,0 +1,55 @@
+from inline_snapshot import snapshot
+import pytest
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        pytest.param(["<a>", "&"], "<a>, &", id="list"),
+        pytest.param(("<a>", 1), "<a>, 1", id="tuple"),
+        pytest.param("<>", "<, >", id="string"),
+        pytest.param([], "", id="empty"),
+    ],
+)
+def test_safeseq(assert_render, value, expected):
+    assert_render('{{ value|safeseq|join:", " }}', {"value": value}, expected)
+
+
+def test_safeseq_without_join(assert_render):
+    assert_render("{{ value|safeseq }}", {"value": ["<a>"]}, "[&#x27;&lt;a&gt;&#x27;]")
+
+
+def test_safeseq_undefined(assert_render):
+    assert_render('{{ value|safeseq|join:", " }}', {}, "")
+
+
+def test_safeseq_not_iterable(assert_render_error):
+    assert_render_error(
+        template="{{ value|safeseq }}",
+        context={"value": 1},
+        exception=TypeError,
+        django_message=snapshot("'int' object is not iterable"),
+        rusty_message=snapshot("""\
+  × 'int' object is not iterable
+   ╭────
+ 1 │ {{ value|safeseq }}
+   ·          ───┬───
+   ·             ╰── here
+   ╰────
+"""),
+    )
+
+
+def test_safeseq_unexpected_argument(assert_parse_error):
+    assert_parse_error(
+        template="{{ value|safeseq:1 }}",
+        django_message=snapshot("safeseq requires 1 arguments, 2 provided"),
+        rusty_message=snapshot("""\
+  × safeseq filter does not take an argument
+   ╭────
+ 1 │ {{ value|safeseq:1 }}
+   ·                  ┬
+   ·                  ╰── unexpected argument
+   ╰────
+"""),
+    )
@@ -0,0 +1,64 @@
+from inline_snapshot import snapshot
+import pytest
+from django.utils.safestring import mark_safe
+
+
+@pytest.mark.parametrize(
+    "argument,expected",
+    [
+        pytest.param("1:3", "bc", id="start_stop"),
+        pytest.param("-2:", "fg", id="negative_start"),
+        pytest.param(":-2", "abcde", id="negative_stop"),
+        pytest.param("::2", "aceg", id="step"),
+        pytest.param("::-1", "gfedcba", id="negative_step"),
+        pytest.param("5:1:-2", "fd", id="start_stop_negative_step"),
+        pytest.param("2", "ab", id="stop"),
+        pytest.param("-100:100", "abcdefg", id="out_of_range"),
+        pytest.param("0::0", "abcdefg", id="zero_step"),
+        pytest.param("a", "abcdefg", id="invalid"),
+        pytest.param("1:2:3:4", "abcdefg", id="too_many_bits"),
+    ],
+)
+def test_slice(assert_render, argument, expected):
+    template = f'{{{{ value|slice:"{argument}" }}}}'
+    assert_render(template, {"value": "abcdefg"}, expected)
+
+
+@pytest.mark.parametrize(
+    "value,argument,expected",
+    [
+        pytest.param([1, 2, 3], ":2", "[1, 2]", id="list"),
+        pytest.param((1, 2, 3), "::-1", "(3, 2, 1)", id="tuple"),
+        pytest.param(range(5), "1:3", "range(1, 3)", id="range"),
+        pytest.param("<b>x</b>", ":3", "&lt;b&gt;", id="escaped"),
+        pytest.param(mark_safe("<b>x</b>"), ":3", "<b>", id="safe"),
+        pytest.param(123, ":1", "123", id="int"),
+    ],
+)
+def test_slice_values(assert_render, value, argument, expected):
+    template = f'{{{{ value|slice:"{argument}" }}}}'
+    assert_render(template, {"value": value}, expected)
+
+
+def test_slice_variable_argument(assert_render):
+    template = "{{ value|slice:argument }}"
+    assert_render(template, {"value": "abcdefg", "argument": 3}, "abc")
+
+
+def test_slice_undefined(assert_render):
+    assert_render('{{ value|slice:":2" }}', {}, "")
+
+
+def test_slice_no_argument(assert_parse_error):
+    assert_parse_error(
+        template="{{ value|slice }}",
+        django_message=snapshot("slice requires 2 arguments, 1 provided"),
+        rusty_message=snapshot("""\
+  × Expected an argument
+   ╭────
+ 1 │ {{ value|slice }}
+   ·          ──┬──
+   ·            ╰── here
+   ╰────
+"""),
+    )
@@ -0,0 +1,124 @@
+from decimal import Decimal
+
+from inline_snapshot import snapshot
+import pytest
+from django.utils.safestring import mark_safe
+
+
+@pytest.mark.parametrize(
+    "value,argument,expected",
+    [
+        pytest.param(1, "03d", "001", id="zero_padded"),
+        pytest.param(-1, "+05d", "-0001", id="negative_zero_padded"),
+        pytest.param(42, "+d", "+42", id="sign"),
+        pytest.param(255, "#x", "0xff", id="hex"),
+        pytest.param(255, "X", "FF", id="upper_hex"),
+        pytest.param(8, "o", "10", id="octal"),
+        pytest.param(3.9, "d", "3", id="float_as_int"),
+        pytest.param(True, "d", "1", id="bool_as_int"),
+        pytest.param(100, "E", "1.000000E+02", id="exponent"),
+        pytest.param(0.000123, ".2e", "1.23e-04", id="small_exponent"),
+        pytest.param(3.14159, ".2f", "3.14", id="fixed"),
+        pytest.param(1.0, "#.0f", "1.", id="alternate_fixed"),
+        pytest.param(0.00001, "g", "1e-05", id="general_small"),
+        pytest.param(123456789.0, "g", "1.23457e+08", id="general_large"),
+        pytest.param(2.5, "g", "2.5", id="general"),
+        pytest.param(1e16, "s", "1e+16", id="float_str"),
+        pytest.param(10, "s", "10", id="int_str"),
+        pytest.param(True, "s", "True", id="bool_str"),
+        pytest.param("abc", "s", "abc", id="string"),
+        pytest.param("abc", "5s", "  abc", id="string_padded"),
+        pytest.param("abc", "-5s.", "abc  .", id="string_left_adjusted"),
+        pytest.param("abcdef", ".3s", "abc", id="string_precision"),
+        pytest.param(5, "d items", "5 items", id="trailing_text"),
+        pytest.param((1, 2), "s", "(1, 2)", id="tuple"),
+        pytest.param([1, 2], "s", "[1, 2]", id="list"),
+        pytest.param(Decimal("1.5"), ".2f", "1.50", id="decimal"),
+        pytest.param(float("inf"), "f", "inf", id="infinity"),
+        pytest.param(2**100, ".3e", "1.268e+30", id="big_int_exponent"),
+        pytest.param(65, "c", "A", id="char"),
+        pytest.param("a", "r", "&#x27;a&#x27;", id="repr"),
+    ],
+)
+def test_stringformat(assert_render, value, argument, expected):
+    template = f'{{{{ value|stringformat:"{argument}" }}}}'
+    assert_render(template, {"value": value}, expected)
+
+
+@pytest.mark.parametrize(
+    "value,argument",
+    [
+        pytest.param("abc", "d", id="string_as_int"),
+        pytest.param(1.5, "x", id="float_as_hex"),
+        pytest.param(1, "", id="empty"),
+        pytest.param(1, "5", id="incomplete"),
+        pytest.param(1, "y", id="unsupported"),
+        pytest.param(1, "d%d", id="too_few_values"),
+        pytest.param((1, 2), "d", id="tuple_as_int"),
+        pytest.param(float("nan"), "d", id="nan_as_int"),
+    ],
+)
+def test_stringformat_invalid(assert_render, value, argument):
+    template = f'{{{{ value|stringformat:"{argument}" }}}}'
+    assert_render(template, {"value": value}, "")
+
+
+def test_stringformat_variable_argument(assert_render):
+    template = "{{ value|stringformat:argument }}"
+    assert_render(template, {"value": 1.5, "argument": "05.1f"}, "001.5")
+
+
+def test_stringformat_int_argument(assert_render):
+    assert_render("{{ value|stringformat:5 }}", {"value": 1}, "")
+
+
+def test_stringformat_undefined(assert_render):
+    assert_render('{{ value|stringformat:"s" }}', {}, "")
+
+
+def test_stringformat_escaped(assert_render):
+    assert_render('{{ value|stringformat:"s" }}', {"value": "<b>"}, "&lt;b&gt;")
+
+
+def test_stringformat_safe(assert_render):
+    template = '{{ value|stringformat:"s" }}'
+    assert_render(template, {"value": mark_safe("<b>")}, "<b>")
+
+
+def test_stringformat_autoescape_off(assert_render):
+    template = (
+        '{% autoescape off %}{{ value|stringformat:"s" }}{% endautoescape %}'
+    )
+    assert_render(template, {"value": "<b>"}, "<b>")
+
+
+def test_stringformat_no_argument(assert_parse_error):
+    assert_parse_error(
+        template="{{ value|stringformat }}",
+        django_message=snapshot("stringformat requires 2 arguments, 1 provided"),
+        rusty_message=snapshot("""\
+  × Expected an argument
+   ╭────
+ 1 │ {{ value|stringformat }}
+   ·          ──────┬─────
+   ·                ╰── here
+   ╰────
+"""),
+    )
+
+
+def test_stringformat_overflow(assert_render_error):
+    assert_render_error(
+        template='{{ value|stringformat:"d" }}',
+        context={"value": float("inf")},
+        exception=OverflowError,
+        django_message=snapshot("cannot convert float infinity to integer"),
+        rusty_message=snapshot("""\
+  × cannot convert float infinity to integer
+   ╭────
+ 1 │ {{ value|stringformat:"d" }}
+   ·          ──────┬─────
+   ·                ╰── here
+   ╰────
+"""),
+    )
@@ -0,0 +1,35 @@
+import pytest
+from django.utils.safestring import mark_safe
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        pytest.param("<a>x</a> <p><b>y</b></p>", "x y", id="tags"),
+        pytest.param(
+            'some <b>html</b> with <script>alert("You smell")</script> '
+            "disallowed <img /> tags",
+            "some html with alert(&quot;You smell&quot;) disallowed  tags",
+            id="script",
+        ),
+        pytest.param("no tags", "no tags", id="no_tags"),
+        pytest.param("1 > 0", "1 &gt; 0", id="only_greater_than"),
+        pytest.param("a < b and c > d", "a &lt; b and c &gt; d", id="not_a_tag"),
+        pytest.param("<<b>script>alert(1)<</b>/script>", "alert(1)", id="nested"),
+        pytest.param("a<!-- c -->b<br/>c", "abc", id="comment_self_closing"),
+        pytest.param("<p>unclosed", "unclosed", id="unclosed"),
+        pytest.param(mark_safe("<p>x &amp; y</p>"), "x &amp; y", id="safe"),
+        pytest.param(123, "123", id="int"),
+    ],
+)
+def test_striptags(assert_render, value, expected):
+    assert_render("{{ value|striptags }}", {"value": value}, expected)
+
+
+def test_striptags_undefined(assert_render):
+    assert_render("{{ value|striptags }}", {}, "")
+
+
+def test_striptags_autoescape_off(assert_render):
+    template = "{% autoescape off %}{{ value|striptags }}{% endautoescape %}"
+    assert_render(template, {"value": "<b>a & b</b>"}, "a & b")
@@ -0,0 +1,66 @@
+from datetime import date, datetime, time
+
+import pytest
+from django.utils import timezone, translation
+
+
+@pytest.mark.parametrize(
+    "template,context,expected",
+    [
+        pytest.param('{{ t|time:"H:i" }}', {"t": time(0, 1)}, "00:01", id="time"),
+        pytest.param("{{ t|time }}", {"t": time(13, 30)}, "1:30 p.m.", id="default"),
+        pytest.param(
+            '{{ d|time:"P" }}', {"d": datetime(2008, 1, 1, 12)}, "noon", id="datetime"
+        ),
+        pytest.param(
+            '{{ t|time:"g:i:s.u A" }}',
+            {"t": time(23, 5, 9, 120)},
+            "11:05:09.000120 PM",
+            id="all_fields",
+        ),
+        pytest.param(
+            r'{{ t|time:"H\h i\m" }}', {"t": time(9, 5)}, "09h 05m", id="escaped"
+        ),
+        pytest.param(
+            '{{ d|time:"e" }}',
+            {"d": datetime(2009, 3, 12, tzinfo=timezone.get_fixed_timezone(30))},
+            "+0030",
+            id="timezone_name",
+        ),
+        pytest.param(
+            '{{ t|time:"O" }}',
+            {"t": time(12, tzinfo=timezone.get_fixed_timezone(30))},
+            "",
+            id="time_has_no_timezone",
+        ),
+        pytest.param(
+            '{{ t|time:"<H>" }}', {"t": time(9)}, "&lt;09&gt;", id="escaped_html"
+        ),
+        pytest.param(
+            '{{ t|time:"" }}', {"t": time(13, 30)}, "1:30 p.m.", id="empty_format"
+        ),
+    ],
+)
+def test_time(assert_render, template, context, expected):
+    assert_render(template, context, expected)
+
+
+@pytest.mark.parametrize(
+    "template,context",
+    [
+        pytest.param('{{ t|time:"Y" }}', {"t": time(13, 30)}, id="date_format"),
+        pytest.param('{{ d|time:"H" }}', {"d": date(2008, 1, 1)}, id="date"),
+        pytest.param("{{ t|time }}", {"t": None}, id="none"),
+        pytest.param("{{ t|time }}", {"t": ""}, id="empty_string"),
+        pytest.param("{{ t|time }}", {"t": "abc"}, id="string"),
+        pytest.param("{{ t|time }}", {"t": 123}, id="int"),
+        pytest.param("{{ t|time }}", {}, id="missing"),
+    ],
+)
+def test_time_empty(assert_render, template, context):
+    assert_render(template, context, "")
+
+
+def test_time_localized(assert_render):
+    with translation.override("fr"):
+        assert_render("{{ t|time }}", {"t": time(13, 30)}, "13:30")
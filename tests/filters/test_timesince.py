@@ -0,0 +1,105 @@
+from datetime import date, datetime, timezone
+
+from inline_snapshot import snapshot
+import pytest
+from django.utils import translation
+from django.utils.timezone import get_fixed_timezone
+
+
+@pytest.mark.parametrize(
+    "a,b,expected",
+    [
+        pytest.param(datetime(2008, 1, 1), datetime(2008, 1, 2), "1\xa0day", id="day"),
+        pytest.param(
+            datetime(2008, 1, 1, 10),
+            datetime(2008, 1, 1, 11, 25, 10),
+            "1\xa0hour, 25\xa0minutes",
+            id="hours_and_minutes",
+        ),
+        pytest.param(
+            datetime(2008, 1, 1),
+            datetime(2008, 1, 16),
+            "2\xa0weeks, 1\xa0day",
+            id="weeks",
+        ),
+        pytest.param(
+            datetime(2006, 1, 1),
+            datetime(2008, 3, 15, 13, 20),
+            "2\xa0years, 2\xa0months",
+            id="depth",
+        ),
+        pytest.param(
+            datetime(2008, 1, 31),
+            datetime(2008, 3, 1),
+            "1\xa0month, 2\xa0days",
+            id="short_month",
+        ),
+        pytest.param(
+            datetime(2008, 1, 1, 0, 0, 30),
+            datetime(2008, 1, 1, 0, 1),
+            "0\xa0minutes",
+            id="seconds",
+        ),
+        pytest.param(
+            datetime(2008, 1, 2), datetime(2008, 1, 1), "0\xa0minutes", id="future"
+        ),
+        pytest.param(date(2008, 1, 1), date(2008, 1, 2), "1\xa0day", id="dates"),
+        pytest.param(
+            date(2008, 1, 1), datetime(2008, 1, 1, 12), "12\xa0hours", id="mixed"
+        ),
+        pytest.param(
+            datetime(2008, 1, 1, 12, tzinfo=get_fixed_timezone(60)),
+            datetime(2008, 1, 1, 12, tzinfo=timezone.utc),
+            "1\xa0hour",
+            id="timezones",
+        ),
+        pytest.param(
+            datetime(2008, 1, 1, 12, tzinfo=timezone.utc),
+            datetime(2008, 1, 2, 12),
+            "",
+            id="aware_and_naive",
+        ),
+        pytest.param(datetime(9999, 1, 1), "", "0\xa0minutes", id="falsy_argument"),
+    ],
+)
+def test_timesince(assert_render, a, b, expected):
+    assert_render("{{ a|timesince:b }}", {"a": a, "b": b}, expected)
+
+
+@pytest.mark.parametrize(
+    "context,expected",
+    [
+        pytest.param({"a": datetime(9999, 1, 1)}, "0\xa0minutes", id="future"),
+        pytest.param({"a": None}, "", id="none"),
+        pytest.param({"a": ""}, "", id="empty_string"),
+        pytest.param({}, "", id="missing"),
+    ],
+)
+def test_timesince_now(assert_render, context, expected):
+    assert_render("{{ a|timesince }}", context, expected)
+
+
+def test_timesince_localized(assert_render):
+    with translation.override("fr"):
+        assert_render(
+            "{{ a|timesince:b }}",
+            {"a": datetime(2008, 1, 1), "b": datetime(2008, 1, 2)},
+            "1\xa0jour",
+        )
+
+
+def test_timesince_not_a_date(assert_render_error):
+    assert_render_error(
+        template="{{ a|timesince }}",
+        context={"a": "abc"},
+        exception=AttributeError,
+        django_message=snapshot("'str' object has no attribute 'year'"),
+        rusty_message=snapshot("""\
+  × 'str' object has no attribute 'year'
+   ╭────
+ 1 │ {{ a|timesince }}
+   ·      ────┬────
+   ·          ╰── here
+   ╰────
+"""),
+    )
@@ -0,0 +1,54 @@
+from datetime import date, datetime, timezone
+
+import pytest
+from django.utils.timezone import get_fixed_timezone
+
+
+@pytest.mark.parametrize(
+    "a,b,expected",
+    [
+        pytest.param(datetime(2008, 1, 2), datetime(2008, 1, 1), "1\xa0day", id="day"),
+        pytest.param(
+            datetime(2008, 1, 1, 12),
+            datetime(2008, 1, 1, 10, 30),
+            "1\xa0hour, 30\xa0minutes",
+            id="hours_and_minutes",
+        ),
+        pytest.param(
+            datetime(2010, 6, 1),
+            datetime(2008, 1, 1),
+            "2\xa0years, 5\xa0months",
+            id="depth",
+        ),
+        pytest.param(
+            datetime(2008, 1, 1), datetime(2008, 1, 2), "0\xa0minutes", id="past"
+        ),
+        pytest.param(date(2008, 1, 2), date(2008, 1, 1), "1\xa0day", id="dates"),
+        pytest.param(
+            datetime(2008, 1, 1, 12, tzinfo=timezone.utc),
+            datetime(2008, 1, 1, 12, tzinfo=get_fixed_timezone(60)),
+            "1\xa0hour",
+            id="timezones",
+        ),
+        pytest.param(
+            datetime(2008, 1, 1, 12),
+            datetime(2008, 1, 1, tzinfo=timezone.utc),
+            "12\xa0hours",
+            id="naive_and_aware",
+        ),
+    ],
+)
+def test_timeuntil(assert_render, a, b, expected):
+    assert_render("{{ a|timeuntil:b }}", {"a": a, "b": b}, expected)
+
+
+@pytest.mark.parametrize(
+    "context,expected",
+    [
+        pytest.param({"a": datetime(2000, 1, 1)}, "0\xa0minutes", id="past"),
+        pytest.param({"a": None}, "", id="none"),
+        pytest.param({}, "", id="missing"),
+    ],
+)
+def test_timeuntil_now(assert_render, context, expected):
+    assert_render("{{ a|timeuntil }}", context, expected)
@@ -0,0 +1,83 @@
+from inline_snapshot import snapshot
+import pytest
+from django.utils.safestring import mark_safe
+
+
+@pytest.mark.parametrize(
+    "length,text,expected",
+    [
+        pytest.param(
+            100,
+            "The quick brown fox jumped over the lazy dog.",
+            "The quick brown fox jumped over the lazy dog.",
+            id="not_truncated",
+        ),
+        pytest.param(
+            21,
+            "The quick brown fox jumped over the lazy dog.",
+            "The quick brown fox …",
+            id="truncated",
+        ),
+        pytest.param(5, "abcde", "abcde", id="exact_length"),
+        pytest.param(1, "asdf", "…", id="only_ellipsis"),
+        pytest.param(0, "asdf", "", id="zero"),
+        pytest.param(-1, "asdf", "", id="negative"),
+        pytest.param(3, "oüoüoü", "oü…", id="normalized"),
+        pytest.param(3, "-B̊B̊----8", "-B̊…", id="combining"),
+        pytest.param(3, 123456, "12…", id="number"),
+        pytest.param(4, "<b>bold</b>", "&lt;b&gt;…", id="escaped"),
+        pytest.param("3", "abcdef", "ab…", id="string_length"),
+        pytest.param("x", "abcdef", "abcdef", id="invalid_length"),
+        pytest.param(9223372036854775808, "abcdef", "abcdef", id="very_large"),
+    ],
+)
+def test_truncatechars(assert_render, length, text, expected):
+    template = "{{ text|truncatechars:length }}"
+    assert_render(template, {"text": text, "length": length}, expected)
+
+
+def test_truncatechars_undefined(assert_render):
+    assert_render("{{ text|truncatechars:3 }}", {}, "")
+
+
+def test_truncatechars_safe(assert_render):
+    template = "{{ a|truncatechars:4 }} {{ b|truncatechars:4 }}"
+    context = {"a": "a & b", "b": mark_safe("a & b")}
+    assert_render(template, context, "a &amp;… a &…")
+
+
+def test_truncatechars_autoescape_off(assert_render):
+    template = "{% autoescape off %}{{ a|truncatechars:4 }}{% endautoescape %}"
+    assert_render(template, {"a": "a & b"}, "a &…")
+
+
+def test_truncatechars_no_argument(assert_parse_error):
+    assert_parse_error(
+        template="{{ text|truncatechars }}",
+        django_message=snapshot("truncatechars requires 2 arguments, 1 provided"),
+        rusty_message=snapshot("""\
+  × Expected an argument
+   ╭────
+ 1 │ {{ text|truncatechars }}
+   ·         ──────┬──────
+   ·               ╰── here
+   ╰────
+"""),
+    )
+
+
+def test_truncatechars_infinite_length(assert_render_error):
+    assert_render_error(
+        template="{{ text|truncatechars:n }}",
+        context={"text": "abc", "n": float("inf")},
+        exception=OverflowError,
+        django_message=snapshot("cannot convert float infinity to integer"),
+        rusty_message=snapshot("""\
+  × cannot convert float infinity to integer
+   ╭────
+ 1 │ {{ text|truncatechars:n }}
+   ·                       ┬
+   ·                       ╰── here
+   ╰────
+"""),
+    )
@@ -0,0 +1,61 @@
+import pytest
+from django.utils.safestring import mark_safe
+
+HTML = '<p id="par"><strong><em>The quick brown fox jumped over the lazy dog.</em></strong></p>'
+
+
+@pytest.mark.parametrize(
+    "length,expected",
+    [
+        pytest.param(80, HTML, id="not_truncated"),
+        pytest.param(46, HTML, id="exact_length"),
+        pytest.param(
+            45,
+            '<p id="par"><strong><em>The quick brown fox jumped over the lazy dog…</em></strong></p>',
+            id="one_short",
+        ),
+        pytest.param(
+            10,
+            '<p id="par"><strong><em>The quick…</em></strong></p>',
+            id="truncated",
+        ),
+        pytest.param(
+            1, '<p id="par"><strong><em>…</em></strong></p>', id="only_ellipsis"
+        ),
+        pytest.param(0, "", id="zero"),
+        pytest.param(-1, "", id="negative"),
+    ],
+)
+def test_truncatechars_html(assert_render, length, expected):
+    template = "{{ html|truncatechars_html:length }}"
+    assert_render(template, {"html": mark_safe(HTML), "length": length}, expected)
+
+
+@pytest.mark.parametrize(
+    "html,expected",
+    [
+        pytest.param("foo</p>", "foo</p>", id="unopened_tag"),
+        pytest.param("<br>abcdefgh", "<br>abcde…", id="void_element"),
+        pytest.param("<p>a &amp; b &lt;c&gt;</p>", "<p>a &amp; b…</p>", id="entities"),
+        pytest.param("<p>&nbsp;abcdefgh</p>", "<p>\xa0abcd…</p>", id="named_entity"),
+        pytest.param("<p>ab<!-- comment -->cdefgh</p>", "<p>abcde…</p>", id="comment"),
+        pytest.param("<p/>abcdefgh", "<p/></p>abcde…", id="self_closing"),
+    ],
+)
+def test_truncatechars_html_markup(assert_render, html, expected):
+    template = "{{ html|truncatechars_html:6 }}"
+    assert_render(template, {"html": mark_safe(html)}, expected)
+
+
+def test_truncatechars_html_unsafe(assert_render):
+    template = "{{ html|truncatechars_html:4 }}"
+    assert_render(template, {"html": "<b>abcdef</b>"}, "&lt;b&gt;abc…&lt;/b&gt;")
+
+
+def test_truncatechars_html_invalid_length(assert_render):
+    template = "{{ html|truncatechars_html:'x' }}"
+    assert_render(template, {"html": mark_safe("<b>abc</b>")}, "<b>abc</b>")
+
+
+def test_truncatechars_html_undefined(assert_render):
+    assert_render("{{ html|truncatechars_html:3 }}", {}, "")
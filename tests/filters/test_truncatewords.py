@@ -0,0 +1,58 @@
+from inline_snapshot import snapshot
+import pytest
+from django.utils.safestring import mark_safe
+
+
+@pytest.mark.parametrize(
+    "length,text,expected",
+    [
+        pytest.param(
+            10,
+            "The quick brown fox jumped over the lazy dog.",
+            "The quick brown fox jumped over the lazy dog.",
+            id="not_truncated",
+        ),
+        pytest.param(
+            4,
+            "The quick brown fox jumped over the lazy dog.",
+            "The quick brown fox …",
+            id="truncated",
+        ),
+        pytest.param(5, "  a \t b\n c  ", "a b c", id="normalizes_whitespace"),
+        pytest.param(2, "a b", "a b", id="exact_length"),
+        pytest.param(0, "a b", "", id="zero"),
+        pytest.param(-1, "a b", "", id="negative"),
+        pytest.param(1, "<b>bold</b> text", "&lt;b&gt;bold&lt;/b&gt; …", id="escaped"),
+        pytest.param("2", "a b c", "a b …", id="string_length"),
+        pytest.param(2.5, "a b c", "a b …", id="float_length"),
+        pytest.param("x", "a  b c", "a  b c", id="invalid_length"),
+    ],
+)
+def test_truncatewords(assert_render, length, text, expected):
+    template = "{{ text|truncatewords:length }}"
+    assert_render(template, {"text": text, "length": length}, expected)
+
+
+def test_truncatewords_undefined(assert_render):
+    assert_render("{{ text|truncatewords:3 }}", {}, "")
+
+
+def test_truncatewords_safe(assert_render):
+    template = "{{ a|truncatewords:2 }} {{ b|truncatewords:2 }}"
+    context = {"a": "a & b", "b": mark_safe("a & b")}
+    assert_render(template, context, "a &amp; … a & …")
+
+
+def test_truncatewords_no_argument(assert_parse_error):
+    assert_parse_error(
+        template="{{ text|truncatewords }}",
+        django_message=snapshot("truncatewords requires 2 arguments, 1 provided"),
+        rusty_message=snapshot("""\
+  × Expected an argument
+   ╭────
+ 1 │ {{ text|truncatewords }}
+   ·         ──────┬──────
+   ·               ╰── here
+   ╰────
+"""),
+    )
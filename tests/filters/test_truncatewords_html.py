@@ -0,0 +1,71 @@
+import pytest
+from django.utils.safestring import mark_safe
+
+HTML = '<p id="par"><strong><em>The quick brown fox jumped over the lazy dog.</em></strong></p>'
+
+
+@pytest.mark.parametrize(
+    "length,expected",
+    [
+        pytest.param(10, HTML, id="not_truncated"),
+        pytest.param(
+            4,
+            '<p id="par"><strong><em>The quick brown fox …</em></strong></p>',
+            id="truncated",
+        ),
+        pytest.param(0, "", id="zero"),
+        pytest.param(-1, "", id="negative"),
+    ],
+)
+def test_truncatewords_html(assert_render, length, expected):
+    template = "{{ html|truncatewords_html:length }}"
+    assert_render(template, {"html": mark_safe(HTML), "length": length}, expected)
+
+
+@pytest.mark.parametrize(
+    "html,expected",
+    [
+        pytest.param(
+            "<p>The  quick \t brown fox jumped</p>",
+            "<p>The quick brown …</p>",
+            id="whitespace",
+        ),
+        pytest.param(
+            '<p>The quick <a href="xyz.html"\n id="mylink">brown fox</a> jumped</p>',
+            '<p>The quick <a href="xyz.html"\n id="mylink">brown …</a></p>',
+            id="newline_in_tag",
+        ),
+        pytest.param(
+            "<br/>The <hr />quick brown fox jumped",
+            "<br/>The <hr />quick brown …",
+            id="void_elements",
+        ),
+        pytest.param(
+            "<i>Buenos d&iacute;as! &#x00bf;C&oacute;mo est&aacute;?</i>",
+            "<i>Buenos días! ¿Cómo …</i>",
+            id="entities",
+        ),
+        pytest.param(
+            "<p>I &lt;3 python, what about you?</p>",
+            "<p>I &lt;3 python, …</p>",
+            id="escaped_entities",
+        ),
+        pytest.param(
+            "<p>one</p> <p>two</p> <p>three</p>",
+            "<p>one</p> <p>two</p> …",
+            id="whitespace_between_tags",
+        ),
+    ],
+)
+def test_truncatewords_html_markup(assert_render, html, expected):
+    template = "{{ html|truncatewords_html:3 }}"
+    assert_render(template, {"html": mark_safe(html)}, expected)
+
+
+def test_truncatewords_html_unsafe(assert_render):
+    template = "{{ html|truncatewords_html:1 }}"
+    assert_render(template, {"html": "<b>a b</b>"}, "&lt;b&gt;a …&lt;/b&gt;")
+
+
+def test_truncatewords_html_undefined(assert_render):
+    assert_render("{{ html|truncatewords_html:3 }}", {}, "")
@@ -0,0 +1,87 @@
+from inline_snapshot import snapshot
+import pytest
+from django.utils.safestring import mark_safe
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        pytest.param(
+            ["item 1", "item 2"],
+            "\t<li>item 1</li>\n\t<li>item 2</li>",
+            id="flat",
+        ),
+        pytest.param(
+            ["item 1", ["item 1.1"]],
+            "\t<li>item 1\n\t<ul>\n\t\t<li>item 1.1</li>\n\t</ul>\n\t</li>",
+            id="nested",
+        ),
+        pytest.param(
+            ["item 1", ["item 1.1", "item1.2"], "item 2"],
+            "\t<li>item 1\n\t<ul>\n\t\t<li>item 1.1</li>\n\t\t<li>item1.2</li>\n"
+            "\t</ul>\n\t</li>\n\t<li>item 2</li>",
+            id="nested_then_flat",
+        ),
+        pytest.param(
+            ["item 1", ["item 1.1", ["item 1.1.1", ["item 1.1.1.1"]]]],
+            "\t<li>item 1\n\t<ul>\n\t\t<li>item 1.1\n\t\t<ul>\n\t\t\t<li>item 1.1.1\n"
+            "\t\t\t<ul>\n\t\t\t\t<li>item 1.1.1.1</li>\n\t\t\t</ul>\n\t\t\t</li>\n"
+            "\t\t</ul>\n\t\t</li>\n\t</ul>\n\t</li>",
+            id="deeply_nested",
+        ),
+        pytest.param(
+            ("States", ("Kansas", ("Lawrence", "Topeka"), "Illinois")),
+            "\t<li>States\n\t<ul>\n\t\t<li>Kansas\n\t\t<ul>\n\t\t\t<li>Lawrence</li>\n"
+            "\t\t\t<li>Topeka</li>\n\t\t</ul>\n\t\t</li>\n\t\t<li>Illinois</li>\n"
+            "\t</ul>\n\t</li>",
+            id="tuples",
+        ),
+        pytest.param(["item 1", []], "\t<li>item 1</li>", id="empty_sublist"),
+        pytest.param([["item 1"]], "\t<li>[&#x27;item 1&#x27;]</li>", id="leading_list"),
+        pytest.param([1, 2], "\t<li>1</li>\n\t<li>2</li>", id="ints"),
+        pytest.param([], "", id="empty"),
+        pytest.param("ab", "\t<li>a</li>\n\t<li>b</li>", id="string"),
+        pytest.param(
+            ["<a>x</a>", mark_safe("<b>y</b>")],
+            "\t<li>&lt;a&gt;x&lt;/a&gt;</li>\n\t<li><b>y</b></li>",
+            id="escaped",
+        ),
+    ],
+)
+def test_unordered_list(assert_render, value, expected):
+    assert_render("{{ value|unordered_list }}", {"value": value}, expected)
+
+
+def test_unordered_list_generator(assert_render):
+    def children():
+        yield "item 1.1"
+
+    value = (item for item in ["item 1", children()])
+    expected = "\t<li>item 1\n\t<ul>\n\t\t<li>item 1.1</li>\n\t</ul>\n\t</li>"
+    assert_render("{{ value|unordered_list }}", {"value": value}, expected)
+
+
+def test_unordered_list_undefined(assert_render):
+    assert_render("{{ value|unordered_list }}", {}, "")
+
+
+def test_unordered_list_autoescape_off(assert_render):
+    template = "{% autoescape off %}{{ value|unordered_list }}{% endautoescape %}"
+    assert_render(template, {"value": ["<a>x</a>"]}, "\t<li><a>x</a></li>")
+
+
+def test_unordered_list_not_iterable(assert_render_error):
+    assert_render_error(
+        template="{{ value|unordered_list }}",
+        context={"value": 1},
+        exception=TypeError,
+        django_message=snapshot("'int' object is not iterable"),
+        rusty_message=snapshot("""\
+  × 'int' object is not iterable
+   ╭────
+ 1 │ {{ value|unordered_list }}
+   ·          ───────┬──────
+   ·                 ╰── here
+   ╰────
+"""),
+    )
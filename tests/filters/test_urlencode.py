@@ -0,0 +1,47 @@
+import pytest
+from django.utils.safestring import mark_safe
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        pytest.param("fran\xe7ois & jill", "fran%C3%A7ois%20%26%20jill", id="unicode"),
+        pytest.param("/test&\"/me?/", "/test%26%22/me%3F/", id="slash_safe"),
+        pytest.param(
+            "http://example.com?a=1&b=2",
+            "http%3A//example.com%3Fa%3D1%26b%3D2",
+            id="url",
+        ),
+        pytest.param(1, "1", id="int"),
+        pytest.param("a~b_c.d-e", "a~b_c.d-e", id="always_safe"),
+    ],
+)
+def test_urlencode(assert_render, value, expected):
+    assert_render("{{ value|urlencode }}", {"value": value}, expected)
+
+
+def test_urlencode_undefined(assert_render):
+    assert_render("{{ value|urlencode }}", {}, "")
+
+
+@pytest.mark.parametrize(
+    "safe,expected",
+    [
+        pytest.param("''", "%2Ftest%26%22%2Fme%3F%2F", id="empty"),
+        pytest.param("'/&'", "/test&amp;%22/me%3F/", id="ampersand"),
+        pytest.param("safe", "/test%26%22/me?/", id="variable"),
+    ],
+)
+def test_urlencode_safe_argument(assert_render, safe, expected):
+    template = f"{{{{ value|urlencode:{safe} }}}}"
+    assert_render(template, {"value": "/test&\"/me?/", "safe": "/?"}, expected)
+
+
+def test_urlencode_safe_input(assert_render):
+    template = "{{ value|urlencode:'&' }}"
+    assert_render(template, {"value": mark_safe("a&b")}, "a&amp;b")
+
+
+def test_urlencode_autoescape_off(assert_render):
+    template = "{% autoescape off %}{{ value|urlencode:'&' }}{% endautoescape %}"
+    assert_render(template, {"value": "a&b"}, "a&b")
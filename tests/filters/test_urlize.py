@@ -0,0 +1,108 @@
+from inline_snapshot import snapshot
+import pytest
+from django.utils.safestring import mark_safe
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        pytest.param(
+            "http://google.com",
+            '<a href="http://google.com" rel="nofollow">http://google.com</a>',
+            id="http",
+        ),
+        pytest.param(
+            "HTTPS://github.com/",
+            '<a href="https://github.com/" rel="nofollow">HTTPS://github.com/</a>',
+            id="uppercase_scheme",
+        ),
+        pytest.param(
+            "www.google.com",
+            '<a href="http://www.google.com" rel="nofollow">www.google.com</a>',
+            id="www",
+        ),
+        pytest.param(
+            "djangoproject.org",
+            '<a href="http://djangoproject.org" rel="nofollow">djangoproject.org</a>',
+            id="tld",
+        ),
+        pytest.param("example.invalid", "example.invalid", id="unknown_tld"),
+        pytest.param(
+            "info@djangoproject.org",
+            '<a href="mailto:info@djangoproject.org">info@djangoproject.org</a>',
+            id="email",
+        ),
+        pytest.param(
+            "(see www.example.com).",
+            '(see <a href="http://www.example.com" rel="nofollow">www.example.com</a>).',
+            id="wrapping_punctuation",
+        ),
+        pytest.param(
+            "Go to djangoproject.com!",
+            'Go to <a href="http://djangoproject.com" rel="nofollow">djangoproject.com</a>!',
+            id="trailing_punctuation",
+        ),
+        pytest.param(
+            "http://en.wikipedia.org/wiki/Django_(web_framework)",
+            '<a href="http://en.wikipedia.org/wiki/Django_(web_framework)" '
+            'rel="nofollow">http://en.wikipedia.org/wiki/Django_(web_framework)</a>',
+            id="balanced_parentheses",
+        ),
+        pytest.param(
+            "http://example.com/?x=&y=<2>",
+            '<a href="http://example.com/?x=&amp;y=" rel="nofollow">'
+            "http://example.com/?x=&amp;y=</a>&lt;2&gt;",
+            id="query",
+        ),
+        pytest.param(
+            "http://example.com/a b",
+            '<a href="http://example.com/a" rel="nofollow">http://example.com/a</a> b',
+            id="split_on_whitespace",
+        ),
+        pytest.param(
+            "<b>www.example.com</b>",
+            '&lt;b&gt;<a href="http://www.example.com" rel="nofollow">'
+            "www.example.com</a>&lt;/b&gt;",
+            id="escaped",
+        ),
+        pytest.param("no links & here", "no links &amp; here", id="no_links"),
+    ],
+)
+def test_urlize(assert_render, value, expected):
+    assert_render("{{ value|urlize }}", {"value": value}, expected)
+
+
+def test_urlize_undefined(assert_render):
+    assert_render("{{ value|urlize }}", {}, "")
+
+
+def test_urlize_safe_input(assert_render):
+    value = mark_safe("a &amp; www.example.com")
+    expected = 'a &amp; <a href="http://www.example.com" rel="nofollow">www.example.com</a>'
+    assert_render("{{ value|urlize }}", {"value": value}, expected)
+
+
+def test_urlize_autoescape_off(assert_render):
+    template = "{% autoescape off %}{{ value|urlize }}{% endautoescape %}"
+    expected = 'a & <a href="http://www.example.com" rel="nofollow">www.example.com</a>'
+    assert_render(template, {"value": "a & www.example.com"}, expected)
+
+
+def test_urlize_idn(assert_render):
+    expected = '<a href="http://xn--h-0fa.ws" rel="nofollow">http://h\xe4.ws</a>'
+    assert_render("{{ value|urlize }}", {"value": "http://h\xe4.ws"}, expected)
+
+
+def test_urlize_with_argument(assert_parse_error):
+    assert_parse_error(
+        template="{{ var|urlize:arg }}",
+        django_message=snapshot("urlize requires 1 arguments, 2 provided"),
+        rusty_message=snapshot("""\
+  × urlize filter does not take an argument
+   ╭────
+ 1 │ {{ var|urlize:arg }}
+   ·               ─┬─
+   ·                ╰── unexpected argument
+   ╰────
+"""),
+    )
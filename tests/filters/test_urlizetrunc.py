@@ -0,0 +1,73 @@
+from inline_snapshot import snapshot
+import pytest
+
+
+URI = "http://31characteruri.com/test/"
+
+
+@pytest.mark.parametrize(
+    "limit,expected",
+    [
+        pytest.param(
+            31,
+            f'<a href="{URI}" rel="nofollow">{URI}</a>',
+            id="not_truncated",
+        ),
+        pytest.param(
+            30,
+            f'<a href="{URI}" rel="nofollow">http://31characteruri.com/tes…</a>',
+            id="truncated",
+        ),
+        pytest.param(1, f'<a href="{URI}" rel="nofollow">…</a>', id="ellipsis"),
+        pytest.param(
+            "30",
+            f'<a href="{URI}" rel="nofollow">http://31characteruri.com/tes…</a>',
+            id="string_limit",
+        ),
+    ],
+)
+def test_urlizetrunc(assert_render, limit, expected):
+    template = "{{ value|urlizetrunc:limit }}"
+    assert_render(template, {"value": URI, "limit": limit}, expected)
+
+
+def test_urlizetrunc_escaped(assert_render):
+    template = "{{ value|urlizetrunc:10 }}"
+    expected = '&quot;<a href="http://google.com" rel="nofollow">http://go…</a>&quot;'
+    assert_render(template, {"value": '"http://google.com"'}, expected)
+
+
+def test_urlizetrunc_undefined(assert_render):
+    assert_render("{{ value|urlizetrunc:10 }}", {}, "")
+
+
+def test_urlizetrunc_no_argument(assert_parse_error):
+    assert_parse_error(
+        template="{{ text|urlizetrunc }}",
+        django_message=snapshot("urlizetrunc requires 2 arguments, 1 provided"),
+        rusty_message=snapshot("""\
+  × Expected an argument
+   ╭────
+ 1 │ {{ text|urlizetrunc }}
+   ·         ─────┬─────
+   ·              ╰── here
+   ╰────
+"""),
+    )
+
+
+def test_urlizetrunc_invalid_limit(assert_render_error):
+    assert_render_error(
+        template="{{ text|urlizetrunc:n }}",
+        context={"text": URI, "n": "x"},
+        exception=ValueError,
+        django_message=snapshot("invalid literal for int() with base 10: 'x'"),
+        rusty_message=snapshot("""\
+  × invalid literal for int() with base 10: 'x'
+   ╭────
+ 1 │ {{ text|urlizetrunc:n }}
+   ·                     ┬
+   ·                     ╰── here
+   ╰────
+"""),
+    )
@@ -2,6 +2,7 @@ from inline_snapshot import snapshot
 from datetime import datetime
 from zoneinfo import ZoneInfo
 
+from django.template import engines
 from django.template.base import VariableDoesNotExist
 from django.test import RequestFactory
 
@@ -144,6 +145,76 @@ def test_simple_tag_takes_context_setitem_in_loop(assert_render):
     assert_render(template=template, context={"items": [1, 0, 4, 0]}, expected="1122")
 
 
+def test_simple_tag_takes_context_push(assert_render):
+    template = "{% load push_context from custom_tags %}{% push_context a=2 b=3 %}"
+    assert_render(template=template, context={"a": 1}, expected="a=2 b=3 | a=1 b=-")
+
+
+def test_simple_tag_takes_context_update(assert_render):
+    template = "{% load update_context from custom_tags %}{% update_context 'a' 2 %}"
+    assert_render(template=template, context={"a": 1}, expected="2 | 1")
+
+
+def test_simple_tag_takes_context_push_setitem(assert_render):
+    template = "{% load push_context_setitem from custom_tags %}{% push_context_setitem 'a' 2 %}{{ a }}"
+    assert_render(template=template, context={"a": 1}, expected="2 2 True | 11")
+
+
+def test_simple_tag_takes_context_update_setitem(assert_render):
+    template = "{% load update_context_setitem from custom_tags %}{{ a }}{% update_context_setitem 'a' 2 %}{{ a }}"
+    assert_render(template=template, context={"a": 1}, expected="12")
+
+
+def test_simple_tag_takes_context_setitem_in_push(assert_render):
+    template = "{% load with_block from custom_tags %}{% for i in items %}{% with_block var='a' %}inner{% end_with_block %}{{ a }}{% endfor %}{{ a }}"
+    assert_render(
+        template=template, context={"a": "outer", "items": [1]}, expected="innerouter"
+    )
+
+
+def test_simple_tag_takes_context_flatten(assert_render):
+    template = "{% load flatten_context from custom_tags %}{% flatten_context %}"
+    expected = "False=False, None=None, True=True, a=1, b=[2]"
+    assert_render(template=template, context={"a": 1, "b": [2]}, expected=expected)
+
+
+def test_simple_tag_takes_context_keys():
+    template = engines["rusty"].from_string(
+        "{% load context_keys from custom_tags %}{% context_keys %}"
+    )
+    assert template.render({"b": 1, "a": 2}) == "False, None, True, a, b"
+
+
+def test_simple_tag_takes_context_new(assert_render):
+    template = "{% load new_context from custom_tags %}{% new_context a=1 %}"
+    assert_render(template=template, context={"b": 2}, expected="1 - True")
+
+
+def test_simple_tag_takes_context_render_context(assert_render):
+    template = "{% load render_count from custom_tags %}{% render_count %}{% render_count %}"
+    assert_render(template=template, context={}, expected="12")
+
+
+def test_simple_tag_takes_context_render_context_include(assert_render):
+    template = "{% load render_count from custom_tags %}{% render_count %}{% include 'render_count.txt' %}{% include 'render_count.txt' only %}{% render_count %}"
+    assert_render(template=template, context={}, expected="1112")
+
+
+def test_simple_tag_takes_context_render_context_new(assert_render):
+    template = "{% load render_count new_context_render_count from custom_tags %}{% render_count %}{% new_context_render_count %}"
+    assert_render(template=template, context={}, expected="12")
+
+
+def test_simple_tag_takes_context_autoescape(assert_render):
+    template = "{% load context_autoescape from custom_tags %}{% context_autoescape %}{% autoescape off %}{% context_autoescape %}{% endautoescape %}"
+    assert_render(template=template, context={}, expected="TrueFalse")
+
+
+def test_simple_tag_takes_context_template(assert_render):
+    template = "{% load context_template from custom_tags %}{% context_template %}"
+    assert_render(template=template, context={}, expected="Template")
+
+
 def test_simple_tag_takes_context_getitem_missing(assert_render_error):
     source_time = datetime(2025, 8, 31, 9, 14, tzinfo=ZoneInfo("Europe/London"))
     django_message = snapshot("'timezone'")
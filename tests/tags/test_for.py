@@ -24,6 +24,36 @@ class BrokenIterator2:
         1 / 0
 
 
+class Sized:
+    def __init__(self, *values):
+        self.values = values
+        self.iterations = 0
+
+    def __len__(self):
+        return len(self.values)
+
+    def __iter__(self):
+        self.iterations += 1
+        return iter(self.values)
+
+
+class Undersized(Sized):
+    def __len__(self):
+        return len(self.values) - 1
+
+
+class Appender:
+    """Adds another item to the list being looped over when rendered."""
+
+    def __init__(self, items):
+        self.items = items
+
+    def __str__(self):
+        if len(self.items) < 3:
+            self.items.append(Appender(self.items))
+        return "x"
+
+
 def test_render_for_loop(assert_render):
     template = "{% for x in y %}{{ x }}{% endfor %}"
     y = [1, 2, "foo"]
@@ -148,6 +178,71 @@ def test_render_for_loop_last(assert_render):
     assert_render(template=template, context={"y": y}, expected=expected)
 
 
+def test_render_for_loop_tuple_reversed_revcounter(assert_render):
+    template = "{% for x in y reversed %}{{ x }}: {{ forloop.revcounter }}\n{% endfor %}"
+    y = ("foo", "bar", "spam")
+    expected = "spam: 3\nbar: 2\nfoo: 1\n"
+    assert_render(template=template, context={"y": y}, expected=expected)
+
+
+def test_render_for_loop_sized_last(assert_render):
+    template = "{% for x in y %}{{ x }}: {{ forloop.last }}\n{% endfor %}"
+    y = Sized("foo", "bar", "spam")
+    expected = "foo: False\nbar: False\nspam: True\n"
+    assert_render(template=template, context={"y": y}, expected=expected)
+    assert y.iterations == 1
+
+
+def test_render_for_loop_sized_reversed(assert_render):
+    template = "{% for x in y reversed %}{{ x }}: {{ forloop.revcounter0 }}\n{% endfor %}"
+    y = Sized("foo", "bar", "spam")
+    expected = "spam: 2\nbar: 1\nfoo: 0\n"
+    assert_render(template=template, context={"y": y}, expected=expected)
+
+
+def test_render_for_loop_generator_last(assert_render):
+    template = "{% for x in y %}{{ x }}: {{ forloop.last }}\n{% endfor %}"
+    y = (x for x in ["foo", "bar", "spam"])
+    expected = "foo: False\nbar: False\nspam: True\n"
+    assert_render(template=template, context={"y": y}, expected=expected)
+
+
+def test_render_for_loop_undersized_revcounter(assert_render):
+    # Django keeps going past the reported length, counting down below one.
+    template = (
+        "{% for x in y %}{{ x }}: {{ forloop.revcounter }} {{ forloop.revcounter0 }} "
+        "{{ forloop.last }}\n{% endfor %}"
+    )
+    y = Undersized("foo", "bar", "spam")
+    expected = "foo: 2 1 False\nbar: 1 0 True\nspam: 0 -1 False\n"
+    assert_render(template=template, context={"y": y}, expected=expected)
+
+
+def test_render_for_loop_list_grows(assert_render):
+    template = "{% for x in y %}{{ x }}{% endfor %}"
+    y = []
+    y.append(Appender(y))
+    assert_render(template=template, context={"y": y}, expected="xxx")
+
+
+def test_render_for_loop_generator_include_parentloop(assert_render):
+    template = "{% for x in xs %}{% include 'forloop_parentloop.txt' %}{% endfor %}"
+    xs = (x for x in ["foo", "bar"])
+    expected = "False 2\nTrue 1\n"
+    assert_render(template=template, context={"xs": xs, "ys": [1]}, expected=expected)
+
+
+def test_render_for_loop_generator_include_forloop(assert_render):
+    template = "{% for x in xs %}{% include 'forloop_object.txt' %}{% endfor %}"
+    xs = (x for x in ["foo"])
+    forloop = (
+        "'counter0': 0, 'counter': 1, 'revcounter': 1, 'revcounter0': 0, "
+        "'first': True, 'last': True"
+    )
+    expected = f"{{'parentloop': {{'parentloop': {{}}, {forloop}}}, {forloop}}}"
+    assert_render(template=template, context={"xs": xs, "ys": [1]}, expected=expected)
+
+
 def test_render_for_loop_forloop_variable(assert_render):
     template = "{% autoescape off %}{% for x in y %}{{ forloop }}{% endfor %}{% endautoescape off %}"
     y = ["foo"]
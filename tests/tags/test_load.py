@@ -14,6 +14,7 @@ def test_load_missing(assert_parse_error):
 cache
 custom_filters
 custom_tags
+humanize
 i18n
 invalid_tags
 l10n
@@ -33,6 +34,7 @@ tz""")
         cache
         custom_filters
         custom_tags
+        humanize
         i18n
         invalid_tags
         l10n
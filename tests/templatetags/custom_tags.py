@@ -1,4 +1,5 @@
 from django import template
+from django.template import engines
 
 
 register = template.Library()
@@ -72,6 +73,93 @@ def counter(context):
     return ""
 
 
+@register.simple_tag(takes_context=True)
+def push_context(context, **kwargs):
+    with context.push(**kwargs):
+        inner = " ".join(f"{key}={context[key]}" for key in sorted(kwargs))
+    outer = " ".join(f"{key}={context.get(key, '-')}" for key in sorted(kwargs))
+    return f"{inner} | {outer}"
+
+
+@register.simple_tag(takes_context=True)
+def update_context(context, key, value):
+    with context.update({key: value}):
+        inner = context[key]
+    return f"{inner} | {context.get(key, '-')}"
+
+
+@register.simple_tag(takes_context=True)
+def push_context_setitem(context, key, value):
+    with context.push() as pushed:
+        pushed[key] = value
+        inner = f"{context[key]} {pushed[key]} {key in pushed}"
+    return f"{inner} | {context.get(key, '-')}"
+
+
+@register.simple_tag(takes_context=True)
+def update_context_setitem(context, key, value):
+    context.update({})[key] = value
+    return ""
+
+
+@register.simple_tag(takes_context=True)
+def flatten_context(context):
+    return ", ".join(f"{k}={v}" for k, v in sorted(context.flatten().items()))
+
+
+@register.simple_tag(takes_context=True)
+def context_keys(context):
+    return ", ".join(sorted(context.keys()))
+
+
+@register.simple_tag(takes_context=True)
+def new_context(context, **kwargs):
+    new = context.new(kwargs)
+    return f"{new.get('a', '-')} {new.get('b', '-')} {new.autoescape}"
+
+
+@register.simple_tag(takes_context=True)
+def render_count(context):
+    count = context.render_context.get("render_count", 0) + 1
+    context.render_context["render_count"] = count
+    return count
+
+
+@register.simple_tag(takes_context=True)
+def new_context_render_count(context):
+    return render_count(context.new())
+
+
+@register.simple_tag(takes_context=True)
+def render_counts(context, chunk_size=None):
+    counts = engines["rusty"].from_string(
+        "{% load render_count from custom_tags %}{% render_count %}{% render_count %}"
+    )
+    if chunk_size is None:
+        return counts.render(context)
+    return "".join(counts.stream(context, chunk_size=chunk_size))
+
+
+@register.simple_tag(takes_context=True)
+def stream_first_chunk(context):
+    chunks = engines["rusty"].from_string(
+        "{% for x in 'ab' %}{% autoescape off %}{{ x }}{% endautoescape %}{% endfor %}"
+    ).stream(context, chunk_size=1)
+    first = next(chunks)
+    chunks.close()
+    return first
+
+
+@register.simple_tag(takes_context=True)
+def context_autoescape(context):
+    return context.autoescape
+
+
+@register.simple_tag(takes_context=True)
+def context_template(context):
+    return type(context.template).__name__
+
+
 @register.simple_block_tag
 def repeat(content, count):
     return content * count
@@ -77,3 +77,14 @@ def test_safe_lower(assert_render):
     template = "{{ html|safe|lower }}"
     expected = "<p>hello world!</p>"
     assert_render(template=template, context={"html": html}, expected=expected)
+
+
+def test_autoescape_off_invalid_str_method(assert_render_error):
+    broken = BrokenDunderStr()
+    assert_render_error(
+        template="{% autoescape off %}{{ broken }}{% endautoescape %}",
+        context={"broken": broken},
+        exception=ZeroDivisionError,
+        django_message=snapshot("division by zero"),
+        rusty_message=snapshot("division by zero"),
+    )
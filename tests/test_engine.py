@@ -1,3 +1,5 @@
+import json
+from concurrent.futures import ThreadPoolExecutor
 from pathlib import Path
 
 import pytest
@@ -7,7 +9,8 @@ from django.template import engines, Context
 from django.template.engine import Engine
 from django.template.library import InvalidTemplateLibrary
 from django.template.exceptions import TemplateDoesNotExist
-from django_rusty_templates import RustyTemplates
+from django.test import override_settings
+from django_rusty_templates import RustyTemplates, precompile_templates
 
 
 def test_import_libraries_import_error():
@@ -413,3 +416,209 @@ def test_render_to_string_no_valid_template():
 
     with pytest.raises(TemplateDoesNotExist):
         rusty_engine.engine.render_to_string("nonexistent.html", {"user": "Bob"})
+
+
+def test_detach():
+    engine = RustyTemplates(
+        {"OPTIONS": {"detach": True}, "NAME": "rust", "DIRS": [], "APP_DIRS": False}
+    )
+    html = "<b>" * 10_000
+    template = engine.from_string("{# comment #}<p>{{ html }}</p>")
+    assert template.render({"html": html}) == f"<p>{'&lt;b&gt;' * 10_000}</p>"
+
+
+def test_detach_threads():
+    engine = RustyTemplates(
+        {
+            "OPTIONS": {"detach": True},
+            "NAME": "rust",
+            "DIRS": [Path(settings.BASE_DIR) / "templates"],
+            "APP_DIRS": False,
+        }
+    )
+
+    def render(n):
+        return engine.get_template("basic.txt").render({"user": n})
+
+    with ThreadPoolExecutor(max_workers=8) as executor:
+        results = list(executor.map(render, range(100)))
+
+    assert results == [f"Hello {n}!\n" for n in range(100)]
+
+
+def test_parse_cache():
+    engine = RustyTemplates(
+        {"OPTIONS": {}, "NAME": "rust", "DIRS": [], "APP_DIRS": False}
+    )
+    engine.from_string("Hello {{ user }}!")
+    template = engine.from_string("Hello {{ user }}!")
+
+    assert template.render({"user": "Lily"}) == "Hello Lily!"
+    assert engine.engine.parse_cache_info() == {
+        "hits": 1,
+        "misses": 1,
+        "maxsize": 128,
+        "currsize": 1,
+    }
+
+
+def test_parse_cache_disabled():
+    engine = RustyTemplates(
+        {
+            "OPTIONS": {"parse_cache_size": 0},
+            "NAME": "rust",
+            "DIRS": [],
+            "APP_DIRS": False,
+        }
+    )
+    engine.from_string("Hello {{ user }}!")
+    template = engine.from_string("Hello {{ user }}!")
+
+    assert template.render({"user": "Lily"}) == "Hello Lily!"
+    assert engine.engine.parse_cache_info() == {
+        "hits": 0,
+        "misses": 0,
+        "maxsize": 0,
+        "currsize": 0,
+    }
+
+
+def test_parse_cache_redefined_filter():
+    from tests.templatetags.custom_filters import double, register
+
+    engine = RustyTemplates(
+        {
+            "OPTIONS": {
+                "libraries": {"custom_filters": "tests.templatetags.custom_filters"}
+            },
+            "NAME": "rust",
+            "DIRS": [],
+            "APP_DIRS": False,
+        }
+    )
+    source = "{% load custom_filters %}{{ user|double }}"
+    cached = engine.from_string(source)
+
+    def shout(value):
+        return value.upper()
+
+    register.filter("double", shout)
+    try:
+        reparsed = engine.from_string(source)
+    finally:
+        register.filter("double", double)
+    restored = engine.from_string(source)
+
+    assert cached.render({"user": "Lily"}) == "LilyLily"
+    assert reparsed.render({"user": "Lily"}) == "LILY"
+    assert restored.render({"user": "Lily"}) == "LilyLily"
+    assert engine.engine.parse_cache_info() == {
+        "hits": 0,
+        "misses": 3,
+        "maxsize": 128,
+        "currsize": 1,
+    }
+
+
+def test_parse_cache_clear():
+    engine = RustyTemplates(
+        {"OPTIONS": {}, "NAME": "rust", "DIRS": [], "APP_DIRS": False}
+    )
+    engine.from_string("Hello {{ user }}!")
+    engine.engine.clear_parse_cache()
+    engine.from_string("Hello {{ user }}!")
+
+    assert engine.engine.parse_cache_info() == {
+        "hits": 0,
+        "misses": 1,
+        "maxsize": 128,
+        "currsize": 1,
+    }
+
+
+def test_precompile(tmp_path):
+    params = {
+        "OPTIONS": {
+            "template_cache_dir": tmp_path,
+            "libraries": {"custom_filters": "tests.templatetags.custom_filters"},
+        },
+        "NAME": "rust",
+        "DIRS": [Path(settings.BASE_DIR) / "templates"],
+        "APP_DIRS": False,
+    }
+    errors = RustyTemplates(params).precompile()
+
+    assert set(errors) >= {"invalid.txt", "parse_error.txt"}
+    assert "basic.txt" not in errors
+    assert "full_example.html" not in errors
+    assert len(list(tmp_path.iterdir())) > 0
+
+    template = RustyTemplates(params).get_template("basic.txt")
+    assert template.render({"user": "Lily"}) == "Hello Lily!\n"
+
+
+def test_precompile_changed_source(tmp_path):
+    (tmp_path / "templates").mkdir()
+    source = tmp_path / "templates" / "greeting.txt"
+    source.write_text("Hello {{ user }}!")
+    params = {
+        "OPTIONS": {"template_cache_dir": tmp_path / "compiled"},
+        "NAME": "rust",
+        "DIRS": [tmp_path / "templates"],
+        "APP_DIRS": False,
+    }
+    assert RustyTemplates(params).precompile() == {}
+
+    source.write_text("Goodbye {{ user }}!")
+    template = RustyTemplates(params).get_template("greeting.txt")
+    assert template.render({"user": "Lily"}) == "Goodbye Lily!"
+
+
+def test_precompile_reused(tmp_path):
+    (tmp_path / "templates").mkdir()
+    (tmp_path / "templates" / "greeting.txt").write_text("Hello {{ user }}!")
+    params = {
+        "OPTIONS": {"template_cache_dir": tmp_path / "compiled"},
+        "NAME": "rust",
+        "DIRS": [tmp_path / "templates"],
+        "APP_DIRS": False,
+    }
+    assert RustyTemplates(params).precompile() == {}
+
+    # Empty the compiled nodes, so only a template loaded from the file renders nothing.
+    [compiled] = (tmp_path / "compiled").iterdir()
+    data = json.loads(compiled.read_text())
+    assert data["origin"] == "greeting.txt"
+    assert data["source"] == "Hello {{ user }}!"
+    data["nodes"] = []
+    compiled.write_text(json.dumps(data))
+
+    template = RustyTemplates(params).get_template("greeting.txt")
+    assert template.render({"user": "Lily"}) == ""
+
+
+def test_precompile_without_template_cache_dir():
+    engine = RustyTemplates(
+        {"OPTIONS": {}, "NAME": "rust", "DIRS": [], "APP_DIRS": False}
+    )
+    with pytest.raises(ImproperlyConfigured) as exc_info:
+        engine.engine.precompile("basic.txt")
+
+    message = "Templates can only be precompiled when template_cache_dir is set."
+    assert str(exc_info.value) == message
+
+
+def test_precompile_templates(tmp_path):
+    templates = [
+        {
+            "BACKEND": "django_rusty_templates.RustyTemplates",
+            "DIRS": [Path(settings.BASE_DIR) / "templates"],
+            "NAME": "rusty",
+            "OPTIONS": {"template_cache_dir": tmp_path},
+        }
+    ]
+    with override_settings(TEMPLATES=templates):
+        errors = precompile_templates()
+
+    assert list(errors) == ["rusty"]
+    assert "basic.txt" not in errors["rusty"]
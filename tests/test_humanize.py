@@ -0,0 +1,354 @@
+from datetime import date, datetime, timedelta, timezone
+from decimal import Decimal
+
+import pytest
+from django.utils.safestring import mark_safe
+from django.utils.translation import override
+from inline_snapshot import snapshot
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        (1, "1st"),
+        (2, "2nd"),
+        (3, "3rd"),
+        (4, "4th"),
+        (11, "11th"),
+        (12, "12th"),
+        (13, "13th"),
+        (101, "101st"),
+        (102, "102nd"),
+        (103, "103rd"),
+        (111, "111th"),
+        (-1, "-1th"),
+        ("22", "22nd"),
+        (2.5, "2nd"),
+        (True, "1st"),
+        ("something else", "something else"),
+        ("<b>", "&lt;b&gt;"),
+        (None, "None"),
+    ],
+)
+def test_ordinal(assert_render, value, expected):
+    template = "{% load humanize %}{{ value|ordinal }}"
+    assert_render(template, {"value": value}, expected)
+
+
+def test_ordinal_missing(assert_render):
+    assert_render("{% load humanize %}{{ value|ordinal }}", {}, "")
+
+
+def test_ordinal_translation(assert_render):
+    with override("de"):
+        assert_render("{% load humanize %}{{ 1|ordinal }}", {}, "1.")
+
+
+def test_ordinal_overflow(assert_render_error):
+    assert_render_error(
+        template="{% load humanize %}{{ n|ordinal }}",
+        context={"n": float("inf")},
+        exception=OverflowError,
+        django_message=snapshot("cannot convert float infinity to integer"),
+        rusty_message=snapshot("""\
+  × cannot convert float infinity to integer
+   ╭────
+ 1 │ {% load humanize %}{{ n|ordinal }}
+   ·                         ───┬───
+   ·                            ╰── here
+   ╰────
+"""),
+    )
+
+
+def test_ordinal_unexpected_argument(assert_parse_error):
+    assert_parse_error(
+        template="{% load humanize %}{{ n|ordinal:1 }}",
+        django_message=snapshot("ordinal requires 1 arguments, 2 provided"),
+        rusty_message=snapshot("""\
+  × ordinal filter does not take an argument
+   ╭────
+ 1 │ {% load humanize %}{{ n|ordinal:1 }}
+   ·                                 ┬
+   ·                                 ╰── unexpected argument
+   ╰────
+"""),
+    )
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        (100, "100"),
+        (1000, "1,000"),
+        (10123, "10,123"),
+        (-1234567, "-1,234,567"),
+        ("1234", "1,234"),
+        (1234.5678, "1,234.5678"),
+        (Decimal("1234.5"), "1,234.5"),
+        ("1234.56", "1,234.56"),
+        ("-1234567.1234567", "-1,234,567.1234567"),
+        (-0.0, "-0.0"),
+        (1e16, "10,000,000,000,000,000"),
+        (1.5e-05, "0.000015"),
+        (float("inf"), "inf"),
+        (Decimal("1E+5"), "100,000"),
+        (Decimal("-0.000"), "-0.000"),
+        (Decimal("-1234.5E-250"), "-1.2345e-247"),
+        ("the quick brown fox", "the quick brown fox"),
+        ("<b>1234</b>", "&lt;b&gt;1234&lt;/b&gt;"),
+        (mark_safe("1234<b>"), "1,234<b>"),
+        (None, "None"),
+    ],
+)
+def test_intcomma(assert_render, value, expected):
+    template = "{% load humanize %}{{ value|intcomma }}"
+    assert_render(template, {"value": value}, expected)
+
+
+def test_intcomma_without_l10n(assert_render):
+    template = "{% load humanize %}{{ value|intcomma:l10n }}"
+    context = {"value": 1234567.25, "l10n": False}
+    assert_render(template, context, "1,234,567.25")
+
+
+def test_intcomma_translation(assert_render):
+    template = "{% load humanize %}{{ a|intcomma }} {{ b|intcomma }}"
+    context = {"a": 1234567, "b": "1234567.5"}
+    with override("de"):
+        assert_render(template, context, "1.234.567 1,234,567.5")
+
+
+def test_intcomma_translation_decimal(assert_render):
+    template = "{% load humanize %}{{ a|intcomma }} {{ b|intcomma }}"
+    context = {"a": Decimal("1234567.5"), "b": 1234567.25}
+    with override("de"):
+        assert_render(template, context, "1.234.567,5 1.234.567,25")
+
+
+def test_intcomma_decimal_nan(assert_render_error):
+    assert_render_error(
+        template="{% load humanize %}{{ n|intcomma }}",
+        context={"n": Decimal("NaN")},
+        exception=TypeError,
+        django_message=snapshot("bad operand type for abs(): 'str'"),
+        rusty_message=snapshot("""\
+  × bad operand type for abs(): 'str'
+   ╭────
+ 1 │ {% load humanize %}{{ n|intcomma }}
+   ·                         ────┬───
+   ·                             ╰── here
+   ╰────
+"""),
+    )
+
+
+def test_intcomma_missing(assert_render):
+    assert_render("{% load humanize %}{{ value|intcomma }}", {}, "")
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        (100, "100"),
+        (999_999, "999999"),
+        (1_000_000, "1.0 million"),
+        (1_200_000, "1.2 million"),
+        (1_290_000, "1.3 million"),
+        (-1_000_000, "-1.0 million"),
+        ("1000000", "1.0 million"),
+        (1_500_000.0, "1.5 million"),
+        (1_000_000_000, "1.0 billion"),
+        (2_000_000_000, "2.0 billion"),
+        (6_000_000_000_000, "6.0 trillion"),
+        (1_300_000_000_000_000, "1.3 quadrillion"),
+        (3_500_000_000_000_000_000_000, "3.5 sextillion"),
+        (8_100_000_000_000_000_000_000_000_000_000_000, "8.1 decillion"),
+        (10**100, "1.0 googol"),
+        (10**101, "10.0 googol"),
+        (10**103, str(10**103)),
+        ("the quick brown fox", "the quick brown fox"),
+        (None, "None"),
+    ],
+)
+def test_intword(assert_render, value, expected):
+    template = "{% load humanize %}{{ value|intword }}"
+    assert_render(template, {"value": value}, expected)
+
+
+def test_intword_translation(assert_render):
+    template = "{% load humanize %}{{ a|intword }} {{ b|intword }}"
+    with override("de"):
+        assert_render(
+            template, {"a": 1_000_000, "b": 2_500_000}, "1,0 Million 2,5 Millionen"
+        )
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        (1, "one"),
+        (2, "two"),
+        (9, "nine"),
+        ("3", "three"),
+        (0, "0"),
+        (10, "10"),
+        (-1, "-1"),
+        ("something else", "something else"),
+        (None, "None"),
+    ],
+)
+def test_apnumber(assert_render, value, expected):
+    template = "{% load humanize %}{{ value|apnumber }}"
+    assert_render(template, {"value": value}, expected)
+
+
+def test_apnumber_translation(assert_render):
+    with override("de"):
+        assert_render("{% load humanize %}{{ 1|apnumber }}", {}, "eins")
+
+
+def test_naturalday(assert_render):
+    today = date.today()
+    context = {
+        "today": today,
+        "now": datetime.now(),
+        "tomorrow": today + timedelta(days=1),
+        "yesterday": today - timedelta(days=1),
+        "other": date(2008, 1, 6),
+    }
+    template = (
+        "{% load humanize %}"
+        "{{ today|naturalday }} {{ now|naturalday }} {{ tomorrow|naturalday }} "
+        "{{ yesterday|naturalday }} {{ other|naturalday }}"
+    )
+    assert_render(template, context, "today today tomorrow yesterday Jan. 6, 2008")
+
+
+def test_naturalday_format(assert_render):
+    template = '{% load humanize %}{{ value|naturalday:"Y-m-d" }}'
+    assert_render(template, {"value": datetime(2008, 1, 6, 12)}, "2008-01-06")
+
+
+def test_naturalday_not_a_date(assert_render):
+    template = "{% load humanize %}{{ value|naturalday }}|{{ missing|naturalday }}"
+    assert_render(template, {"value": "<b>"}, "&lt;b&gt;|")
+
+
+def test_naturalday_translation(assert_render):
+    with override("de"):
+        template = "{% load humanize %}{{ value|naturalday }}"
+        assert_render(template, {"value": date.today()}, "heute")
+
+
+@pytest.mark.parametrize(
+    "delta,expected",
+    [
+        (timedelta(), "now"),
+        (timedelta(seconds=-1), "a second ago"),
+        (timedelta(seconds=-30), "30\xa0seconds ago"),
+        (timedelta(minutes=-1, seconds=-30), "a minute ago"),
+        (timedelta(minutes=-10, seconds=-30), "10\xa0minutes ago"),
+        (timedelta(hours=-1, minutes=-1), "an hour ago"),
+        (timedelta(hours=-23, minutes=-1), "23\xa0hours ago"),
+        (timedelta(days=-3, hours=-2, minutes=-1), "3\xa0days, 2\xa0hours ago"),
+        (timedelta(days=-400), "1\xa0year, 1\xa0month ago"),
+        (timedelta(minutes=10, seconds=30), "10\xa0minutes from now"),
+        (timedelta(hours=1, minutes=30), "an hour from now"),
+        (timedelta(hours=5, minutes=30), "5\xa0hours from now"),
+        (timedelta(days=1, hours=3, minutes=30), "1\xa0day, 3\xa0hours from now"),
+    ],
+)
+def test_naturaltime(assert_render, delta, expected):
+    template = "{% load humanize %}{{ value|naturaltime }}"
+    assert_render(template, {"value": datetime.now() + delta}, expected)
+
+
+def test_naturaltime_aware(assert_render):
+    value = datetime.now(timezone.utc) - timedelta(hours=2, minutes=1)
+    template = "{% load humanize %}{{ value|naturaltime }}"
+    assert_render(template, {"value": value}, "2\xa0hours ago")
+
+
+def test_naturaltime_not_a_date(assert_render):
+    template = "{% load humanize %}{{ value|naturaltime }}|{{ missing|naturaltime }}"
+    assert_render(template, {"value": "<b>"}, "&lt;b&gt;|")
+
+
+def test_naturaltime_translation(assert_render):
+    template = "{% load humanize %}{{ value|naturaltime }}"
+    with override("de"):
+        assert_render(template, {"value": datetime.now()}, "jetzt")
+
+
+def test_naturaltime_date(assert_render_error):
+    assert_render_error(
+        template="{% load humanize %}{{ d|naturaltime }}",
+        context={"d": date(2008, 1, 6)},
+        exception=AttributeError,
+        django_message=snapshot("'datetime.date' object has no attribute 'utcoffset'"),
+        rusty_message=snapshot("""\
+  × 'datetime.date' object has no attribute 'utcoffset'
+   ╭────
+ 1 │ {% load humanize %}{{ d|naturaltime }}
+   ·                         ─────┬─────
+   ·                              ╰── here
+   ╰────
+"""),
+    )
+
+
+def test_load_humanize_filter(assert_render):
+    template = "{% load intcomma from humanize %}{{ value|intcomma }}"
+    assert_render(template, {"value": 1000}, "1,000")
+
+
+def test_load_humanize_filter_only(assert_parse_error):
+    template = "{% load intcomma from humanize %}{{ value|ordinal }}"
+    django_message = snapshot("Invalid filter: 'ordinal'")
+    rusty_message = snapshot("""\
+  × Invalid filter: 'ordinal'
+   ╭────
+ 1 │ {% load intcomma from humanize %}{{ value|ordinal }}
+   ·                                           ───┬───
+   ·                                              ╰── here
+   ╰────
+""")
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_load_humanize_missing_filter(assert_parse_error):
+    template = "{% load missing from humanize %}"
+    django_message = snapshot(
+        "'missing' is not a valid tag or filter in tag library 'humanize'"
+    )
+    rusty_message = snapshot("""\
+  × 'missing' is not a valid tag or filter in tag library 'humanize'
+   ╭────
+ 1 │ {% load missing from humanize %}
+   ·         ───┬───      ────┬───
+   ·            │             ╰── library
+   ·            ╰── tag or filter
+   ╰────
+""")
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
+
+
+def test_humanize_not_loaded(assert_parse_error):
+    template = "{{ value|intcomma }}"
+    django_message = snapshot("Invalid filter: 'intcomma'")
+    rusty_message = snapshot("""\
+  × Invalid filter: 'intcomma'
+   ╭────
+ 1 │ {{ value|intcomma }}
+   ·          ────┬───
+   ·              ╰── here
+   ╰────
+""")
+    assert_parse_error(
+        template=template, django_message=django_message, rusty_message=rusty_message
+    )
@@ -3,6 +3,7 @@ from django.shortcuts import render
 from django.template import Context, RequestContext
 from django.template.response import SimpleTemplateResponse, TemplateResponse
 from django.test import RequestFactory
+from django.utils import timezone
 
 
 class TestUserFacingTemplateRenderSuccess:
@@ -74,10 +75,7 @@ class TestUserFacingTemplateRenderRequiresDict:
 
     def test_render_shortcut_requires_dict(self, template_engine):
         if template_engine.name == "rusty":
-            pytest.skip(
-                "Rusty engine currently fails with `argument 'context': 'Context' object cannot be cast as 'dict'`"
-                "We should refine the error to match django"
-            )
+            pytest.skip("Rusty engine accepts Context and RequestContext objects")
 
         template = "basic.txt"
         request = RequestFactory().get("/")
@@ -96,10 +94,7 @@ class TestUserFacingTemplateRenderRequiresDict:
 
     def test_template_render_requires_dict(self, template_engine):
         if template_engine.name == "rusty":
-            pytest.skip(
-                "Rusty engine currently fails with `argument 'context': 'Context' object cannot be cast as 'dict'`"
-                "We should refine the error to match django"
-            )
+            pytest.skip("Rusty engine accepts Context and RequestContext objects")
 
         template = template_engine.from_string("{{ foo }}")
         context = Context({"foo": "bar"})
@@ -116,10 +111,7 @@ class TestUserFacingTemplateRenderRequiresDict:
 
     def test_simple_template_response_requires_dict(self, template_engine):
         if template_engine.name == "rusty":
-            pytest.skip(
-                "Rusty engine currently fails with `argument 'context': 'Context' object cannot be cast as 'dict'`"
-                "We should refine the error to match django"
-            )
+            pytest.skip("Rusty engine accepts Context and RequestContext objects")
 
         template = template_engine.from_string("{{ foo }}")
         context = Context({"foo": "bar"})
@@ -139,10 +131,7 @@ class TestUserFacingTemplateRenderRequiresDict:
 
     def test_template_response_requires_dict(self, template_engine):
         if template_engine.name == "rusty":
-            pytest.skip(
-                "Rusty engine currently fails with `argument 'context': 'Context' object cannot be cast as 'dict'`"
-                "We should refine the error to match django"
-            )
+            pytest.skip("Rusty engine accepts Context and RequestContext objects")
 
         request = RequestFactory().get("/")
         template = template_engine.from_string("{{ foo }}")
@@ -160,3 +149,57 @@ class TestUserFacingTemplateRenderRequiresDict:
             TypeError, match="context must be a dict rather than RequestContext"
         ):
             response.render()
+
+
+class TestRenderDjangoContext:
+    """
+    The engine-level `Template.render` accepts Django's `Context` and `RequestContext`.
+    """
+
+    def test_context_stack_is_flattened(self, template_engine):
+        template = template_engine.engine.from_string("{{ foo }} {{ bar }}")
+        context = Context({"foo": "outer", "bar": "bar"})
+        context.push({"foo": "inner"})
+
+        assert template.render(context) == "inner bar"
+
+    def test_context_autoescape(self, template_engine):
+        template = template_engine.engine.from_string("{{ html }}")
+        context = Context({"html": "<p>"}, autoescape=False)
+
+        assert template.render(context) == "<p>"
+
+    def test_request_context_processors_run_once(self, template_engine):
+        calls = []
+
+        def processor(request):
+            calls.append(request)
+            return {"extra": "processed", "foo": "processor"}
+
+        request = RequestFactory().get("/path/")
+        template = template_engine.engine.from_string(
+            "{{ foo }} {{ extra }} {{ request.path }}"
+        )
+        context = RequestContext(request, {"foo": "bar"}, processors=[processor])
+
+        assert template.render(context) == "processor processed /path/"
+        assert calls == [request]
+
+    def test_request_context_push_overrides_processors(self, template_engine):
+        def processor(request):
+            return {"foo": "processor", "bar": "processor"}
+
+        request = RequestFactory().get("/path/")
+        template = template_engine.engine.from_string("{{ foo }} {{ bar }}")
+        context = RequestContext(request, {"foo": "bar"}, processors=[processor])
+        context.push({"bar": "pushed"})
+
+        assert template.render(context) == "processor pushed"
+
+    def test_top_level_assignment_written_back(self, template_engine):
+        template = template_engine.engine.from_string('{% now "Y" as year %}')
+        context = Context({"foo": "bar"})
+
+        assert template.render(context) == ""
+        assert context["year"] == str(timezone.now().year)
+        assert context["foo"] == "bar"
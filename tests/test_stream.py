@@ -0,0 +1,132 @@
+import pytest
+from django.template import Context, engines
+from django.template.base import VariableDoesNotExist
+from django.template.exceptions import TemplateDoesNotExist
+
+
+def stream(template, context=None, **kwargs):
+    template = engines["rusty"].from_string(template)
+    return list(template.stream(context, **kwargs))
+
+
+def test_stream():
+    template = "{% for user in users %}Hello {{ user }}! {% endfor %}Goodbye"
+    users = ["Lily", "Jacob", "Bryony"]
+    chunks = stream(template, {"users": users})
+    assert chunks == ["Hello Lily! Hello Jacob! Hello Bryony! Goodbye"]
+
+
+def test_stream_chunk_size():
+    template = "{% for user in users %}Hello {{ user }}! {% endfor %}Goodbye"
+    users = ["Lily", "Jacob", "Bryony"]
+    chunks = stream(template, {"users": users}, chunk_size=10)
+    assert chunks == ["Hello Lily", "! Hello Jacob", "! Hello Bryony", "! Goodbye"]
+
+
+def test_stream_chunk_size_one():
+    template = "{% if yes %}a{% for x in 'bc' %}{{ x }}{% endfor %}{% endif %}{{ x }}d"
+    chunks = stream(template, {"yes": True, "x": ""}, chunk_size=1)
+    assert chunks == ["a", "b", "c", "d"]
+
+
+def test_stream_empty():
+    assert stream("") == []
+    assert stream("{% for x in missing %}{% empty %}{% endfor %}") == []
+
+
+def test_stream_matches_render():
+    template = engines["rusty"].from_string(
+        "{% autoescape off %}{{ html }}{% endautoescape %}{{ html }}"
+        "{% for a, b in pairs %}{{ forloop.counter }}{{ a }}{{ b }}"
+        "{% if forloop.last %}.{% else %},{% endif %}{% endfor %}"
+        "{% for x in missing %}{% empty %}none{% endfor %}"
+        "{% include 'name_snippet.txt' with person='Lily' greeting='Hi' only %}"
+        "{% include 'basic.txt' %}{{ person }}"
+    )
+    context = {"html": "<p>", "pairs": [(1, 2), (3, 4)], "user": "Jacob"}
+    expected = template.render(context)
+    assert "".join(template.stream(context, chunk_size=1)) == expected
+
+
+def test_stream_generator_is_lazy():
+    consumed = []
+
+    def numbers():
+        for n in range(3):
+            consumed.append(n)
+            yield n
+
+    template = engines["rusty"].from_string(
+        "{% for n in numbers %}{{ n }}{{ forloop.counter }}{% endfor %}"
+    )
+    chunks = template.stream({"numbers": numbers()}, chunk_size=1)
+    assert next(chunks) == "0"
+    assert consumed == [0]
+    assert list(chunks) == ["1", "1", "2", "2", "3"]
+    assert consumed == [0, 1, 2]
+
+
+def test_stream_generator_with_length():
+    def numbers():
+        yield from range(3)
+
+    template = engines["rusty"].from_string(
+        "{% for n in numbers %}{{ n }}{% if not forloop.last %},{% endif %}{% endfor %}"
+    )
+    assert "".join(template.stream({"numbers": numbers()})) == "0,1,2"
+
+
+def test_stream_django_context():
+    template = engines["rusty"].from_string(
+        "{% firstof 'Lily' as name %}{% for x in 'ab' %}{{ x }}{% endfor %}"
+    )
+    context = Context({"name": "Jacob"})
+    chunks = template.stream(context, chunk_size=1)
+    assert next(chunks) == "a"
+    assert context["name"] == "Jacob"
+    assert list(chunks) == ["b"]
+    assert context["name"] == "Lily"
+
+
+def test_stream_error():
+    template = engines["rusty"].from_string(
+        "{% for x in 'ab' %}{{ x }}{% endfor %}{{ foo|add:bar }}"
+    )
+    with pytest.raises(VariableDoesNotExist) as render_error:
+        template.render({"foo": 1})
+
+    chunks = template.stream({"foo": 1}, chunk_size=1)
+    assert next(chunks) == "a"
+    assert next(chunks) == "b"
+    with pytest.raises(VariableDoesNotExist) as stream_error:
+        next(chunks)
+    assert str(stream_error.value) == str(render_error.value)
+    assert list(chunks) == []
+
+
+def test_stream_include_error():
+    template = engines["rusty"].from_string(
+        "{% for x in 'ab' %}{% include 'nested/invalid.txt' %}{% endfor %}"
+    )
+    with pytest.raises(TemplateDoesNotExist) as render_error:
+        template.render({})
+    with pytest.raises(TemplateDoesNotExist) as stream_error:
+        list(template.stream({}))
+    assert str(stream_error.value) == str(render_error.value)
+
+
+def test_stream_render_context():
+    # Like `{% cycle %}`, `render_count` keeps its state in `context.render_context`.
+    template = engines["rusty"].from_string(
+        "{% load render_counts from custom_tags %}"
+        "{% render_counts %} {% render_counts chunk_size=1 %}"
+    )
+    assert template.render({}) == "12 12"
+
+
+def test_stream_close():
+    template = engines["rusty"].from_string(
+        "{% load stream_first_chunk from custom_tags %}"
+        "{% stream_first_chunk %}{{ x }}{{ html }}"
+    )
+    assert template.render({"x": "c", "html": "<"}) == "ac&lt;"
@@ -0,0 +1,81 @@
+import pytest
+from django.template import engines
+from django.template.base import VariableDoesNotExist
+from django.template.engine import Engine as DjangoEngine
+from django.template.exceptions import TemplateSyntaxError
+
+from django_rusty_templates import Engine as RustyEngine
+
+SHARED_KEYS = ("name", "line", "top", "bottom", "total", "source_lines")
+
+
+@pytest.fixture
+def debug_engines():
+    return DjangoEngine(debug=True), RustyEngine(debug=True)
+
+
+def test_syntax_error_template_debug(debug_engines):
+    template = "<p>\n{{ foo|title'foo' }}\n</p>\n"
+    django_engine, rusty_engine = debug_engines
+
+    with pytest.raises(TemplateSyntaxError) as django_info:
+        django_engine.from_string(template)
+    with pytest.raises(TemplateSyntaxError) as rusty_info:
+        rusty_engine.from_string(template)
+
+    django_debug = django_info.value.template_debug
+    rusty_debug = rusty_info.value.template_debug
+    for key in SHARED_KEYS:
+        assert rusty_debug[key] == django_debug[key]
+    assert rusty_debug["name"] == "<unknown source>"
+    assert rusty_debug["during"] == "&#x27;foo&#x27;"
+    assert rusty_debug["before"] == "{{ foo|title"
+    assert rusty_debug["after"] == " }}\n"
+
+
+def test_render_error_template_debug(debug_engines):
+    template = "<p>\n{{ foo|default:bar }}\n</p>\n"
+    django_engine, rusty_engine = debug_engines
+
+    with pytest.raises(VariableDoesNotExist) as django_info:
+        django_engine.from_string(template).render(django_engine.context_class({}))
+    with pytest.raises(VariableDoesNotExist) as rusty_info:
+        rusty_engine.from_string(template).render({})
+
+    django_debug = django_info.value.template_debug
+    rusty_debug = rusty_info.value.template_debug
+    for key in SHARED_KEYS:
+        assert rusty_debug[key] == django_debug[key]
+    assert rusty_debug["during"] == "bar"
+
+
+def test_included_template_debug():
+    engine = RustyEngine(
+        debug=True,
+        loaders=[
+            (
+                "django.template.loaders.locmem.Loader",
+                {
+                    "outer.txt": "{% include 'inner.txt' %}",
+                    "inner.txt": "inner\n{{ foo|default:bar }}",
+                },
+            )
+        ],
+    )
+
+    with pytest.raises(VariableDoesNotExist) as exc_info:
+        engine.get_template("outer.txt").render({})
+
+    template_debug = exc_info.value.template_debug
+    assert template_debug["name"] == "inner.txt"
+    assert template_debug["line"] == 2
+    assert template_debug["during"] == "bar"
+
+
+def test_no_template_debug_without_debug():
+    template = engines["rusty"].engine.from_string("{{ foo|default:bar }}")
+
+    with pytest.raises(VariableDoesNotExist) as exc_info:
+        template.render({})
+
+    assert not hasattr(exc_info.value, "template_debug")
@@ -159,3 +159,21 @@ def test_do_not_call_and_alters_data(assert_render):
     context = {"foo": both}
     assert_render(template=template, context=context, expected="0")
     assert both.data == 0
+
+
+def raise_unicode_decode_error():
+    return b"\xff".decode("utf-8")
+
+
+def test_render_unicode_decode_error(assert_render):
+    template = "{{ broken }}"
+    context = {"broken": raise_unicode_decode_error}
+    assert_render(template=template, context=context, expected="")
+
+
+def test_render_unicode_decode_error_attribute(assert_render):
+    class Broken:
+        method = staticmethod(raise_unicode_decode_error)
+
+    template = "{{ broken.method }}"
+    assert_render(template=template, context={"broken": Broken()}, expected="")